@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
 use crate::{ApiError, ApiErrorKind, Detail};
@@ -79,3 +80,12 @@ impl SubmittedVerify {
         Ok(())
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub issued_on: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+    /// if this is the session that made the request
+    pub current: bool,
+}
@@ -1,9 +1,10 @@
 use std::default::Default;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use reqwest::Url;
-use reqwest::blocking::RequestBuilder;
 use reqwest_cookie_store::{CookieStore, CookieStoreRwLock};
 
 pub mod error;
@@ -12,8 +13,88 @@ pub mod users;
 pub mod sec;
 pub mod fs;
 pub mod iterate;
+pub mod retry;
+pub(crate) mod request;
+
+#[cfg(feature = "async")]
+pub mod nonblocking;
 
 use error::{ApiClientError, RequestError};
+use retry::{RetryPolicy, RequestBuilder};
+use request::{Endpoint, Request, Response};
+
+/// the results of a timed ping to the server
+#[derive(Debug)]
+pub struct Pong {
+    /// time it took to send the request and receive the full response
+    pub rtt: Duration,
+    /// the time the server processed the ping if it provided one
+    pub server_time: Option<DateTime<Utc>>,
+    /// the local time when the request was sent
+    pub sent: DateTime<Utc>,
+}
+
+impl Pong {
+    /// estimates the difference between the server clock and the local
+    /// clock assuming that the request and response took the same amount of
+    /// time
+    pub fn skew(&self) -> Option<chrono::Duration> {
+        let server_time = self.server_time?;
+        let half = chrono::Duration::from_std(self.rtt / 2).ok()?;
+
+        Some(server_time - (self.sent + half))
+    }
+}
+
+pub(crate) const DEFAULT_USER_AGENT: &str = "rfs-api-client/0.1.0";
+
+pub(crate) fn read_cookie_store<R>(reader: R) -> Result<CookieStore, ApiClientError>
+where
+    R: std::io::BufRead
+{
+    CookieStore::load_json(reader)
+        .map_err(|e| ApiClientError::CookieStore(e))
+}
+
+pub(crate) fn missing_cookie_store(err: std::io::Error, exists: bool) -> Result<CookieStore, ApiClientError> {
+    match err.kind() {
+        std::io::ErrorKind::NotFound => {
+            if exists {
+                Err(ApiClientError::StdIo(err))
+            } else {
+                Ok(CookieStore::default())
+            }
+        },
+        _ => Err(ApiClientError::StdIo(err))
+    }
+}
+
+/// creates the default headers that will be sent with every request. if a
+/// bearer token is provided then it will be sent in the authorization header
+pub(crate) fn default_headers(token: Option<&str>) -> Result<reqwest::header::HeaderMap, ApiClientError> {
+    let mut headers = reqwest::header::HeaderMap::new();
+
+    if let Some(token) = token {
+        let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}"))
+            .map_err(|e| ApiClientError::InvalidToken(e))?;
+        value.set_sensitive(true);
+
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+
+    Ok(headers)
+}
+
+pub(crate) fn write_cookie_store<W>(store: &CookieStoreRwLock, writer: &mut W) -> Result<(), ApiClientError>
+where
+    W: std::io::Write
+{
+    let store = store.read()
+        .map_err(|_e| ApiClientError::PoisonedLock)?;
+
+    store.save_json(writer)
+        .map_err(|e| ApiClientError::CookieStore(e))
+}
 
 pub struct Info {
     pub(crate) url: Url
@@ -29,6 +110,7 @@ pub struct ApiClient {
     pub(crate) cookie_file: Option<Box<Path>>,
     pub(crate) store: Arc<CookieStoreRwLock>,
     pub(crate) client: reqwest::blocking::Client,
+    pub(crate) retry: RetryPolicy,
     pub(crate) info: Info
 }
 
@@ -38,53 +120,63 @@ impl ApiClient {
             url: Url::parse("https://localhost/").unwrap(),
             file: None,
             exists: false,
-            agent: None
+            agent: None,
+            token: None,
+            retry: RetryPolicy::new(),
         }
     }
 
-    pub(crate) fn get<U>(&self, path: U) -> RequestBuilder
-    where
-        U: AsRef<str>,
-    {
-        let url = self.info.url.join(path.as_ref()).unwrap();
-
-        self.client.get(url)
+    /// creates a copy of the client that will use the given retry policy.
+    /// the cookie store and connection pool are shared with the original
+    pub fn with_retry(&self, retry: RetryPolicy) -> ApiClient {
+        ApiClient {
+            cookie_file: self.cookie_file.clone(),
+            store: self.store.clone(),
+            client: self.client.clone(),
+            retry,
+            info: Info {
+                url: self.info.url.clone()
+            }
+        }
     }
 
-    pub(crate) fn post<U>(&self, path: U) -> RequestBuilder
+    pub(crate) fn get<U>(&self, path: U) -> RequestBuilder
     where
-        U: AsRef<str>
+        U: AsRef<str>,
     {
         let url = self.info.url.join(path.as_ref()).unwrap();
 
-        self.client.post(url)
+        RequestBuilder::new(self.client.get(url), reqwest::Method::GET, self.retry.clone())
     }
 
-    pub(crate) fn put<U>(&self, path: U) -> RequestBuilder
-    where
-        U: AsRef<str>
-    {
-        let url = self.info.url.join(path.as_ref()).unwrap();
-
-        self.client.put(url)
-    }
+    /// creates a request builder from the description of a request
+    pub(crate) fn build(&self, request: Request) -> Result<RequestBuilder, RequestError> {
+        let parts = request.into_parts(&self.info.url)?;
+        let mut builder = RequestBuilder::new(
+            self.client.request(parts.method.clone(), parts.url),
+            parts.method,
+            self.retry.clone()
+        );
+
+        for (key, value) in parts.headers {
+            builder = builder.header(key, value);
+        }
 
-    pub(crate) fn patch<U>(&self, path: U) -> RequestBuilder
-    where
-        U: AsRef<str>
-    {
-        let url = self.info.url.join(path.as_ref()).unwrap();
+        if let Some(body) = parts.body {
+            builder = builder.body(body);
+        }
 
-        self.client.patch(url)
+        Ok(builder)
     }
 
-    pub(crate) fn delete<U>(&self, path: U) -> RequestBuilder
+    /// sends the request of an endpoint and parses the response
+    pub(crate) fn send<E>(&self, endpoint: &E) -> Result<E::Output, RequestError>
     where
-        U: AsRef<str>
+        E: Endpoint
     {
-        let url = self.info.url.join(path.as_ref()).unwrap();
+        let res = self.build(endpoint.request()?)?.send()?;
 
-        self.client.delete(url)
+        E::response(Response::from_blocking(res)?)
     }
 
     pub fn save_session(&self) -> Result<bool, ApiClientError> {
@@ -92,8 +184,6 @@ impl ApiClient {
             return Ok(false);
         };
 
-        let store = self.store.read()
-            .map_err(|_e| ApiClientError::PoisonedLock)?;
         let file = std::fs::OpenOptions::new()
             .write(true)
             .truncate(true)
@@ -102,8 +192,7 @@ impl ApiClient {
             .map_err(|e| ApiClientError::StdIo(e))?;
         let mut writer = std::io::BufWriter::new(file);
 
-        store.save_json(&mut writer)
-            .map_err(|e| ApiClientError::CookieStore(e))?;
+        write_cookie_store(&self.store, &mut writer)?;
 
         Ok(true)
     }
@@ -124,13 +213,42 @@ impl ApiClient {
             _ => Err(RequestError::Api(res.json()?))
         }
     }
+
+    /// pings the server and records how long it took to get a response
+    pub fn ping_timed(&self) -> Result<Pong, RequestError> {
+        let sent = Utc::now();
+        let start = Instant::now();
+
+        let res = self.get("/ping").send()?;
+
+        match res.status() {
+            reqwest::StatusCode::OK => {
+                let server_time = res.headers()
+                    .get("x-server-time")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .and_then(DateTime::from_timestamp_millis);
+
+                res.text()?;
+
+                Ok(Pong {
+                    rtt: start.elapsed(),
+                    server_time,
+                    sent,
+                })
+            }
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
 }
 
 pub struct ApiClientBuilder {
     url: Url,
     file: Option<PathBuf>,
     exists: bool,
-    agent: Option<String>
+    agent: Option<String>,
+    token: Option<String>,
+    retry: RetryPolicy,
 }
 
 impl ApiClientBuilder {
@@ -168,30 +286,27 @@ impl ApiClientBuilder {
         self.agent = Some(user_agent.into());
     }
 
+    /// sets a bot token that will be sent with every request. the server
+    /// will use the token over any session cookies that are present
+    pub fn bearer_token<T>(&mut self, token: T)
+    where
+        T: Into<String>
+    {
+        self.token = Some(token.into());
+    }
+
+    pub fn retry(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
     pub fn build(self) -> Result<ApiClient, ApiClientError> {
-        let user_agent = self.agent.unwrap_or("rfs-api-client/0.1.0".into());
+        let user_agent = self.agent.unwrap_or(DEFAULT_USER_AGENT.into());
         let store = if let Some(path) = &self.file {
             match std::fs::OpenOptions::new()
                 .read(true)
                 .open(&path) {
-                Ok(file) => {
-                    let reader = std::io::BufReader::new(file);
-
-                    CookieStore::load_json(reader)
-                        .map_err(|e| ApiClientError::CookieStore(e))?
-                },
-                Err(err) => match err.kind() {
-                    std::io::ErrorKind::NotFound => {
-                        if self.exists {
-                            return Err(ApiClientError::StdIo(err));
-                        } else {
-                            CookieStore::default()
-                        }
-                    },
-                    _ => {
-                        return Err(ApiClientError::StdIo(err));
-                    }
-                }
+                Ok(file) => read_cookie_store(std::io::BufReader::new(file))?,
+                Err(err) => missing_cookie_store(err, self.exists)?
             }
         } else {
             CookieStore::default()
@@ -200,6 +315,7 @@ impl ApiClientBuilder {
         let store = Arc::new(CookieStoreRwLock::new(store));
         let client = reqwest::blocking::Client::builder()
             .cookie_provider(store.clone())
+            .default_headers(default_headers(self.token.as_deref())?)
             .user_agent(user_agent)
             .build()
             .map_err(|e| ApiClientError::Reqwest(e))?;
@@ -208,6 +324,7 @@ impl ApiClientBuilder {
             cookie_file: self.file.map(|v| v.into_boxed_path()),
             store,
             client,
+            retry: self.retry,
             info: Info {
                 url: self.url
             }
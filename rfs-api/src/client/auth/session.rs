@@ -1,5 +1,8 @@
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::ApiClient;
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::Payload;
 use crate::auth::session::{
     RequestUser,
@@ -7,6 +10,7 @@ use crate::auth::session::{
     SubmittedAuth,
     RequestedVerify,
     SubmittedVerify,
+    SessionInfo,
 };
 
 pub struct RequestAuth {
@@ -26,12 +30,26 @@ impl RequestAuth {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<RequestedAuth>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<RequestedAuth>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RequestAuth {
+    type Output = Option<Payload<RequestedAuth>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.validate()?;
 
-        let res = client.post("/auth/session/request")
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post("/auth/session/request")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(Some(res.json()?)),
             reqwest::StatusCode::NO_CONTENT => Ok(None),
@@ -55,10 +73,24 @@ impl SubmitAuth {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<RequestedVerify>>, RequestError> {
-        let res = client.post("/auth/session/submit")
-            .json(&self.body)
-            .send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<RequestedVerify>>, RequestError> {
+        client.send(&self).await
+    }
+}
 
+impl Endpoint for SubmitAuth {
+    type Output = Option<Payload<RequestedVerify>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::post("/auth/session/submit")
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(Some(res.json()?)),
             reqwest::StatusCode::NO_CONTENT => Ok(None),
@@ -91,12 +123,26 @@ impl SubmitVerify {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for SubmitVerify {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.validate()?;
 
-        let res = client.post("/auth/session/verify")
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post("/auth/session/verify")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -112,8 +158,23 @@ impl DropSession {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete("/auth/session/drop").send()?;
+        client.send(&self)
+    }
 
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DropSession {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete("/auth/session/drop"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         if res.status() != reqwest::StatusCode::NO_CONTENT {
             Err(RequestError::Api(res.json()?))
         } else {
@@ -121,3 +182,105 @@ impl DropSession {
         }
     }
 }
+
+pub struct RetrieveSessions {}
+
+impl RetrieveSessions {
+    pub fn new() -> Self {
+        RetrieveSessions {}
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Vec<SessionInfo>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<SessionInfo>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveSessions {
+    type Output = Payload<Vec<SessionInfo>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get("/auth/session"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?)),
+        }
+    }
+}
+
+pub struct RevokeSession {
+    id: String,
+}
+
+impl RevokeSession {
+    pub fn id<I>(id: I) -> Self
+    where
+        I: Into<String>
+    {
+        RevokeSession { id: id.into() }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RevokeSession {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete(format!("/auth/session/{}", self.id)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(RequestError::Api(res.json()?)),
+        }
+    }
+}
+
+/// revokes all sessions except for the one making the request
+pub struct RevokeAllSessions {}
+
+impl RevokeAllSessions {
+    pub fn new() -> Self {
+        RevokeAllSessions {}
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RevokeAllSessions {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete("/auth/session"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(RequestError::Api(res.json()?)),
+        }
+    }
+}
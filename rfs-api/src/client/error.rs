@@ -9,6 +9,7 @@ pub enum ApiClientError {
     CookieStore(Box<dyn std::error::Error + Sync + Send>),
     Reqwest(reqwest::Error),
     StdIo(std::io::Error),
+    InvalidToken(reqwest::header::InvalidHeaderValue),
 }
 
 impl fmt::Display for ApiClientError {
@@ -18,6 +19,7 @@ impl fmt::Display for ApiClientError {
             ApiClientError::CookieStore(_) => write!(f, "ApiClientError::CookieStore"),
             ApiClientError::Reqwest(_) => write!(f, "ApiClientError::Reqwest"),
             ApiClientError::StdIo(_) => write!(f, "ApiClientError::StdIo"),
+            ApiClientError::InvalidToken(_) => write!(f, "ApiClientError::InvalidToken"),
         }
     }
 }
@@ -28,6 +30,7 @@ impl Error for ApiClientError {
             ApiClientError::CookieStore(v) => Some(&**v),
             ApiClientError::Reqwest(v) => Some(v),
             ApiClientError::StdIo(v) => Some(v),
+            ApiClientError::InvalidToken(v) => Some(v),
             _ => None
         }
     }
@@ -39,14 +42,20 @@ pub enum RequestError {
     Api(#[from] ApiError),
 
     #[error(transparent)]
-    Reqwest(#[from] reqwest::Error)
+    Reqwest(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Query(#[from] serde_urlencoded::ser::Error),
 }
 
 impl RequestError {
-    pub fn as_api(self) -> Result<ApiError, reqwest::Error> {
+    pub fn as_api(self) -> Result<ApiError, RequestError> {
         match self {
             RequestError::Api(v) => Ok(v),
-            RequestError::Reqwest(v) => Err(v)
+            err => Err(err)
         }
     }
 }
@@ -1,9 +1,13 @@
 use rfs_lib::ids;
-use reqwest::blocking::Body;
-use reqwest::blocking::Response;
+use chrono::{DateTime, Utc};
+use reqwest::blocking;
+use reqwest::header::HeaderMap;
 
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::{ApiClient, iterate};
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::{
     Payload,
     ApiError,
@@ -11,12 +15,24 @@ use crate::{
     Tags,
     Limit,
     Offset,
+    Validator,
 };
 use crate::fs::{
+    BatchUpdate as BatchUpdateBody,
+    BatchResult,
     CreateDir as CreateDirBody,
+    OnConflict,
     UpdateMetadata as UpdateMetadataBody,
     Item,
     ItemMin,
+    Trashed,
+    UserStats,
+};
+use crate::fs::sync::{
+    ManifestEntry,
+    SyncManifest,
+    SyncMode,
+    SyncPlan,
 };
 
 pub mod storage;
@@ -31,8 +47,74 @@ impl RetrieveItem {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<Item>>, RequestError> {
-        let res = client.get(format!("/api/fs/{}", self.uid)).send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<Item>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveItem {
+    type Output = Option<Payload<Item>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get(format!("/api/fs/{}", self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(Some(res.json()?)),
+            reqwest::StatusCode::NOT_FOUND => {
+                let err: ApiError = res.json()?;
+
+                if *err.kind() == ApiErrorKind::FileNotFound {
+                    return Ok(None);
+                }
+
+                Err(RequestError::Api(err))
+            },
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct LookupItem {
+    storage_uid: ids::StorageUid,
+    path: String,
+}
+
+impl LookupItem {
+    pub fn path<P>(storage_uid: ids::StorageUid, path: P) -> Self
+    where
+        P: Into<String>
+    {
+        LookupItem {
+            storage_uid,
+            path: path.into(),
+        }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Option<Payload<Item>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<Item>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for LookupItem {
+    type Output = Option<Payload<Item>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get("/api/fs/lookup")
+            .query(&[("storage", self.storage_uid.to_string()), ("path", self.path.clone())]))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(Some(res.json()?)),
             reqwest::StatusCode::NOT_FOUND => {
@@ -89,7 +171,20 @@ impl RetrieveRoots {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<ItemMin>>, RequestError> {
-        let mut builder = client.get("/api/fs");
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<ItemMin>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for RetrieveRoots {
+    type Output = Payload<Vec<ItemMin>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get("/api/fs");
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -101,8 +196,10 @@ impl RetrieveRoots {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -181,7 +278,20 @@ impl RetrieveContents {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<ItemMin>>, RequestError> {
-        let mut builder = client.get(format!("/api/fs/{}/contents", self.uid));
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<ItemMin>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for RetrieveContents {
+    type Output = Payload<Vec<ItemMin>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get(format!("/api/fs/{}/contents", self.uid));
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -193,8 +303,10 @@ impl RetrieveContents {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -231,65 +343,79 @@ impl iterate::Pageable for RetrieveContents {
     }
 }
 
-pub struct DownloadItem {
-    uid: ids::FSUid
-}
-
-impl DownloadItem {
-    pub fn uid(uid: ids::FSUid) -> Self {
-        DownloadItem { uid }
-    }
-
-    pub fn send(&self, client: &ApiClient) -> Result<Response, RequestError> {
-        let res = client.get(format!("/api/fs/{}/download", self.uid))
-            .send()?;
-
-        match res.status() {
-            reqwest::StatusCode::OK => Ok(res),
-            _ => Err(RequestError::Api(res.json()?))
-        }
+/// formats a tag for a search query as "name" or "name:value"
+fn tag_param(tag: String, value: Option<String>) -> String {
+    if let Some(value) = value {
+        format!("{tag}:{value}")
+    } else {
+        tag
     }
 }
 
-pub struct CreateDir {
-    parent: ids::FSUid,
-    body: CreateDirBody
+pub struct SearchItems {
+    tags: Vec<(&'static str, String)>,
+    name: Option<String>,
+    storage: Option<ids::StorageUid>,
+    ignore_case: bool,
+    limit: Option<Limit>,
+    offset: Option<Offset>,
+    last_id: Option<ids::FSUid>,
 }
 
-impl CreateDir {
-    pub fn basename<B>(parent: ids::FSUid, basename: B) -> Self
-    where
-        B: Into<String>
-    {
-        CreateDir {
-            parent,
-            body: CreateDirBody {
-                basename: basename.into(),
-                tags: None,
-                comment: None
-            }
+impl SearchItems {
+    pub fn new() -> Self {
+        SearchItems {
+            tags: Vec::new(),
+            name: None,
+            storage: None,
+            ignore_case: false,
+            limit: None,
+            offset: None,
+            last_id: None,
         }
     }
 
-    pub fn comment<C>(&mut self, comment: C) -> &mut Self
+    /// adds a tag that the results must have. if a value is given then the
+    /// tag must also have that value
+    pub fn tag<T, V>(&mut self, tag: T, value: Option<V>) -> &mut Self
     where
-        C: Into<String>
+        T: Into<String>,
+        V: Into<String>,
     {
-        self.body.comment = Some(comment.into());
+        self.tags.push(("tag", tag_param(tag.into(), value.map(Into::into))));
         self
     }
 
-    pub fn add_tag<T, V>(&mut self, tag: T, value: Option<V>) -> &mut Self
+    /// adds a tag prefix that the results must have a tag starting with. if
+    /// a value is given then the tag must also have that value
+    pub fn tag_prefix<T, V>(&mut self, prefix: T, value: Option<V>) -> &mut Self
     where
         T: Into<String>,
         V: Into<String>,
     {
-        if let Some(tags) = &mut self.body.tags {
-            tags.insert(tag.into(), value.map(|v| v.into()));
-        } else {
-            self.body.tags = Some(Tags::from([(tag.into(), value.map(|v| v.into()))]));
-        }
+        self.tags.push(("tag_prefix", tag_param(prefix.into(), value.map(Into::into))));
+        self
+    }
+
+    /// compares the values of the tags without case
+    pub fn ignore_case(&mut self, ignore_case: bool) -> &mut Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    /// only returns items with a basename containing the given value. the
+    /// comparison ignores case
+    pub fn name<N>(&mut self, name: N) -> &mut Self
+    where
+        N: Into<String>
+    {
+        self.name = Some(name.into());
+        self
+    }
 
+    /// only returns items in the given storage medium
+    pub fn storage(&mut self, storage: ids::StorageUid) -> &mut Self {
+        self.storage = Some(storage);
         self
     }
 
@@ -299,111 +425,83 @@ impl CreateDir {
         V: Into<String>,
         I: IntoIterator<Item = (T, Option<V>)>
     {
-        if let Some(tags) = &mut self.body.tags {
-            for (k, v) in iter {
-                tags.insert(k.into(), v.map(|v| v.into()));
-            }
-        } else {
-            let mut tags = Tags::new();
-
-            for (k, v) in iter {
-                tags.insert(k.into(), v.map(|v| v.into()));
-            }
-
-            self.body.tags = Some(tags);
+        for (tag, value) in iter {
+            self.tag(tag, value);
         }
 
         self
     }
 
-    pub fn send(self, client: &ApiClient) -> Result<Payload<Item>, RequestError> {
-        let res = client.post(format!("/api/fs/{}", self.parent))
-            .json(&self.body)
-            .send()?;
-
-        match res.status() {
-            reqwest::StatusCode::CREATED => Ok(res.json()?),
-            _ => Err(RequestError::Api(res.json()?))
-        }
+    pub fn limit<L>(&mut self, limit: L) -> &mut Self
+    where
+        L: Into<Option<Limit>>
+    {
+        self.limit = limit.into();
+        self
     }
-}
-
-pub struct SendReadable {
-    uid: ids::FSUid,
-    basename: Option<String>,
-    content_type: Option<mime::Mime>,
-    content_length: Option<u64>,
-    hash: Option<String>,
-}
 
-impl SendReadable {
-    pub fn create<B>(parent: ids::FSUid, basename: B) -> SendReadable
+    pub fn offset<O>(&mut self, offset: O) -> &mut Self
     where
-        B: Into<String>,
+        O: Into<Option<Offset>>
     {
-        SendReadable {
-            uid: parent,
-            basename: Some(basename.into()),
-            content_type: None,
-            content_length: None,
-            hash: None,
-        }
+        self.offset = offset.into();
+        self
     }
 
-    pub fn update(uid: ids::FSUid) -> SendReadable {
-        SendReadable {
-            uid,
-            basename: None,
-            content_type: None,
-            content_length: None,
-            hash: None,
-        }
+    pub fn last_id<I>(&mut self, last_id: I) -> &mut Self
+    where
+        I: Into<Option<ids::FSUid>>
+    {
+        self.last_id = last_id.into();
+        self
     }
 
-    pub fn content_type(&mut self, mime: mime::Mime) -> &mut Self {
-        self.content_type = Some(mime);
-        self
+    pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<ItemMin>>, RequestError> {
+        client.send(self)
     }
 
-    pub fn content_length(&mut self, length: u64) -> &mut Self {
-        self.content_length = Some(length);
-        self
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<ItemMin>>, RequestError> {
+        client.send(self).await
     }
+}
 
-    pub fn hash<H,V>(&mut self, hash_type: H, hash_value: V) -> &mut Self
-    where
-        H: Into<String>,
-        V: Into<String>,
-    {
-        let hash_type = hash_type.into();
-        let hash_value = hash_value.into();
+impl Endpoint for SearchItems {
+    type Output = Payload<Vec<ItemMin>>;
 
-        self.hash = Some(format!("{hash_type}:{hash_value}"));
-        self
-    }
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get("/api/fs/search");
 
-    pub fn send<R>(self, client: &ApiClient, reader: R) -> Result<Payload<Item>, RequestError>
-    where
-        R: std::io::Read + Send + 'static
-    {
-        let content_type = self.content_type.unwrap_or(mime::APPLICATION_OCTET_STREAM);
-        let mut builder = client.put(format!("/api/fs/{}", self.uid))
-            .header("content-type", content_type.to_string());
+        for (key, tag) in &self.tags {
+            builder = builder.query(&[(key, tag)]);
+        }
 
-        if let Some(length) = self.content_length {
-            builder = builder.header("content-length", length);
+        if let Some(name) = &self.name {
+            builder = builder.query(&[("name", name)]);
         }
 
-        if let Some(basename) = self.basename {
-            builder = builder.header("x-basename", basename);
+        if let Some(storage) = &self.storage {
+            builder = builder.query(&[("storage", storage)]);
         }
 
-        if let Some(hash) = self.hash {
-            builder = builder.header("x-hash", hash);
+        if self.ignore_case {
+            builder = builder.query(&[("ignore_case", true)]);
+        }
+
+        if let Some(limit) = &self.limit {
+            builder = builder.query(&[("limit", limit)]);
+        }
+
+        if let Some(last_id) = &self.last_id {
+            builder = builder.query(&[("last_id", last_id)]);
+        } else if let Some(offset) = &self.offset {
+            builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.body(Body::new(reader)).send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -411,37 +509,672 @@ impl SendReadable {
     }
 }
 
-pub struct UpdateMetadata {
+impl iterate::Pageable for SearchItems {
+    type Id = ids::FSUid;
+    type Item = ItemMin;
+
+    #[inline]
+    fn get_last_id(item: &Self::Item) -> Option<Self::Id> {
+        Some(match item {
+            ItemMin::Root(root) => root.uid.clone(),
+            ItemMin::Directory(dir) => dir.uid.clone(),
+            ItemMin::File(file) => file.uid.clone(),
+        })
+    }
+
+    #[inline]
+    fn set_limit(&mut self, limit: Option<Limit>) {
+        self.limit(limit);
+    }
+
+    #[inline]
+    fn set_last_id(&mut self, id: Option<Self::Id>) {
+        self.last_id(id);
+    }
+
+    #[inline]
+    fn send(&self, client: &ApiClient) -> Result<Payload<Vec<Self::Item>>, RequestError> {
+        SearchItems::send(self, client)
+    }
+}
+
+pub struct RetrieveSyncPlan {
     uid: ids::FSUid,
-    body: UpdateMetadataBody
+    body: SyncManifest,
 }
 
-impl UpdateMetadata {
+impl RetrieveSyncPlan {
     pub fn uid(uid: ids::FSUid) -> Self {
-        UpdateMetadata {
+        RetrieveSyncPlan {
             uid,
-            body: UpdateMetadataBody {
-                tags: None,
-                comment: None
+            body: SyncManifest {
+                mode: SyncMode::default(),
+                entries: Vec::new(),
             }
         }
     }
 
-    pub fn comment<C>(&mut self, comment: C) -> &mut Self
-    where
-        C: Into<String>
-    {
-        self.body.comment = Some(comment.into());
+    pub fn mode(&mut self, mode: SyncMode) -> &mut Self {
+        self.body.mode = mode;
         self
     }
 
-    pub fn add_tag<T, V>(&mut self, tag: T, value: Option<V>) -> &mut Self
+    /// adds a file from the client. the path is relative to the directory
+    /// being synced and the hash is the hex encoded blake3 hash of the file
+    pub fn add_entry<P, H>(&mut self, path: P, hash: H, size: u64) -> &mut Self
     where
-        T: Into<String>,
-        V: Into<String>,
+        P: Into<String>,
+        H: Into<String>,
     {
-        if let Some(tags) = &mut self.body.tags {
-            tags.insert(tag.into(), value.map(|v| v.into()));
+        self.body.entries.push(ManifestEntry {
+            path: path.into(),
+            hash: hash.into(),
+            size,
+        });
+        self
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<SyncPlan>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<SyncPlan>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveSyncPlan {
+    type Output = Payload<SyncPlan>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        self.body.validate()?;
+
+        Ok(Request::post(format!("/api/fs/{}/sync-plan", self.uid))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct RetrieveStats {}
+
+impl RetrieveStats {
+    pub fn new() -> Self {
+        RetrieveStats {}
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Vec<UserStats>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<UserStats>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveStats {
+    type Output = Payload<Vec<UserStats>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get("/api/fs/stats"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+/// the request for downloading the contents of a file
+fn download_request(uid: &ids::FSUid) -> Request {
+    Request::get(format!("/api/fs/{}/download", uid))
+}
+
+/// checks the error returned when requesting the contents of a file. a
+/// file that was not found is not considered an error
+fn download_error(status: reqwest::StatusCode, err: ApiError) -> Result<(), RequestError> {
+    if status == reqwest::StatusCode::NOT_FOUND && *err.kind() == ApiErrorKind::FileNotFound {
+        Ok(())
+    } else {
+        Err(RequestError::Api(err))
+    }
+}
+
+/// reads the mime of the contents from the headers of a response
+fn data_mime(headers: &HeaderMap) -> Option<mime::Mime> {
+    headers.get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+}
+
+pub struct DownloadItem {
+    uid: ids::FSUid
+}
+
+impl DownloadItem {
+    pub fn uid(uid: ids::FSUid) -> Self {
+        DownloadItem { uid }
+    }
+
+    pub fn send(&self, client: &ApiClient) -> Result<blocking::Response, RequestError> {
+        let res = client.build(download_request(&self.uid))?.send()?;
+
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<reqwest::Response, RequestError> {
+        let res = client.build(download_request(&self.uid))?.send().await?;
+
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res),
+            _ => Err(RequestError::Api(res.json().await?))
+        }
+    }
+}
+
+pub struct RetrieveData {
+    uid: ids::FSUid
+}
+
+impl RetrieveData {
+    pub fn uid(uid: ids::FSUid) -> Self {
+        RetrieveData { uid }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Option<DataReader>, RequestError> {
+        let res = client.build(download_request(&self.uid))?.send()?;
+        let status = res.status();
+
+        if status == reqwest::StatusCode::OK {
+            return Ok(Some(DataReader::new(res)));
+        }
+
+        download_error(status, res.json()?)?;
+
+        Ok(None)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<AsyncDataReader>, RequestError> {
+        let res = client.build(download_request(&self.uid))?.send().await?;
+        let status = res.status();
+
+        if status == reqwest::StatusCode::OK {
+            return Ok(Some(AsyncDataReader::new(res)));
+        }
+
+        download_error(status, res.json().await?)?;
+
+        Ok(None)
+    }
+}
+
+/// streams the contents of a file from the server
+///
+/// the data is read directly from the response as it is received so the
+/// entire file will not be buffered in memory
+pub struct DataReader {
+    response: blocking::Response,
+    content_length: Option<u64>,
+    mime: Option<mime::Mime>,
+}
+
+impl DataReader {
+    fn new(response: blocking::Response) -> Self {
+        let content_length = response.content_length();
+        let mime = data_mime(response.headers());
+
+        DataReader {
+            response,
+            content_length,
+            mime,
+        }
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    pub fn mime(&self) -> Option<&mime::Mime> {
+        self.mime.as_ref()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    pub fn into_response(self) -> blocking::Response {
+        self.response
+    }
+}
+
+impl std::io::Read for DataReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        std::io::Read::read(&mut self.response, buf)
+    }
+}
+
+/// async version of [`DataReader`]
+///
+/// the contents are received in chunks as they arrive from the server
+#[cfg(feature = "async")]
+pub struct AsyncDataReader {
+    response: reqwest::Response,
+    content_length: Option<u64>,
+    mime: Option<mime::Mime>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncDataReader {
+    fn new(response: reqwest::Response) -> Self {
+        let content_length = response.content_length();
+        let mime = data_mime(response.headers());
+
+        AsyncDataReader {
+            response,
+            content_length,
+            mime,
+        }
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    pub fn mime(&self) -> Option<&mime::Mime> {
+        self.mime.as_ref()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    /// retrieves the next chunk of the contents. None is returned once
+    /// everything has been received
+    pub async fn chunk(&mut self) -> Result<Option<bytes::Bytes>, RequestError> {
+        Ok(self.response.chunk().await?)
+    }
+
+    pub fn into_response(self) -> reqwest::Response {
+        self.response
+    }
+}
+
+pub struct CreateDir {
+    parent: ids::FSUid,
+    recursive: bool,
+    body: CreateDirBody
+}
+
+impl CreateDir {
+    pub fn basename<B>(parent: ids::FSUid, basename: B) -> Self
+    where
+        B: Into<String>
+    {
+        CreateDir {
+            parent,
+            recursive: false,
+            body: CreateDirBody {
+                basename: basename.into(),
+                tags: None,
+                comment: None
+            }
+        }
+    }
+
+    /// treats the basename as a relative path and creates any missing
+    /// intermediate directories
+    pub fn recursive(&mut self, recursive: bool) -> &mut Self {
+        self.recursive = recursive;
+        self
+    }
+
+    pub fn comment<C>(&mut self, comment: C) -> &mut Self
+    where
+        C: Into<String>
+    {
+        self.body.comment = Some(comment.into());
+        self
+    }
+
+    pub fn add_tag<T, V>(&mut self, tag: T, value: Option<V>) -> &mut Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+    {
+        if let Some(tags) = &mut self.body.tags {
+            tags.insert(tag.into(), value.map(|v| v.into()));
+        } else {
+            self.body.tags = Some(Tags::from([(tag.into(), value.map(|v| v.into()))]));
+        }
+
+        self
+    }
+
+    pub fn add_iter_tags<I, T, V>(&mut self, iter: I) -> &mut Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+        I: IntoIterator<Item = (T, Option<V>)>
+    {
+        if let Some(tags) = &mut self.body.tags {
+            for (k, v) in iter {
+                tags.insert(k.into(), v.map(|v| v.into()));
+            }
+        } else {
+            let mut tags = Tags::new();
+
+            for (k, v) in iter {
+                tags.insert(k.into(), v.map(|v| v.into()));
+            }
+
+            self.body.tags = Some(tags);
+        }
+
+        self
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Item>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Item>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateDir {
+    type Output = Payload<Item>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::post(format!("/api/fs/{}", self.parent))
+            .json(&self.body);
+
+        if self.recursive {
+            builder = builder.query(&[("recursive", true)]);
+        }
+
+        Ok(builder)
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::CREATED => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct SendReadable {
+    uid: ids::FSUid,
+    basename: Option<String>,
+    content_type: Option<mime::Mime>,
+    content_length: Option<u64>,
+    hash: Option<String>,
+    on_conflict: Option<OnConflict>,
+    mime: Option<mime::Mime>,
+    allow_mime_change: bool,
+    mtime: Option<DateTime<Utc>>,
+}
+
+impl SendReadable {
+    pub fn create<B>(parent: ids::FSUid, basename: B) -> SendReadable
+    where
+        B: Into<String>,
+    {
+        SendReadable {
+            uid: parent,
+            basename: Some(basename.into()),
+            content_type: None,
+            content_length: None,
+            hash: None,
+            on_conflict: None,
+            mime: None,
+            allow_mime_change: false,
+            mtime: None,
+        }
+    }
+
+    pub fn update(uid: ids::FSUid) -> SendReadable {
+        SendReadable {
+            uid,
+            basename: None,
+            content_type: None,
+            content_length: None,
+            hash: None,
+            on_conflict: None,
+            mime: None,
+            allow_mime_change: false,
+            mtime: None,
+        }
+    }
+
+    pub fn content_type(&mut self, mime: mime::Mime) -> &mut Self {
+        self.content_type = Some(mime);
+        self
+    }
+
+    pub fn content_length(&mut self, length: u64) -> &mut Self {
+        self.content_length = Some(length);
+        self
+    }
+
+    pub fn hash<H,V>(&mut self, hash_type: H, hash_value: V) -> &mut Self
+    where
+        H: Into<String>,
+        V: Into<String>,
+    {
+        let hash_type = hash_type.into();
+        let hash_value = hash_value.into();
+
+        self.hash = Some(format!("{hash_type}:{hash_value}"));
+        self
+    }
+
+    /// computes the blake3 hash of the reader so that the server is able to
+    /// verify the contents it received. the reader is rewound to the start
+    /// once finished
+    pub fn hash_reader<R>(&mut self, reader: &mut R) -> std::io::Result<&mut Self>
+    where
+        R: std::io::Read + std::io::Seek
+    {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_reader(&mut *reader)?;
+
+        reader.rewind()?;
+
+        self.hash = Some(format!("blake3:{}", hasher.finalize()));
+        Ok(self)
+    }
+
+    /// only used when creating a new file
+    pub fn on_conflict(&mut self, on_conflict: OnConflict) -> &mut Self {
+        self.on_conflict = Some(on_conflict);
+        self
+    }
+
+    /// the mime of the file. takes precedence over the content type
+    pub fn mime(&mut self, mime: mime::Mime) -> &mut Self {
+        self.mime = Some(mime);
+        self
+    }
+
+    /// allows for the mime of an existing file to change when it is updated
+    pub fn allow_mime_change(&mut self, allow: bool) -> &mut Self {
+        self.allow_mime_change = allow;
+        self
+    }
+
+    /// the modification time of the file that the server will store
+    pub fn mtime(&mut self, mtime: DateTime<Utc>) -> &mut Self {
+        self.mtime = Some(mtime);
+        self
+    }
+
+    /// sets the modification time from the metadata of a local file
+    pub fn mtime_from_metadata(&mut self, metadata: &std::fs::Metadata) -> std::io::Result<&mut Self> {
+        self.mtime = Some(DateTime::from(metadata.modified()?));
+        Ok(self)
+    }
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+
+        if let Some(on_conflict) = &self.on_conflict {
+            let value = match on_conflict {
+                OnConflict::Error => "error",
+                OnConflict::Overwrite => "overwrite",
+                OnConflict::Rename => "rename",
+            };
+
+            query.push(("on_conflict", value.to_owned()));
+        }
+
+        if let Some(mime) = &self.mime {
+            query.push(("mime", mime.to_string()));
+        }
+
+        if self.allow_mime_change {
+            query.push(("allow_mime_change", "true".to_owned()));
+        }
+
+        query
+    }
+
+    fn headers(self) -> Vec<(&'static str, String)> {
+        let content_type = self.content_type.unwrap_or(mime::APPLICATION_OCTET_STREAM);
+        let mut headers = vec![("content-type", content_type.to_string())];
+
+        if let Some(length) = self.content_length {
+            headers.push(("content-length", length.to_string()));
+        }
+
+        if let Some(basename) = self.basename {
+            headers.push(("x-basename", basename));
+        }
+
+        if let Some(hash) = self.hash {
+            headers.push(("x-content-hash", hash));
+        }
+
+        if let Some(mtime) = self.mtime {
+            headers.push(("x-mtime", mtime.to_rfc3339()));
+        }
+
+        headers
+    }
+
+    /// the request without a body since each client provides its own
+    fn request(self) -> Request {
+        let mut builder = Request::put(format!("/api/fs/{}", self.uid))
+            .query(&self.query());
+
+        for (key, value) in self.headers() {
+            builder = builder.header(key, value);
+        }
+
+        builder
+    }
+
+    fn response(res: Response) -> Result<Payload<Item>, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+
+    pub fn send<R>(self, client: &ApiClient, reader: R) -> Result<Payload<Item>, RequestError>
+    where
+        R: std::io::Read + Send + 'static
+    {
+        let res = client.build(self.request())?
+            .body(blocking::Body::new(reader))
+            .send()?;
+
+        Self::response(Response::from_blocking(res)?)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async<B>(self, client: &nonblocking::ApiClient, body: B) -> Result<Payload<Item>, RequestError>
+    where
+        B: Into<reqwest::Body>
+    {
+        let res = client.build(self.request())?
+            .body(body)
+            .send().await?;
+
+        Self::response(Response::from_async(res).await?)
+    }
+}
+
+pub struct UpdateMetadata {
+    uid: ids::FSUid,
+    body: UpdateMetadataBody
+}
+
+impl UpdateMetadata {
+    pub fn uid(uid: ids::FSUid) -> Self {
+        UpdateMetadata {
+            uid,
+            body: UpdateMetadataBody {
+                tags: None,
+                remove_tags: None,
+                comment: None,
+                group_uid: None,
+                expected_updated: None,
+            }
+        }
+    }
+
+    /// only apply the update if the item has not been changed since the
+    /// given updated timestamp
+    pub fn expected_updated<U>(&mut self, updated: U) -> &mut Self
+    where
+        U: Into<Option<DateTime<Utc>>>
+    {
+        self.body.expected_updated = Some(updated.into());
+        self
+    }
+
+    /// sets the group that will share the item. None will remove the
+    /// current group
+    pub fn group<G>(&mut self, group_uid: G) -> &mut Self
+    where
+        G: Into<Option<ids::GroupUid>>
+    {
+        self.body.group_uid = Some(group_uid.into());
+        self
+    }
+
+    pub fn comment<C>(&mut self, comment: C) -> &mut Self
+    where
+        C: Into<String>
+    {
+        self.body.comment = Some(comment.into());
+        self
+    }
+
+    pub fn add_tag<T, V>(&mut self, tag: T, value: Option<V>) -> &mut Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+    {
+        if let Some(tags) = &mut self.body.tags {
+            tags.insert(tag.into(), value.map(|v| v.into()));
         } else {
             self.body.tags = Some(Tags::from([(tag.into(), value.map(|v| v.into()))]));
         }
@@ -472,11 +1205,111 @@ impl UpdateMetadata {
         self
     }
 
+    /// removes the tag from the item without needing to send the rest of
+    /// the tags
+    pub fn remove_tag<T>(&mut self, tag: T) -> &mut Self
+    where
+        T: Into<String>
+    {
+        self.body.remove_tags.get_or_insert_with(Vec::new)
+            .push(tag.into());
+        self
+    }
+
     pub fn send(self, client: &ApiClient) -> Result<Payload<Item>, RequestError> {
-        let res = client.patch(format!("/api/fs/{}", self.uid))
-            .json(&self.body)
-            .send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Item>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for UpdateMetadata {
+    type Output = Payload<Item>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::patch(format!("/api/fs/{}", self.uid))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct BatchUpdate {
+    body: BatchUpdateBody,
+}
+
+impl BatchUpdate {
+    pub fn uids<I>(uids: I) -> Self
+    where
+        I: IntoIterator<Item = ids::FSUid>
+    {
+        BatchUpdate {
+            body: BatchUpdateBody {
+                ids: uids.into_iter().collect(),
+                add_tags: None,
+                remove_tags: None,
+                comment: None,
+            }
+        }
+    }
+
+    /// sets the comment for all items. an empty comment will remove it
+    pub fn comment<C>(&mut self, comment: C) -> &mut Self
+    where
+        C: Into<String>
+    {
+        self.body.comment = Some(comment.into());
+        self
+    }
+
+    pub fn add_tag<T, V>(&mut self, tag: T, value: Option<V>) -> &mut Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+    {
+        self.body.add_tags.get_or_insert_with(Tags::new)
+            .insert(tag.into(), value.map(|v| v.into()));
+        self
+    }
+
+    pub fn remove_tag<T>(&mut self, tag: T) -> &mut Self
+    where
+        T: Into<String>
+    {
+        self.body.remove_tags.get_or_insert_with(Vec::new)
+            .push(tag.into());
+        self
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Vec<BatchResult>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<BatchResult>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for BatchUpdate {
+    type Output = Payload<Vec<BatchResult>>;
 
+    fn request(&self) -> Result<Request, RequestError> {
+        self.body.assert_ok()?;
+
+        Ok(Request::patch("/api/fs/batch")
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -485,20 +1318,187 @@ impl UpdateMetadata {
 }
 
 pub struct DeleteItem {
-    uid: ids::FSUid
+    uid: ids::FSUid,
+    purge: bool,
 }
 
 impl DeleteItem {
     pub fn uid(uid: ids::FSUid) -> Self {
-        DeleteItem { uid }
+        DeleteItem { uid, purge: false }
+    }
+
+    /// permanently removes the item instead of moving it to the trash
+    pub fn purge(&mut self, purge: bool) -> &mut Self {
+        self.purge = purge;
+        self
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete(format!("/api/fs/{}", self.uid)).send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DeleteItem {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::delete(format!("/api/fs/{}", self.uid));
+
+        if self.purge {
+            builder = builder.query(&[("purge", true)]);
+        }
 
+        Ok(builder)
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
         }
     }
 }
+
+pub struct RestoreItem {
+    uid: ids::FSUid,
+}
+
+impl RestoreItem {
+    pub fn uid(uid: ids::FSUid) -> Self {
+        RestoreItem { uid }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Item>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Item>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RestoreItem {
+    type Output = Payload<Item>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::post(format!("/api/fs/{}/restore", self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct RetrieveTrash {
+    limit: Option<Limit>,
+    offset: Option<Offset>,
+    last_id: Option<ids::FSUid>,
+}
+
+impl RetrieveTrash {
+    pub fn new() -> Self {
+        RetrieveTrash {
+            limit: None,
+            offset: None,
+            last_id: None,
+        }
+    }
+
+    pub fn limit<L>(&mut self, limit: L) -> &mut Self
+    where
+        L: Into<Option<Limit>>
+    {
+        self.limit = limit.into();
+        self
+    }
+
+    pub fn offset<O>(&mut self, offset: O) -> &mut Self
+    where
+        O: Into<Option<Offset>>
+    {
+        self.offset = offset.into();
+        self
+    }
+
+    pub fn last_id<I>(&mut self, last_id: I) -> &mut Self
+    where
+        I: Into<Option<ids::FSUid>>
+    {
+        self.last_id = last_id.into();
+        self
+    }
+
+    pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<Trashed>>, RequestError> {
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<Trashed>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for RetrieveTrash {
+    type Output = Payload<Vec<Trashed>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get("/api/fs/trash");
+
+        if let Some(limit) = &self.limit {
+            builder = builder.query(&[("limit", limit)]);
+        }
+
+        if let Some(last_id) = &self.last_id {
+            builder = builder.query(&[("last_id", last_id)]);
+        } else if let  Some(offset) = &self.offset {
+            builder = builder.query(&[("offset", offset)]);
+        }
+
+        Ok(builder)
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+impl iterate::Pageable for RetrieveTrash {
+    type Id = ids::FSUid;
+    type Item = Trashed;
+
+    #[inline]
+    fn get_last_id(item: &Self::Item) -> Option<Self::Id> {
+        Some(match &item.item {
+            ItemMin::Root(root) => root.uid.clone(),
+            ItemMin::Directory(dir) => dir.uid.clone(),
+            ItemMin::File(file) => file.uid.clone(),
+        })
+    }
+
+    #[inline]
+    fn set_limit(&mut self, limit: Option<Limit>) {
+        self.limit(limit);
+    }
+
+    #[inline]
+    fn set_last_id(&mut self, id: Option<Self::Id>) {
+        self.last_id(id);
+    }
+
+    #[inline]
+    fn send(&self, client: &ApiClient) -> Result<Payload<Vec<Self::Item>>, RequestError> {
+        self.send(client)
+    }
+}
@@ -3,7 +3,10 @@ use std::path::PathBuf;
 use rfs_lib::ids;
 
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::{ApiClient, iterate};
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::{
     Payload,
     ApiError,
@@ -15,11 +18,14 @@ use crate::{
 use crate::fs::{
     CreateStorage as CreateStorageBody,
     UpdateStorage as UpdateStorageBody,
+    RelocateStorage as RelocateStorageBody,
     Storage,
     StorageMin,
     backend,
 };
 
+use super::tag_param;
+
 pub struct QueryStorage {
     limit: Option<Limit>,
     offset: Option<Offset>,
@@ -60,7 +66,20 @@ impl QueryStorage {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<StorageMin>>, RequestError> {
-        let mut builder = client.get("/api/fs/storage");
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<StorageMin>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for QueryStorage {
+    type Output = Payload<Vec<StorageMin>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get("/api/fs/storage");
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -72,8 +91,10 @@ impl QueryStorage {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -106,6 +127,160 @@ impl iterate::Pageable for QueryStorage {
     }
 }
 
+pub struct SearchStorage {
+    tags: Vec<(&'static str, String)>,
+    ignore_case: bool,
+    limit: Option<Limit>,
+    offset: Option<Offset>,
+    last_id: Option<ids::StorageUid>,
+}
+
+impl SearchStorage {
+    pub fn new() -> Self {
+        SearchStorage {
+            tags: Vec::new(),
+            ignore_case: false,
+            limit: None,
+            offset: None,
+            last_id: None,
+        }
+    }
+
+    /// adds a tag that the results must have. if a value is given then the
+    /// tag must also have that value
+    pub fn tag<T, V>(&mut self, tag: T, value: Option<V>) -> &mut Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+    {
+        self.tags.push(("tag", tag_param(tag.into(), value.map(Into::into))));
+        self
+    }
+
+    /// adds a tag prefix that the results must have a tag starting with. if
+    /// a value is given then the tag must also have that value
+    pub fn tag_prefix<T, V>(&mut self, prefix: T, value: Option<V>) -> &mut Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+    {
+        self.tags.push(("tag_prefix", tag_param(prefix.into(), value.map(Into::into))));
+        self
+    }
+
+    /// compares the values of the tags without case
+    pub fn ignore_case(&mut self, ignore_case: bool) -> &mut Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    pub fn add_iter_tags<I, T, V>(&mut self, iter: I) -> &mut Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+        I: IntoIterator<Item = (T, Option<V>)>
+    {
+        for (tag, value) in iter {
+            self.tag(tag, value);
+        }
+
+        self
+    }
+
+    pub fn limit<L>(&mut self, limit: L) -> &mut Self
+    where
+        L: Into<Option<Limit>>
+    {
+        self.limit = limit.into();
+        self
+    }
+
+    pub fn offset<O>(&mut self, offset: O) -> &mut Self
+    where
+        O: Into<Option<Offset>>
+    {
+        self.offset = offset.into();
+        self
+    }
+
+    pub fn last_id<I>(&mut self, last_id: I) -> &mut Self
+    where
+        I: Into<Option<ids::StorageUid>>
+    {
+        self.last_id = last_id.into();
+        self
+    }
+
+    pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<StorageMin>>, RequestError> {
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<StorageMin>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for SearchStorage {
+    type Output = Payload<Vec<StorageMin>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get("/api/fs/storage/search");
+
+        for (key, tag) in &self.tags {
+            builder = builder.query(&[(key, tag)]);
+        }
+
+        if self.ignore_case {
+            builder = builder.query(&[("ignore_case", true)]);
+        }
+
+        if let Some(limit) = &self.limit {
+            builder = builder.query(&[("limit", limit)]);
+        }
+
+        if let Some(last_id) = &self.last_id {
+            builder = builder.query(&[("last_id", last_id)]);
+        } else if let Some(offset) = &self.offset {
+            builder = builder.query(&[("offset", offset)]);
+        }
+
+        Ok(builder)
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+impl iterate::Pageable for SearchStorage {
+    type Id = ids::StorageUid;
+    type Item = StorageMin;
+
+    #[inline]
+    fn get_last_id(item: &Self::Item) -> Option<Self::Id> {
+        Some(item.uid.clone())
+    }
+
+    #[inline]
+    fn set_limit(&mut self, limit: Option<Limit>) {
+        self.limit(limit);
+    }
+
+    #[inline]
+    fn set_last_id(&mut self, id: Option<Self::Id>) {
+        self.last_id(id);
+    }
+
+    #[inline]
+    fn send(&self, client: &ApiClient) -> Result<Payload<Vec<Self::Item>>, RequestError> {
+        SearchStorage::send(self, client)
+    }
+}
+
 pub struct RetrieveStorage {
     uid: ids::StorageUid
 }
@@ -116,8 +291,23 @@ impl RetrieveStorage {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<Storage>>, RequestError> {
-        let res = client.get(format!("/api/fs/storage/{}", self.uid)).send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<Storage>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveStorage {
+    type Output = Option<Payload<Storage>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get(format!("/api/fs/storage/{}", self.uid)))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             reqwest::StatusCode::NOT_FOUND => {
@@ -148,13 +338,52 @@ impl CreateStorage {
             body: CreateStorageBody {
                 name: name.into(),
                 backend: backend::CreateConfig::Local {
-                    path: path.into()
+                    path: path.into(),
+                    dedup: false,
+                    tmp: None,
+                    compression: false,
                 },
                 tags: Tags::new()
             }
         }
     }
 
+    pub fn dedup(&mut self, enable: bool) -> &mut Self {
+        match &mut self.body.backend {
+            backend::CreateConfig::Local { dedup, .. } => {
+                *dedup = enable;
+            }
+        }
+
+        self
+    }
+
+    /// compresses file contents before they are written to disk
+    pub fn compression(&mut self, enable: bool) -> &mut Self {
+        match &mut self.body.backend {
+            backend::CreateConfig::Local { compression, .. } => {
+                *compression = enable;
+            }
+        }
+
+        self
+    }
+
+    /// directory that uploads are written to before they are moved into
+    /// place. should be on the same filesystem as the storage path
+    pub fn tmp<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<PathBuf>
+    {
+        match &mut self.body.backend {
+            backend::CreateConfig::Local { tmp, .. } => {
+                *tmp = Some(path.into());
+            }
+        }
+
+        self
+    }
+
     pub fn comment<C>(&mut self, _comment: C) -> &mut Self
     where
         C: Into<String>
@@ -185,10 +414,24 @@ impl CreateStorage {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Storage>, RequestError> {
-        let res = client.post("/api/fs/storage")
-            .json(&self.body)
-            .send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Storage>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateStorage {
+    type Output = Payload<Storage>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::post("/api/fs/storage")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::CREATED => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -208,7 +451,8 @@ impl UpdateStorage {
             body: UpdateStorageBody {
                 name: None,
                 backend: None,
-                tags: None
+                tags: None,
+                remove_tags: None,
             }
         }
     }
@@ -221,6 +465,34 @@ impl UpdateStorage {
         self
     }
 
+    fn local_backend(&mut self) -> &mut backend::UpdateConfig {
+        self.body.backend.get_or_insert(backend::UpdateConfig::Local {
+            dedup: None,
+            compression: None,
+        })
+    }
+
+    pub fn dedup(&mut self, enable: bool) -> &mut Self {
+        match self.local_backend() {
+            backend::UpdateConfig::Local { dedup, .. } => {
+                *dedup = Some(enable);
+            }
+        }
+
+        self
+    }
+
+    /// only applies to file contents written after the update
+    pub fn compression(&mut self, enable: bool) -> &mut Self {
+        match self.local_backend() {
+            backend::UpdateConfig::Local { compression, .. } => {
+                *compression = Some(enable);
+            }
+        }
+
+        self
+    }
+
     pub fn add_tag<T, V>(&mut self, tag: T, value: Option<V>) -> &mut Self
     where
         T: Into<String>,
@@ -258,11 +530,88 @@ impl UpdateStorage {
         self
     }
 
+    /// removes the tag from the storage medium without needing to send the
+    /// rest of the tags
+    pub fn remove_tag<T>(&mut self, tag: T) -> &mut Self
+    where
+        T: Into<String>
+    {
+        self.body.remove_tags.get_or_insert_with(Vec::new)
+            .push(tag.into());
+        self
+    }
+
     pub fn send(self, client: &ApiClient) -> Result<Payload<Storage>, RequestError> {
-        let res = client.patch(format!("/api/fs/storage/{}", self.uid))
-            .json(&self.body)
-            .send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Storage>, RequestError> {
+        client.send(&self).await
+    }
+}
 
+impl Endpoint for UpdateStorage {
+    type Output = Payload<Storage>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::patch(format!("/api/fs/storage/{}", self.uid))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct RelocateStorage {
+    uid: ids::StorageUid,
+    body: RelocateStorageBody,
+}
+
+impl RelocateStorage {
+    pub fn local<P>(uid: ids::StorageUid, path: P) -> Self
+    where
+        P: Into<PathBuf>
+    {
+        RelocateStorage {
+            uid,
+            body: RelocateStorageBody {
+                path: path.into(),
+                verify: false,
+            }
+        }
+    }
+
+    /// checks that a sample of files exist under the new path before the
+    /// storage medium is updated
+    pub fn verify(&mut self, verify: bool) -> &mut Self {
+        self.body.verify = verify;
+        self
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Storage>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Storage>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RelocateStorage {
+    type Output = Payload<Storage>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::post(format!("/api/fs/storage/{}/relocate", self.uid))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -280,8 +629,23 @@ impl DeleteStorage {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete(format!("/api/fs/storage/{}", self.uid)).send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DeleteStorage {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete(format!("/api/fs/storage/{}", self.uid)))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
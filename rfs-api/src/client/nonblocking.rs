@@ -0,0 +1,193 @@
+use std::default::Default;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use reqwest::{Url, RequestBuilder};
+use reqwest_cookie_store::{CookieStore, CookieStoreRwLock};
+
+use crate::client::error::{ApiClientError, RequestError};
+use crate::client::request::{Endpoint, Request, Response};
+use crate::client::{
+    Info,
+    DEFAULT_USER_AGENT,
+    default_headers,
+    read_cookie_store,
+    missing_cookie_store,
+    write_cookie_store,
+};
+
+/// async version of [`crate::client::ApiClient`].
+///
+/// the request builders provide a `send_async` method that accepts this
+/// client in place of the blocking one. both clients send the same request
+/// description created by the builder.
+pub struct ApiClient {
+    pub(crate) cookie_file: Option<Box<Path>>,
+    pub(crate) store: Arc<CookieStoreRwLock>,
+    pub(crate) client: reqwest::Client,
+    pub(crate) info: Info
+}
+
+impl ApiClient {
+    pub fn builder() -> ApiClientBuilder {
+        ApiClientBuilder {
+            url: Url::parse("https://localhost/").unwrap(),
+            file: None,
+            exists: false,
+            agent: None,
+            token: None,
+        }
+    }
+
+    pub(crate) fn get<U>(&self, path: U) -> RequestBuilder
+    where
+        U: AsRef<str>,
+    {
+        let url = self.info.url.join(path.as_ref()).unwrap();
+
+        self.client.get(url)
+    }
+
+    /// creates a request builder from the description of a request
+    pub(crate) fn build(&self, request: Request) -> Result<RequestBuilder, RequestError> {
+        let parts = request.into_parts(&self.info.url)?;
+        let mut builder = self.client.request(parts.method, parts.url);
+
+        for (key, value) in parts.headers {
+            builder = builder.header(key, value);
+        }
+
+        if let Some(body) = parts.body {
+            builder = builder.body(body);
+        }
+
+        Ok(builder)
+    }
+
+    /// sends the request of an endpoint and parses the response
+    pub(crate) async fn send<E>(&self, endpoint: &E) -> Result<E::Output, RequestError>
+    where
+        E: Endpoint
+    {
+        let res = self.build(endpoint.request()?)?.send().await?;
+
+        E::response(Response::from_async(res).await?)
+    }
+
+    pub async fn save_session(&self) -> Result<bool, ApiClientError> {
+        let Some(cookie_file) = &self.cookie_file else {
+            return Ok(false);
+        };
+
+        // the store lock is only held while serializing so it will not be
+        // held across the await when writing to the file
+        let mut buffer = Vec::new();
+
+        write_cookie_store(&self.store, &mut buffer)?;
+
+        tokio::fs::write(cookie_file, buffer)
+            .await
+            .map_err(|e| ApiClientError::StdIo(e))?;
+
+        Ok(true)
+    }
+
+    pub fn info(&self) -> &Info {
+        &self.info
+    }
+
+    pub async fn ping(&self) -> Result<bool, RequestError> {
+        let res = self.get("/ping").send().await?;
+
+        match res.status() {
+            reqwest::StatusCode::OK => {
+                let body = res.text().await?;
+
+                Ok(body.as_str() == "pong")
+            }
+            _ => Err(RequestError::Api(res.json().await?))
+        }
+    }
+}
+
+pub struct ApiClientBuilder {
+    url: Url,
+    file: Option<PathBuf>,
+    exists: bool,
+    agent: Option<String>,
+    token: Option<String>,
+}
+
+impl ApiClientBuilder {
+    pub fn secure(&mut self, is_secure: bool) {
+        if is_secure {
+            self.url.set_scheme("https").unwrap();
+        } else {
+            self.url.set_scheme("http").unwrap();
+        }
+    }
+
+    pub fn host<H>(&mut self, host: H) -> bool
+    where
+        H: AsRef<str>
+    {
+        self.url.set_host(Some(host.as_ref())).is_ok()
+    }
+
+    pub fn port(&mut self, port: Option<u16>) {
+        self.url.set_port(port).unwrap()
+    }
+
+    pub fn cookie_file(&mut self, path: PathBuf) {
+        self.file = Some(path);
+    }
+
+    pub fn cookie_file_exits(&mut self, exists: bool) {
+        self.exists = exists;
+    }
+
+    pub fn user_agent<U>(&mut self, user_agent: U)
+    where
+        U: Into<String>
+    {
+        self.agent = Some(user_agent.into());
+    }
+
+    /// sets a bot token that will be sent with every request. the server
+    /// will use the token over any session cookies that are present
+    pub fn bearer_token<T>(&mut self, token: T)
+    where
+        T: Into<String>
+    {
+        self.token = Some(token.into());
+    }
+
+    pub async fn build(self) -> Result<ApiClient, ApiClientError> {
+        let user_agent = self.agent.unwrap_or(DEFAULT_USER_AGENT.into());
+        let store = if let Some(path) = &self.file {
+            match tokio::fs::read(&path).await {
+                Ok(contents) => read_cookie_store(contents.as_slice())?,
+                Err(err) => missing_cookie_store(err, self.exists)?
+            }
+        } else {
+            CookieStore::default()
+        };
+
+        let store = Arc::new(CookieStoreRwLock::new(store));
+        let client = reqwest::Client::builder()
+            .cookie_provider(store.clone())
+            .default_headers(default_headers(self.token.as_deref())?)
+            .user_agent(user_agent)
+            .build()
+            .map_err(|e| ApiClientError::Reqwest(e))?;
+
+        Ok(ApiClient {
+            cookie_file: self.file.map(|v| v.into_boxed_path()),
+            store,
+            client,
+            info: Info {
+                url: self.url
+            }
+        })
+    }
+}
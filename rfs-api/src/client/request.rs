@@ -0,0 +1,186 @@
+use bytes::Bytes;
+use reqwest::{Method, StatusCode, Url};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::client::error::RequestError;
+
+/// describes a request to the server independent of the client that will
+/// send it. the blocking and async clients both build their requests from
+/// this so the method, path, query and body are only specified once
+pub(crate) struct Request {
+    method: Method,
+    path: String,
+    query: Vec<String>,
+    headers: Vec<(&'static str, String)>,
+    json: Option<Vec<u8>>,
+    error: Option<RequestError>,
+}
+
+impl Request {
+    fn new<P>(method: Method, path: P) -> Self
+    where
+        P: Into<String>
+    {
+        Request {
+            method,
+            path: path.into(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            json: None,
+            error: None,
+        }
+    }
+
+    pub(crate) fn get<P>(path: P) -> Self
+    where
+        P: Into<String>
+    {
+        Self::new(Method::GET, path)
+    }
+
+    pub(crate) fn post<P>(path: P) -> Self
+    where
+        P: Into<String>
+    {
+        Self::new(Method::POST, path)
+    }
+
+    pub(crate) fn put<P>(path: P) -> Self
+    where
+        P: Into<String>
+    {
+        Self::new(Method::PUT, path)
+    }
+
+    pub(crate) fn patch<P>(path: P) -> Self
+    where
+        P: Into<String>
+    {
+        Self::new(Method::PATCH, path)
+    }
+
+    pub(crate) fn delete<P>(path: P) -> Self
+    where
+        P: Into<String>
+    {
+        Self::new(Method::DELETE, path)
+    }
+
+    /// appends the url encoded query. any errors are returned when the
+    /// request is sent
+    pub(crate) fn query<T>(mut self, query: &T) -> Self
+    where
+        T: Serialize + ?Sized
+    {
+        if self.error.is_none() {
+            match serde_urlencoded::to_string(query) {
+                Ok(encoded) => if !encoded.is_empty() {
+                    self.query.push(encoded);
+                },
+                Err(err) => self.error = Some(RequestError::Query(err)),
+            }
+        }
+
+        self
+    }
+
+    pub(crate) fn header(mut self, key: &'static str, value: String) -> Self {
+        self.headers.push((key, value));
+        self
+    }
+
+    /// serializes the json body of the request. any errors are returned when
+    /// the request is sent
+    pub(crate) fn json<T>(mut self, json: &T) -> Self
+    where
+        T: Serialize + ?Sized
+    {
+        if self.error.is_none() {
+            match serde_json::to_vec(json) {
+                Ok(bytes) => self.json = Some(bytes),
+                Err(err) => self.error = Some(RequestError::Json(err)),
+            }
+        }
+
+        self
+    }
+
+    /// splits the request into the parts needed by a client. the path and
+    /// query are joined to the given base url
+    pub(crate) fn into_parts(self, base: &Url) -> Result<Parts, RequestError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let mut url = base.join(&self.path).unwrap();
+
+        if !self.query.is_empty() {
+            url.set_query(Some(&self.query.join("&")));
+        }
+
+        let mut headers = self.headers;
+
+        if self.json.is_some() {
+            headers.push(("content-type", mime::APPLICATION_JSON.to_string()));
+        }
+
+        Ok(Parts {
+            method: self.method,
+            url,
+            headers,
+            body: self.json,
+        })
+    }
+}
+
+pub(crate) struct Parts {
+    pub(crate) method: Method,
+    pub(crate) url: Url,
+    pub(crate) headers: Vec<(&'static str, String)>,
+    pub(crate) body: Option<Vec<u8>>,
+}
+
+/// a response from the server that has been fully received
+pub(crate) struct Response {
+    status: StatusCode,
+    body: Bytes,
+}
+
+impl Response {
+    pub(crate) fn from_blocking(res: reqwest::blocking::Response) -> Result<Self, RequestError> {
+        let status = res.status();
+        let body = res.bytes()?;
+
+        Ok(Response { status, body })
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn from_async(res: reqwest::Response) -> Result<Self, RequestError> {
+        let status = res.status();
+        let body = res.bytes().await?;
+
+        Ok(Response { status, body })
+    }
+
+    pub(crate) fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub(crate) fn json<T>(&self) -> Result<T, serde_json::Error>
+    where
+        T: DeserializeOwned
+    {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+/// a request that can be sent by either client with a response that is
+/// parsed the same regardless of the client used
+pub(crate) trait Endpoint {
+    type Output;
+
+    fn request(&self) -> Result<Request, RequestError>;
+
+    fn response(res: Response) -> Result<Self::Output, RequestError>;
+}
@@ -0,0 +1,209 @@
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::Method;
+use reqwest::StatusCode;
+use reqwest::blocking::{Body, Response};
+
+/// controls how requests are retried when they fail from transient errors
+///
+/// only idempotent requests (`GET`, `PUT`, `DELETE`, ...) are retried unless
+/// specified otherwise. requests with bodies that cannot be cloned, like
+/// streamed uploads, will only ever be sent once.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: Duration,
+    non_idempotent: bool,
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+            jitter: Duration::from_millis(100),
+            non_idempotent: false,
+        }
+    }
+
+    /// a policy that will never retry a request
+    pub fn none() -> Self {
+        let mut rtn = Self::new();
+        rtn.max_attempts(1);
+        rtn
+    }
+
+    /// total number of times a request will be sent including the first
+    pub fn max_attempts(&mut self, max_attempts: u32) -> &mut Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// the starting delay between attempts. doubles after every attempt
+    pub fn base_delay(&mut self, base_delay: Duration) -> &mut Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// the largest amount of time to wait between attempts
+    pub fn max_delay(&mut self, max_delay: Duration) -> &mut Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// the upper bound of a random amount of time added to each delay
+    pub fn jitter(&mut self, jitter: Duration) -> &mut Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// allows for retrying non idempotent requests like `POST` and `PATCH`
+    pub fn non_idempotent(&mut self, allow: bool) -> &mut Self {
+        self.non_idempotent = allow;
+        self
+    }
+
+    fn allowed(&self, method: &Method) -> bool {
+        if self.max_attempts <= 1 {
+            return false;
+        }
+
+        if self.non_idempotent {
+            return true;
+        }
+
+        match *method {
+            Method::GET |
+            Method::HEAD |
+            Method::PUT |
+            Method::DELETE |
+            Method::OPTIONS => true,
+            _ => false,
+        }
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(2u32.saturating_pow(attempt - 1));
+        let jitter_ms = self.jitter.as_millis() as u64;
+
+        let jitter = if jitter_ms > 0 {
+            Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_ms))
+        } else {
+            Duration::ZERO
+        };
+
+        exp.min(self.max_delay) + jitter
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new()
+    }
+}
+
+fn retry_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// only supports the delay-seconds format of the header
+fn retry_after(res: &Response) -> Option<Duration> {
+    let value = res.headers().get("retry-after")?;
+    let secs = value.to_str().ok()?.trim().parse::<u64>().ok()?;
+
+    Some(Duration::from_secs(secs))
+}
+
+fn send_logged(
+    method: &Method,
+    builder: reqwest::blocking::RequestBuilder
+) -> Result<Response, reqwest::Error> {
+    let result = builder.send();
+
+    match &result {
+        Ok(res) => tracing::debug!("{method} {} {}", res.url(), res.status()),
+        Err(err) => tracing::debug!("{method} request failed. {err}"),
+    }
+
+    result
+}
+
+/// wrapper around the reqwest blocking request builder that will apply the
+/// retry policy of the client when sent
+pub(crate) struct RequestBuilder {
+    inner: reqwest::blocking::RequestBuilder,
+    method: Method,
+    policy: RetryPolicy,
+}
+
+impl RequestBuilder {
+    pub(crate) fn new(
+        inner: reqwest::blocking::RequestBuilder,
+        method: Method,
+        policy: RetryPolicy
+    ) -> Self {
+        RequestBuilder { inner, method, policy }
+    }
+
+    pub(crate) fn header(self, key: &'static str, value: String) -> Self {
+        RequestBuilder {
+            inner: self.inner.header(key, value),
+            ..self
+        }
+    }
+
+    pub(crate) fn body<B>(self, body: B) -> Self
+    where
+        B: Into<Body>
+    {
+        RequestBuilder {
+            inner: self.inner.body(body),
+            ..self
+        }
+    }
+
+    pub(crate) fn send(self) -> Result<Response, reqwest::Error> {
+        if !self.policy.allowed(&self.method) {
+            return send_logged(&self.method, self.inner);
+        }
+
+        let mut attempt = 1;
+        let mut current = self.inner;
+
+        loop {
+            let Some(next) = current.try_clone() else {
+                return send_logged(&self.method, current);
+            };
+
+            let delay = match send_logged(&self.method, current) {
+                Ok(res) => {
+                    if attempt >= self.policy.max_attempts || !retry_status(res.status()) {
+                        return Ok(res);
+                    }
+
+                    retry_after(&res)
+                        .map(|d| d.min(self.policy.max_delay))
+                        .unwrap_or_else(|| self.policy.delay(attempt))
+                }
+                Err(err) => {
+                    if attempt >= self.policy.max_attempts || !(err.is_connect() || err.is_timeout()) {
+                        return Err(err);
+                    }
+
+                    self.policy.delay(attempt)
+                }
+            };
+
+            tracing::debug!("retrying request. attempt: {attempt} delay: {}ms", delay.as_millis());
+
+            std::thread::sleep(delay);
+
+            attempt += 1;
+            current = next;
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod roles;
 pub mod secrets;
+pub mod check;
@@ -0,0 +1,72 @@
+use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
+use crate::client::ApiClient;
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
+use crate::{Payload, Validator};
+use crate::sec::roles::Permission;
+use crate::sec::check::{
+    CheckPermissions as CheckPermissionsBody,
+    PermissionCheck,
+};
+
+pub struct CheckPermissions {
+    body: CheckPermissionsBody
+}
+
+impl CheckPermissions {
+    pub fn new() -> Self {
+        CheckPermissions {
+            body: CheckPermissionsBody {
+                permissions: Vec::new(),
+            }
+        }
+    }
+
+    pub fn add_permission<P>(&mut self, permission: P) -> &mut Self
+    where
+        P: Into<Permission>
+    {
+        self.body.permissions.push(permission.into());
+        self
+    }
+
+    pub fn add_iter_permission<I, P>(&mut self, iter: I) -> &mut Self
+    where
+        P: Into<Permission>,
+        I: IntoIterator<Item = P>
+    {
+        for item in iter {
+            self.body.permissions.push(item.into());
+        }
+
+        self
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Vec<PermissionCheck>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<PermissionCheck>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CheckPermissions {
+    type Output = Payload<Vec<PermissionCheck>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        self.body.assert_ok()?;
+
+        Ok(Request::post("/api/sec/check")
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
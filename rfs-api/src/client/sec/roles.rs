@@ -1,7 +1,10 @@
 use rfs_lib::ids;
 
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::{ApiClient, iterate};
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::{
     Payload,
     Validator,
@@ -64,7 +67,20 @@ impl QueryRoles {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<RoleListItem>>, RequestError> {
-        let mut builder = client.get("/api/sec/roles");
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<RoleListItem>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for QueryRoles {
+    type Output = Payload<Vec<RoleListItem>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get("/api/sec/roles");
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -76,8 +92,10 @@ impl QueryRoles {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?)),
@@ -120,8 +138,23 @@ impl RetrieveRole {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<Role>>, RequestError> {
-        let res = client.get(format!("/api/sec/roles/{}", self.uid)).send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<Role>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveRole {
+    type Output = Option<Payload<Role>>;
 
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get(format!("/api/sec/roles/{}", self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(Some(res.json()?)),
             reqwest::StatusCode::NOT_FOUND => {
@@ -180,7 +213,20 @@ impl QueryRoleUsers {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<RoleUser>>, RequestError> {
-        let mut builder = client.get(format!("/api/sec/roles/{}/users", self.uid));
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<RoleUser>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for QueryRoleUsers {
+    type Output = Payload<Vec<RoleUser>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get(format!("/api/sec/roles/{}/users", self.uid));
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -192,8 +238,10 @@ impl QueryRoleUsers {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -268,7 +316,20 @@ impl QueryRoleGroups {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<RoleGroup>>, RequestError> {
-        let mut builder = client.get(format!("/api/sec/roles/{}/groups", self.uid));
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<RoleGroup>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for QueryRoleGroups {
+    type Output = Payload<Vec<RoleGroup>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get(format!("/api/sec/roles/{}/groups", self.uid));
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -280,8 +341,10 @@ impl QueryRoleGroups {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -352,12 +415,26 @@ impl CreateRole {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Role>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Role>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateRole {
+    type Output = Payload<Role>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.post("/api/sec/roles")
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post("/api/sec/roles")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::CREATED => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -419,12 +496,26 @@ impl UpdateRole {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Role>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Role>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for UpdateRole {
+    type Output = Payload<Role>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.patch(format!("/api/sec/roles/{}", self.uid))
-            .json(&self.body)
-            .send()?;
+        Ok(Request::patch(format!("/api/sec/roles/{}", self.uid))
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -442,8 +533,23 @@ impl DeleteRole {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete(format!("/api/sec/roles/{}", self.uid)).send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DeleteRole {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete(format!("/api/sec/roles/{}", self.uid)))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -483,12 +589,26 @@ impl AddRoleUsers {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for AddRoleUsers {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.post(format!("/api/sec/roles/{}/users", self.uid))
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post(format!("/api/sec/roles/{}/users", self.uid))
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -528,12 +648,26 @@ impl DropRoleUsers {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DropRoleUsers {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.delete(format!("/api/sec/roles/{}/users", self.uid))
-            .json(&self.body)
-            .send()?;
+        Ok(Request::delete(format!("/api/sec/roles/{}/users", self.uid))
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -573,12 +707,26 @@ impl AddRoleGroups {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for AddRoleGroups {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.post(format!("/api/sec/roles/{}/groups", self.uid))
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post(format!("/api/sec/roles/{}/groups", self.uid))
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -618,12 +766,26 @@ impl DropRoleGroups {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DropRoleGroups {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.delete(format!("/api/sec/roles/{}/groups", self.uid))
-            .json(&self.body)
-            .send()?;
+        Ok(Request::delete(format!("/api/sec/roles/{}/groups", self.uid))
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
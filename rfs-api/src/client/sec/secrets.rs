@@ -1,5 +1,8 @@
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::ApiClient;
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::{Payload, ApiError, ApiErrorKind};
 use crate::sec::secrets::{
     PasswordListItem,
@@ -15,8 +18,23 @@ impl QueryPasswordSecrets {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Vec<PasswordListItem>>, RequestError> {
-        let res = client.get("/api/sec/secrets/password").send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<PasswordListItem>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for QueryPasswordSecrets {
+    type Output = Payload<Vec<PasswordListItem>>;
 
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get("/api/sec/secrets/password"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -34,8 +52,23 @@ impl RetrievePasswordSecret {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<PasswordVersion>>, RequestError> {
-        let res = client.get(format!("/api/sec/secrets/password/{}", self.version)).send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<PasswordVersion>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrievePasswordSecret {
+    type Output = Option<Payload<PasswordVersion>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get(format!("/api/sec/secrets/password/{}", self.version)))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(Some(res.json()?)),
             reqwest::StatusCode::NOT_FOUND => {
@@ -60,8 +93,23 @@ impl QuerySessionSecrets {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Vec<SessionListItem>>, RequestError> {
-        let res = client.get("/api/sec/secrets/session").send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<SessionListItem>>, RequestError> {
+        client.send(&self).await
+    }
+}
 
+impl Endpoint for QuerySessionSecrets {
+    type Output = Payload<Vec<SessionListItem>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get("/api/sec/secrets/session"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -77,8 +125,23 @@ impl CreatePasswordSecret {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.post("/api/sec/secrets/password").send()?;
+        client.send(&self)
+    }
 
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreatePasswordSecret {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::post("/api/sec/secrets/password"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -94,8 +157,23 @@ impl CreateSessionSecret {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.post("/api/sec/secrets/session").send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateSessionSecret {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::post("/api/sec/secrets/session"))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -113,9 +191,23 @@ impl DeletePasswordSecret {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete(format!("/api/sec/secrets/password/{}", self.version))
-            .send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DeletePasswordSecret {
+    type Output = ();
 
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete(format!("/api/sec/secrets/password/{}", self.version)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -133,10 +225,61 @@ impl DeleteSessionSecret {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete("/api/sec/secrets/session")
-            .query(&[("amount", self.amount)])
-            .send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DeleteSessionSecret {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete("/api/sec/secrets/session")
+            .query(&[("amount", self.amount)]))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct RetireSessionSecret {
+    id: String
+}
+
+impl RetireSessionSecret {
+    pub fn id<I>(id: I) -> Self
+    where
+        I: Into<String>
+    {
+        RetireSessionSecret { id: id.into() }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetireSessionSecret {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete(format!("/api/sec/secrets/session/{}", self.id)))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -1,7 +1,10 @@
 use rfs_lib::ids;
 
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::{ApiClient, iterate};
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::{
     Validator,
     Payload,
@@ -10,13 +13,17 @@ use crate::{
     Limit,
     Offset,
 };
+use crate::fs::UserUsage;
 use crate::users::{
     CreateUser as CreateUserBody,
     UpdateUser as UpdateUserBody,
+    UpdateMe as UpdateMeBody,
     User,
     ListItem,
 };
 
+pub mod bots;
+pub mod email;
 pub mod groups;
 pub mod password;
 pub mod totp;
@@ -61,7 +68,20 @@ impl QueryUsers {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<ListItem>>, RequestError> {
-        let mut builder = client.get("/api/user");
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<ListItem>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for QueryUsers {
+    type Output = Payload<Vec<ListItem>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get("/api/user");
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -73,8 +93,10 @@ impl QueryUsers {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -117,8 +139,23 @@ impl RetrieveUser {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<User>>, RequestError> {
-        let res = client.get(format!("/api/user/{}", self.uid)).send()?;
+        client.send(&self)
+    }
 
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<User>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveUser {
+    type Output = Option<Payload<User>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get(format!("/api/user/{}", self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(Some(res.json()?)),
             reqwest::StatusCode::NOT_FOUND => {
@@ -135,6 +172,40 @@ impl RetrieveUser {
     }
 }
 
+pub struct RetrieveUserUsage {
+    uid: ids::UserUid
+}
+
+impl RetrieveUserUsage {
+    pub fn uid(uid: ids::UserUid) -> Self {
+        RetrieveUserUsage { uid }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<UserUsage>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<UserUsage>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveUserUsage {
+    type Output = Payload<UserUsage>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get(format!("/api/user/{}/usage", self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
 pub struct CreateUser {
     body: CreateUserBody
 }
@@ -163,12 +234,26 @@ impl CreateUser {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<User>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<User>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateUser {
+    type Output = Payload<User>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.post("/api/user")
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post("/api/user")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::CREATED => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -209,12 +294,119 @@ impl UpdateUser {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<User>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<User>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for UpdateUser {
+    type Output = Payload<User>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        self.body.assert_ok()?;
+
+        Ok(Request::patch(format!("/api/user/{}", self.uid))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct RetrieveMe {}
+
+impl RetrieveMe {
+    pub fn new() -> Self {
+        RetrieveMe {}
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<User>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<User>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveMe {
+    type Output = Payload<User>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get("/api/user/me"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct UpdateMe {
+    body: UpdateMeBody
+}
+
+impl UpdateMe {
+    pub fn new() -> Self {
+        UpdateMe {
+            body: UpdateMeBody::default()
+        }
+    }
+
+    pub fn display_name<D>(&mut self, display_name: Option<D>) -> &mut Self
+    where
+        D: Into<String>
+    {
+        self.body.display_name = Some(display_name.map(|v| v.into()));
+        self
+    }
+
+    /// the email will be unverified until the token sent out is verified
+    pub fn email<E>(&mut self, email: E) -> &mut Self
+    where
+        E: Into<String>
+    {
+        self.body.email = Some(email.into());
+        self
+    }
+
+    pub fn preferences(&mut self, preferences: serde_json::Value) -> &mut Self {
+        self.body.preferences = Some(preferences);
+        self
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<User>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<User>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for UpdateMe {
+    type Output = Payload<User>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.patch(format!("/api/user/{}", self.uid))
-            .json(&self.body)
-            .send()?;
+        Ok(Request::patch("/api/user/me")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -224,16 +416,45 @@ impl UpdateUser {
 
 pub struct DeleteUser {
     uid: ids::UserUid,
+    purge: bool,
 }
 
 impl DeleteUser {
     pub fn uid(uid: ids::UserUid) -> Self {
-        DeleteUser { uid }
+        DeleteUser { uid, purge: false }
+    }
+
+    /// removes all storage and fs items owned by the user. without this the
+    /// request will fail if the user owns any data
+    pub fn purge(&mut self, purge: bool) -> &mut Self {
+        self.purge = purge;
+        self
+    }
+
+    pub fn send(&self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(self)
     }
 
-    pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete(format!("/api/user/{}", self.uid)).send()?;
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for DeleteUser {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::delete(format!("/api/user/{}", self.uid));
+
+        if self.purge {
+            builder = builder.query(&[("purge", true)]);
+        }
+
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -0,0 +1,287 @@
+use rfs_lib::ids;
+
+use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
+use crate::client::{ApiClient, iterate};
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
+use crate::{
+    Payload,
+    Validator,
+    ApiError,
+    ApiErrorKind,
+    Limit,
+    Offset
+};
+use crate::users::bots::{
+    CreateBot as CreateBotBody,
+    Bot,
+    BotToken,
+    ListItem,
+};
+
+pub struct QueryBots {
+    user_uid: ids::UserUid,
+    limit: Option<Limit>,
+    offset: Option<Offset>,
+    last_id: Option<ids::BotUid>,
+}
+
+impl QueryBots {
+    pub fn user_uid(user_uid: ids::UserUid) -> Self {
+        QueryBots {
+            user_uid,
+            limit: None,
+            offset: None,
+            last_id: None,
+        }
+    }
+
+    pub fn limit<L>(&mut self, limit: L) -> &mut Self
+    where
+        L: Into<Option<Limit>>
+    {
+        self.limit = limit.into();
+        self
+    }
+
+    pub fn offset<O>(&mut self, offset: O) -> &mut Self
+    where
+        O: Into<Option<Offset>>
+    {
+        self.offset = offset.into();
+        self
+    }
+
+    pub fn last_id<I>(&mut self, last_id: I) -> &mut Self
+    where
+        I: Into<Option<ids::BotUid>>
+    {
+        self.last_id = last_id.into();
+        self
+    }
+
+    pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<ListItem>>, RequestError> {
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<ListItem>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for QueryBots {
+    type Output = Payload<Vec<ListItem>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get(format!("/api/user/{}/bot", self.user_uid));
+
+        if let Some(limit) = &self.limit {
+            builder = builder.query(&[("limit", limit)]);
+        }
+
+        if let Some(last_id) = &self.last_id {
+            builder = builder.query(&[("last_id", last_id)]);
+        } else if let Some(offset) = &self.offset {
+            builder = builder.query(&[("offset", offset)]);
+        }
+
+        Ok(builder)
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?)),
+        }
+    }
+}
+
+impl iterate::Pageable for QueryBots {
+    type Id = ids::BotUid;
+    type Item = ListItem;
+
+    #[inline]
+    fn get_last_id(item: &Self::Item) -> Option<Self::Id> {
+        Some(item.uid.clone())
+    }
+
+    #[inline]
+    fn set_limit(&mut self, limit: Option<Limit>) {
+        self.limit(limit);
+    }
+
+    #[inline]
+    fn set_last_id(&mut self, id: Option<Self::Id>) {
+        self.last_id(id);
+    }
+
+    #[inline]
+    fn send(&self, client: &ApiClient) -> Result<Payload<Vec<Self::Item>>, RequestError> {
+        self.send(client)
+    }
+}
+
+pub struct RetrieveBot {
+    user_uid: ids::UserUid,
+    uid: ids::BotUid,
+}
+
+impl RetrieveBot {
+    pub fn uid(user_uid: ids::UserUid, uid: ids::BotUid) -> Self {
+        RetrieveBot { user_uid, uid }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Option<Payload<Bot>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<Bot>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveBot {
+    type Output = Option<Payload<Bot>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get(format!("/api/user/{}/bot/{}", self.user_uid, self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(Some(res.json()?)),
+            reqwest::StatusCode::NOT_FOUND => {
+                let err: ApiError = res.json()?;
+
+                if *err.kind() == ApiErrorKind::BotNotFound {
+                    return Ok(None);
+                }
+
+                Err(RequestError::Api(err))
+            },
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct CreateBot {
+    user_uid: ids::UserUid,
+    body: CreateBotBody,
+}
+
+impl CreateBot {
+    pub fn name<N>(user_uid: ids::UserUid, name: N, role_uid: ids::RoleUid) -> Self
+    where
+        N: Into<String>
+    {
+        CreateBot {
+            user_uid,
+            body: CreateBotBody {
+                name: name.into(),
+                role_uid,
+            }
+        }
+    }
+
+    /// sends the request. the token of the bot is only returned here and
+    /// when it is rotated
+    pub fn send(self, client: &ApiClient) -> Result<Payload<BotToken>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<BotToken>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateBot {
+    type Output = Payload<BotToken>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        self.body.assert_ok()?;
+
+        Ok(Request::post(format!("/api/user/{}/bot", self.user_uid))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::CREATED => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct RotateBot {
+    user_uid: ids::UserUid,
+    uid: ids::BotUid,
+}
+
+impl RotateBot {
+    pub fn uid(user_uid: ids::UserUid, uid: ids::BotUid) -> Self {
+        RotateBot { user_uid, uid }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<BotToken>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<BotToken>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RotateBot {
+    type Output = Payload<BotToken>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::put(format!("/api/user/{}/bot/{}", self.user_uid, self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?)),
+        }
+    }
+}
+
+pub struct DeleteBot {
+    user_uid: ids::UserUid,
+    uid: ids::BotUid,
+}
+
+impl DeleteBot {
+    pub fn uid(user_uid: ids::UserUid, uid: ids::BotUid) -> Self {
+        DeleteBot { user_uid, uid }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Bot>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Bot>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DeleteBot {
+    type Output = Payload<Bot>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete(format!("/api/user/{}/bot/{}", self.user_uid, self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?)),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
+use crate::client::ApiClient;
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
+use crate::Validator;
+use crate::users::email::{
+    SetEmail as SetEmailBody,
+    VerifyEmail as VerifyEmailBody,
+};
+
+pub struct SetEmail {
+    body: SetEmailBody,
+}
+
+impl SetEmail {
+    /// sets the email of the current user. the email will be unverified
+    /// until the token that is sent out is given to [`VerifyEmail`]
+    pub fn email<E>(email: E) -> Self
+    where
+        E: Into<String>
+    {
+        SetEmail {
+            body: SetEmailBody {
+                email: email.into(),
+            }
+        }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for SetEmail {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        self.body.validate()?;
+
+        Ok(Request::post("/api/user/email")
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::ACCEPTED => Ok(()),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct VerifyEmail {
+    body: VerifyEmailBody,
+}
+
+impl VerifyEmail {
+    pub fn token<T>(token: T) -> Self
+    where
+        T: Into<String>
+    {
+        VerifyEmail {
+            body: VerifyEmailBody {
+                token: token.into(),
+            }
+        }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for VerifyEmail {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        self.body.validate()?;
+
+        Ok(Request::post("/api/user/email/verify")
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use rfs_lib::ids;
 
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::{ApiClient, iterate};
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::{
     Payload,
     Validator,
@@ -60,7 +63,20 @@ impl QueryGroups {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<ListItem>>, RequestError> {
-        let mut builder= client.get("/api/user/group");
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<ListItem>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for QueryGroups {
+    type Output = Payload<Vec<ListItem>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get("/api/user/group");
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -72,8 +88,10 @@ impl QueryGroups {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?)),
@@ -148,7 +166,20 @@ impl QueryGroupUsers {
     }
 
     pub fn send(&self, client: &ApiClient) -> Result<Payload<Vec<GroupUser>>, RequestError> {
-        let mut builder = client.get(format!("/api/user/group/{}/users", self.uid));
+        client.send(self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(&self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<GroupUser>>, RequestError> {
+        client.send(self).await
+    }
+}
+
+impl Endpoint for QueryGroupUsers {
+    type Output = Payload<Vec<GroupUser>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        let mut builder = Request::get(format!("/api/user/group/{}/users", self.uid));
 
         if let Some(limit) = &self.limit {
             builder = builder.query(&[("limit", limit)]);
@@ -160,8 +191,10 @@ impl QueryGroupUsers {
             builder = builder.query(&[("offset", offset)]);
         }
 
-        let res = builder.send()?;
+        Ok(builder)
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -204,8 +237,23 @@ impl RetrieveGroup {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<Group>>, RequestError> {
-        let res = client.get(format!("/api/user/group/{}", self.uid)).send()?;
+        client.send(&self)
+    }
 
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<Group>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveGroup {
+    type Output = Option<Payload<Group>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get(format!("/api/user/group/{}", self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(Some(res.json()?)),
             reqwest::StatusCode::NOT_FOUND => {
@@ -239,12 +287,26 @@ impl CreateGroup {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Group>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Group>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateGroup {
+    type Output = Payload<Group>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.post("/api/user/group")
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post("/api/user/group")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::CREATED => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -271,12 +333,26 @@ impl UpdateGroup {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Group>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Group>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for UpdateGroup {
+    type Output = Payload<Group>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.assert_ok()?;
 
-        let res = client.patch(format!("/user/group/{}", self.uid))
-            .json(&self.body)
-            .send()?;
+        Ok(Request::patch(format!("/user/group/{}", self.uid))
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?)),
@@ -294,8 +370,23 @@ impl DeleteGroup {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Group>, RequestError> {
-        let res = client.delete(format!("/api/user/group/{}", self.uid)).send()?;
+        client.send(&self)
+    }
 
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Group>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DeleteGroup {
+    type Output = Payload<Group>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete(format!("/api/user/group/{}", self.uid)))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?)),
@@ -335,10 +426,24 @@ impl AddUsers {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.post(format!("/api/user/group/{}/users", self.uid))
-            .json(&self.body)
-            .send()?;
+        client.send(&self)
+    }
 
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for AddUsers {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::post(format!("/api/user/group/{}/users", self.uid))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -377,13 +482,29 @@ impl DropUsers {
         self
     }
 
-    pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete(format!("/api/user/group/{}/users", self.uid))
-            .json(&self.body)
-            .send()?;
+    /// sends the request and returns the uids of the users that were
+    /// removed from the group
+    pub fn send(self, client: &ApiClient) -> Result<Payload<Vec<ids::UserUid>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Vec<ids::UserUid>>, RequestError> {
+        client.send(&self).await
+    }
+}
 
+impl Endpoint for DropUsers {
+    type Output = Payload<Vec<ids::UserUid>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete(format!("/api/user/group/{}/users", self.uid))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
-            reqwest::StatusCode::NO_CONTENT => Ok(()),
+            reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?)),
         }
     }
@@ -1,5 +1,8 @@
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::ApiClient;
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::users::password::CreatePassword;
 
 pub struct UpdatePassword {
@@ -21,13 +24,26 @@ impl UpdatePassword {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for UpdatePassword {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.validate()?;
 
-        let url = client.info.url.join("/api/user/password").unwrap();
-        let res = client.client.post(url)
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post("/api/user/password")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
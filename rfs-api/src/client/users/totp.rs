@@ -1,5 +1,8 @@
 use crate::client::error::RequestError;
+use crate::client::request::{Endpoint, Request, Response};
 use crate::client::ApiClient;
+#[cfg(feature = "async")]
+use crate::client::nonblocking;
 use crate::{Payload, ApiError, ApiErrorKind, Detail};
 use crate::users::totp::{
     CreateTotp as CreateTotpBody,
@@ -7,6 +10,7 @@ use crate::users::totp::{
     CreateTotpHash,
     UpdateTotpHash,
     TotpRecovery,
+    TotpRecoveryList,
     Totp,
     Algo,
 };
@@ -19,8 +23,23 @@ impl RetrieveTotp {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<Totp>>, RequestError> {
-        let res = client.get("/api/user/totp").send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<Totp>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveTotp {
+    type Output = Option<Payload<Totp>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get("/api/user/totp"))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(Some(res.json()?)),
             reqwest::StatusCode::NOT_FOUND => {
@@ -68,10 +87,24 @@ impl CreateTotp {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Totp>, RequestError> {
-        let res = client.post("/api/user/totp")
-            .json(&self.body)
-            .send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Totp>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateTotp {
+    type Output = Payload<Totp>;
 
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::post("/api/user/totp")
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::CREATED => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -116,10 +149,24 @@ impl UpdateTotp {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<Totp>, RequestError> {
-        let res = client.patch("/api/user/totp")
-            .json(&self.body)
-            .send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<Totp>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for UpdateTotp {
+    type Output = Payload<Totp>;
 
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::patch("/api/user/totp")
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?)),
@@ -135,8 +182,23 @@ impl DeleteTotp {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
-        let res = client.delete("/api/user/totp").send()?;
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
 
+impl Endpoint for DeleteTotp {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::delete("/api/user/totp"))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
@@ -151,9 +213,24 @@ impl RetrieveTotpRecovery {
         RetrieveTotpRecovery {}
     }
 
-    pub fn send(self, client: &ApiClient) -> Result<Payload<Vec<TotpRecovery>>, RequestError> {
-        let res = client.get("/api/user/totp/recovery").send()?;
+    pub fn send(self, client: &ApiClient) -> Result<Payload<TotpRecoveryList>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<TotpRecoveryList>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveTotpRecovery {
+    type Output = Payload<TotpRecoveryList>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        Ok(Request::get("/api/user/totp/recovery"))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -178,12 +255,26 @@ impl CreateTotpRecovery {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<TotpRecovery>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<TotpRecovery>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for CreateTotpRecovery {
+    type Output = Payload<TotpRecovery>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         self.body.validate()?;
 
-        let res = client.post("/api/user/totp/recovery")
-            .json(&self.body)
-            .send()?;
+        Ok(Request::post("/api/user/totp/recovery")
+            .json(&self.body))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::CREATED => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -206,6 +297,19 @@ impl RetrieveTotpRecoveryKey {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Option<Payload<TotpRecovery>>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Option<Payload<TotpRecovery>>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RetrieveTotpRecoveryKey {
+    type Output = Option<Payload<TotpRecovery>>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         if !rfs_lib::sec::authn::totp::recovery::key_valid(&self.key) {
             return Err(RequestError::Api(ApiError::from((
                 ApiErrorKind::ValidationFailed,
@@ -213,8 +317,10 @@ impl RetrieveTotpRecoveryKey {
             ))));
         }
 
-        let res = client.get(format!("/api/user/totp/recovery/{}", self.key)).send()?;
+        Ok(Request::get(format!("/api/user/totp/recovery/{}", self.key)))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             reqwest::StatusCode::NOT_FOUND => {
@@ -264,6 +370,19 @@ impl UpdateTotpRecovery {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<Payload<TotpRecovery>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<TotpRecovery>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for UpdateTotpRecovery {
+    type Output = Payload<TotpRecovery>;
+
+    fn request(&self) -> Result<Request, RequestError> {
         if !rfs_lib::sec::authn::totp::recovery::key_valid(&self.key) {
             return Err(RequestError::Api(ApiError::from((
                 ApiErrorKind::ValidationFailed,
@@ -273,10 +392,57 @@ impl UpdateTotpRecovery {
 
         self.body.validate()?;
 
-        let res = client.patch(format!("/api/user/totp/recovery/{}", self.key))
-            .json(&self.body)
-            .send()?;
+        Ok(Request::patch(format!("/api/user/totp/recovery/{}", self.key))
+            .json(&self.body))
+    }
+
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
+        match res.status() {
+            reqwest::StatusCode::OK => Ok(res.json()?),
+            _ => Err(RequestError::Api(res.json()?))
+        }
+    }
+}
+
+pub struct RegenTotpRecovery {
+    key: String
+}
+
+impl RegenTotpRecovery {
+    pub fn key<K>(key: K) -> Self
+    where
+        K: Into<String>
+    {
+        RegenTotpRecovery {
+            key: key.into()
+        }
+    }
+
+    pub fn send(self, client: &ApiClient) -> Result<Payload<TotpRecovery>, RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<Payload<TotpRecovery>, RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for RegenTotpRecovery {
+    type Output = Payload<TotpRecovery>;
+
+    fn request(&self) -> Result<Request, RequestError> {
+        if !rfs_lib::sec::authn::totp::recovery::key_valid(&self.key) {
+            return Err(RequestError::Api(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("key")
+            ))));
+        }
+
+        Ok(Request::post(format!("/api/user/totp/recovery/{}/regen", self.key)))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::OK => Ok(res.json()?),
             _ => Err(RequestError::Api(res.json()?))
@@ -299,6 +465,19 @@ impl DeleteTotpRecovery {
     }
 
     pub fn send(self, client: &ApiClient) -> Result<(), RequestError> {
+        client.send(&self)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn send_async(self, client: &nonblocking::ApiClient) -> Result<(), RequestError> {
+        client.send(&self).await
+    }
+}
+
+impl Endpoint for DeleteTotpRecovery {
+    type Output = ();
+
+    fn request(&self) -> Result<Request, RequestError> {
         if !rfs_lib::sec::authn::totp::recovery::key_valid(&self.key) {
             return Err(RequestError::Api(ApiError::from((
                 ApiErrorKind::ValidationFailed,
@@ -306,8 +485,10 @@ impl DeleteTotpRecovery {
             ))));
         }
 
-        let res = client.delete(format!("/api/user/totp/recovery/{}", self.key)).send()?;
+        Ok(Request::delete(format!("/api/user/totp/recovery/{}", self.key)))
+    }
 
+    fn response(res: Response) -> Result<Self::Output, RequestError> {
         match res.status() {
             reqwest::StatusCode::NO_CONTENT => Ok(()),
             _ => Err(RequestError::Api(res.json()?))
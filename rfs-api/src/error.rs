@@ -26,6 +26,7 @@ pub enum ApiErrorKind {
     InvalidAuthMethod,
     InvalidTotp,
     InvalidTotpHash,
+    TooManyAttempts,
 
     InvalidSession,
     SessionExpired,
@@ -34,8 +35,10 @@ pub enum ApiErrorKind {
     SessionUnauthenticated,
 
     MechanismNotFound,
+    InvalidToken,
     TotpNotFound,
     TotpRecoveryNotFound,
+    TotpRecoveryLimit,
     PasswordNotFound,
 
     // sec
@@ -58,12 +61,17 @@ pub enum ApiErrorKind {
     InvalidHash,
     NoContentType,
     MimeMismatch,
+    ChecksumMismatch,
     NotFile,
 
     // users
 
     UserNotFound,
     GroupNotFound,
+    BotNotFound,
+    UserOwnsData,
+    LastAdmin,
+    EmailNotConfigured,
 
     // tags
 
@@ -73,8 +81,11 @@ pub enum ApiErrorKind {
 
     InternalFailure,
     Timeout,
+    TooManyRequests,
+    ServerBusy,
 
     AlreadyExists,
+    PreconditionFailed,
     NotFound,
 
     NoWork,
@@ -99,9 +110,98 @@ impl std::fmt::Display for ApiErrorKind {
     }
 }
 
-impl From<&ApiErrorKind> for StatusCode {
-    fn from(kind: &ApiErrorKind) -> Self {
-        match kind {
+impl ApiErrorKind {
+    /// stable numeric code for the kind. codes are grouped by the area they
+    /// come from and will not change once assigned
+    pub fn code(&self) -> u16 {
+        match self {
+            // auth
+
+            ApiErrorKind::PermissionDenied => 1000,
+            ApiErrorKind::Unauthenticated => 1001,
+            ApiErrorKind::AlreadyAuthenticated => 1002,
+            ApiErrorKind::AuthRequired => 1003,
+            ApiErrorKind::VerifyRequired => 1004,
+            ApiErrorKind::InvalidPassword => 1005,
+            ApiErrorKind::InvalidAuthMethod => 1006,
+            ApiErrorKind::InvalidTotp => 1007,
+            ApiErrorKind::InvalidTotpHash => 1008,
+            ApiErrorKind::TooManyAttempts => 1009,
+            ApiErrorKind::InvalidSession => 1010,
+            ApiErrorKind::SessionExpired => 1011,
+            ApiErrorKind::SessionNotFound => 1012,
+            ApiErrorKind::SessionUnverified => 1013,
+            ApiErrorKind::SessionUnauthenticated => 1014,
+            ApiErrorKind::MechanismNotFound => 1015,
+            ApiErrorKind::InvalidToken => 1016,
+            ApiErrorKind::TotpNotFound => 1017,
+            ApiErrorKind::TotpRecoveryNotFound => 1018,
+            ApiErrorKind::PasswordNotFound => 1019,
+            ApiErrorKind::TotpRecoveryLimit => 1020,
+
+            // sec
+
+            ApiErrorKind::RoleNotFound => 2000,
+            ApiErrorKind::SecretNotFound => 2001,
+
+            // storage
+
+            ApiErrorKind::StorageNotFound => 3000,
+            ApiErrorKind::DirNotFound => 3001,
+            ApiErrorKind::NotAbsolutePath => 3002,
+            ApiErrorKind::NotDirectory => 3003,
+
+            // fs
+
+            ApiErrorKind::MaxSize => 4000,
+            ApiErrorKind::FileNotFound => 4001,
+            ApiErrorKind::InvalidType => 4002,
+            ApiErrorKind::InvalidHash => 4003,
+            ApiErrorKind::NoContentType => 4004,
+            ApiErrorKind::MimeMismatch => 4005,
+            ApiErrorKind::NotFile => 4006,
+            ApiErrorKind::ChecksumMismatch => 4007,
+
+            // users
+
+            ApiErrorKind::UserNotFound => 5000,
+            ApiErrorKind::GroupNotFound => 5001,
+            ApiErrorKind::BotNotFound => 5002,
+            ApiErrorKind::UserOwnsData => 5003,
+            ApiErrorKind::LastAdmin => 5004,
+            ApiErrorKind::EmailNotConfigured => 5005,
+
+            // tags
+
+            ApiErrorKind::InvalidTags => 6000,
+
+            // general
+
+            ApiErrorKind::InternalFailure => 9000,
+            ApiErrorKind::Timeout => 9001,
+            ApiErrorKind::TooManyRequests => 9002,
+            ApiErrorKind::AlreadyExists => 9003,
+            ApiErrorKind::PreconditionFailed => 9004,
+            ApiErrorKind::NotFound => 9005,
+            ApiErrorKind::NoWork => 9006,
+            ApiErrorKind::NoOp => 9007,
+            ApiErrorKind::NotPermitted => 9008,
+            ApiErrorKind::ValidationFailed => 9009,
+            ApiErrorKind::InvalidData => 9010,
+            ApiErrorKind::MissingData => 9011,
+            ApiErrorKind::InvalidProperty => 9012,
+            ApiErrorKind::InvalidUri => 9013,
+            ApiErrorKind::InvalidHeaderValue => 9014,
+            ApiErrorKind::InvalidMimeType => 9015,
+            ApiErrorKind::InvalidMethod => 9016,
+            ApiErrorKind::InvalidRequest => 9017,
+            ApiErrorKind::ServerBusy => 9018,
+        }
+    }
+
+    /// the http status that is returned with the kind
+    pub fn status(&self) -> StatusCode {
+        match self {
             ApiErrorKind::AlreadyAuthenticated |
             ApiErrorKind::InvalidAuthMethod |
             ApiErrorKind::NotAbsolutePath |
@@ -111,6 +211,7 @@ impl From<&ApiErrorKind> for StatusCode {
             ApiErrorKind::InvalidHash |
             ApiErrorKind::NoContentType |
             ApiErrorKind::MimeMismatch |
+            ApiErrorKind::ChecksumMismatch |
             ApiErrorKind::NotFile |
             ApiErrorKind::InvalidTags |
             ApiErrorKind::NoWork |
@@ -132,7 +233,8 @@ impl From<&ApiErrorKind> for StatusCode {
             ApiErrorKind::SessionNotFound |
             ApiErrorKind::SessionUnverified |
             ApiErrorKind::SessionUnauthenticated |
-            ApiErrorKind::MechanismNotFound
+            ApiErrorKind::MechanismNotFound |
+            ApiErrorKind::InvalidToken
                 => StatusCode::UNAUTHORIZED,
 
             ApiErrorKind::PermissionDenied |
@@ -153,6 +255,7 @@ impl From<&ApiErrorKind> for StatusCode {
             ApiErrorKind::FileNotFound |
             ApiErrorKind::UserNotFound |
             ApiErrorKind::GroupNotFound |
+            ApiErrorKind::BotNotFound |
             ApiErrorKind::NotFound
                 => StatusCode::NOT_FOUND,
 
@@ -162,15 +265,37 @@ impl From<&ApiErrorKind> for StatusCode {
             ApiErrorKind::Timeout
                 => StatusCode::REQUEST_TIMEOUT,
 
-            ApiErrorKind::AlreadyExists
+            ApiErrorKind::TooManyRequests |
+            ApiErrorKind::TooManyAttempts
+                => StatusCode::TOO_MANY_REQUESTS,
+
+            ApiErrorKind::AlreadyExists |
+            ApiErrorKind::TotpRecoveryLimit |
+            ApiErrorKind::UserOwnsData |
+            ApiErrorKind::LastAdmin
                 => StatusCode::CONFLICT,
 
+            ApiErrorKind::PreconditionFailed
+                => StatusCode::PRECONDITION_FAILED,
+
             ApiErrorKind::InternalFailure
                 => StatusCode::INTERNAL_SERVER_ERROR,
+
+            ApiErrorKind::EmailNotConfigured
+                => StatusCode::NOT_IMPLEMENTED,
+
+            ApiErrorKind::ServerBusy
+                => StatusCode::SERVICE_UNAVAILABLE,
         }
     }
 }
 
+impl From<&ApiErrorKind> for StatusCode {
+    fn from(kind: &ApiErrorKind) -> Self {
+        kind.status()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Detail {
     Keys(Vec<String>),
@@ -247,11 +372,13 @@ impl std::fmt::Display for Detail {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ApiError {
     kind: ApiErrorKind,
     detail: Option<Detail>,
     msg: Option<String>,
+    #[serde(default)]
+    request_id: Option<u64>,
 }
 
 impl ApiError {
@@ -259,7 +386,8 @@ impl ApiError {
         ApiError {
             kind: ApiErrorKind::InternalFailure,
             detail: None,
-            msg: None
+            msg: None,
+            request_id: None,
         }
     }
 
@@ -288,6 +416,10 @@ impl ApiError {
         &self.kind
     }
 
+    pub fn code(&self) -> u16 {
+        self.kind.code()
+    }
+
     pub fn detail(&self) -> Option<&Detail> {
         self.detail.as_ref()
     }
@@ -295,6 +427,41 @@ impl ApiError {
     pub fn message(&self) -> Option<&str> {
         self.msg.as_ref().map(|v| v.as_str())
     }
+
+    /// the id of the request that caused the error. used to find the
+    /// request in the server logs
+    pub fn request_id(&self) -> Option<u64> {
+        self.request_id
+    }
+
+    pub fn set_request_id(&mut self, request_id: Option<u64>) -> Option<u64> {
+        std::mem::replace(&mut self.request_id, request_id)
+    }
+}
+
+impl Serialize for ApiError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        use serde::ser::SerializeStruct;
+
+        let len = if self.request_id.is_some() { 5 } else { 4 };
+
+        let mut state = serializer.serialize_struct("ApiError", len)?;
+        state.serialize_field("kind", &self.kind)?;
+        state.serialize_field("code", &self.kind.code())?;
+        state.serialize_field("detail", &self.detail)?;
+        state.serialize_field("msg", &self.msg)?;
+
+        if let Some(request_id) = &self.request_id {
+            state.serialize_field("request_id", request_id)?;
+        } else {
+            state.skip_field("request_id")?;
+        }
+
+        state.end()
+    }
 }
 
 impl std::error::Error for ApiError {}
@@ -322,7 +489,8 @@ impl From<ApiErrorKind> for ApiError
         ApiError {
             kind,
             detail: None,
-            msg: None
+            msg: None,
+            request_id: None,
         }
     }
 }
@@ -335,7 +503,8 @@ where
         ApiError {
             kind,
             detail: Some(detail.into()),
-            msg: None
+            msg: None,
+            request_id: None,
         }
     }
 }
@@ -349,14 +518,15 @@ where
         ApiError {
             kind,
             detail: Some(detail.into()),
-            msg: Some(msg.into())
+            msg: Some(msg.into()),
+            request_id: None,
         }
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let status = (&self.kind).into();
+        let status = self.kind.status();
 
         match serialize_json(status, &self) {
             Ok(res) => res,
@@ -1,17 +1,22 @@
+use std::path::PathBuf;
+
 use rfs_lib::ids;
-use rfs_lib::serde::mime_str;
+use rfs_lib::serde::{mime_str, nested_option};
 
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
-use crate::Tags;
+use crate::{Tags, Validator, ApiError, ApiErrorKind, Detail};
 
 pub mod backend;
+pub mod sync;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Root {
     pub uid: ids::FSUid,
     pub user_uid: ids::UserUid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_uid: Option<ids::GroupUid>,
     pub storage_uid: ids::StorageUid,
     pub basename: String,
     pub backend: backend::Node,
@@ -36,6 +41,8 @@ pub struct RootMin {
 pub struct File {
     pub uid: ids::FSUid,
     pub user_uid: ids::UserUid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_uid: Option<ids::GroupUid>,
     pub storage_uid: ids::StorageUid,
     pub parent: ids::FSUid,
     pub basename: String,
@@ -50,6 +57,9 @@ pub struct File {
     pub created: DateTime<Utc>,
     pub updated: Option<DateTime<Utc>>,
     pub deleted: Option<DateTime<Utc>>,
+    /// modification time provided by the client that uploaded the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,21 +80,128 @@ pub struct FileMin {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateMetadata {
     pub tags: Option<Tags>,
+    /// tags to remove from the item. this is applied after any tags given
+    /// have replaced the existing ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove_tags: Option<Vec<String>>,
     pub comment: Option<String>,
+    /// sets the group that shares ownership of the item. null will remove
+    /// the group
+    #[serde(default, deserialize_with = "nested_option", skip_serializing_if = "Option::is_none")]
+    pub group_uid: Option<Option<ids::GroupUid>>,
+    /// the updated timestamp of the item that the client last saw. if the
+    /// item has been changed since then the update will be rejected. null
+    /// is for an item that has never been updated
+    #[serde(default, deserialize_with = "nested_option", skip_serializing_if = "Option::is_none")]
+    pub expected_updated: Option<Option<DateTime<Utc>>>,
 }
 
 impl UpdateMetadata {
     pub fn has_work(&self) -> bool {
         self.tags.is_some() ||
+            self.remove_tags.is_some() ||
+            self.comment.is_some() ||
+            self.group_uid.is_some()
+    }
+}
+
+/// applies the same metadata changes to a list of fs items. tags are added
+/// to or removed from the existing tags of each item instead of replacing
+/// them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchUpdate {
+    pub ids: Vec<ids::FSUid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub add_tags: Option<Tags>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove_tags: Option<Vec<String>>,
+    /// an empty comment will remove the comment from the items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl Validator for BatchUpdate {
+    fn validate(&self) -> Result<(), ApiError> {
+        if self.ids.len() > rfs_lib::fs::MAX_BATCH_ITEMS {
+            return Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("ids")
+            )));
+        }
+
+        if let Some(tags) = &self.add_tags {
+            for (key, value) in tags {
+                let value_ok = value.as_ref()
+                    .map(rfs_lib::tags::value_valid)
+                    .unwrap_or(true);
+
+                if !rfs_lib::tags::key_valid(key) || !value_ok {
+                    return Err(ApiError::from((
+                        ApiErrorKind::ValidationFailed,
+                        Detail::with_key("add_tags")
+                    )));
+                }
+            }
+        }
+
+        if let Some(tags) = &self.remove_tags {
+            if !tags.iter().all(rfs_lib::tags::key_valid) {
+                return Err(ApiError::from((
+                    ApiErrorKind::ValidationFailed,
+                    Detail::with_key("remove_tags")
+                )));
+            }
+        }
+
+        if let Some(comment) = &self.comment {
+            if !comment.is_empty() && !rfs_lib::fs::comment_valid(comment) {
+                return Err(ApiError::from((
+                    ApiErrorKind::ValidationFailed,
+                    Detail::with_key("comment")
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn has_work(&self) -> bool {
+        !self.ids.is_empty() && (
+            self.add_tags.is_some() ||
+            self.remove_tags.is_some() ||
             self.comment.is_some()
+        )
     }
 }
 
+/// the result of a batch update for a single fs item
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub id: ids::FSUid,
+    /// the reason the item was not updated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ApiError>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum CreateItem {
     Dir(CreateDir)
 }
 
+/// how an upload should be handled when a file with the same basename
+/// already exists in the parent directory
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnConflict {
+    /// respond with an already exists error
+    #[default]
+    Error,
+    /// replace the contents of the existing file
+    Overwrite,
+    /// pick a basename that is not in use
+    Rename,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateDir {
     pub basename: String,
@@ -96,10 +213,16 @@ pub struct CreateDir {
 pub struct Directory {
     pub uid: ids::FSUid,
     pub user_uid: ids::UserUid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_uid: Option<ids::GroupUid>,
     pub storage_uid: ids::StorageUid,
     pub parent: ids::FSUid,
     pub basename: String,
     pub path: String,
+    /// hash of the basenames and hashes of all the children in the
+    /// directory. changes whenever anything beneath the directory changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<Vec<u8>>,
     pub tags: Tags,
     pub comment: Option<String>,
     pub backend: backend::Node,
@@ -127,6 +250,8 @@ pub enum Item {
     Directory(Directory),
 }
 
+/// the shallow form of [`Item`] returned when listing items. tags and
+/// comments are left out so that lists can be retrieved without joining them
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ItemMin {
     Root(RootMin),
@@ -134,6 +259,48 @@ pub enum ItemMin {
     Directory(DirectoryMin),
 }
 
+impl ItemMin {
+    pub fn uid(&self) -> &ids::FSUid {
+        match self {
+            ItemMin::Root(root) => &root.uid,
+            ItemMin::File(file) => &file.uid,
+            ItemMin::Directory(dir) => &dir.uid,
+        }
+    }
+}
+
+/// the number of fs items and the sum of their sizes
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UsageCount {
+    pub count: u64,
+    pub size: u64,
+}
+
+/// fs items owned by a user broken down by type. items in the trash are
+/// included since they still take up space
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserUsage {
+    pub user_uid: ids::UserUid,
+    pub roots: UsageCount,
+    pub directories: UsageCount,
+    pub files: UsageCount,
+}
+
+/// totals for a single user across all storage mediums
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserStats {
+    pub user_uid: ids::UserUid,
+    pub username: String,
+    pub count: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Trashed {
+    pub item: ItemMin,
+    pub deleted: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateStorage {
     pub name: String,
@@ -161,17 +328,44 @@ pub struct StorageMin {
     pub backend: backend::Config,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelocateStorage {
+    /// the new absolute path of the storage medium
+    pub path: PathBuf,
+    /// checks that a sample of files exist under the new path before
+    /// updating the storage medium
+    #[serde(default)]
+    pub verify: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateStorage {
     pub name: Option<String>,
     pub backend: Option<backend::UpdateConfig>,
     pub tags: Option<Tags>,
+    /// tags to remove from the storage medium. this is applied after any
+    /// tags given have replaced the existing ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove_tags: Option<Vec<String>>,
 }
 
 impl UpdateStorage {
     pub fn has_work(&self) -> bool {
         self.name.is_some() ||
             self.backend.is_some() ||
-            self.tags.is_some()
+            self.tags.is_some() ||
+            self.remove_tags.is_some()
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Event {
+    pub id: i64,
+    pub fs_uid: ids::FSUid,
+    /// the user that made the change. None if the user has since been
+    /// deleted
+    pub user_uid: Option<ids::UserUid>,
+    pub action: rfs_lib::fs::event::Action,
+    pub detail: Option<serde_json::Value>,
+    pub created: DateTime<Utc>,
+}
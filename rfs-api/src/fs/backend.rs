@@ -4,7 +4,13 @@ use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConfigLocal {
-    pub path: PathBuf
+    pub path: PathBuf,
+    #[serde(default)]
+    pub dedup: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmp: Option<PathBuf>,
+    #[serde(default)]
+    pub compression: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,6 +22,10 @@ pub enum Config {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NodeLocal {
     pub path: PathBuf,
+    #[serde(default)]
+    pub compressed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored_size: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,11 +37,23 @@ pub enum Node {
 #[derive(Debug, Serialize, Deserialize)]
 pub enum CreateConfig {
     Local {
-        path: PathBuf
+        path: PathBuf,
+        #[serde(default)]
+        dedup: bool,
+        /// directory that uploads are written to before being moved into
+        /// place. defaults to a directory in the root of the storage medium
+        #[serde(default)]
+        tmp: Option<PathBuf>,
+        /// compresses file contents with zstd before writing them to disk
+        #[serde(default)]
+        compression: bool,
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum UpdateConfig {
-    Local {}
+    Local {
+        dedup: Option<bool>,
+        compression: Option<bool>,
+    }
 }
@@ -0,0 +1,79 @@
+use rfs_lib::ids;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{Validator, ApiError, ApiErrorKind, Detail};
+
+/// the direction that a sync plan is created for
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncMode {
+    /// make the server match the client
+    #[default]
+    Push,
+}
+
+/// a single file on the client
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// path of the file relative to the directory being synced. segments
+    /// are separated by "/"
+    pub path: String,
+    /// hex encoded blake3 hash of the file contents
+    pub hash: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncManifest {
+    #[serde(default)]
+    pub mode: SyncMode,
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Validator for SyncManifest {
+    fn validate(&self) -> Result<(), ApiError> {
+        for entry in &self.entries {
+            if !entry.path.split('/').all(rfs_lib::fs::basename_valid) {
+                return Err(ApiError::from((
+                    ApiErrorKind::ValidationFailed,
+                    Detail::with_key("entries")
+                )));
+            }
+
+            if entry.hash.len() != 64 || !entry.hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(ApiError::from((
+                    ApiErrorKind::ValidationFailed,
+                    Detail::with_key("entries")
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncItem {
+    /// path of the item relative to the directory being synced
+    pub path: String,
+    /// the existing item on the server if there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<ids::FSUid>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncPlan {
+    pub mode: SyncMode,
+    /// files that are missing or different on the server. files with a uid
+    /// should overwrite the existing file
+    pub upload: Vec<SyncItem>,
+    /// items on the server that are not in the manifest. directories are
+    /// listed without their contents
+    pub delete: Vec<SyncItem>,
+    /// paths that cannot be synced since the server has a directory where
+    /// the client has a file or the item is not accessible
+    pub conflicts: Vec<String>,
+    /// number of files that already match
+    pub unchanged: u64,
+}
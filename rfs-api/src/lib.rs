@@ -12,6 +12,7 @@ pub mod users;
 pub mod auth;
 pub mod sec;
 pub mod fs;
+pub mod ws;
 
 mod payload;
 pub use payload::{
@@ -122,7 +122,15 @@ where
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Pagination {
     limit: Limit,
-    offset: Option<Offset>
+    offset: Option<Offset>,
+    /// cursor for the next page of results. only present if the results
+    /// filled the page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+    /// total number of items available across all pages. only present for
+    /// endpoints that are able to count the results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    total: Option<u64>,
 }
 
 impl Pagination {
@@ -130,6 +138,8 @@ impl Pagination {
         Self {
             limit: Limit::Small,
             offset: None,
+            next: None,
+            total: None,
         }
     }
 
@@ -160,6 +170,27 @@ impl Pagination {
         self.offset = Some(offset);
         self
     }
+
+    pub fn next(&self) -> Option<&str> {
+        self.next.as_deref()
+    }
+
+    pub fn set_next<N>(&mut self, next: N) -> &mut Self
+    where
+        N: ToString
+    {
+        self.next = Some(next.to_string());
+        self
+    }
+
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    pub fn set_total(&mut self, total: u64) -> &mut Self {
+        self.total = Some(total);
+        self
+    }
 }
 
 impl std::default::Default for Pagination {
@@ -173,6 +204,8 @@ impl From<(Limit, Offset)> for Pagination {
         Pagination {
             limit: v.0,
             offset: Some(v.1),
+            next: None,
+            total: None,
         }
     }
 }
@@ -182,6 +215,8 @@ impl From<Limit> for Pagination {
         Pagination {
             limit,
             offset: None,
+            next: None,
+            total: None,
         }
     }
 }
@@ -191,6 +226,8 @@ impl From<&Limit> for Pagination {
         Pagination {
             limit: limit.clone(),
             offset: None,
+            next: None,
+            total: None,
         }
     }
 }
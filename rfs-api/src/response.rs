@@ -24,7 +24,7 @@ pub fn serialize_json(
 }
 
 pub fn error_json() -> Response {
-    let body = r#"{"kind":"InternalFailure"}"#;
+    let body = r#"{"kind":"InternalFailure","code":9000}"#;
 
     Response::builder()
         .status(StatusCode::INTERNAL_SERVER_ERROR)
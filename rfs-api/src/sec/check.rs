@@ -0,0 +1,24 @@
+use rfs_lib::sec::authz::permission::{Ability, Scope};
+
+use serde::{Serialize, Deserialize};
+
+use crate::Validator;
+use crate::sec::roles::Permission;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckPermissions {
+    pub permissions: Vec<Permission>,
+}
+
+impl Validator for CheckPermissions {
+    fn has_work(&self) -> bool {
+        !self.permissions.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PermissionCheck {
+    pub scope: Scope,
+    pub ability: Ability,
+    pub granted: bool,
+}
@@ -8,11 +8,16 @@ pub struct PasswordListItem {
     #[serde(with = "from_to_str")]
     pub version: u64,
     pub created: DateTime<Utc>,
+    /// the version is the one used for new passwords
+    pub newest: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SessionListItem {
+    pub id: String,
     pub created: DateTime<Utc>,
+    /// the key is the one used for new sessions
+    pub newest: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
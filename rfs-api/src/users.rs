@@ -5,6 +5,8 @@ use serde::{Serialize, Deserialize};
 
 use crate::{Validator, ApiError, ApiErrorKind, Detail};
 
+pub mod bots;
+pub mod email;
 pub mod groups;
 pub mod password;
 pub mod totp;
@@ -25,7 +27,12 @@ pub struct Email {
 pub struct User {
     pub uid: ids::UserUid,
     pub username: String,
-    pub email: Option<Email>
+    pub email: Option<Email>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// free form settings that clients can store for the user
+    #[serde(default)]
+    pub preferences: serde_json::Value,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -50,7 +57,7 @@ impl Validator for CreateUser {
         }
 
         if let Some(email) = &self.email {
-            if rfs_lib::users::email_valid(email) {
+            if !rfs_lib::users::email_valid(email) {
                 invalid.push("email");
             }
         }
@@ -105,3 +112,57 @@ impl Validator for UpdateUser {
         self.username.is_some() || self.email.is_some()
     }
 }
+
+/// the fields that a user is able to update on their own record. privileged
+/// fields like the username can only be changed with the user scope
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateMe {
+    /// null will remove the display name
+    #[serde(default, deserialize_with = "nested_option", skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<Option<String>>,
+    /// the email will be unverified until the token sent is verified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// replaces the current preferences. must be an object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferences: Option<serde_json::Value>,
+}
+
+impl Validator for UpdateMe {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut invalid = Vec::new();
+
+        if let Some(Some(display_name)) = &self.display_name {
+            if !rfs_lib::users::display_name_valid(display_name) {
+                invalid.push("display_name");
+            }
+        }
+
+        if let Some(email) = &self.email {
+            if !rfs_lib::users::email_valid(email) {
+                invalid.push("email");
+            }
+        }
+
+        if let Some(preferences) = &self.preferences {
+            if !preferences.is_object() {
+                invalid.push("preferences");
+            }
+        }
+
+        if !invalid.is_empty() {
+            Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::mult_keys(invalid)
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn has_work(&self) -> bool {
+        self.display_name.is_some() ||
+            self.email.is_some() ||
+            self.preferences.is_some()
+    }
+}
@@ -0,0 +1,49 @@
+use rfs_lib::ids;
+
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+
+use crate::{Validator, ApiError, ApiErrorKind, Detail};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListItem {
+    pub uid: ids::BotUid,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bot {
+    pub uid: ids::BotUid,
+    pub user_uid: ids::UserUid,
+    pub role_uid: ids::RoleUid,
+    pub name: String,
+    pub created: DateTime<Utc>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+/// a bot along with its token. the token is only available when the bot is
+/// created or when the token is rotated
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotToken {
+    pub bot: Bot,
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateBot {
+    pub name: String,
+    pub role_uid: ids::RoleUid,
+}
+
+impl Validator for CreateBot {
+    fn validate(&self) -> Result<(), ApiError> {
+        if !rfs_lib::users::bots::name_valid(&self.name) {
+            Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("name")
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{Validator, ApiError, ApiErrorKind, Detail};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetEmail {
+    pub email: String,
+}
+
+impl Validator for SetEmail {
+    fn validate(&self) -> Result<(), ApiError> {
+        if !rfs_lib::users::email_valid(&self.email) {
+            Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("email")
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyEmail {
+    pub token: String,
+}
+
+impl Validator for VerifyEmail {
+    fn validate(&self) -> Result<(), ApiError> {
+        if self.token.is_empty() {
+            Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("token")
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
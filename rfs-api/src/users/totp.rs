@@ -11,7 +11,15 @@ pub struct Totp {
     pub algo: String,
     pub secret: Vec<u8>,
     pub digits: u32,
-    pub step: u64
+    pub step: u64,
+    /// the number of steps before the current one that the server will
+    /// accept codes for
+    #[serde(default)]
+    pub window_before: u8,
+    /// the number of steps after the current one that the server will
+    /// accept codes for
+    #[serde(default)]
+    pub window_after: u8,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,6 +30,15 @@ pub struct TotpRecovery {
     pub used: bool
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TotpRecoveryList {
+    /// number of recovery keys the user has
+    pub count: usize,
+    /// max number of recovery keys the user is allowed to have
+    pub max: u16,
+    pub keys: Vec<TotpRecovery>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateTotp {
     #[serde(default, skip_serializing_if = "Option::is_none")]
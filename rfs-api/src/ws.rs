@@ -0,0 +1,77 @@
+use rfs_lib::ids;
+use serde::{Serialize, Deserialize};
+
+use crate::{ApiError, Limit};
+use crate::fs::{Event, Item, ItemMin};
+
+/// a message sent by the client over the websocket
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Request {
+    /// chosen by the client and sent back with the response so that
+    /// requests can be matched with their responses
+    pub id: u64,
+    #[serde(flatten)]
+    pub op: Operation,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    /// retrieves a single fs item
+    Retrieve {
+        uid: ids::FSUid,
+    },
+    /// lists the contents of a directory
+    Contents {
+        uid: ids::FSUid,
+        /// start after the given item
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<ids::FSUid>,
+        #[serde(default)]
+        limit: Limit,
+    },
+    /// sends the changes made to items beneath a directory until the client
+    /// unsubscribes. the id of the request identifies the subscription
+    Subscribe {
+        uid: ids::FSUid,
+        /// only send events after the given event id
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<i64>,
+    },
+    /// stops sending events for a subscription
+    Unsubscribe {
+        subscription: u64,
+    },
+}
+
+/// a message sent by the server over the websocket
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    Item {
+        id: u64,
+        item: Item,
+    },
+    Contents {
+        id: u64,
+        items: Vec<ItemMin>,
+    },
+    Subscribed {
+        id: u64,
+    },
+    Unsubscribed {
+        id: u64,
+    },
+    /// a change for a subscription
+    Event {
+        subscription: u64,
+        event: Event,
+    },
+    /// the request failed. the id is not present if the request could not
+    /// be parsed
+    Error {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<u64>,
+        error: ApiError,
+    },
+}
@@ -1,34 +1,42 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::OnceLock;
 
 use rfs_api::client::ApiClient;
 use rfs_api::client::auth::session::DropSession;
-use rfs_api::client::users::password::UpdatePassword;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, CommandFactory, ArgAction};
+use clap_complete::Shell;
 
 use crate::error::{self, Context};
+use crate::formatting::{self, OutputFormat};
 
+mod auth;
 mod fs;
 mod user;
 mod sec;
 mod totp;
 mod connect;
+mod config;
+mod interactive;
 
 /// a cli for interacting with a RFS.
 ///
 /// provides options for modifying data on a server as well as administration 
 /// processes. if no command is provided then it will enter interactive mode.
 #[derive(Debug, Parser)]
+#[command(subcommand_negates_reqs = true)]
 struct Cli {
     /// host name of server
     ///
     /// will be used in a url so the value must be valid for the hostname part
-    /// of a url. examples: example.com | 10.0.0.2 | [fd34::2]
-    host: String,
+    /// of a url. examples: example.com | 10.0.0.2 | [fd34::2]. required for
+    /// all commands except completions
+    host: Option<String>,
 
     /// port of server
     ///
-    /// if no port is provided it will default to 80 (http) or 443 (https)
+    /// if no port is provided it will use the config value or default to 80
+    /// (http) or 443 (https)
     #[arg(short, long)]
     port: Option<u16>,
 
@@ -45,13 +53,90 @@ struct Cli {
     #[arg(long)]
     cookies: Option<PathBuf>,
 
+    /// bot token to authenticate with
+    ///
+    /// if a token is not specified then it will attempt to use the
+    /// "RFS_TOKEN" environment variable. the token will be used over any
+    /// session stored in the cookies file
+    #[arg(long)]
+    token: Option<String>,
+
+    /// file that stores default values for the cli
+    ///
+    /// if a file is not specified then it will attempt to load
+    /// "rfs_config.toml" in the same directory as the cookies file. any
+    /// flags provided will override values in the file
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// format of data written to stdout
+    ///
+    /// "table" is meant to be human readable while "json" will output the
+    /// data received from the server for use in scripts. defaults to
+    /// "table" if not specified in the config
+    #[arg(long)]
+    output: Option<OutputFormat>,
+
+    /// increases the amount of logging output
+    ///
+    /// "-v" will log the requests sent to the server and "-vv" will log
+    /// everything else the cli is doing. will override the "RUST_LOG"
+    /// environment variable when given
+    #[arg(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// suppresses informational messages
+    ///
+    /// only the requested data and errors will be written. works with both
+    /// output formats
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// prints what a destructive command would do without doing it
+    ///
+    /// the affected data is retrieved from the server but nothing will be
+    /// modified. only applies to commands that delete or detach data
+    #[arg(long)]
+    dry_run: bool,
+
     #[command(subcommand)]
-    command: Cmds,
+    command: Option<Cmds>,
+}
+
+static DRY_RUN: OnceLock<bool> = OnceLock::new();
+
+/// sets if destructive commands should only report what they would do for
+/// the rest of the process. only the first call will have an effect
+fn set_dry_run(dry_run: bool) {
+    let _ = DRY_RUN.set(dry_run);
+}
+
+/// if destructive commands should only report what they would do
+pub fn dry_run() -> bool {
+    DRY_RUN.get().copied().unwrap_or(false)
+}
+
+fn init_tracing(verbose: u8) {
+    use tracing_subscriber::{FmtSubscriber, EnvFilter};
+
+    let filter = match verbose {
+        0 => EnvFilter::from_default_env(),
+        1 => EnvFilter::new("rfs_cli=debug,rfs_api=debug"),
+        _ => EnvFilter::new("rfs_cli=trace,rfs_api=trace"),
+    };
+
+    FmtSubscriber::builder()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init()
+        .expect("failed to initialize global tracing subscriber");
 }
 
 pub fn start() -> error::Result {
     let args = Cli::parse();
 
+    init_tracing(args.verbose);
+
     let session_file = if let Some(arg) = args.cookies {
         arg.clone()
     } else {
@@ -60,31 +145,56 @@ pub fn start() -> error::Result {
         current_dir
     };
 
+    let config_file = config::resolve_path(args.config, &session_file);
+    let config = config::Config::load(&config_file)?;
+
+    formatting::set_output_format(args.output.or(config.output).unwrap_or_default());
+    formatting::set_quiet(args.quiet);
+    set_dry_run(args.dry_run);
+
+    match args.command {
+        Some(Cmds::Completions(given)) => return completions(given),
+        Some(Cmds::Config(given)) => return config::handle(&config_file, config, given),
+        _ => {}
+    }
+
+    let history_file = interactive::resolve_path(&session_file);
+
+    let Some(host) = args.host.or(config.host) else {
+        return Err(error::Error::from("a host name is required. provide one or set it in the config")
+            .kind(error::ErrorKind::Validation));
+    };
+
     let mut client_builder = ApiClient::builder();
 
-    if let Some((host, port)) = args.host.rsplit_once(':') {
+    if let Some((host, port)) = host.rsplit_once(':') {
         let port = u16::from_str(port)
-            .context(format!("invalid port number given in domain. given: \"{}\"", port))?;
+            .context(format!("invalid port number given in domain. given: \"{}\"", port))
+            .map_err(|err| err.kind(error::ErrorKind::Validation))?;
 
         client_builder.port(Some(port));
 
         if !client_builder.host(host.to_owned()) {
-            return Err(format!(
+            return Err(error::Error::from(format!(
                 "cannot set host to the value provided. {host}"
-            ).into());
+            )).kind(error::ErrorKind::Validation));
         }
     } else {
-        if !client_builder.host(args.host.clone()) {
-            return Err(format!(
-                "cannot set host to the value provided. {}", args.host
-            ).into());
+        if !client_builder.host(host.clone()) {
+            return Err(error::Error::from(format!(
+                "cannot set host to the value provided. {host}"
+            )).kind(error::ErrorKind::Validation));
         }
     }
 
     client_builder.cookie_file(session_file);
-    client_builder.secure(!args.insecure);
 
-    if let Some(port) = args.port {
+    if let Some(token) = args.token.or_else(|| std::env::var("RFS_TOKEN").ok()) {
+        client_builder.bearer_token(token);
+    }
+    client_builder.secure(!args.insecure && config.secure.unwrap_or(true));
+
+    if let Some(port) = args.port.or(config.port) {
         client_builder.port(Some(port));
     }
 
@@ -92,14 +202,31 @@ pub fn start() -> error::Result {
         .context("failed to create api client")?;
 
     match args.command {
-        Cmds::Connect => connect(&mut client),
-        Cmds::Disconnect => disconnect(&mut client),
-        Cmds::Password => password(&mut client),
-        Cmds::Totp(given) => totp::handle(&mut client, given),
-        Cmds::Fs(given) => fs::handle(&mut client, given),
-        Cmds::Users(given) => user::handle(&mut client, given),
-        Cmds::Sec(given) => sec::handle(&mut client, given),
-        Cmds::Ping => ping(&mut client),
+        Some(command) => dispatch(&mut client, &config_file, command),
+        None => interactive::run(&mut client, &config_file, &history_file),
+    }
+}
+
+/// runs the given command. completions and config are only dispatched here
+/// when in interactive mode since they do not require a client otherwise
+fn dispatch(client: &mut ApiClient, config_file: &Path, command: Cmds) -> error::Result {
+    match command {
+        Cmds::Connect => connect(client),
+        Cmds::Disconnect => disconnect(client),
+        Cmds::Password => auth::passwd(client),
+        Cmds::Auth(given) => auth::handle(client, given),
+        Cmds::Totp(given) => totp::handle(client, given),
+        Cmds::Fs(given) => fs::handle(client, given),
+        Cmds::Sync(given) => fs::sync::sync(client, given),
+        Cmds::Users(given) => user::handle(client, given),
+        Cmds::Sec(given) => sec::handle(client, given),
+        Cmds::Ping(given) => ping(client, given),
+        Cmds::Completions(given) => completions(given),
+        Cmds::Config(given) => {
+            let config = config::Config::load(config_file)?;
+
+            config::handle(config_file, config, given)
+        }
     }
 }
 
@@ -116,12 +243,21 @@ enum Cmds {
     /// updates the current password to a new one
     Password,
 
+    /// manages authentication data for the current user
+    Auth(auth::AuthArgs),
+
     /// interacts with data specific to totp 2FA
     Totp(totp::TotpArgs),
 
     /// interacts with fs items on a server
     Fs(fs::FsArgs),
 
+    /// makes a directory on a server match a local directory
+    ///
+    /// only files that are missing or have changed are uploaded. use the
+    /// global --dry-run flag to print the plan without changing anything
+    Sync(fs::sync::SyncArgs),
+
     /// interacts with users on a server
     Users(user::UsersArgs),
 
@@ -129,7 +265,26 @@ enum Cmds {
     Sec(sec::SecArgs),
 
     /// pings the server for activity
-    Ping,
+    Ping(PingArgs),
+
+    /// generates a completion script for the given shell
+    Completions(CompletionsArgs),
+
+    /// views or updates the cli config file
+    Config(config::ConfigArgs),
+}
+
+#[derive(Debug, clap::Args)]
+struct PingArgs {
+    /// the number of pings to send to the server
+    #[arg(short, long, default_value_t = 1)]
+    count: u32,
+}
+
+#[derive(Debug, clap::Args)]
+struct CompletionsArgs {
+    /// the shell to generate the script for
+    shell: Shell,
 }
 
 fn connect(client: &mut ApiClient) -> error::Result {
@@ -152,36 +307,41 @@ fn disconnect(client: &mut ApiClient) -> error::Result {
     Ok(())
 }
 
-fn password(client: &mut ApiClient) -> error::Result {
-    let current_prompt = "current: ";
-    let updated_prompt = "updated: ";
-    let confirm_prompt = "confirm: ";
-
-    let current = rpassword::prompt_password(&current_prompt)?;
-    let updated = rpassword::prompt_password(&updated_prompt)?;
-    let mut confirm;
+fn ping(client: &mut ApiClient, args: PingArgs) -> error::Result {
+    let quiet = formatting::quiet();
+    let mut times = Vec::with_capacity(args.count as usize);
 
-    loop {
-        confirm = rpassword::prompt_password(&confirm_prompt)?;
+    for seq in 0..args.count.max(1) {
+        let pong = client.ping_timed().context("failed to ping server")?;
+        let rtt = pong.rtt.as_secs_f64() * 1000.0;
 
-        if confirm != updated {
-            println!("updated and confirm do not match");
-        } else {
-            break;
+        if !quiet {
+            if let Some(skew) = pong.skew() {
+                println!("pong seq={seq} time={rtt:.3}ms skew={}ms", skew.num_milliseconds());
+            } else {
+                println!("pong seq={seq} time={rtt:.3}ms");
+            }
         }
+
+        times.push(rtt);
     }
 
-    UpdatePassword::update_password(current, updated, confirm)
-        .send(client)
-        .context("failed to update password")?;
+    if !quiet && times.len() > 1 {
+        let min = times.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = times.iter().copied().fold(0.0, f64::max);
+        let avg = times.iter().sum::<f64>() / times.len() as f64;
+
+        println!("{} pings min/avg/max = {min:.3}/{avg:.3}/{max:.3} ms", times.len());
+    }
 
     Ok(())
 }
 
-fn ping(client: &mut ApiClient) -> error::Result {
-    client.ping().context("failed to ping server")?;
+fn completions(args: CompletionsArgs) -> error::Result {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_owned();
 
-    println!("pong");
+    clap_complete::generate(args.shell, &mut cmd, name, &mut std::io::stdout());
 
     Ok(())
 }
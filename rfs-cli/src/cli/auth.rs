@@ -0,0 +1,226 @@
+use rfs_api::ApiErrorKind;
+use rfs_api::client::ApiClient;
+use rfs_api::client::users::password::UpdatePassword;
+use rfs_api::client::auth::session::{
+    RetrieveSessions,
+    RevokeSession,
+    RevokeAllSessions,
+};
+
+use clap::{Subcommand, Args};
+
+use crate::error::{self, Context};
+use crate::formatting::{self, TextTable, Column, DateFormat, PRETTY_OPTIONS};
+
+#[derive(Debug, Args)]
+pub struct AuthArgs {
+    #[command(subcommand)]
+    command: AuthCmds,
+}
+
+#[derive(Debug, Subcommand)]
+enum AuthCmds {
+    /// changes the password of the current user
+    Passwd,
+
+    /// interacts with the sessions of the current user
+    Sessions(SessionsArgs),
+}
+
+pub fn handle(client: &mut ApiClient, args: AuthArgs) -> error::Result {
+    match args.command {
+        AuthCmds::Passwd => passwd(client),
+        AuthCmds::Sessions(given) => handle_sessions(client, given),
+    }
+}
+
+/// prompts for the current and new password. the new password is checked
+/// before anything is sent to the server
+pub fn passwd(client: &mut ApiClient) -> error::Result {
+    let current = rpassword::prompt_password("current: ")?;
+    let mut updated;
+
+    loop {
+        updated = rpassword::prompt_password("updated: ")?;
+
+        if !rfs_lib::sec::authn::password_valid(&updated) {
+            println!(
+                "password must be between {} and {} characters and cannot contain control characters",
+                rfs_lib::sec::authn::MIN_PASSWORD_CHARS,
+                rfs_lib::sec::authn::MAX_PASSWORD_CHARS,
+            );
+            continue;
+        }
+
+        let confirm = rpassword::prompt_password("confirm: ")?;
+
+        if confirm != updated {
+            println!("updated and confirm do not match");
+        } else {
+            break;
+        }
+    }
+
+    let confirm = updated.clone();
+
+    if let Err(err) = UpdatePassword::update_password(current, updated, confirm).send(client) {
+        let api = err.as_api().context("failed to update password")?;
+
+        return match api.kind() {
+            ApiErrorKind::InvalidPassword => Err(error::Error::from("the current password is invalid")
+                .kind(error::ErrorKind::Auth)),
+            _ => Err(error::Error::from(api)),
+        };
+    }
+
+    if !formatting::quiet() {
+        println!("password updated");
+    }
+
+    // the server should keep the current session but if it was dropped then
+    // a new one is needed to continue using the cli
+    if let Err(err) = RetrieveSessions::new().send(client) {
+        let api = err.as_api().context("failed to check current session")?;
+
+        match api.kind() {
+            ApiErrorKind::Unauthenticated |
+            ApiErrorKind::InvalidSession |
+            ApiErrorKind::SessionExpired |
+            ApiErrorKind::SessionNotFound |
+            ApiErrorKind::SessionUnverified |
+            ApiErrorKind::SessionUnauthenticated => {
+                println!("session was invalidated. login with the new password");
+
+                super::connect(client)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Args)]
+struct SessionsArgs {
+    #[command(subcommand)]
+    command: SessionsCmds,
+}
+
+#[derive(Debug, Subcommand)]
+enum SessionsCmds {
+    /// lists the active sessions of the current user
+    List(ListArgs),
+
+    /// revokes the specified session
+    Revoke(RevokeArgs),
+
+    /// revokes all sessions except for the current one
+    RevokeAll,
+}
+
+fn handle_sessions(client: &ApiClient, args: SessionsArgs) -> error::Result {
+    match args.command {
+        SessionsCmds::List(given) => list(client, given),
+        SessionsCmds::Revoke(given) => revoke(client, given),
+        SessionsCmds::RevokeAll => revoke_all(client),
+    }
+}
+
+#[derive(Debug, Args)]
+struct ListArgs {
+    /// the format of timestamps
+    #[arg(long, default_value_t)]
+    ts_format: DateFormat,
+}
+
+fn list(client: &ApiClient, args: ListArgs) -> error::Result {
+    let sessions = RetrieveSessions::new()
+        .send(client)
+        .context("failed to retrieve sessions")?
+        .into_payload();
+
+    let mut table = TextTable::with_columns([
+        Column::builder("id").build(),
+        Column::builder("issued").build(),
+        Column::builder("expires").build(),
+        Column::builder("current").build(),
+    ]);
+
+    for session in sessions {
+        let mut row = table.add_row();
+        row.set_col(0, session.id.clone());
+        row.set_col(1, formatting::datetime_to_string(&session.issued_on, &args.ts_format));
+        row.set_col(2, formatting::datetime_to_string(&session.expires, &args.ts_format));
+
+        if session.current {
+            row.set_col(3, "*");
+        }
+
+        row.finish(session);
+    }
+
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
+        println!("no contents");
+    } else {
+        table.print(&PRETTY_OPTIONS)
+            .context("failed to output results to stdout")?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Args)]
+struct RevokeArgs {
+    /// id of the session to revoke
+    id: String,
+}
+
+fn revoke(client: &ApiClient, args: RevokeArgs) -> error::Result {
+    if crate::cli::dry_run() {
+        println!("dry run: session {} would be revoked", args.id);
+        return Ok(());
+    }
+
+    RevokeSession::id(args.id)
+        .send(client)
+        .context("failed to revoke session")?;
+
+    if !formatting::quiet() {
+        println!("session revoked");
+    }
+
+    Ok(())
+}
+
+fn revoke_all(client: &ApiClient) -> error::Result {
+    if crate::cli::dry_run() {
+        let sessions = RetrieveSessions::new()
+            .send(client)
+            .context("failed to retrieve sessions")?
+            .into_payload();
+
+        let mut count = 0;
+
+        for session in sessions.iter().filter(|session| !session.current) {
+            println!("dry run: session {} would be revoked", session.id);
+
+            count += 1;
+        }
+
+        println!("dry run: {count} sessions would be revoked");
+        return Ok(());
+    }
+
+    RevokeAllSessions::new()
+        .send(client)
+        .context("failed to revoke sessions")?;
+
+    if !formatting::quiet() {
+        println!("all other sessions revoked");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clap::{Subcommand, Args, ValueEnum};
+use serde::{Serialize, Deserialize};
+
+use crate::error::{self, Context};
+use crate::formatting::{self, OutputFormat};
+
+/// default values for the cli that are used when the associated flag is not
+/// provided
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secure: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputFormat>,
+}
+
+impl Config {
+    /// loads the config from the given path. if the file does not exist then
+    /// an empty config is returned
+    pub fn load(path: &Path) -> error::Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(err) => match err.kind() {
+                std::io::ErrorKind::NotFound => return Ok(Config::default()),
+                _ => return Err(error::Error::new()
+                    .context(format!("failed to read config file: \"{}\"", path.display()))
+                    .source(err))
+            }
+        };
+
+        toml::from_str(&contents)
+            .context(format!("failed to parse config file: \"{}\"", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> error::Result {
+        let contents = toml::to_string_pretty(self)
+            .context("failed to serialize config")?;
+
+        std::fs::write(path, contents)
+            .context(format!("failed to write config file: \"{}\"", path.display()))
+    }
+}
+
+/// resolves the config file path. if one is not provided then it will look
+/// for "rfs_config.toml" in the same directory as the cookies file
+pub fn resolve_path(given: Option<PathBuf>, cookies: &Path) -> PathBuf {
+    if let Some(path) = given {
+        return path;
+    }
+
+    match cookies.parent() {
+        Some(parent) => parent.join("rfs_config.toml"),
+        None => PathBuf::from("rfs_config.toml"),
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    command: Option<ConfigCmds>,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCmds {
+    /// sets a value in the config file
+    Set(SetArgs),
+
+    /// removes a value from the config file
+    Unset(UnsetArgs),
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum ConfigKey {
+    Host,
+    Port,
+    Secure,
+    Output,
+}
+
+pub fn handle(path: &Path, config: Config, args: ConfigArgs) -> error::Result {
+    if let Some(cmd) = args.command {
+        match cmd {
+            ConfigCmds::Set(given) => set(path, config, given),
+            ConfigCmds::Unset(given) => unset(path, config, given),
+        }
+    } else {
+        get(path, config)
+    }
+}
+
+fn get(path: &Path, config: Config) -> error::Result {
+    if formatting::json_output() {
+        formatting::print_json(&config)
+            .context("failed to output results to stdout")?;
+
+        return Ok(());
+    }
+
+    println!("file: \"{}\"", path.display());
+
+    if let Some(host) = config.host {
+        println!("host: {host}");
+    }
+
+    if let Some(port) = config.port {
+        println!("port: {port}");
+    }
+
+    if let Some(secure) = config.secure {
+        println!("secure: {secure}");
+    }
+
+    if let Some(output) = config.output {
+        println!("output: {output}");
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Args)]
+struct SetArgs {
+    /// the name of the value to set
+    key: ConfigKey,
+
+    /// the value to store
+    value: String,
+}
+
+fn set(path: &Path, mut config: Config, args: SetArgs) -> error::Result {
+    match args.key {
+        ConfigKey::Host => {
+            config.host = Some(args.value);
+        }
+        ConfigKey::Port => {
+            let port = u16::from_str(&args.value)
+                .context(format!("invalid port number. given: \"{}\"", args.value))?;
+
+            config.port = Some(port);
+        }
+        ConfigKey::Secure => {
+            let secure = bool::from_str(&args.value)
+                .context(format!("invalid secure value, expecting true or false. given: \"{}\"", args.value))?;
+
+            config.secure = Some(secure);
+        }
+        ConfigKey::Output => {
+            let Ok(output) = OutputFormat::from_str(&args.value, true) else {
+                return Err(format!("invalid output format. given: \"{}\"", args.value).into());
+            };
+
+            config.output = Some(output);
+        }
+    }
+
+    config.save(path)
+}
+
+#[derive(Debug, Args)]
+struct UnsetArgs {
+    /// the name of the value to remove
+    key: ConfigKey,
+}
+
+fn unset(path: &Path, mut config: Config, args: UnsetArgs) -> error::Result {
+    match args.key {
+        ConfigKey::Host => config.host = None,
+        ConfigKey::Port => config.port = None,
+        ConfigKey::Secure => config.secure = None,
+        ConfigKey::Output => config.output = None,
+    }
+
+    config.save(path)
+}
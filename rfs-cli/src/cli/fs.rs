@@ -1,15 +1,18 @@
 use std::path::PathBuf;
 use std::ffi::OsStr;
-use std::io::Seek;
 
 use rfs_api::client::ApiClient;
+use rfs_api::client::iterate;
 use rfs_api::client::fs::{
     CreateDir,
+    RetrieveContents,
     RetrieveItem,
     SendReadable,
     UpdateMetadata,
     DeleteItem,
+    RestoreItem,
 };
+use rfs_api::fs::{Item, ItemMin};
 use clap::{Subcommand, Args};
 
 use crate::error::{self, Context};
@@ -19,7 +22,10 @@ use crate::path::{normalize_from, metadata};
 
 mod get;
 mod download;
+mod find;
 mod storage;
+mod upload;
+pub mod sync;
 
 #[derive(Debug, Args)]
 pub struct FsArgs {
@@ -47,6 +53,12 @@ enum FsCmds {
     /// deletes the desired fs item
     Delete(DeleteArgs),
 
+    /// restores an fs item from the trash
+    Restore(RestoreArgs),
+
+    /// finds fs items by their tags
+    Find(find::FindArgs),
+
     /// interacts with storage mediums on a server
     Storage(storage::StorageArgs),
 }
@@ -59,6 +71,8 @@ pub fn handle(client: &ApiClient, args: FsArgs) -> error::Result {
             FsCmds::Update(given) => update(client, given),
             FsCmds::Upload(given) => upload(client, given),
             FsCmds::Delete(given) => delete(client, given),
+            FsCmds::Restore(given) => restore(client, given),
+            FsCmds::Find(given) => find::find(client, given),
             FsCmds::Storage(given) => storage::handle(client, given),
         }
     } else {
@@ -96,6 +110,22 @@ fn ext_mime(ext: &OsStr, fallback: Option<mime::Mime>) -> error::Result<mime::Mi
     }
 }
 
+fn file_mime(
+    file_path: &std::path::Path,
+    mime: Option<mime::Mime>,
+    fallback: Option<mime::Mime>
+) -> error::Result<mime::Mime> {
+    if let Some(given) = mime {
+        Ok(given)
+    } else if let Some(ext) = file_path.extension() {
+        ext_mime(ext, fallback)
+    } else if let Some(given) = fallback {
+        Ok(given)
+    } else {
+        Ok(mime::APPLICATION_OCTET_STREAM)
+    }
+}
+
 #[derive(Debug, Args)]
 struct CreateArgs {
     /// the parent fs item to create the new fs item under
@@ -125,16 +155,25 @@ enum CreateType {
     /// creates a directory
     Dir {
         /// basename of the new directory
-        basename: String
+        basename: String,
+
+        /// treats the basename as a relative path and creates any missing
+        /// parent directories
+        #[arg(short, long)]
+        parents: bool,
     }
 }
 
 fn create(client: &ApiClient, args: CreateArgs) -> error::Result {
     match args.create_type {
-        CreateType::Dir { basename } => {
+        CreateType::Dir { basename, parents } => {
             let mut stdout = std::io::stdout();
             let mut builder = CreateDir::basename(args.parent, basename);
 
+            if parents {
+                builder.recursive(true);
+            }
+
             if !args.tags.is_empty() {
                 builder.add_iter_tags(args.tags);
             }
@@ -171,6 +210,14 @@ struct UpdateArgs {
     #[arg(long, conflicts_with("comment"))]
     drop_comment: bool,
 
+    /// shares the fs item with the members of the given group
+    #[arg(long, conflicts_with("drop_group"))]
+    group: Option<rfs_lib::ids::GroupUid>,
+
+    /// removes the group of the given fs item
+    #[arg(long, conflicts_with("group"))]
+    drop_group: bool,
+
     #[command(flatten)]
     output_options: OutputOptions,
 }
@@ -207,6 +254,12 @@ fn update(client: &ApiClient, args: UpdateArgs) -> error::Result {
         builder.comment(String::new());
     }
 
+    if let Some(group) = args.group {
+        builder.group(group);
+    } else if args.drop_group {
+        builder.group(None);
+    }
+
     let result = builder.send(client)
         .context("failed to update fs item")?
         .into_payload();
@@ -224,6 +277,14 @@ struct UploadArgs {
     /// path of the file to upload
     path: PathBuf,
 
+    /// the fs item to upload into. directories will be uploaded recursively
+    #[arg(long)]
+    dest: Option<rfs_lib::ids::FSUid>,
+
+    /// overwrite files that already exist on the server
+    #[arg(long, requires("dest"))]
+    overwrite: bool,
+
     /// upload a hash of the file to the server to validate against
     #[arg(long)]
     hash: bool,
@@ -248,7 +309,7 @@ struct UploadArgs {
     output_options: OutputOptions,
 
     #[command(subcommand)]
-    upload_type: UploadType
+    upload_type: Option<UploadType>
 }
 
 #[derive(Debug, Subcommand)]
@@ -269,18 +330,28 @@ enum UploadType {
     }
 }
 
-fn get_hash(file: &std::fs::File) -> error::Result<blake3::Hash> {
-    let mut hasher = blake3::Hasher::new();
-
-    hasher.update_reader(file)
-        .context("error when creating hash of file")?;
-
-    Ok(hasher.finalize())
-}
-
 fn upload(client: &ApiClient, args: UploadArgs) -> error::Result {
     let cwd = cwd()?;
     let file_path = normalize_from(&cwd, args.path);
+
+    let upload_type = match (args.dest, args.upload_type) {
+        (Some(dest), None) => {
+            return upload::upload_tree(client, dest, file_path, upload::TreeOptions {
+                hash: args.hash,
+                overwrite: args.overwrite,
+                mime: args.mime,
+                fallback: args.fallback,
+            });
+        }
+        (None, Some(upload_type)) => upload_type,
+        (Some(_), Some(_)) => {
+            return Err("--dest cannot be used with an upload type".into());
+        }
+        (None, None) => {
+            return Err("an upload type or --dest must be specified".into());
+        }
+    };
+
     let metadata = metadata(&file_path)
         .context("failed to retrieve metadata for file")?
         .context("file not found")?;
@@ -295,7 +366,7 @@ fn upload(client: &ApiClient, args: UploadArgs) -> error::Result {
         .open(&file_path)
         .context("failed to open file")?;
 
-    let mut builder = match args.upload_type {
+    let mut builder = match upload_type {
         UploadType::New { parent, basename } => {
             let basename = basename.unwrap_or(path_basename(&file_path)?
                 .context("no basename was provided and the current file did not contain a file name")?);
@@ -308,24 +379,14 @@ fn upload(client: &ApiClient, args: UploadArgs) -> error::Result {
     };
 
     builder.content_length(metadata.len());
+    builder.mtime_from_metadata(&metadata)
+        .context("failed to retrieve modification time of file")?;
 
-    if let Some(given) = args.mime {
-        builder.content_type(given);
-    } else {
-        if let Some(ext) = file_path.extension() {
-            builder.content_type(ext_mime(ext, args.fallback)?);
-        } else if let Some(given) = args.fallback {
-            builder.content_type(given);
-        } else {
-            builder.content_type(mime::APPLICATION_OCTET_STREAM);
-        }
-    }
+    builder.content_type(file_mime(&file_path, args.mime, args.fallback)?);
 
     if args.hash {
-        builder.hash("blake3", get_hash(&file)?.to_string());
-
-        file.rewind()
-            .context("failed to reset file cursor after hashing")?;
+        builder.hash_reader(&mut file)
+            .context("error when creating hash of file")?;
     }
 
     let result = builder.send(client, file)
@@ -344,12 +405,119 @@ fn upload(client: &ApiClient, args: UploadArgs) -> error::Result {
 struct DeleteArgs {
     /// uid of the fs item to delete
     uid: rfs_lib::ids::FSUid,
+
+    /// permanently removes the item instead of moving it to the trash
+    #[arg(long)]
+    purge: bool,
 }
 
 fn delete(client: &ApiClient, args: DeleteArgs) -> error::Result {
-    DeleteItem::uid(args.uid)
-        .send(client)
+    if crate::cli::dry_run() {
+        return delete_dry_run(client, args);
+    }
+
+    let mut builder = DeleteItem::uid(args.uid);
+    builder.purge(args.purge);
+
+    builder.send(client)
         .context("failed to delete fs item")?;
 
     Ok(())
 }
+
+/// lists the items that would be removed by the delete without sending it
+fn delete_dry_run(client: &ApiClient, args: DeleteArgs) -> error::Result {
+    let item = RetrieveItem::uid(args.uid.clone())
+        .send(client)
+        .context("failed to retrieve the fs item")?
+        .context("desired fs item was not found")?
+        .into_payload();
+
+    let action = if args.purge {
+        "permanently removed"
+    } else {
+        "moved to the trash"
+    };
+
+    match item {
+        Item::File(file) => {
+            println!("dry run: file would be {action}");
+            println!("{}", file.basename);
+        }
+        Item::Directory(dir) => {
+            let mut files = 0;
+            let mut dirs = 1;
+
+            println!("dry run: directory and contents would be {action}");
+            println!("{}/", dir.basename);
+
+            list_tree(client, args.uid, &dir.basename, &mut files, &mut dirs)?;
+
+            println!("{dirs} directories, {files} files");
+        }
+        Item::Root(root) => {
+            println!("dry run: root \"{}\" would be {action}", root.basename);
+        }
+    }
+
+    Ok(())
+}
+
+/// prints every item under the given directory with its path relative to
+/// the directory being deleted
+fn list_tree(
+    client: &ApiClient,
+    uid: rfs_lib::ids::FSUid,
+    prefix: &str,
+    files: &mut u64,
+    dirs: &mut u64,
+) -> error::Result {
+    let mut builder = RetrieveContents::uid(uid);
+
+    for result in iterate::Iterate::new(client, &mut builder) {
+        let item = result.context("failed to retrieve fs item contents")?;
+
+        match item {
+            ItemMin::Directory(dir) => {
+                let path = format!("{prefix}/{}", dir.basename);
+
+                *dirs += 1;
+
+                println!("{path}/");
+
+                list_tree(client, dir.uid, &path, files, dirs)?;
+            }
+            ItemMin::File(file) => {
+                *files += 1;
+
+                println!("{prefix}/{}", file.basename);
+            }
+            ItemMin::Root(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Args)]
+struct RestoreArgs {
+    /// uid of the fs item to restore
+    uid: rfs_lib::ids::FSUid,
+
+    #[command(flatten)]
+    output_options: OutputOptions,
+}
+
+fn restore(client: &ApiClient, args: RestoreArgs) -> error::Result {
+    let result = RestoreItem::uid(args.uid)
+        .send(client)
+        .context("failed to restore fs item")?
+        .into_payload();
+
+    let mut stdout = std::io::stdout();
+
+    formatting::write_fs_item(&mut stdout, &result, &args.output_options)
+        .context("failed to output to stdout")?;
+
+    Ok(())
+}
@@ -1,11 +1,13 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[cfg(target_family = "unix")]
 use std::os::unix::fs::FileTypeExt;
 
 use rfs_lib::ids;
-use rfs_api::client::ApiClient;
-use rfs_api::client::fs::DownloadItem;
+use rfs_api::client::{ApiClient, iterate};
+use rfs_api::client::fs::{RetrieveItem, RetrieveContents, RetrieveData, DataReader};
+use rfs_api::fs::{Item, ItemMin};
 use clap::Args;
 use reqwest::header::HeaderMap;
 
@@ -22,10 +24,14 @@ pub struct DownloadArgs {
     /// the uid of the item to retrieve
     uid: ids::FSUid,
 
-    /// the output path for the file
-    #[arg(short, long)]
+    /// the output path for the file or directory
+    #[arg(short, long, visible_alias = "out")]
     output: Option<PathBuf>,
 
+    /// overwrite local files that already exist
+    #[arg(long)]
+    force: bool,
+
     #[command(flatten)]
     format_options: OutputOptions
 }
@@ -163,31 +169,34 @@ fn resolve_file_path(given: Option<PathBuf>, filename: &str) -> error::Result<Pa
     }
 }
 
-pub fn download(client: &ApiClient, mut args: DownloadArgs) -> error::Result {
-    let mut response = DownloadItem::uid(args.uid.clone())
-        .send(client)
-        .context("failed download file")?;
+fn write_data(
+    mut reader: DataReader,
+    output_path: &Path,
+    force: bool,
+) -> error::Result<(u64, Duration)> {
+    let checksum = get_checksum(reader.headers())?;
 
-    let headers = response.headers();
-    let filename = get_filename(headers)?;
-    let checksum = get_checksum(headers)?;
-
-    let output_path = resolve_file_path(args.output.take(), &filename)?;
+    if !force && metadata(output_path)
+        .context("failed to retrieve metadata for output path")?
+        .is_some()
+    {
+        return Err("output file already exists. use --force to overwrite".into());
+    }
 
     let mut hasher = blake3::Hasher::new();
     let mut output = std::fs::OpenOptions::new()
         .write(true)
         .truncate(true)
         .create(true)
-        .open(&output_path)
+        .open(output_path)
         .context("failed to open output file")?;
 
     let mut pipe = Pipe::new(&mut hasher, &mut output);
 
     let start = std::time::Instant::now();
 
-    let bytes_read = response.copy_to(&mut pipe)
-        .context("error when reading response")? as u64;
+    let bytes_read = std::io::copy(&mut reader, &mut pipe)
+        .context("error when reading response")?;
 
     let duration = start.elapsed();
 
@@ -199,17 +208,159 @@ pub fn download(client: &ApiClient, mut args: DownloadArgs) -> error::Result {
         }
     }
 
-    let bits_read = (bytes_read * 8) as u128;
-
-    let millis = duration.as_millis();
+    Ok((bytes_read, duration))
+}
 
-    let bits_per_sec = (bits_read / millis) * 1000;
+fn print_transfer(path: &Path, bytes_read: u64, duration: Duration, options: &OutputOptions) {
+    let millis = duration.as_millis().max(1);
+    let bits_per_sec = ((bytes_read * 8) as u128 * 1000) / millis;
 
     println!(
-        "{} {duration:#?} {}",
-        formatting::bytes_to_unit(bytes_read, &args.format_options.size_format),
+        "downloaded {} {} {duration:#?} {}",
+        path.display(),
+        formatting::bytes_to_unit(bytes_read, &options.size_format),
         formatting::value_to_unit(bits_per_sec as u64, &BaseSize::Base10, "b/s"),
     );
+}
+
+fn retrieve_data(client: &ApiClient, uid: ids::FSUid) -> error::Result<DataReader> {
+    RetrieveData::uid(uid)
+        .send(client)
+        .context("failed download file")?
+        .context("file not found")
+}
+
+pub fn download(client: &ApiClient, mut args: DownloadArgs) -> error::Result {
+    let item = RetrieveItem::uid(args.uid.clone())
+        .send(client)
+        .context("failed to retrieve fs item")?
+        .context("fs item not found")?
+        .into_payload();
+
+    match item {
+        Item::File(_) => {
+            let reader = retrieve_data(client, args.uid.clone())?;
+            let filename = get_filename(reader.headers())?;
+
+            let output_path = resolve_file_path(args.output.take(), &filename)?;
+
+            let (bytes_read, duration) = write_data(reader, &output_path, args.force)?;
+
+            print_transfer(&output_path, bytes_read, duration, &args.format_options);
+
+            Ok(())
+        }
+        Item::Directory(dir) => download_tree(client, dir.uid, dir.basename, args),
+        Item::Root(root) => download_tree(client, root.uid, root.basename, args),
+    }
+}
+
+fn resolve_dir_path(given: Option<PathBuf>, basename: &str) -> error::Result<PathBuf> {
+    let curr_dir = std::env::current_dir()
+        .context("failed to retrieve current working directory")?;
+
+    if let Some(given) = given {
+        let resolved = normalize_from(&curr_dir, given);
+
+        if let Some(metadata) = metadata(&resolved)
+            .context("failed to resolve the output path")?
+        {
+            if metadata.is_dir() {
+                Ok(resolved.join(basename))
+            } else {
+                Err("output path is not a directory".into())
+            }
+        } else {
+            Ok(resolved)
+        }
+    } else {
+        Ok(curr_dir.join(basename))
+    }
+}
+
+fn download_tree(
+    client: &ApiClient,
+    uid: ids::FSUid,
+    basename: String,
+    mut args: DownloadArgs,
+) -> error::Result {
+    let root = resolve_dir_path(args.output.take(), &basename)?;
+
+    std::fs::create_dir_all(&root)
+        .context("failed to create output directory")?;
+
+    let mut downloaded: usize = 0;
+    let mut failed: Vec<(PathBuf, error::Error)> = Vec::new();
+    let mut stack = vec![(uid, root, PathBuf::from(basename))];
+
+    while let Some((remote, local, relative)) = stack.pop() {
+        let mut builder = RetrieveContents::uid(remote);
+
+        for result in iterate::Iterate::new(client, &mut builder) {
+            let item = match result {
+                Ok(item) => item,
+                Err(err) => {
+                    let err = error::Error::from(err)
+                        .context("failed to retrieve directory contents");
+
+                    println!("failed {}: {err}", relative.display());
+
+                    failed.push((relative.clone(), err));
+
+                    break;
+                }
+            };
+
+            match item {
+                ItemMin::Directory(dir) => {
+                    let dir_local = local.join(&dir.basename);
+                    let dir_relative = relative.join(&dir.basename);
+
+                    if let Err(err) = std::fs::create_dir_all(&dir_local) {
+                        let err = error::Error::from(err)
+                            .context("failed to create directory");
+
+                        println!("failed {}: {err}", dir_relative.display());
+
+                        failed.push((dir_relative, err));
+
+                        continue;
+                    }
+
+                    stack.push((dir.uid, dir_local, dir_relative));
+                }
+                ItemMin::File(file) => {
+                    let file_local = local.join(&file.basename);
+                    let file_relative = relative.join(&file.basename);
+
+                    let result = retrieve_data(client, file.uid)
+                        .and_then(|reader| write_data(reader, &file_local, args.force));
+
+                    match result {
+                        Ok((bytes_read, duration)) => {
+                            print_transfer(&file_relative, bytes_read, duration, &args.format_options);
+
+                            downloaded += 1;
+                        }
+                        Err(err) => {
+                            println!("failed {}: {err}", file_relative.display());
+
+                            failed.push((file_relative, err));
+                        }
+                    }
+                }
+                ItemMin::Root(_) => {}
+            }
+        }
+    }
+
+    if !formatting::quiet() || !failed.is_empty() {
+        println!("downloaded: {downloaded} failed: {}", failed.len());
+    }
+
+    for (path, err) in &failed {
+        println!("    {}: {err}", path.display());
+    }
 
     Ok(())
 }
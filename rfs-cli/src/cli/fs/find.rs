@@ -0,0 +1,151 @@
+use rfs_lib::query::{Limit, Offset};
+use rfs_api::fs::ItemMin;
+use rfs_api::client::{ApiClient, iterate};
+use rfs_api::client::fs::SearchItems;
+use clap::Args;
+
+use crate::error::{self, Context};
+use crate::util;
+use crate::formatting::{self, OutputOptions, TextTable, Column, Float, PRETTY_OPTIONS};
+
+use super::get::sort_item;
+
+#[derive(Debug, Args)]
+pub struct FindArgs {
+    /// a tag that the items must have
+    ///
+    /// given as "name" or "name:value". can be specified multiple times and
+    /// items must have all of the tags given
+    #[arg(
+        long,
+        required_unless_present_any(["tag_prefix", "name"]),
+        value_parser(util::parse_tag)
+    )]
+    tag: Vec<util::Tag>,
+
+    /// a prefix that a tag of the items must start with
+    ///
+    /// given as "prefix" or "prefix:value". can be specified multiple times
+    #[arg(long, value_parser(util::parse_tag))]
+    tag_prefix: Vec<util::Tag>,
+
+    /// compares the values of tags without case
+    #[arg(long)]
+    ignore_case: bool,
+
+    /// text that the basename of the items must contain
+    ///
+    /// the comparison ignores case. tags are optional when given
+    #[arg(long)]
+    name: Option<String>,
+
+    /// only finds items in the given storage medium
+    #[arg(long)]
+    storage: Option<rfs_lib::ids::StorageUid>,
+
+    /// the number of items to retrieve. one of 25, 50, or 100
+    ///
+    /// if a limit or offset is not given then all matching items will be
+    /// retrieved
+    #[arg(long, value_parser(util::parse_limit))]
+    limit: Option<Limit>,
+
+    /// the page of items to retrieve
+    #[arg(long)]
+    offset: Option<Offset>,
+
+    #[command(flatten)]
+    output_options: OutputOptions,
+}
+
+pub fn find(client: &ApiClient, args: FindArgs) -> error::Result {
+    let mut builder = SearchItems::new();
+    builder.add_iter_tags(args.tag)
+        .ignore_case(args.ignore_case);
+
+    for (prefix, value) in args.tag_prefix {
+        builder.tag_prefix(prefix, value);
+    }
+
+    if let Some(name) = args.name {
+        builder.name(name);
+    }
+
+    if let Some(storage) = args.storage {
+        builder.storage(storage);
+    }
+
+    let mut table = TextTable::with_columns([
+        Column::builder("type").build(),
+        Column::builder("uid").float(Float::Right).build(),
+        Column::builder("storage id").float(Float::Right).build(),
+        Column::builder("size").float(Float::Right).build(),
+        Column::builder("path").build(),
+        Column::builder("mod").float(Float::Right).build(),
+    ]);
+
+    let found = if args.limit.is_some() || args.offset.is_some() {
+        builder.limit(args.limit)
+            .offset(args.offset)
+            .send(client)
+            .context("failed to search for fs items")?
+            .into_payload()
+    } else {
+        let mut rtn = Vec::new();
+
+        for result in iterate::Iterate::new(client, &mut builder) {
+            rtn.push(result.context("failed to search for fs items")?);
+        }
+
+        rtn
+    };
+
+    for item in found {
+        let mut row = table.add_row();
+
+        match &item {
+            ItemMin::Root(root) => {
+                let time = root.updated.as_ref().unwrap_or(&root.created);
+
+                row.set_col(0, "root");
+                row.set_col(1, root.uid.clone());
+                row.set_col(2, root.storage_uid.clone());
+                row.set_col(4, root.basename.clone());
+                row.set_col(5, formatting::datetime_to_string(&time, &args.output_options.ts_format));
+            }
+            ItemMin::Directory(dir) => {
+                let time = dir.updated.as_ref().unwrap_or(&dir.created);
+
+                row.set_col(0, "dir");
+                row.set_col(1, dir.uid.clone());
+                row.set_col(2, dir.storage_uid.clone());
+                row.set_col(4, format!("{}/{}", dir.path, dir.basename));
+                row.set_col(5, formatting::datetime_to_string(&time, &args.output_options.ts_format));
+            }
+            ItemMin::File(file) => {
+                let time = file.updated.as_ref().unwrap_or(&file.created);
+
+                row.set_col(0, "file");
+                row.set_col(1, file.uid.clone());
+                row.set_col(2, file.storage_uid.clone());
+                row.set_col(3, formatting::bytes_to_unit(file.size, &args.output_options.size_format));
+                row.set_col(4, format!("{}/{}", file.path, file.basename));
+                row.set_col(5, formatting::datetime_to_string(&time, &args.output_options.ts_format));
+            }
+        }
+
+        row.finish_sort_by(item, sort_item);
+    }
+
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
+        println!("no items found");
+    } else {
+        table.print(&PRETTY_OPTIONS)
+            .context("failed to output results to stdout")?;
+    }
+
+    Ok(())
+}
@@ -27,7 +27,7 @@ pub struct GetArgs {
     output_options: OutputOptions
 }
 
-fn sort_item(a: &ItemMin, b: &ItemMin) -> bool {
+pub fn sort_item(a: &ItemMin, b: &ItemMin) -> bool {
     match (a, b) {
         (ItemMin::Root(a_root), ItemMin::Root(b_root)) =>
             match a_root.basename.cmp(&b_root.basename) {
@@ -66,6 +66,10 @@ fn retrieve_id(client: &ApiClient, uid: ids::FSUid, args: GetArgs) -> error::Res
         .context("desired fs item was not found")?
         .into_payload();
 
+    if formatting::json_output() {
+        return retrieve_id_json(client, uid, result, args);
+    }
+
     match result {
         Item::Root(root) => {
             formatting::write_fs_root(&mut stdout, &root, &args.output_options)
@@ -132,7 +136,10 @@ fn retrieve_id(client: &ApiClient, uid: ids::FSUid, args: GetArgs) -> error::Res
             row.finish_sort_by(item, sort_item);
         }
 
-        if table.is_empty() {
+        if formatting::json_output() {
+            table.print_json()
+                .context("failed to output results to stdout")?;
+        } else if table.is_empty() {
             println!("no contents");
         } else {
             table.write(&mut stdout, &PRETTY_OPTIONS)
@@ -143,6 +150,39 @@ fn retrieve_id(client: &ApiClient, uid: ids::FSUid, args: GetArgs) -> error::Res
     Ok(())
 }
 
+/// outputs the item and its contents as a single json object so that the
+/// output can be parsed as one value
+fn retrieve_id_json(client: &ApiClient, uid: ids::FSUid, item: Item, args: GetArgs) -> error::Result {
+    if args.no_contents || matches!(item, Item::File(_)) {
+        formatting::print_json(&item)
+            .context("failed to output to stdout")?;
+
+        return Ok(());
+    }
+
+    let mut builder = RetrieveContents::uid(uid);
+    let mut contents = Vec::new();
+
+    for result in iterate::Iterate::new(client, &mut builder) {
+        contents.push(result.context("failed to retrieve fs item contents")?);
+    }
+
+    contents.sort_by(|a, b| if sort_item(b, a) {
+        Ordering::Less
+    } else if sort_item(a, b) {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    });
+
+    formatting::print_json(&serde_json::json!({
+        "item": item,
+        "contents": contents,
+    })).context("failed to output to stdout")?;
+
+    Ok(())
+}
+
 fn retrieve_roots(client: &ApiClient, args: GetArgs) -> error::Result {
     let mut builder = RetrieveRoots::new();
     let mut table = TextTable::with_columns([
@@ -174,7 +214,10 @@ fn retrieve_roots(client: &ApiClient, args: GetArgs) -> error::Result {
         row.finish_sort_by(item, sort_item);
     }
 
-    if table.is_empty() {
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
         println!("no roots");
     } else {
         table.print(&PRETTY_OPTIONS)
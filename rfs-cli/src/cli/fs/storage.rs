@@ -1,13 +1,18 @@
 use std::path::PathBuf;
 
+use rfs_lib::query::{Limit, Offset};
 use rfs_api::client::{ApiClient, iterate};
 use rfs_api::client::fs::storage::{
     QueryStorage,
+    SearchStorage,
     CreateStorage,
     RetrieveStorage,
     UpdateStorage,
+    RelocateStorage,
 };
+use rfs_api::ApiErrorKind;
 use rfs_api::fs::{
+    Storage,
     StorageMin,
     backend,
 };
@@ -33,6 +38,10 @@ enum StorageCmds {
     Create(CreateArgs),
     /// updates an existing storage medium
     Update(UpdateArgs),
+    /// updates the path of a storage medium after it has been moved
+    Relocate(RelocateArgs),
+    /// finds storage mediums by their tags
+    Find(FindArgs),
 }
 
 pub fn handle(client: &ApiClient, args: StorageArgs) -> error::Result {
@@ -40,6 +49,8 @@ pub fn handle(client: &ApiClient, args: StorageArgs) -> error::Result {
         match cmd {
             StorageCmds::Create(given) => create(client, given),
             StorageCmds::Update(given) => update(client, given),
+            StorageCmds::Relocate(given) => relocate(client, given),
+            StorageCmds::Find(given) => find(client, given),
         }
     } else {
         get(client, args.get)
@@ -60,6 +71,40 @@ struct GetArgs {
     output_options: OutputOptions,
 }
 
+fn print_storage(storage: Storage, options: &OutputOptions) -> error::Result {
+    if formatting::json_output() {
+        formatting::print_json(&storage)
+            .context("failed to output results to stdout")?;
+
+        return Ok(());
+    }
+
+    println!("{} {}", storage.name, storage.uid);
+    println!("owner: {}", storage.user_uid);
+    println!("created: {}", formatting::datetime_to_string(&storage.created, &options.ts_format));
+
+    if let Some(updated) = storage.updated {
+        println!("updated: {}", formatting::datetime_to_string(&updated, &options.ts_format));
+    }
+
+    match storage.backend {
+        backend::Config::Local(local) => {
+            println!("backend: Local");
+            println!("    path: \"{}\"", local.path.display());
+            println!("    dedup: {}", local.dedup);
+            println!("    compression: {}", local.compression);
+
+            if let Some(tmp) = &local.tmp {
+                println!("    tmp: \"{}\"", tmp.display());
+            }
+        }
+    }
+
+    println!("{}", WriteTags::new(&storage.tags));
+
+    Ok(())
+}
+
 fn get(client: &ApiClient, args: GetArgs) -> error::Result {
     if let Some(uid) = args.uid {
         let found = RetrieveStorage::uid(uid)
@@ -68,22 +113,7 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             .context("storage id not found")?
             .into_payload();
 
-        println!("{} {}", found.name, found.uid);
-        println!("owner: {}", found.user_uid);
-        println!("created: {}", formatting::datetime_to_string(&found.created, &args.output_options.ts_format));
-
-        if let Some(updated) = found.updated {
-            println!("updated: {}", formatting::datetime_to_string(&updated, &args.output_options.ts_format));
-        }
-
-        match found.backend {
-            backend::Config::Local(local) => {
-                println!("backend: Local");
-                println!("    path: \"{}\"", local.path.display());
-            }
-        }
-
-        println!("{}", WriteTags::new(&found.tags));
+        print_storage(found, &args.output_options)?;
     } else {
         let mut builder = QueryStorage::new();
         let mut table = TextTable::with_columns([
@@ -112,7 +142,10 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             row.finish_sort_by(item, sort_storage);
         }
 
-        if table.is_empty() {
+        if formatting::json_output() {
+            table.print_json()
+                .context("failed to output results to stdout")?;
+        } else if table.is_empty() {
             println!("no contents");
         } else {
             table.print(&PRETTY_OPTIONS)
@@ -148,14 +181,50 @@ enum CreateType {
     Local {
         /// path on the server to create the local storage
         #[arg(long)]
-        path: PathBuf
+        path: PathBuf,
+
+        /// store identical file contents only once
+        #[arg(long)]
+        dedup: bool,
+
+        /// directory that uploads are written to before being moved into
+        /// place
+        ///
+        /// should be on the same filesystem as the path. defaults to ".tmp"
+        /// in the root of the path
+        #[arg(long)]
+        tmp: Option<PathBuf>,
+
+        /// compress file contents before writing them to disk
+        #[arg(long)]
+        compression: bool,
     }
 }
 
 fn create(client: &ApiClient, args: CreateArgs) -> error::Result<()> {
+    if !rfs_lib::fs::storage::name_valid(&args.name) {
+        return Err(error::Error::new().context("invalid storage name provided"));
+    }
+
     match args.create_type {
-        CreateType::Local { path } => {
+        CreateType::Local { path, dedup, tmp, compression } => {
+            if !path.is_absolute() {
+                return Err(error::Error::new()
+                    .context("the path for local storage must be absolute"));
+            }
+
             let mut builder = CreateStorage::local(args.name, path);
+            builder.dedup(dedup);
+            builder.compression(compression);
+
+            if let Some(tmp) = tmp {
+                if !tmp.is_absolute() {
+                    return Err(error::Error::new()
+                        .context("the tmp path for local storage must be absolute"));
+                }
+
+                builder.tmp(tmp);
+            }
 
             if let Some(comment) = args.comment {
                 builder.comment(comment);
@@ -163,11 +232,26 @@ fn create(client: &ApiClient, args: CreateArgs) -> error::Result<()> {
 
             builder.add_iter_tags(args.tags);
 
-            let result = builder.send(client)
-                .context("failed to create storage")?
-                .into_payload();
+            let result = match builder.send(client) {
+                Ok(payload) => payload.into_payload(),
+                Err(err) => {
+                    let api = err.as_api()
+                        .context("failed to create storage")?;
+
+                    let msg = match api.kind() {
+                        ApiErrorKind::AlreadyExists => "a storage medium with the given name already exists",
+                        ApiErrorKind::ValidationFailed => "the server rejected the given name or tags",
+                        ApiErrorKind::NotAbsolutePath => "the given path is not absolute on the server",
+                        ApiErrorKind::DirNotFound => "the given path was not found on the server",
+                        ApiErrorKind::NotDirectory => "the given path is not a directory on the server",
+                        _ => return Err(error::Error::from(api).context("failed to create storage")),
+                    };
+
+                    return Err(error::Error::new().context(msg));
+                }
+            };
 
-            println!("{:#?}", result);
+            print_storage(result, &OutputOptions::default())?;
         }
     }
 
@@ -190,6 +274,14 @@ struct UpdateArgs {
     /// renames the given storage medium
     #[arg(long)]
     rename: Option<String>,
+
+    /// enables or disables content deduplication
+    #[arg(long)]
+    dedup: Option<bool>,
+
+    /// enables or disables compression of new file contents
+    #[arg(long)]
+    compression: Option<bool>,
 }
 
 fn update(client: &ApiClient, args: UpdateArgs) -> error::Result<()> {
@@ -212,6 +304,14 @@ fn update(client: &ApiClient, args: UpdateArgs) -> error::Result<()> {
         builder.name(rename);
     }
 
+    if let Some(dedup) = args.dedup {
+        builder.dedup(dedup);
+    }
+
+    if let Some(compression) = args.compression {
+        builder.compression(compression);
+    }
+
     if let Some(tags) = args.tags {
         builder.add_iter_tags(tags.merge_existing(current.tags));
     }
@@ -220,7 +320,124 @@ fn update(client: &ApiClient, args: UpdateArgs) -> error::Result<()> {
         .context("failed to update storage")?
         .into_payload();
 
-    println!("{:#?}", result);
+    print_storage(result, &OutputOptions::default())
+}
+
+#[derive(Debug, Args)]
+struct RelocateArgs {
+    /// uid of the storage medium to relocate
+    uid: rfs_lib::ids::StorageUid,
+
+    /// the new absolute path of the storage medium
+    path: PathBuf,
+
+    /// checks that a sample of files exist under the new path first
+    #[arg(long)]
+    verify: bool,
+}
+
+fn relocate(client: &ApiClient, args: RelocateArgs) -> error::Result<()> {
+    if !args.path.is_absolute() {
+        return Err(error::Error::new()
+            .context("the path for local storage must be absolute"));
+    }
+
+    let mut builder = RelocateStorage::local(args.uid, args.path);
+    builder.verify(args.verify);
+
+    let result = builder.send(client)
+        .context("failed to relocate storage")?
+        .into_payload();
+
+    print_storage(result, &OutputOptions::default())
+}
+
+#[derive(Debug, Args)]
+struct FindArgs {
+    /// a tag that the storage mediums must have
+    ///
+    /// given as "name" or "name:value". can be specified multiple times and
+    /// mediums must have all of the tags given
+    #[arg(long, required_unless_present("tag_prefix"), value_parser(util::parse_tag))]
+    tag: Vec<util::Tag>,
+
+    /// a prefix that a tag of the mediums must start with
+    ///
+    /// given as "prefix" or "prefix:value". can be specified multiple times
+    #[arg(long, value_parser(util::parse_tag))]
+    tag_prefix: Vec<util::Tag>,
+
+    /// compares the values of tags without case
+    #[arg(long)]
+    ignore_case: bool,
+
+    /// the number of mediums to retrieve. one of 25, 50, or 100
+    ///
+    /// if a limit or offset is not given then all matching mediums will be
+    /// retrieved
+    #[arg(long, value_parser(util::parse_limit))]
+    limit: Option<Limit>,
+
+    /// the page of mediums to retrieve
+    #[arg(long)]
+    offset: Option<Offset>,
+}
+
+fn find(client: &ApiClient, args: FindArgs) -> error::Result {
+    let mut builder = SearchStorage::new();
+    builder.add_iter_tags(args.tag)
+        .ignore_case(args.ignore_case);
+
+    for (prefix, value) in args.tag_prefix {
+        builder.tag_prefix(prefix, value);
+    }
+
+    let mut table = TextTable::with_columns([
+        Column::builder("uid").float(Float::Right).build(),
+        Column::builder("name").build(),
+        Column::builder("type").build(),
+    ]);
+
+    let found = if args.limit.is_some() || args.offset.is_some() {
+        builder.limit(args.limit)
+            .offset(args.offset)
+            .send(client)
+            .context("failed to search for storage")?
+            .into_payload()
+    } else {
+        let mut rtn = Vec::new();
+
+        for result in iterate::Iterate::new(client, &mut builder) {
+            rtn.push(result.context("failed to search for storage")?);
+        }
+
+        rtn
+    };
+
+    for item in found {
+        let mut row = table.add_row();
+
+        row.set_col(0, item.uid.clone());
+        row.set_col(1, item.name.clone());
+
+        match &item.backend {
+            backend::Config::Local(_) => {
+                row.set_col(2, "Local");
+            }
+        }
+
+        row.finish_sort_by(item, sort_storage);
+    }
+
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
+        println!("no storage found");
+    } else {
+        table.print(&PRETTY_OPTIONS)
+            .context("failed to output results to stdout")?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,339 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rfs_lib::ids;
+use rfs_api::client::ApiClient;
+use rfs_api::client::fs::{CreateDir, DeleteItem, RetrieveSyncPlan, SendReadable};
+use rfs_api::fs::{Item, ItemMin};
+use rfs_api::fs::sync::{SyncItem, SyncPlan};
+use clap::Args;
+
+use crate::error::{self, Context};
+use crate::formatting;
+use crate::path::normalize_from;
+
+use super::upload::remote_contents;
+
+#[derive(Debug, Args)]
+pub struct SyncArgs {
+    /// the local directory to sync from
+    local_dir: PathBuf,
+
+    /// the uid of the directory on the server to sync to
+    uid: ids::FSUid,
+
+    /// removes items on the server that do not exist locally
+    ///
+    /// without this the items are only reported. items are moved to the
+    /// trash on the server
+    #[arg(long)]
+    delete: bool,
+}
+
+/// a file found in the local directory
+struct LocalFile {
+    path: PathBuf,
+    hash: blake3::Hash,
+    size: u64,
+}
+
+#[derive(Default)]
+struct Summary {
+    uploaded: usize,
+    deleted: usize,
+    failed: Vec<(String, error::Error)>,
+}
+
+/// walks the local directory and hashes every file found. the returned map
+/// is keyed by the path relative to the directory with "/" separators
+fn build_manifest(root: &Path) -> error::Result<HashMap<String, LocalFile>> {
+    let mut rtn = HashMap::new();
+    let mut stack = vec![(root.to_path_buf(), String::new())];
+
+    while let Some((local, relative)) = stack.pop() {
+        let read_dir = std::fs::read_dir(&local)
+            .context(format!("failed to read directory. {}", local.display()))?;
+
+        for entry in read_dir {
+            let entry = entry.context("failed to read directory entry")?;
+            let entry_path = entry.path();
+
+            let Some(name) = entry.file_name().to_str().map(|name| name.to_owned()) else {
+                println!("WARNING: skipping non utf-8 path {}", entry_path.display());
+
+                continue;
+            };
+
+            let entry_relative = if relative.is_empty() {
+                name
+            } else {
+                format!("{relative}/{name}")
+            };
+
+            let file_type = entry.file_type()
+                .context(format!("failed to retrieve file type. {}", entry_path.display()))?;
+
+            if file_type.is_symlink() {
+                println!("WARNING: skipping symlink {entry_relative}");
+            } else if file_type.is_dir() {
+                stack.push((entry_path, entry_relative));
+            } else if file_type.is_file() {
+                let mut file = std::fs::File::open(&entry_path)
+                    .context(format!("failed to open file. {}", entry_path.display()))?;
+                let mut hasher = blake3::Hasher::new();
+
+                let size = std::io::copy(&mut file, &mut hasher)
+                    .context(format!("failed to hash file. {}", entry_path.display()))?;
+
+                rtn.insert(entry_relative, LocalFile {
+                    path: entry_path,
+                    hash: hasher.finalize(),
+                    size,
+                });
+            } else {
+                println!("WARNING: skipping unknown file type {entry_relative}");
+            }
+        }
+    }
+
+    Ok(rtn)
+}
+
+/// resolves directory paths relative to the directory being synced to their
+/// uids on the server, creating any that are missing
+struct RemoteDirs<'a> {
+    client: &'a ApiClient,
+    known: HashMap<String, ids::FSUid>,
+    listed: HashSet<String>,
+}
+
+impl<'a> RemoteDirs<'a> {
+    fn new(client: &'a ApiClient, root: ids::FSUid) -> Self {
+        RemoteDirs {
+            client,
+            known: HashMap::from([(String::new(), root)]),
+            listed: HashSet::new(),
+        }
+    }
+
+    fn resolve(&mut self, path: &str) -> error::Result<ids::FSUid> {
+        if let Some(uid) = self.known.get(path) {
+            return Ok(uid.clone());
+        }
+
+        let (parent_path, name) = path.rsplit_once('/')
+            .unwrap_or(("", path));
+
+        let parent = self.resolve(parent_path)?;
+
+        if self.listed.insert(parent_path.to_owned()) {
+            for (basename, item) in remote_contents(self.client, &parent)? {
+                let child_path = if parent_path.is_empty() {
+                    basename
+                } else {
+                    format!("{parent_path}/{basename}")
+                };
+
+                if let ItemMin::Directory(dir) = item {
+                    self.known.insert(child_path, dir.uid);
+                }
+            }
+
+            if let Some(uid) = self.known.get(path) {
+                return Ok(uid.clone());
+            }
+        }
+
+        let created = CreateDir::basename(parent, name)
+            .send(self.client)
+            .context(format!("failed to create directory. {path}"))?
+            .into_payload();
+
+        let Item::Directory(dir) = created else {
+            return Err("server did not respond with a directory".into());
+        };
+
+        self.known.insert(path.to_owned(), dir.uid.clone());
+
+        Ok(dir.uid)
+    }
+}
+
+fn upload_file(
+    client: &ApiClient,
+    dirs: &mut RemoteDirs,
+    item: &SyncItem,
+    local: &LocalFile,
+) -> error::Result {
+    let mut builder = if let Some(uid) = &item.uid {
+        SendReadable::update(uid.clone())
+    } else {
+        let (parent_path, basename) = item.path.rsplit_once('/')
+            .unwrap_or(("", item.path.as_str()));
+
+        SendReadable::create(dirs.resolve(parent_path)?, basename)
+    };
+
+    let file = std::fs::File::open(&local.path)
+        .context("failed to open file")?;
+    let metadata = file.metadata()
+        .context("failed to retrieve metadata for file")?;
+
+    builder.content_length(local.size);
+    builder.mtime_from_metadata(&metadata)
+        .context("failed to retrieve modification time of file")?;
+    builder.content_type(super::file_mime(&local.path, None, None)?);
+    builder.hash("blake3", local.hash.to_hex().as_str());
+
+    builder.send(client, file)
+        .context("failed to upload file")?;
+
+    Ok(())
+}
+
+fn print_plan(plan: &SyncPlan, delete: bool) -> error::Result {
+    if formatting::json_output() {
+        return formatting::print_json(plan)
+            .context("failed to output to stdout");
+    }
+
+    println!("dry run: no changes will be made");
+
+    for item in &plan.upload {
+        if item.uid.is_some() {
+            println!("update {}", item.path);
+        } else {
+            println!("create {}", item.path);
+        }
+    }
+
+    if delete {
+        for item in &plan.delete {
+            println!("delete {}", item.path);
+        }
+    }
+
+    for path in &plan.conflicts {
+        println!("conflict {path}");
+    }
+
+    println!(
+        "upload: {} delete: {} unchanged: {} conflicts: {}",
+        plan.upload.len(),
+        if delete { plan.delete.len() } else { 0 },
+        plan.unchanged,
+        plan.conflicts.len(),
+    );
+
+    if !delete && !plan.delete.is_empty() {
+        println!("{} items only exist on the server. use --delete to remove them", plan.delete.len());
+    }
+
+    Ok(())
+}
+
+/// makes the contents of a directory on the server match a local directory.
+/// a manifest of the local files is sent to the server and the returned plan
+/// is then carried out
+pub fn sync(client: &ApiClient, args: SyncArgs) -> error::Result {
+    let cwd = super::cwd()?;
+    let root = normalize_from(&cwd, args.local_dir);
+
+    if !std::fs::metadata(&root)
+        .context("failed to retrieve metadata for local directory")?
+        .is_dir()
+    {
+        return Err("the provided local path is not a directory".into());
+    }
+
+    let local = build_manifest(&root)?;
+
+    let mut builder = RetrieveSyncPlan::uid(args.uid.clone());
+
+    for (path, file) in &local {
+        builder.add_entry(path.clone(), file.hash.to_hex().as_str(), file.size);
+    }
+
+    let plan = builder.send(client)
+        .context("failed to retrieve sync plan")?
+        .into_payload();
+
+    if crate::cli::dry_run() {
+        return print_plan(&plan, args.delete);
+    }
+
+    let quiet = formatting::quiet();
+    let mut summary = Summary::default();
+    let mut dirs = RemoteDirs::new(client, args.uid);
+    let total = plan.upload.len();
+
+    for (index, item) in plan.upload.iter().enumerate() {
+        let result = local.get(&item.path)
+            .context("server requested a file that is not in the manifest")
+            .and_then(|file| upload_file(client, &mut dirs, item, file));
+
+        match result {
+            Ok(()) => {
+                if !quiet {
+                    println!("[{}/{total}] uploaded {}", index + 1, item.path);
+                }
+
+                summary.uploaded += 1;
+            }
+            Err(err) => {
+                println!("[{}/{total}] failed {}: {err}", index + 1, item.path);
+
+                summary.failed.push((item.path.clone(), err));
+            }
+        }
+    }
+
+    if args.delete {
+        for item in &plan.delete {
+            let Some(uid) = &item.uid else {
+                continue;
+            };
+
+            match DeleteItem::uid(uid.clone()).send(client) {
+                Ok(()) => {
+                    if !quiet {
+                        println!("deleted {}", item.path);
+                    }
+
+                    summary.deleted += 1;
+                }
+                Err(err) => {
+                    let err = error::Error::from(err)
+                        .context("failed to delete fs item");
+
+                    println!("failed {}: {err}", item.path);
+
+                    summary.failed.push((item.path.clone(), err));
+                }
+            }
+        }
+    } else if !quiet && !plan.delete.is_empty() {
+        println!("{} items only exist on the server. use --delete to remove them", plan.delete.len());
+    }
+
+    for path in &plan.conflicts {
+        println!("WARNING: conflict {path}");
+    }
+
+    if !quiet || !summary.failed.is_empty() {
+        println!(
+            "uploaded: {} deleted: {} unchanged: {} conflicts: {} failed: {}",
+            summary.uploaded,
+            summary.deleted,
+            plan.unchanged,
+            plan.conflicts.len(),
+            summary.failed.len(),
+        );
+    }
+
+    for (path, err) in &summary.failed {
+        println!("    {path}: {err}");
+    }
+
+    Ok(())
+}
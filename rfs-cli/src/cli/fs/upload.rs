@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use rfs_lib::ids;
+use rfs_api::client::{ApiClient, iterate};
+use rfs_api::client::fs::{CreateDir, RetrieveContents, SendReadable};
+use rfs_api::fs::ItemMin;
+
+use crate::error::{self, Context};
+use crate::formatting;
+
+pub struct TreeOptions {
+    pub hash: bool,
+    pub overwrite: bool,
+    pub mime: Option<mime::Mime>,
+    pub fallback: Option<mime::Mime>,
+}
+
+#[derive(Default)]
+struct Summary {
+    uploaded: usize,
+    dirs: usize,
+    skipped: usize,
+    failed: Vec<(PathBuf, error::Error)>,
+}
+
+enum FileResult {
+    Uploaded,
+    Skipped,
+}
+
+fn item_basename(item: &ItemMin) -> &str {
+    match item {
+        ItemMin::Root(root) => &root.basename,
+        ItemMin::Directory(dir) => &dir.basename,
+        ItemMin::File(file) => &file.basename,
+    }
+}
+
+pub(super) fn remote_contents(client: &ApiClient, uid: &ids::FSUid) -> error::Result<HashMap<String, ItemMin>> {
+    let mut builder = RetrieveContents::uid(uid.clone());
+    let mut rtn = HashMap::new();
+
+    for result in iterate::Iterate::new(client, &mut builder) {
+        let item = result.context("failed to retrieve contents of remote directory")?;
+
+        rtn.insert(item_basename(&item).to_owned(), item);
+    }
+
+    Ok(rtn)
+}
+
+fn file_name(path: &Path) -> error::Result<String> {
+    super::path_basename(&path.to_path_buf())?
+        .context("the provided path does not contain a file name")
+}
+
+fn send_file(
+    client: &ApiClient,
+    parent: &ids::FSUid,
+    existing: Option<&ItemMin>,
+    path: &Path,
+    options: &TreeOptions,
+) -> error::Result<FileResult> {
+    let basename = file_name(path)?;
+
+    let mut builder = match existing {
+        Some(ItemMin::File(file)) => {
+            if !options.overwrite {
+                return Ok(FileResult::Skipped);
+            }
+
+            SendReadable::update(file.uid.clone())
+        }
+        Some(_) => {
+            return Err("a non file item already exists on the server".into());
+        }
+        None => SendReadable::create(parent.clone(), basename)
+    };
+
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .open(path)
+        .context("failed to open file")?;
+    let metadata = file.metadata()
+        .context("failed to retrieve metadata for file")?;
+
+    builder.content_length(metadata.len());
+    builder.mtime_from_metadata(&metadata)
+        .context("failed to retrieve modification time of file")?;
+    builder.content_type(super::file_mime(path, options.mime.clone(), options.fallback.clone())?);
+
+    if options.hash {
+        builder.hash_reader(&mut file)
+            .context("error when creating hash of file")?;
+    }
+
+    builder.send(client, file)
+        .context("failed to upload file")?;
+
+    Ok(FileResult::Uploaded)
+}
+
+fn ensure_dir(
+    client: &ApiClient,
+    parent: &ids::FSUid,
+    existing: Option<&ItemMin>,
+    basename: String,
+    summary: &mut Summary,
+) -> error::Result<ids::FSUid> {
+    match existing {
+        Some(ItemMin::Directory(dir)) => Ok(dir.uid.clone()),
+        Some(_) => Err("a non directory item already exists on the server".into()),
+        None => {
+            let created = CreateDir::basename(parent.clone(), basename)
+                .send(client)
+                .context("failed to create directory")?
+                .into_payload();
+
+            summary.dirs += 1;
+
+            match created {
+                rfs_api::fs::Item::Directory(dir) => Ok(dir.uid),
+                _ => Err("server did not respond with a directory".into())
+            }
+        }
+    }
+}
+
+fn record_file(summary: &mut Summary, relative: PathBuf, result: error::Result<FileResult>) {
+    match result {
+        Ok(FileResult::Uploaded) => {
+            if !formatting::quiet() {
+                println!("uploaded {}", relative.display());
+            }
+
+            summary.uploaded += 1;
+        }
+        Ok(FileResult::Skipped) => {
+            if !formatting::quiet() {
+                println!("skipped {}: already exists", relative.display());
+            }
+
+            summary.skipped += 1;
+        }
+        Err(err) => {
+            println!("failed {}: {err}", relative.display());
+
+            summary.failed.push((relative, err));
+        }
+    }
+}
+
+/// uploads the given path into the destination fs item. directories are
+/// walked recursively and recreated on the server with the same structure.
+pub fn upload_tree(
+    client: &ApiClient,
+    dest: ids::FSUid,
+    path: PathBuf,
+    options: TreeOptions,
+) -> error::Result {
+    let metadata = std::fs::symlink_metadata(&path)
+        .context("failed to retrieve metadata for path")?;
+
+    if metadata.is_symlink() {
+        return Err("the provided path is a symlink".into());
+    }
+
+    let mut summary = Summary::default();
+    let basename = file_name(&path)?;
+    let contents = remote_contents(client, &dest)?;
+
+    if metadata.is_file() {
+        let result = send_file(client, &dest, contents.get(&basename), &path, &options);
+
+        record_file(&mut summary, PathBuf::from(basename), result);
+    } else if metadata.is_dir() {
+        let root = ensure_dir(client, &dest, contents.get(&basename), basename.clone(), &mut summary)?;
+        let mut stack = vec![(path, root, PathBuf::from(basename))];
+
+        while let Some((local, remote, relative)) = stack.pop() {
+            let contents = match remote_contents(client, &remote) {
+                Ok(c) => c,
+                Err(err) => {
+                    println!("failed {}: {err}", relative.display());
+
+                    summary.failed.push((relative, err));
+
+                    continue;
+                }
+            };
+
+            let mut entries = Vec::new();
+
+            match std::fs::read_dir(&local) {
+                Ok(read_dir) => for entry in read_dir {
+                    match entry {
+                        Ok(entry) => entries.push(entry),
+                        Err(err) => {
+                            println!("failed {}: {err}", relative.display());
+                        }
+                    }
+                }
+                Err(err) => {
+                    let err = error::Error::new()
+                        .context("failed to read directory")
+                        .source(err);
+
+                    println!("failed {}: {err}", relative.display());
+
+                    summary.failed.push((relative, err));
+
+                    continue;
+                }
+            }
+
+            entries.sort_by_key(|e| e.file_name());
+
+            for entry in entries {
+                let entry_path = entry.path();
+                let entry_relative = relative.join(entry.file_name());
+
+                let file_type = match entry.file_type() {
+                    Ok(t) => t,
+                    Err(err) => {
+                        let err = error::Error::new()
+                            .context("failed to retrieve file type")
+                            .source(err);
+
+                        println!("failed {}: {err}", entry_relative.display());
+
+                        summary.failed.push((entry_relative, err));
+
+                        continue;
+                    }
+                };
+
+                if file_type.is_symlink() {
+                    println!("WARNING: skipping symlink {}", entry_relative.display());
+
+                    summary.skipped += 1;
+                } else if file_type.is_dir() {
+                    let result = file_name(&entry_path).and_then(|name| {
+                        let existing = contents.get(&name);
+
+                        ensure_dir(client, &remote, existing, name, &mut summary)
+                    });
+
+                    match result {
+                        Ok(uid) => stack.push((entry_path, uid, entry_relative)),
+                        Err(err) => {
+                            println!("failed {}: {err}", entry_relative.display());
+
+                            summary.failed.push((entry_relative, err));
+                        }
+                    }
+                } else if file_type.is_file() {
+                    let result = file_name(&entry_path).and_then(|name| {
+                        send_file(client, &remote, contents.get(&name), &entry_path, &options)
+                    });
+
+                    record_file(&mut summary, entry_relative, result);
+                } else {
+                    println!("WARNING: skipping unknown file type {}", entry_relative.display());
+
+                    summary.skipped += 1;
+                }
+            }
+        }
+    } else {
+        return Err("the provided path is not a file or directory".into());
+    }
+
+    if !formatting::quiet() || !summary.failed.is_empty() {
+        println!(
+            "uploaded: {} directories created: {} skipped: {} failed: {}",
+            summary.uploaded,
+            summary.dirs,
+            summary.skipped,
+            summary.failed.len()
+        );
+    }
+
+    for (path, err) in &summary.failed {
+        println!("    {}: {err}", path.display());
+    }
+
+    Ok(())
+}
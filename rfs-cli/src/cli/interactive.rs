@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use rfs_api::client::ApiClient;
+use clap::Parser;
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use crate::error::{self, Context};
+
+use super::Cmds;
+
+/// commands available when in interactive mode. "quit" and "exit" are
+/// handled before parsing
+#[derive(Debug, Parser)]
+#[command(no_binary_name = true)]
+struct Interactive {
+    #[command(subcommand)]
+    command: Cmds,
+}
+
+/// resolves the history file path. it will be "rfs_history.txt" in the same
+/// directory as the cookies file
+pub fn resolve_path(cookies: &Path) -> PathBuf {
+    match cookies.parent() {
+        Some(parent) => parent.join("rfs_history.txt"),
+        None => PathBuf::from("rfs_history.txt"),
+    }
+}
+
+/// reads commands from stdin until "quit", "exit", or EOF is given. Ctrl-C
+/// will cancel the current line. errors from commands are printed and will
+/// not end the loop
+pub fn run(client: &mut ApiClient, config_file: &Path, history_file: &Path) -> error::Result {
+    let mut editor = DefaultEditor::new()
+        .context("failed to create line editor")?;
+
+    if history_file.try_exists()? {
+        editor.load_history(history_file)
+            .context(format!("failed to load history file: \"{}\"", history_file.display()))?;
+    }
+
+    loop {
+        let line = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(error::Error::new()
+                .context("failed to read line from stdin")
+                .source(err)),
+        };
+
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        editor.add_history_entry(trimmed)
+            .context("failed to add line to history")?;
+
+        if trimmed == "quit" || trimmed == "exit" {
+            break;
+        }
+
+        let words = match shell_words::split(trimmed) {
+            Ok(words) => words,
+            Err(err) => {
+                println!("invalid command. {}", err);
+                continue;
+            }
+        };
+
+        let interactive = match Interactive::try_parse_from(words) {
+            Ok(interactive) => interactive,
+            Err(err) => {
+                // prints help and version output as well as parse errors
+                let _ = err.print();
+                continue;
+            }
+        };
+
+        if let Err(err) = super::dispatch(client, config_file, interactive.command) {
+            println!("{}", err);
+        }
+    }
+
+    editor.save_history(history_file)
+        .context(format!("failed to save history file: \"{}\"", history_file.display()))?;
+
+    Ok(())
+}
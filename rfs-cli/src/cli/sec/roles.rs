@@ -16,12 +16,13 @@ use rfs_api::client::sec::roles::{
     RetrieveRole,
     UpdateRole
 };
+use rfs_api::sec::roles::Role;
 use rfs_lib::sec::authz::permission::{Scope, Ability};
 
 use clap::{Subcommand, Args};
 
 use crate::error::{self, Context};
-use crate::formatting::{TextTable, Column, Float, PRETTY_OPTIONS};
+use crate::formatting::{self, TextTable, Column, Float, PRETTY_OPTIONS};
 
 #[derive(Debug, Args)]
 pub struct RolesArgs {
@@ -71,6 +72,37 @@ struct GetArgs {
     uid: Option<ids::RoleUid>,
 }
 
+fn print_role(role: Role) -> error::Result {
+    if formatting::json_output() {
+        formatting::print_json(&role)
+            .context("failed to output results to stdout")?;
+
+        return Ok(());
+    }
+
+    let mut table = TextTable::with_columns([
+        Column::builder("scope").build(),
+        Column::builder("ability").build()
+    ]);
+
+    println!("uid: {}\nname: \"{}\"", role.uid, role.name);
+
+    for perm in role.permissions {
+        let mut row = table.add_row();
+        row.set_col(0, perm.scope.as_str());
+        row.set_col(1, perm.ability.as_str());
+
+        row.finish_sort(perm);
+    }
+
+    if !table.is_empty() {
+        table.print(&PRETTY_OPTIONS)
+            .context("failed to output results to stdout")?;
+    }
+
+    Ok(())
+}
+
 fn get(client: &ApiClient, args: GetArgs) -> error::Result {
     if let Some(uid) = args.uid {
         let result = RetrieveRole::uid(uid)
@@ -78,26 +110,7 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             .context("failed to retrieve role")?;
 
         if let Some(payload) = result {
-            let inner = payload.into_payload();
-            let mut table = TextTable::with_columns([
-                Column::builder("scope").build(),
-                Column::builder("ability").build()
-            ]);
-
-            println!("uid: {}\nname: \"{}\"", inner.uid, inner.name);
-
-            for perm in inner.permissions {
-                let mut row = table.add_row();
-                row.set_col(0, perm.scope.as_str());
-                row.set_col(1, perm.ability.as_str());
-
-                row.finish_sort(perm);
-            }
-
-            if !table.is_empty() {
-                table.print(&PRETTY_OPTIONS)
-                    .context("failed to output results to stdout")?;
-            }
+            print_role(payload.into_payload())?;
         } else {
             println!("role not found");
         }
@@ -117,7 +130,10 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             row.finish(role);
         }
 
-        if table.is_empty() {
+        if formatting::json_output() {
+            table.print_json()
+                .context("failed to output results to stdout")?;
+        } else if table.is_empty() {
             println!("no contents");
         } else {
             table.print(&PRETTY_OPTIONS)
@@ -162,9 +178,7 @@ fn create(client: &ApiClient, args: CreateArgs) -> error::Result {
         .context("failed to create role")?
         .into_payload();
 
-    println!("{:?}", result);
-
-    Ok(())
+    print_role(result)
 }
 
 #[derive(Debug, Args)]
@@ -244,9 +258,7 @@ fn update(client: &ApiClient, args: UpdateArgs) -> error::Result {
         .context("failed to update role")?
         .into_payload();
 
-    println!("{:#?}", result);
-
-    Ok(())
+    print_role(result)
 }
 
 #[derive(Debug, Args)]
@@ -257,6 +269,36 @@ struct DeleteArgs {
 }
 
 fn delete(client: &ApiClient, args: DeleteArgs) -> error::Result {
+    if crate::cli::dry_run() {
+        let Some(role) = RetrieveRole::uid(args.uid.clone())
+            .send(client)
+            .context("failed to retrieve role")? else {
+            println!("role not found");
+            return Ok(());
+        };
+
+        let role = role.into_payload();
+        let mut users = 0;
+        let mut groups = 0;
+
+        for result in iterate::Iterate::new(client, &mut QueryRoleUsers::uid(args.uid.clone())) {
+            result.context("failed to retrieve role users")?;
+
+            users += 1;
+        }
+
+        for result in iterate::Iterate::new(client, &mut QueryRoleGroups::uid(args.uid)) {
+            result.context("failed to retrieve role groups")?;
+
+            groups += 1;
+        }
+
+        println!("dry run: role \"{}\" {} would be deleted", role.name, role.uid);
+        println!("{users} users and {groups} groups would be detached");
+
+        return Ok(());
+    }
+
     DeleteRole::uid(args.uid)
         .send(client)
         .context("failed to delete role")?;
@@ -316,7 +358,10 @@ fn get_users(client: &ApiClient, args: GetUsersArgs) -> error::Result {
         row.finish(user);
     }
 
-    if table.is_empty() {
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
         println!("no contents");
     } else {
         table.print(&PRETTY_OPTIONS)
@@ -358,6 +403,22 @@ struct DropUsersArgs {
 }
 
 fn drop_users(client: &ApiClient, args: DropUsersArgs) -> error::Result {
+    if crate::cli::dry_run() {
+        let given: HashSet<ids::UserUid> = HashSet::from_iter(args.user);
+
+        println!("dry run: users that would be dropped from the role");
+
+        for result in iterate::Iterate::new(client, &mut QueryRoleUsers::uid(args.uid)) {
+            let user = result.context("failed to retrieve role users")?;
+
+            if given.contains(&user.uid) {
+                println!("{}", user.uid);
+            }
+        }
+
+        return Ok(());
+    }
+
     let mut builder = DropRoleUsers::uid(args.uid);
     builder.add_iter_id(args.user);
     builder.send(client)
@@ -418,7 +479,10 @@ fn get_groups(client: &ApiClient, args: GetGroupsArgs) -> error::Result {
         row.finish(group);
     }
 
-    if table.is_empty() {
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
         println!("no contents");
     } else {
         table.print(&PRETTY_OPTIONS)
@@ -460,6 +524,22 @@ struct DropGroupsArgs {
 }
 
 fn drop_groups(client: &ApiClient, args: DropGroupsArgs) -> error::Result {
+    if crate::cli::dry_run() {
+        let given: HashSet<ids::GroupUid> = HashSet::from_iter(args.group);
+
+        println!("dry run: groups that would be dropped from the role");
+
+        for result in iterate::Iterate::new(client, &mut QueryRoleGroups::uid(args.uid)) {
+            let group = result.context("failed to retrieve role groups")?;
+
+            if given.contains(&group.uid) {
+                println!("{}", group.uid);
+            }
+        }
+
+        return Ok(());
+    }
+
     let mut builder = DropRoleGroups::uid(args.uid);
     builder.add_iter_id(args.group);
     builder.send(client)
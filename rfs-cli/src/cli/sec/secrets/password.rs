@@ -9,7 +9,7 @@ use rfs_api::client::sec::secrets::{
 use clap::{Subcommand, Args};
 
 use crate::error::{self, Context};
-use crate::formatting::{TextTable, Column, Float, PRETTY_OPTIONS};
+use crate::formatting::{self, TextTable, Column, Float, PRETTY_OPTIONS};
 
 #[derive(Debug, Args)]
 pub struct PasswordArgs {
@@ -56,7 +56,12 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
 
         let payload = result.into_payload();
 
-        println!("version: {} | used by: {}\ncreated: {}", payload.version, payload.in_use, payload.created);
+        if formatting::json_output() {
+            formatting::print_json(&payload)
+                .context("failed to output results to stdout")?;
+        } else {
+            println!("version: {} | used by: {}\ncreated: {}", payload.version, payload.in_use, payload.created);
+        }
     } else {
         let result = QueryPasswordSecrets::new()
             .send(client)
@@ -65,16 +70,25 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
         let mut table = TextTable::with_columns([
             Column::builder("version").float(Float::Right).build(),
             Column::builder("created").float(Float::Right).build(),
+            Column::builder("newest").build(),
         ]);
 
         for secret in result {
             let mut row = table.add_row();
             row.set_col(0, secret.version);
             row.set_col(1, secret.created);
+
+            if secret.newest {
+                row.set_col(2, "*");
+            }
+
             row.finish(secret);
         }
 
-        if table.is_empty() {
+        if formatting::json_output() {
+            table.print_json()
+                .context("failed to output results to stdout")?;
+        } else if table.is_empty() {
             println!("no secrets");
         } else {
             table.print(&PRETTY_OPTIONS)
@@ -101,6 +115,11 @@ struct RemoveArgs {
 }
 
 fn remove(client: &ApiClient, args: RemoveArgs) -> error::Result {
+    if crate::cli::dry_run() {
+        println!("dry run: password secret version {} would be removed", args.version);
+        return Ok(());
+    }
+
     DeletePasswordSecret::version(args.version)
         .send(client)
         .context("failed to remove password secret")?;
@@ -3,12 +3,13 @@ use rfs_api::client::sec::secrets::{
     CreateSessionSecret,
     DeleteSessionSecret,
     QuerySessionSecrets,
+    RetireSessionSecret,
 };
 
 use clap::{Subcommand, Args};
 
 use crate::error::{self, Context};
-use crate::formatting::{TextTable, Column, Float, PRETTY_OPTIONS};
+use crate::formatting::{self, TextTable, Column, Float, PRETTY_OPTIONS};
 
 #[derive(Debug, Args)]
 pub struct SessionArgs {
@@ -22,7 +23,14 @@ enum SessionCmds {
     Update,
 
     /// removes the oldest session secret
-    Remove
+    Remove,
+
+    /// removes a specific session secret. sessions created with the secret
+    /// will no longer be valid
+    Retire {
+        /// id of the session secret
+        id: String,
+    },
 }
 
 pub fn handle(client: &ApiClient, args: SessionArgs) -> error::Result {
@@ -30,6 +38,7 @@ pub fn handle(client: &ApiClient, args: SessionArgs) -> error::Result {
         match cmd {
             SessionCmds::Update => update(client),
             SessionCmds::Remove => remove(client),
+            SessionCmds::Retire { id } => retire(client, id),
         }
     } else {
         get(client)
@@ -42,16 +51,27 @@ fn get(client: &ApiClient) -> error::Result {
         .context("failed to retrieve session secrets")?
         .into_payload();
     let mut table = TextTable::with_columns([
-        Column::builder("created").float(Float::Right).build()
+        Column::builder("id").build(),
+        Column::builder("created").float(Float::Right).build(),
+        Column::builder("newest").build(),
     ]);
 
     for secret in result {
         let mut row = table.add_row();
-        row.set_col(0, secret.created);
+        row.set_col(0, secret.id.clone());
+        row.set_col(1, secret.created);
+
+        if secret.newest {
+            row.set_col(2, "*");
+        }
+
         row.finish(secret);
     }
 
-    if table.is_empty() {
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
         println!("no secrets");
     } else {
         table.print(&PRETTY_OPTIONS)
@@ -70,9 +90,27 @@ fn update(client: &ApiClient) -> error::Result {
 }
 
 fn remove(client: &ApiClient) -> error::Result {
+    if crate::cli::dry_run() {
+        println!("dry run: 1 session secret would be removed");
+        return Ok(());
+    }
+
     DeleteSessionSecret::amount(1)
         .send(client)
         .context("failed to remove session secret")?;
 
     Ok(())
 }
+
+fn retire(client: &ApiClient, id: String) -> error::Result {
+    if crate::cli::dry_run() {
+        println!("dry run: session secret {id} would be retired");
+        return Ok(());
+    }
+
+    RetireSessionSecret::id(id)
+        .send(client)
+        .context("failed to retire session secret")?;
+
+    Ok(())
+}
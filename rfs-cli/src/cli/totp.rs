@@ -5,11 +5,12 @@ use rfs_api::client::users::totp::{
     DeleteTotp,
     UpdateTotp,
 };
-use rfs_api::users::totp::Algo;
+use rfs_api::users::totp::{Algo, Totp};
 
 use clap::{Subcommand, Args, ValueEnum};
 
 use crate::error::{self, Context};
+use crate::formatting::{self, HexString};
 
 mod recovery;
 
@@ -64,13 +65,28 @@ pub fn handle(client: &ApiClient, args: TotpArgs) -> error::Result {
     }
 }
 
+fn print_totp(totp: Totp) -> error::Result {
+    if formatting::json_output() {
+        formatting::print_json(&totp)
+            .context("failed to output results to stdout")?;
+    } else {
+        println!("algo: {}", totp.algo);
+        println!("digits: {}", totp.digits);
+        println!("step: {}", totp.step);
+        println!("window: {} before, {} after", totp.window_before, totp.window_after);
+        println!("secret: {}", HexString::new(&totp.secret));
+    }
+
+    Ok(())
+}
+
 fn get(client: &ApiClient) -> error::Result {
     let result = RetrieveTotp::new()
         .send(client)
         .context("failed to retrieve totp data")?;
 
     if let Some(payload) = result {
-        println!("{:?}", payload.into_payload());
+        print_totp(payload.into_payload())?;
     } else {
         println!("no totp 2FA enabled");
     }
@@ -112,12 +128,15 @@ fn enable(client: &ApiClient, args: EnableArgs) -> error::Result {
         .context("failed to enable totp 2FA")?
         .into_payload();
 
-    println!("{:?}", result);
-
-    Ok(())
+    print_totp(result)
 }
 
 fn disable(client: &ApiClient) -> error::Result {
+    if crate::cli::dry_run() {
+        println!("dry run: totp 2FA and its recovery keys would be removed");
+        return Ok(());
+    }
+
     DeleteTotp::new()
         .send(client)
         .context("failed to disable totp 2FA")?;
@@ -165,7 +184,5 @@ fn update(client: &ApiClient, args: UpdateArgs) -> error::Result {
         .context("failed to update totp")?
         .into_payload();
 
-    println!("{:?}", result);
-
-    Ok(())
+    print_totp(result)
 }
@@ -1,16 +1,18 @@
+use rfs_api::ApiErrorKind;
 use rfs_api::users::totp::TotpRecovery;
 use rfs_api::client::ApiClient;
 use rfs_api::client::users::totp::{
     RetrieveTotpRecovery,
     CreateTotpRecovery,
     UpdateTotpRecovery,
+    RegenTotpRecovery,
     DeleteTotpRecovery,
 };
 
 use clap::{Subcommand, Args};
 
 use crate::error::{self, Context};
-use crate::formatting::{TextTable, Column, PRETTY_OPTIONS};
+use crate::formatting::{self, TextTable, Column, PRETTY_OPTIONS};
 
 #[derive(Debug, Args)]
 pub struct RecoveryArgs {
@@ -26,6 +28,9 @@ enum RecoveryCmds {
     /// updates a recovery code
     Update(UpdateArgs),
 
+    /// regenerates a recovery code so that it can be used again
+    Regen(RegenArgs),
+
     /// deletes a recovery code
     Delete(DeleteArgs)
 }
@@ -35,6 +40,7 @@ pub fn handle(client: &ApiClient, args: RecoveryArgs) -> error::Result {
         match cmd {
             RecoveryCmds::Create(given) => create(client, given),
             RecoveryCmds::Update(given) => update(client, given),
+            RecoveryCmds::Regen(given) => regen(client, given),
             RecoveryCmds::Delete(given) => delete(client, given),
         }
     } else {
@@ -42,7 +48,14 @@ pub fn handle(client: &ApiClient, args: RecoveryArgs) -> error::Result {
     }
 }
 
-fn print_recovery(recovery: &TotpRecovery) {
+fn print_recovery(recovery: &TotpRecovery) -> error::Result {
+    if formatting::json_output() {
+        formatting::print_json(recovery)
+            .context("failed to output results to stdout")?;
+
+        return Ok(());
+    }
+
     print!("{} ", recovery.key);
 
     if recovery.used {
@@ -50,6 +63,8 @@ fn print_recovery(recovery: &TotpRecovery) {
     }
 
     println!("{}", recovery.hash);
+
+    Ok(())
 }
 
 fn sort_recovery(a: &TotpRecovery, b: &TotpRecovery) -> bool {
@@ -67,7 +82,7 @@ fn get(client: &ApiClient) -> error::Result {
         Column::builder("hash").build(),
     ]);
 
-    for recovery in result {
+    for recovery in result.keys {
         let mut row = table.add_row();
         row.set_col(0, recovery.key.clone());
         row.set_col(1, recovery.used);
@@ -75,11 +90,16 @@ fn get(client: &ApiClient) -> error::Result {
         row.finish_sort_by(recovery, sort_recovery);
     }
 
-    if table.is_empty() {
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
         println!("no recovery keys");
     } else {
         table.print(&PRETTY_OPTIONS)
             .context("failed to output to stdout")?;
+
+        println!("{} of {} recovery keys", result.count, result.max);
     }
 
     Ok(())
@@ -93,14 +113,23 @@ struct CreateArgs {
 }
 
 fn create(client: &ApiClient, args: CreateArgs) -> error::Result {
-    let result = CreateTotpRecovery::key(args.key)
-        .send(client)
-        .context("failed to create totp recovery key")?
-        .into_payload();
-
-    print_recovery(&result);
+    let result = match CreateTotpRecovery::key(args.key).send(client) {
+        Ok(payload) => payload.into_payload(),
+        Err(err) => {
+            let api = err.as_api()
+                .context("failed to create totp recovery key")?;
+
+            let msg = match api.kind() {
+                ApiErrorKind::AlreadyExists => "a recovery key with the given key already exists",
+                ApiErrorKind::TotpRecoveryLimit => "the max number of recovery keys has been reached. remove an existing key to create a new one",
+                _ => return Err(error::Error::from(api).context("failed to create totp recovery key")),
+            };
+
+            return Err(error::Error::new().context(msg));
+        }
+    };
 
-    Ok(())
+    print_recovery(&result)
 }
 
 #[derive(Debug, Args)]
@@ -131,9 +160,23 @@ fn update(client: &ApiClient, args: UpdateArgs) -> error::Result {
         .context("failed to update totp recovery key")?
         .into_payload();
 
-    print_recovery(&result);
+    print_recovery(&result)
+}
 
-    Ok(())
+#[derive(Debug, Args)]
+struct RegenArgs {
+    /// the desired key to regenerate
+    #[arg(long)]
+    key: String
+}
+
+fn regen(client: &ApiClient, args: RegenArgs) -> error::Result {
+    let result = RegenTotpRecovery::key(args.key)
+        .send(client)
+        .context("failed to regenerate totp recovery key")?
+        .into_payload();
+
+    print_recovery(&result)
 }
 
 #[derive(Debug, Args)]
@@ -144,6 +187,11 @@ struct DeleteArgs {
 }
 
 fn delete(client: &ApiClient, args: DeleteArgs) -> error::Result {
+    if crate::cli::dry_run() {
+        println!("dry run: totp recovery key \"{}\" would be deleted", args.key);
+        return Ok(());
+    }
+
     DeleteTotpRecovery::key(args.key)
         .send(client)
         .context("failed to delete totp recovery key")?;
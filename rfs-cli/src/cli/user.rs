@@ -10,12 +10,12 @@ use rfs_api::client::users::{
     UpdateUser,
 };
 use rfs_api::client::users::groups::QueryGroupUsers;
-use rfs_api::users::ListItem;
+use rfs_api::users::{User, ListItem};
 
 use clap::{Subcommand, Args};
 
 use crate::error::{self, Context};
-use crate::formatting::{TextTable, Column, Float, PRETTY_OPTIONS};
+use crate::formatting::{self, TextTable, Column, Float, PRETTY_OPTIONS};
 
 mod group;
 
@@ -60,6 +60,25 @@ fn sort_user(a: &ListItem, b: &ListItem) -> bool {
     }
 }
 
+fn print_user(user: User) -> error::Result {
+    if formatting::json_output() {
+        formatting::print_json(&user)
+            .context("failed to output results to stdout")?;
+    } else {
+        println!("{} {}", user.uid, user.username);
+
+        if let Some(email) = user.email {
+            println!(
+                "email: {} {}",
+                email.email,
+                if email.verified { "verified" } else { "unverified" }
+            );
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Args)]
 struct GetArgs {
     /// retrieves information about a single user
@@ -82,15 +101,7 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             .context("user not found")?
             .into_payload();
 
-        println!("{} {}", user.uid, user.username);
-
-        if let Some(email) = user.email {
-            println!(
-                "email: {} {}",
-                email.email,
-                if email.verified { "verified" } else { "unverified" }
-            );
-        }
+        print_user(user)?;
     } else if let Some(group) = args.group {
         let mut builder = QueryGroupUsers::uid(group);
         let mut table = TextTable::with_columns([
@@ -105,7 +116,10 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             row.finish(user);
         }
 
-        if table.is_empty() {
+        if formatting::json_output() {
+            table.print_json()
+                .context("failed to output results to stdout")?;
+        } else if table.is_empty() {
             println!("no contents");
         } else {
             table.print(&PRETTY_OPTIONS)
@@ -127,7 +141,10 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             row.finish_sort_by(user, sort_user);
         }
 
-        if table.is_empty() {
+        if formatting::json_output() {
+            table.print_json()
+                .context("failed to output results to stdout")?;
+        } else if table.is_empty() {
             println!("no contents");
         } else {
             table.print(&PRETTY_OPTIONS)
@@ -172,9 +189,7 @@ fn create(client: &ApiClient, args: CreateArgs) -> error::Result<()> {
         .context("failed to create new user")?
         .into_payload();
 
-    println!("{:?}", result);
-
-    Ok(())
+    print_user(result)
 }
 
 #[derive(Debug, Args)]
@@ -213,7 +228,5 @@ fn update(client: &ApiClient, args: UpdateArgs) -> error::Result<()> {
         .context("failed to update desired user")?
         .into_payload();
 
-    println!("{:?}", result);
-
-    Ok(())
+    print_user(result)
 }
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use rfs_lib::ids;
 use rfs_api::client::{ApiClient, iterate};
 use rfs_api::client::users::groups::{
@@ -10,11 +12,12 @@ use rfs_api::client::users::groups::{
     AddUsers,
     DropUsers,
 };
+use rfs_api::users::groups::Group;
 
 use clap::{Subcommand, Args};
 
 use crate::error::{self, Context};
-use crate::formatting::{TextTable, Column, Float, PRETTY_OPTIONS};
+use crate::formatting::{self, TextTable, Column, Float, PRETTY_OPTIONS};
 
 #[derive(Debug, Args)]
 pub struct GroupsArgs {
@@ -60,6 +63,22 @@ struct GetArgs {
     uid: Option<ids::GroupUid>,
 }
 
+fn print_group(group: Group) -> error::Result {
+    if formatting::json_output() {
+        formatting::print_json(&group)
+            .context("failed to output results to stdout")?;
+    } else {
+        println!("{} {}", group.uid, group.name);
+        println!("created: {}", group.created);
+
+        if let Some(updated) = group.updated {
+            println!("updated: {updated}");
+        }
+    }
+
+    Ok(())
+}
+
 fn get(client: &ApiClient, args: GetArgs) -> error::Result {
     if let Some(uid) = args.uid {
         let result = RetrieveGroup::uid(uid)
@@ -67,7 +86,7 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             .context("failed to retrieve group")?;
 
         if let Some(payload) = result {
-            println!("{:#?}", payload.into_payload());
+            print_group(payload.into_payload())?;
         } else {
             println!("group not found");
         }
@@ -86,7 +105,10 @@ fn get(client: &ApiClient, args: GetArgs) -> error::Result {
             row.finish(group);
         }
 
-        if table.is_empty() {
+        if formatting::json_output() {
+            table.print_json()
+                .context("failed to output results to stdout")?;
+        } else if table.is_empty() {
             println!("no contents");
         } else {
             table.print(&PRETTY_OPTIONS)
@@ -110,9 +132,7 @@ fn create(client: &ApiClient, args: CreateArgs) -> error::Result {
         .context("failed to create new group")?
         .into_payload();
 
-    println!("{:#?}", result);
-
-    Ok(())
+    print_group(result)
 }
 
 #[derive(Debug, Args)]
@@ -132,9 +152,7 @@ fn update(client: &ApiClient, args: UpdateArgs) -> error::Result {
         .context("failed to update group")?
         .into_payload();
 
-    println!("{:#?}", result);
-
-    Ok(())
+    print_group(result)
 }
 
 #[derive(Debug, Args)]
@@ -145,14 +163,35 @@ struct DeleteArgs {
 }
 
 fn delete(client: &ApiClient, args: DeleteArgs) -> error::Result {
+    if crate::cli::dry_run() {
+        let Some(group) = RetrieveGroup::uid(args.uid.clone())
+            .send(client)
+            .context("failed to retrieve group")? else {
+            println!("group not found");
+            return Ok(());
+        };
+
+        let group = group.into_payload();
+        let mut users = 0;
+
+        for result in iterate::Iterate::new(client, &mut QueryGroupUsers::uid(args.uid)) {
+            result.context("failed to retrieve group users")?;
+
+            users += 1;
+        }
+
+        println!("dry run: group \"{}\" {} would be deleted", group.name, group.uid);
+        println!("{users} users would be removed from the group");
+
+        return Ok(());
+    }
+
     let result = DeleteGroup::uid(args.uid)
         .send(client)
         .context("failed to delete group")?
         .into_payload();
 
-    println!("{:#?}", result);
-
-    Ok(())
+    print_group(result)
 }
 
 #[derive(Debug, Args)]
@@ -208,7 +247,10 @@ fn get_users(client: &ApiClient, args: GetUsersArgs) -> error::Result {
         row.finish(user);
     }
 
-    if table.is_empty() {
+    if formatting::json_output() {
+        table.print_json()
+            .context("failed to output results to stdout")?;
+    } else if table.is_empty() {
         println!("no contents");
     } else {
         table.print(&PRETTY_OPTIONS)
@@ -250,10 +292,39 @@ struct DropUsersArgs {
 }
 
 fn drop_users(client: &ApiClient, args: DropUsersArgs) -> error::Result {
+    if crate::cli::dry_run() {
+        let given: HashSet<ids::UserUid> = HashSet::from_iter(args.users);
+
+        println!("dry run: users that would be removed from the group");
+
+        for result in iterate::Iterate::new(client, &mut QueryGroupUsers::uid(args.uid)) {
+            let user = result.context("failed to retrieve group users")?;
+
+            if given.contains(&user.uid) {
+                println!("{}", user.uid);
+            }
+        }
+
+        return Ok(());
+    }
+
     let mut builder = DropUsers::uid(args.uid);
     builder.add_iter(args.users);
-    builder.send(client)
-        .context("failed to drop users from group")?;
+
+    let dropped = builder.send(client)
+        .context("failed to drop users from group")?
+        .into_payload();
+
+    if formatting::json_output() {
+        formatting::print_json(&dropped)
+            .context("failed to output results to stdout")?;
+    } else if dropped.is_empty() {
+        println!("no users were removed from the group");
+    } else {
+        for uid in dropped {
+            println!("removed {uid}");
+        }
+    }
 
     Ok(())
 }
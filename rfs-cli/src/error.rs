@@ -2,8 +2,72 @@ use rfs_lib::context_trait;
 
 type BoxDynError = Box<dyn std::error::Error + Send + Sync>;
 
+/// the general category of an error. used to determine the exit code of the
+/// process so that scripts can react to different failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Other,
+    Io,
+    Network,
+    Auth,
+    Validation,
+    NotFound,
+    Server,
+}
+
+impl ErrorKind {
+    /// the exit code for the process when the error is returned from a
+    /// command
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::Auth => 2,
+            ErrorKind::NotFound => 3,
+            ErrorKind::Validation => 4,
+            ErrorKind::Network => 5,
+            ErrorKind::Server => 6,
+            ErrorKind::Io => 7,
+        }
+    }
+
+    fn from_api(err: &rfs_api::ApiError) -> Self {
+        match err.kind().status().as_u16() {
+            401 | 403 => ErrorKind::Auth,
+            404 => ErrorKind::NotFound,
+            400..=499 => ErrorKind::Validation,
+            500..=599 => ErrorKind::Server,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// attempts to determine the kind from the source of an error
+    fn from_source(src: &BoxDynError) -> Self {
+        if let Some(err) = src.downcast_ref::<rfs_api::client::error::RequestError>() {
+            match err {
+                rfs_api::client::error::RequestError::Api(err) => Self::from_api(err),
+                rfs_api::client::error::RequestError::Reqwest(_) => ErrorKind::Network,
+                rfs_api::client::error::RequestError::Json(_) |
+                rfs_api::client::error::RequestError::Query(_) => ErrorKind::Other,
+            }
+        } else if let Some(err) = src.downcast_ref::<rfs_api::ApiError>() {
+            Self::from_api(err)
+        } else if src.is::<reqwest::Error>() {
+            ErrorKind::Network
+        } else if src.is::<std::io::Error>() {
+            ErrorKind::Io
+        } else if src.is::<url::ParseError>() {
+            ErrorKind::Validation
+        } else if let Some(err) = src.downcast_ref::<Error>() {
+            err.kind
+        } else {
+            ErrorKind::Other
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Error {
+    kind: ErrorKind,
     context: Option<String>,
     src: Option<BoxDynError>,
 }
@@ -13,11 +77,21 @@ pub type Result<T = ()> = std::result::Result<T, Error>;
 impl Error {
     pub fn new() -> Error {
         Error {
+            kind: ErrorKind::Other,
             context: None,
             src: None,
         }
     }
 
+    pub fn kind(mut self, kind: ErrorKind) -> Error {
+        self.kind = kind;
+        self
+    }
+
+    pub fn get_kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
     pub fn context<C>(mut self, cxt: C) -> Error
     where
         C: Into<String>
@@ -26,11 +100,19 @@ impl Error {
         self
     }
 
+    /// sets the source of the error. if the kind has not been set then it
+    /// will be determined from the source
     pub fn source<S>(mut self, src: S) -> Error
     where
         S: Into<BoxDynError>
     {
-        self.src = Some(src.into());
+        let src = src.into();
+
+        if self.kind == ErrorKind::Other {
+            self.kind = ErrorKind::from_source(&src);
+        }
+
+        self.src = Some(src);
         self
     }
 }
@@ -115,7 +197,10 @@ impl From<rfs_api::client::error::RequestError> for Error {
         match err {
             rfs_api::client::error::RequestError::Reqwest(err) => Self::from(err)
                 .context("error server request"),
-            rfs_api::client::error::RequestError::Api(err) => Self::from(err)
+            rfs_api::client::error::RequestError::Api(err) => Self::from(err),
+            err => Self::new()
+                .context("error server request")
+                .source(err)
         }
     }
 }
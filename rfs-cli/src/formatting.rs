@@ -3,9 +3,84 @@ use std::collections::{HashMap, BinaryHeap};
 use std::fmt::{Write, Formatter, Display, Result as FmtResult};
 use std::default::Default;
 use std::iter::Iterator;
+use std::sync::OnceLock;
 
 use chrono::{DateTime, Utc, Local, SecondsFormat};
 use clap::{Args, ValueEnum};
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Table
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            OutputFormat::Table => write!(f, "table"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// sets the output format for the rest of the process. only the first call
+/// will have an effect
+pub fn set_output_format(format: OutputFormat) {
+    let _ = OUTPUT_FORMAT.set(format);
+}
+
+pub fn output_format() -> OutputFormat {
+    OUTPUT_FORMAT.get().copied().unwrap_or_default()
+}
+
+#[inline]
+pub fn json_output() -> bool {
+    output_format() == OutputFormat::Json
+}
+
+static QUIET: OnceLock<bool> = OnceLock::new();
+
+/// sets if informational messages should be suppressed for the rest of the
+/// process. only the first call will have an effect
+pub fn set_quiet(quiet: bool) {
+    let _ = QUIET.set(quiet);
+}
+
+/// if informational messages, like progress or success notices, should be
+/// suppressed. requested data and errors are always written
+pub fn quiet() -> bool {
+    QUIET.get().copied().unwrap_or(false)
+}
+
+pub fn write_json<O, T>(output: &mut O, value: &T) -> std::io::Result<()>
+where
+    O: std::io::Write,
+    T: Serialize + ?Sized,
+{
+    serde_json::to_writer_pretty(&mut *output, value)?;
+
+    write!(output, "\n")
+}
+
+#[inline]
+pub fn print_json<T>(value: &T) -> std::io::Result<()>
+where
+    T: Serialize + ?Sized,
+{
+    let mut stdout = std::io::stdout();
+
+    write_json(&mut stdout, value)
+}
 
 #[derive(Debug)]
 pub struct SizeType<T = u64> {
@@ -533,7 +608,32 @@ impl<T, const N: usize> TextTable<T, N> {
     }
 }
 
-#[derive(Debug, Args)]
+impl<T, const N: usize> TextTable<T, N>
+where
+    T: Serialize
+{
+    /// writes the data of each row as a json array in the same order as
+    /// the rows would be displayed
+    pub fn write_json<O>(&self, output: &mut O) -> std::io::Result<()>
+    where
+        O: std::io::Write
+    {
+        let list: Vec<&T> = self.rows.iter()
+            .map(|(data, _)| data)
+            .collect();
+
+        write_json(output, &list)
+    }
+
+    #[inline]
+    pub fn print_json(&self) -> std::io::Result<()> {
+        let mut stdout = std::io::stdout();
+
+        self.write_json(&mut stdout)
+    }
+}
+
+#[derive(Debug, Default, Args)]
 pub struct OutputOptions {
     /// specifies the format for the file size output
     #[arg(long, default_value_t)]
@@ -548,6 +648,10 @@ pub fn write_fs_file<O>(output: &mut O, file: &rfs_api::fs::File, options: &Outp
 where
     O: std::io::Write
 {
+    if json_output() {
+        return write_json(output, file);
+    }
+
     write!(
         output,
         "file {} {}/{} {}\n",
@@ -581,6 +685,10 @@ pub fn write_fs_root<O>(output: &mut O, root: &rfs_api::fs::Root, options: &Outp
 where
     O: std::io::Write
 {
+    if json_output() {
+        return write_json(output, root);
+    }
+
     write!(output, "root {} {}\n", root.uid, root.basename)?;
     write!(output, "created: {}\n", datetime_to_string(&root.created, &options.ts_format))?;
 
@@ -603,6 +711,10 @@ pub fn write_fs_dir<O>(output: &mut O, dir: &rfs_api::fs::Directory, options: &O
 where
     O: std::io::Write
 {
+    if json_output() {
+        return write_json(output, dir);
+    }
+
     write!(output, "directory {} {}/{}\n", dir.uid, dir.path, dir.basename)?;
     write!(output, "parent: {}\n", dir.parent)?;
     write!(output, "created: {}\n", datetime_to_string(&dir.created, &options.ts_format))?;
@@ -6,15 +6,9 @@ mod path;
 mod formatting;
 
 fn main() {
-    use tracing_subscriber::{FmtSubscriber, EnvFilter};
-
-    FmtSubscriber::builder()
-        .with_env_filter(EnvFilter::from_default_env())
-        .try_init()
-        .expect("failed to initialize global tracing subscriber");
-
     if let Err(err) = cli::start() {
         println!("{}", err);
+
+        std::process::exit(err.get_kind().exit_code());
     }
 }
-
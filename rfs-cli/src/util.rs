@@ -11,6 +11,15 @@ pub fn parse_mime(arg: &str) -> Result<mime::Mime, String> {
     }
 }
 
+pub fn parse_limit(arg: &str) -> Result<rfs_lib::query::Limit, String> {
+    match arg {
+        "25" => Ok(rfs_lib::query::Limit::Small),
+        "50" => Ok(rfs_lib::query::Limit::Medium),
+        "100" => Ok(rfs_lib::query::Limit::Large),
+        _ => Err("limit must be 25, 50, or 100".into())
+    }
+}
+
 pub type Tag = (String, Option<String>);
 
 pub fn parse_tag(arg: &str) -> Result<Tag, String> {
@@ -0,0 +1,49 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// embeds the sql files in the migrations directory so that the binary does
+/// not depend on the directory it is run from
+fn main() {
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let migrations_dir = manifest_dir.join("migrations");
+
+    println!("cargo:rerun-if-changed={}", migrations_dir.display());
+
+    let mut found = Vec::new();
+
+    for entry in std::fs::read_dir(&migrations_dir).expect("failed to read migrations directory") {
+        let entry = entry.expect("failed to read migrations directory entry");
+        let path = entry.path();
+
+        if !entry.file_type().expect("failed to retrieve file type").is_file() {
+            continue;
+        }
+
+        if path.extension().map(|ext| ext != "sql").unwrap_or(true) {
+            continue;
+        }
+
+        let name = path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_else(|| panic!("migration file name is not valid utf-8. {}", path.display()))
+            .to_owned();
+
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        found.push((name, path));
+    }
+
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut contents = String::from("pub(crate) static MIGRATIONS: &[(&str, &str)] = &[\n");
+
+    for (name, path) in found {
+        writeln!(contents, "    ({:?}, include_str!({:?})),", name, path.display().to_string()).unwrap();
+    }
+
+    contents.push_str("];\n");
+
+    std::fs::write(out_dir.join("migrations.rs"), contents)
+        .expect("failed to write migrations list");
+}
@@ -0,0 +1,8 @@
+-- supports prefix searches on tag names ("tag like 'prefix%'") and case
+-- insensitive searches on tag values ("lower(value) = lower($1)")
+
+create index if not exists fs_tags_tag_search_prefix on fs_tags (tag text_pattern_ops);
+create index if not exists fs_tags_tag_search_lower_value on fs_tags (lower(value));
+
+create index if not exists storage_tags_tag_search_prefix on storage_tags (tag text_pattern_ops);
+create index if not exists storage_tags_tag_search_lower_value on storage_tags (lower(value));
@@ -0,0 +1,12 @@
+-- pending verifications for the email addresses of users. only a hash of
+-- the token given to the user is stored
+
+create table if not exists user_email_verify (
+    user_id bigint not null primary key references users (id) on delete cascade,
+
+    email varchar not null,
+    token_hash bytea not null unique,
+
+    issued timestamp with time zone not null,
+    expires timestamp with time zone not null
+);
@@ -0,0 +1,4 @@
+-- profile fields that a user is able to update for themselves
+
+alter table users add column if not exists display_name varchar;
+alter table users add column if not exists preferences jsonb not null default '{}';
@@ -0,0 +1,5 @@
+-- items that are not in the trash cannot share a basename in the same
+-- parent. any existing duplicates must be renamed or trashed before this can
+-- be applied
+
+create unique index if not exists fs_parent_basename_unique on fs (parent, basename) where deleted is null;
@@ -0,0 +1,3 @@
+-- modification time of a file as reported by the client that uploaded it
+
+alter table fs add column if not exists mtime timestamp with time zone;
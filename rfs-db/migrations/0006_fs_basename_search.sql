@@ -0,0 +1,6 @@
+-- supports searching for items by a partial basename
+-- ("basename ilike '%name%'")
+
+create extension if not exists pg_trgm;
+
+create index if not exists fs_basename_trgm on fs using gin (basename gin_trgm_ops);
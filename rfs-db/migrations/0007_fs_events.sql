@@ -0,0 +1,16 @@
+-- changes made to fs items. not referencing fs(id) so that the history of
+-- an item will remain after it has been deleted
+
+create table if not exists fs_events (
+    id bigint primary key generated always as identity,
+
+    fs_id bigint not null,
+    user_id bigint not null references users(id),
+
+    action varchar not null,
+    detail jsonb,
+
+    created timestamp with time zone not null
+);
+
+create index if not exists fs_events_fs_id on fs_events (fs_id);
@@ -0,0 +1,18 @@
+-- bot accounts owned by users. bots authenticate with a token and are
+-- limited to a single role. only a hash of the token is stored
+
+create table if not exists bots (
+    id bigint primary key generated always as identity,
+    uid varchar not null unique,
+
+    user_id bigint not null references users (id),
+    role_id bigint not null references authz_roles (id),
+
+    name varchar not null,
+    token_hash bytea not null unique,
+
+    created timestamp with time zone not null,
+    updated timestamp with time zone,
+
+    unique (user_id, name)
+);
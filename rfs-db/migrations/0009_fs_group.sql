@@ -0,0 +1,3 @@
+-- items can be shared with the members of a group
+
+alter table fs add column if not exists group_id bigint references groups(id);
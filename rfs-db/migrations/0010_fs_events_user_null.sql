@@ -0,0 +1,9 @@
+-- events are kept when the user that made them is deleted. the user_id is
+-- set to null instead
+
+alter table fs_events alter column user_id drop not null;
+
+alter table fs_events drop constraint if exists fs_events_user_id_fkey;
+
+alter table fs_events add constraint fs_events_user_id_fkey
+    foreign key (user_id) references users(id) on delete set null;
@@ -5,7 +5,10 @@ create table users (
     username varchar not null unique,
 
     email varchar unique,
-    email_verified bool not null default false
+    email_verified bool not null default false,
+
+    display_name varchar,
+    preferences jsonb not null default '{}'
 );
 
 create table groups (
@@ -22,6 +25,16 @@ create table group_users (
     constraint unique_user_group unique(user_id, group_id)
 );
 
+create table user_email_verify (
+    user_id bigint not null primary key references users (id) on delete cascade,
+
+    email varchar not null,
+    token_hash bytea not null unique,
+
+    issued timestamp with time zone not null,
+    expires timestamp with time zone not null
+);
+
 create table auth_password (
     user_id bigint not null primary key references users(id),
     version bigint not null default 0,
@@ -90,6 +103,22 @@ create table user_roles (
     primary key (user_id, role_id)
 );
 
+create table bots (
+    id bigint primary key generated always as identity,
+    uid varchar not null unique,
+
+    user_id bigint not null references users (id),
+    role_id bigint not null references authz_roles (id),
+
+    name varchar not null,
+    token_hash bytea not null unique,
+
+    created timestamp with time zone not null,
+    updated timestamp with time zone,
+
+    unique (user_id, name)
+);
+
 create table storage (
     id bigint primary key generated always as identity,
     uid varchar not null unique,
@@ -115,11 +144,15 @@ create table storage_tags (
     constraint unique_storage_id_tag primary key (storage_id, tag)
 );
 
+create index storage_tags_tag_search_prefix on storage_tags (tag text_pattern_ops);
+create index storage_tags_tag_search_lower_value on storage_tags (lower(value));
+
 create table fs (
     id bigint primary key generated always as identity,
     uid varchar not null unique,
 
     user_id bigint not null references users(id),
+    group_id bigint references groups(id),
     storage_id bigint not null references storage(id),
     parent bigint references fs(id),
 
@@ -140,9 +173,16 @@ create table fs (
 
     created timestamp with time zone not null,
     updated timestamp with time zone,
-    deleted timestamp with time zone
+    deleted timestamp with time zone,
+    mtime timestamp with time zone
 );
 
+create unique index fs_parent_basename_unique on fs (parent, basename) where deleted is null;
+
+create extension if not exists pg_trgm;
+
+create index fs_basename_trgm on fs using gin (basename gin_trgm_ops);
+
 create table fs_tags (
     fs_id bigint not null references fs(id),
     tag varchar not null,
@@ -151,6 +191,9 @@ create table fs_tags (
     constraint unique_fs_id_tag primary key (fs_id, tag)
 );
 
+create index fs_tags_tag_search_prefix on fs_tags (tag text_pattern_ops);
+create index fs_tags_tag_search_lower_value on fs_tags (lower(value));
+
 create table fs_checksums (
     fs_id bigint not null references fs(id),
     algo varchar not null,
@@ -158,3 +201,20 @@ create table fs_checksums (
 
     constraint unique_fs_id_algo primary key (fs_id, algo)
 );
+
+create table fs_events (
+    id bigint primary key generated always as identity,
+
+    -- not referencing fs(id) so that the history of an item will remain after
+    -- it has been deleted
+    fs_id bigint not null,
+    -- null if the user has been deleted
+    user_id bigint references users(id) on delete set null,
+
+    action varchar not null,
+    detail jsonb,
+
+    created timestamp with time zone not null
+);
+
+create index fs_events_fs_id on fs_events (fs_id);
@@ -27,6 +27,12 @@ fn commands() -> clap::Command {
                         .action(ArgAction::SetTrue)
                         .help("rollback changes made to the database")
                 )
+                .arg(
+                    Arg::new("seed-roles")
+                        .long("seed-roles")
+                        .action(ArgAction::SetTrue)
+                        .help("creates the built-in admin and viewer roles")
+                )
                 .arg(args::db::connect())
                 .arg(args::db::user())
                 .arg(args::db::password())
@@ -37,6 +43,7 @@ fn commands() -> clap::Command {
         )
         .subcommand(
             Command::new("migrate")
+                .subcommand_required(true)
                 .about("databasae migration operations")
                 .arg(args::db::connect())
                 .arg(args::db::user())
@@ -67,10 +74,20 @@ fn commands() -> clap::Command {
                                 .action(ArgAction::SetFalse)
                                 .help("process will continue if missing migrates are found")
                         )
+                        .arg(
+                            Arg::new("dry-run")
+                                .long("dry-run")
+                                .action(ArgAction::SetTrue)
+                                .help("prints the sql of pending migrates without running them")
+                        )
                 )
                 .subcommand(
                     Command::new("list")
-                        .about("lists currently available migrates")
+                        .about("lists currently available migrates and if they have been applied")
+                )
+                .subcommand(
+                    Command::new("status")
+                        .about("shows pending, divergent, and missing migrates")
                 )
                 .subcommand(
                     Command::new("last-applied")
@@ -129,7 +146,7 @@ async fn exec(matches: &clap::ArgMatches) -> error::Result<()> {
     match matches.subcommand() {
         Some(("setup", setup_matches)) => run::setup(&setup_matches).await?,
         Some(("ids", ids_matches)) => run::ids(&ids_matches)?,
-        Some(("migrate", migrate_matches)) => {},
+        Some(("migrate", migrate_matches)) => run::migrate(&migrate_matches).await?,
         _ => unreachable!()
     };
 
@@ -1,6 +1,7 @@
 mod setup;
 mod ids;
-//mod migrate;
+mod migrate;
 
 pub use setup::run as setup;
 pub use ids::run as ids;
+pub use migrate::run as migrate;
@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use clap::ArgMatches;
+use tokio_postgres::{Client, GenericClient};
+
+use crate::error;
+use crate::conn;
+
+// generated by build.rs from the sql files in rfs-db/migrations
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+/// a migration file that was embedded from the migrations directory
+struct Available {
+    name: &'static str,
+    sql: &'static str,
+    checksum: blake3::Hash,
+}
+
+/// a migration that has been recorded as applied in the database
+struct Applied {
+    name: String,
+    checksum: Vec<u8>,
+    applied: DateTime<Utc>,
+}
+
+enum State<'a> {
+    /// available and applied with the same contents
+    Applied(&'a Available, &'a Applied),
+    /// available but not applied
+    Pending(&'a Available),
+    /// applied but the contents of the file have changed since
+    Divergent(&'a Available, &'a Applied),
+    /// applied but the file is no longer available
+    Missing(&'a Applied),
+}
+
+/// the embedded migrations. already sorted by name
+fn load_available() -> Vec<Available> {
+    MIGRATIONS.iter()
+        .map(|&(name, sql)| Available {
+            name,
+            sql,
+            checksum: blake3::hash(sql.as_bytes()),
+        })
+        .collect()
+}
+
+async fn ensure_table(conn: &impl GenericClient) -> error::Result<()> {
+    conn.batch_execute(
+        "\
+        create table if not exists _migrations (\
+            name varchar primary key, \
+            checksum bytea not null, \
+            applied timestamp with time zone not null\
+        )"
+    ).await?;
+
+    Ok(())
+}
+
+async fn load_applied(conn: &impl GenericClient) -> error::Result<Vec<Applied>> {
+    let rows = conn.query(
+        "select name, checksum, applied from _migrations order by name",
+        &[]
+    ).await?;
+
+    Ok(rows.into_iter()
+        .map(|row| Applied {
+            name: row.get(0),
+            checksum: row.get(1),
+            applied: row.get(2),
+        })
+        .collect())
+}
+
+/// matches up the available migrations with the applied ones
+fn compare<'a>(available: &'a [Available], applied: &'a [Applied]) -> Vec<State<'a>> {
+    let mut by_name: BTreeMap<&str, State<'a>> = BTreeMap::new();
+
+    for item in available {
+        by_name.insert(item.name, State::Pending(item));
+    }
+
+    for item in applied {
+        let state = match by_name.remove(item.name.as_str()) {
+            Some(State::Pending(found)) => if found.checksum.as_bytes().as_slice() == item.checksum.as_slice() {
+                State::Applied(found, item)
+            } else {
+                State::Divergent(found, item)
+            },
+            _ => State::Missing(item),
+        };
+
+        by_name.insert(item.name.as_str(), state);
+    }
+
+    by_name.into_values().collect()
+}
+
+fn print_state(state: &State<'_>) {
+    match state {
+        State::Applied(available, applied) => {
+            println!("applied   {} {}", available.name, applied.applied);
+        }
+        State::Pending(available) => {
+            println!("pending   {}", available.name);
+        }
+        State::Divergent(available, applied) => {
+            println!("divergent {} {}", available.name, applied.applied);
+        }
+        State::Missing(applied) => {
+            println!("missing   {} {}", applied.name, applied.applied);
+        }
+    }
+}
+
+async fn list(conn: &Client) -> error::Result<()> {
+    let available = load_available();
+    let applied = load_applied(conn).await?;
+
+    for state in compare(&available, &applied) {
+        print_state(&state);
+    }
+
+    Ok(())
+}
+
+async fn status(conn: &Client) -> error::Result<()> {
+    let available = load_available();
+    let applied = load_applied(conn).await?;
+    let mut up_to_date = true;
+
+    for state in compare(&available, &applied) {
+        if let State::Applied(..) = state {
+            continue;
+        }
+
+        up_to_date = false;
+
+        print_state(&state);
+    }
+
+    if up_to_date {
+        println!("database is up to date");
+    }
+
+    Ok(())
+}
+
+async fn last_applied(conn: &Client) -> error::Result<()> {
+    let maybe_row = conn.query_opt(
+        "select name, applied from _migrations order by applied desc, name desc limit 1",
+        &[]
+    ).await?;
+
+    if let Some(row) = maybe_row {
+        let name: String = row.get(0);
+        let applied: DateTime<Utc> = row.get(1);
+
+        println!("{name} {applied}");
+    } else {
+        println!("no migrations applied");
+    }
+
+    Ok(())
+}
+
+async fn applied(conn: &Client) -> error::Result<()> {
+    let applied = load_applied(conn).await?;
+
+    if applied.is_empty() {
+        println!("no migrations applied");
+    }
+
+    for item in applied {
+        println!("{} {}", item.name, item.applied);
+    }
+
+    Ok(())
+}
+
+async fn apply(conn: &impl GenericClient, migration: &Available) -> error::Result<()> {
+    tracing::event!(
+        tracing::Level::INFO,
+        name = %migration.name,
+        checksum = %migration.checksum,
+        "applying migration"
+    );
+
+    if let Err(err) = conn.batch_execute(migration.sql).await {
+        return Err(error::Error::new()
+            .kind("MigrationFailed")
+            .message(format!("failed to apply migration {}", migration.name))
+            .source(err));
+    }
+
+    conn.execute(
+        "insert into _migrations (name, checksum, applied) values ($1, $2, $3)",
+        &[&migration.name, &migration.checksum.as_bytes().as_slice(), &Utc::now()]
+    ).await?;
+
+    println!("applied {} {}", migration.name, migration.checksum);
+
+    Ok(())
+}
+
+async fn run_migrations(conn: &mut Client, args: &ArgMatches) -> error::Result<()> {
+    let group = args.get_flag("groups");
+    let dry_run = args.get_flag("dry-run");
+    let abort_divergent = args.get_flag("abort-divergent");
+    let abort_missing = args.get_flag("abort-missing");
+
+    let available = load_available();
+    let applied = load_applied(&*conn).await?;
+    let mut pending = Vec::new();
+    let mut abort = false;
+
+    for state in compare(&available, &applied) {
+        match state {
+            State::Applied(..) => {}
+            State::Pending(migration) => pending.push(migration),
+            State::Divergent(..) => {
+                print_state(&state);
+
+                abort = abort || abort_divergent;
+            }
+            State::Missing(..) => {
+                print_state(&state);
+
+                abort = abort || abort_missing;
+            }
+        }
+    }
+
+    if abort {
+        return Err(error::Error::new()
+            .kind("MigrationsInvalid")
+            .message("divergent or missing migrations found"));
+    }
+
+    if pending.is_empty() {
+        println!("no pending migrations");
+
+        return Ok(());
+    }
+
+    if dry_run {
+        for migration in pending {
+            println!("-- {}\n{}", migration.name, migration.sql.trim());
+        }
+
+        return Ok(());
+    }
+
+    if group {
+        let transaction = conn.transaction().await?;
+
+        for migration in pending {
+            apply(&transaction, migration).await?;
+        }
+
+        transaction.commit().await?;
+    } else {
+        for migration in pending {
+            let transaction = conn.transaction().await?;
+
+            apply(&transaction, migration).await?;
+
+            transaction.commit().await?;
+        }
+    }
+
+    println!("migrations applied");
+
+    Ok(())
+}
+
+pub async fn run(args: &ArgMatches) -> error::Result<()> {
+    let mut conn = conn::postgres(args).await?;
+
+    ensure_table(&conn).await?;
+
+    match args.subcommand() {
+        Some(("run", run_args)) => run_migrations(&mut conn, run_args).await?,
+        Some(("list", _)) => list(&conn).await?,
+        Some(("status", _)) => status(&conn).await?,
+        Some(("last-applied", _)) => last_applied(&conn).await?,
+        Some(("applied", _)) => applied(&conn).await?,
+        _ => unreachable!()
+    }
+
+    Ok(())
+}
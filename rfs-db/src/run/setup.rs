@@ -1,8 +1,71 @@
 use clap::ArgMatches;
+use rfs_lib::ids;
+use rfs_lib::sec::authz::permission::{Scope, Ability};
+use tokio_postgres::GenericClient;
 
 use crate::error;
 use crate::conn;
 
+/// creates a role with the given permissions. if the role already exists
+/// then any missing permissions will be added to it
+async fn seed_role(
+    conn: &impl GenericClient,
+    name: &str,
+    permissions: &[(Scope, Ability)]
+) -> error::Result<()> {
+    let uid = ids::RoleUid::gen();
+
+    let role_id: i64 = if let Some(row) = conn.query_opt(
+        "\
+        insert into authz_roles (uid, name) \
+        values ($1, $2) \
+        on conflict (name) do nothing \
+        returning id",
+        &[&uid.inner(), &name]
+    ).await? {
+        row.get(0)
+    } else {
+        conn.query_one(
+            "select id from authz_roles where name = $1",
+            &[&name]
+        ).await?.get(0)
+    };
+
+    for (scope, ability) in permissions {
+        conn.execute(
+            "\
+            insert into authz_permissions (role_id, scope, ability) \
+            values ($1, $2, $3) \
+            on conflict (role_id, scope, ability) do nothing",
+            &[&role_id, &scope.as_str(), &ability.as_str()]
+        ).await?;
+    }
+
+    println!("seeded role {name}");
+
+    Ok(())
+}
+
+/// creates the built-in roles. admin has every ability for every scope
+/// and viewer is only able to read
+async fn seed_roles(conn: &impl GenericClient) -> error::Result<()> {
+    let mut admin = Vec::new();
+    let mut viewer = Vec::new();
+
+    for scope in Scope::ALL {
+        for ability in Ability::ALL {
+            admin.push((scope.clone(), ability));
+        }
+
+        viewer.push((scope, Ability::Read));
+    }
+
+    seed_role(conn, "admin", &admin).await?;
+    seed_role(conn, "viewer", &viewer).await?;
+
+    Ok(())
+}
+
 pub async fn run(args: &ArgMatches) -> error::Result<()> {
     let mut conn = conn::postgres(args).await?;
     let current_dir = std::env::current_dir()?;
@@ -47,6 +110,10 @@ pub async fn run(args: &ArgMatches) -> error::Result<()> {
         }
     }
 
+    if !failed && args.get_flag("seed-roles") {
+        seed_roles(&transaction).await?;
+    }
+
     if args.get_flag("rollback") || failed {
         tracing::event!(
             tracing::Level::INFO,
@@ -1,12 +1,15 @@
 use crate::validation::check_control_leading_trailing;
 
 pub mod storage;
+pub mod event;
 
 pub const MAX_BASENAME_CHARS: usize = 512;
 pub const MIN_BASENAME_CHARS: usize = 1;
 
 pub const MAX_COMMENT_CHARS: usize = 1024;
 
+pub const MAX_BATCH_ITEMS: usize = 100;
+
 fn valid_pathname_char(ch: &char) -> bool {
     (match ch {
         '/' | '\\' => false,
@@ -54,6 +57,12 @@ fn valid_pathname(given: &str, min_chars: usize, max_chars: usize, allow_whitesp
 }
 
 pub fn basename_valid(given: &str) -> bool {
+    // "." and ".." resolve to the current and parent directories and some
+    // filesystems will silently strip trailing dots
+    if given == "." || given == ".." || given.ends_with('.') {
+        return false;
+    }
+
     valid_pathname(given, MIN_BASENAME_CHARS, MAX_BASENAME_CHARS, true)
 }
 
@@ -70,6 +79,9 @@ mod test {
         let valid = [
             "file_name.txt",
             "a",
+            ".hidden",
+            "double..dot",
+            "with space",
         ];
 
         for test in valid {
@@ -86,6 +98,13 @@ mod test {
             "\\leading_back_slash",
             "trailing_back_slash\\",
             "middle\\back_slask",
+            ".",
+            "..",
+            "trailing_dot.",
+            " leading_space",
+            "trailing_space ",
+            "null\0byte",
+            "new\nline",
             max_len.as_str()
         ];
 
@@ -0,0 +1,69 @@
+use std::error::Error;
+
+use serde::{Serialize, Deserialize};
+use bytes::BytesMut;
+use postgres_types::{to_sql_checked, Type, IsNull, ToSql, FromSql};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub enum Action {
+    Created,
+    Updated,
+    Moved,
+    Deleted,
+    Restored,
+}
+
+impl Action {
+    pub fn from_str(v: &str) -> Option<Self> {
+        match v {
+            "Created" => Some(Action::Created),
+            "Updated" => Some(Action::Updated),
+            "Moved" => Some(Action::Moved),
+            "Deleted" => Some(Action::Deleted),
+            "Restored" => Some(Action::Restored),
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Action::Created => "Created",
+            Action::Updated => "Updated",
+            Action::Moved => "Moved",
+            Action::Deleted => "Deleted",
+            Action::Restored => "Restored",
+        }
+    }
+}
+
+impl<'a> FromSql<'a> for Action {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let v = <&str as FromSql>::from_sql(ty, raw)?;
+
+        Action::from_str(v)
+            .ok_or("invalid sql value for Action. expecting \
+                \"Created\", \
+                \"Updated\", \
+                \"Moved\", \
+                \"Deleted\", \
+                \"Restored\"".into())
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&str as FromSql>::accepts(ty)
+    }
+}
+
+impl ToSql for Action {
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let v = self.as_str();
+
+        v.to_sql(ty, w)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&str as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
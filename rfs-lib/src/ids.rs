@@ -21,6 +21,16 @@ pub struct InvalidIdInteger;
 #[error("provided string contains invalid characters or is less than or equal to zero")]
 pub struct InvalidIdString;
 
+/// parses any of the local id types from a string. the string must be a
+/// positive integer and will be the same as what [`Display`] outputs for the
+/// id
+pub fn parse_flake_id<T>(given: &str) -> Result<T, InvalidIdString>
+where
+    T: FromStr<Err = InvalidIdString>
+{
+    T::from_str(given)
+}
+
 macro_rules! id_type {
     ($name:ident) => {
         #[derive(
@@ -293,3 +303,77 @@ set_type!(FSSet, FSId, FSUid);
 id_type!(StorageId);
 uid_type!(StorageUid);
 set_type!(StorageSet, StorageId, StorageUid);
+
+id_type!(BotId);
+uid_type!(BotUid);
+set_type!(BotSet, BotId, BotUid);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip<T>(value: i64)
+    where
+        T: TryFrom<i64, Error = InvalidIdInteger> + FromStr<Err = InvalidIdString> + Display + PartialEq + std::fmt::Debug
+    {
+        let id = T::try_from(value).expect("valid integer for id");
+        let formatted = id.to_string();
+
+        assert_eq!(formatted, value.to_string());
+
+        let parsed: T = parse_flake_id(&formatted).expect("failed to parse formatted id");
+
+        assert_eq!(parsed, id);
+    }
+
+    fn invalid<T>()
+    where
+        T: FromStr<Err = InvalidIdString> + std::fmt::Debug
+    {
+        for given in ["", "0", "-1", "abc", "1.5", " 1"] {
+            assert!(parse_flake_id::<T>(given).is_err(), "invalid string parsed {:?}", given);
+        }
+    }
+
+    #[test]
+    fn fs_id() {
+        round_trip::<FSId>(1);
+        round_trip::<FSId>(i64::MAX);
+        invalid::<FSId>();
+    }
+
+    #[test]
+    fn user_id() {
+        round_trip::<UserId>(1);
+        round_trip::<UserId>(i64::MAX);
+        invalid::<UserId>();
+    }
+
+    #[test]
+    fn storage_id() {
+        round_trip::<StorageId>(1);
+        round_trip::<StorageId>(i64::MAX);
+        invalid::<StorageId>();
+    }
+
+    #[test]
+    fn role_id() {
+        round_trip::<RoleId>(1);
+        round_trip::<RoleId>(i64::MAX);
+        invalid::<RoleId>();
+    }
+
+    #[test]
+    fn group_id() {
+        round_trip::<GroupId>(1);
+        round_trip::<GroupId>(i64::MAX);
+        invalid::<GroupId>();
+    }
+
+    #[test]
+    fn bot_id() {
+        round_trip::<BotId>(1);
+        round_trip::<BotId>(i64::MAX);
+        invalid::<BotId>();
+    }
+}
@@ -93,3 +93,9 @@ pub fn digits_valid(given: &u32) -> bool {
 pub fn step_valid(given: &u64) -> bool {
     *given <= 120
 }
+
+pub const MAX_WINDOW: u8 = 5;
+
+pub fn window_valid(given: &u8) -> bool {
+    *given <= MAX_WINDOW
+}
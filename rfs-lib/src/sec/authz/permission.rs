@@ -16,13 +16,21 @@ pub fn role_name_valid(given: &str) -> bool {
 pub enum Ability {
     Read,
     Write,
+    Delete,
 }
 
 impl Ability {
+    pub const ALL: [Ability; 3] = [
+        Ability::Read,
+        Ability::Write,
+        Ability::Delete,
+    ];
+
     pub fn from_str(v: &str) -> Option<Self> {
         match v {
             "Read" => Some(Ability::Read),
             "Write" => Some(Ability::Write),
+            "Delete" => Some(Ability::Delete),
             _ => None
         }
     }
@@ -31,6 +39,7 @@ impl Ability {
         match self {
             Ability::Read => "Read",
             Ability::Write => "Write",
+            Ability::Delete => "Delete",
         }
     }
 }
@@ -40,7 +49,7 @@ impl<'a> FromSql<'a> for Ability {
         let v = <&str as FromSql>::from_sql(ty, raw)?;
 
         Ability::from_str(v)
-            .ok_or("invalid sql value for Ability. expecting \"Read\" or \"Write\"".into())
+            .ok_or("invalid sql value for Ability. expecting \"Read\", \"Write\", or \"Delete\"".into())
     }
 
     fn accepts(ty: &Type) -> bool {
@@ -73,6 +82,15 @@ pub enum Scope {
 }
 
 impl Scope {
+    pub const ALL: [Scope; 6] = [
+        Scope::Fs,
+        Scope::SecRoles,
+        Scope::SecSecrets,
+        Scope::Storage,
+        Scope::User,
+        Scope::UserGroup,
+    ];
+
     pub fn from_str(v: &str) -> Option<Self> {
         match v {
             "Fs" => Some(Scope::Fs),
@@ -129,3 +147,22 @@ impl ToSql for Scope {
 
     to_sql_checked!();
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn all_scopes_round_trip() {
+        for scope in Scope::ALL {
+            assert_eq!(Scope::from_str(scope.as_str()), Some(scope.clone()));
+        }
+    }
+
+    #[test]
+    fn all_abilities_round_trip() {
+        for ability in Ability::ALL {
+            assert_eq!(Ability::from_str(ability.as_str()), Some(ability.clone()));
+        }
+    }
+}
@@ -5,4 +5,5 @@ pub type Timestamp = u64;
 
 pub const PASSWORDS_KEY_INFO: &[u8; 9] = b"passwords";
 pub const SESSIONS_KEY_INFO: &[u8; 8] = b"sessions";
+pub const BOTS_KEY_INFO: &[u8; 4] = b"bots";
 
@@ -1,15 +1,21 @@
 use email_address::EmailAddress;
 
-use crate::validation::check_control_whitespace;
+use crate::validation::{check_control_whitespace, check_control_leading_trailing};
 
+pub mod bots;
 pub mod groups;
 
 pub const MAX_USERNAME_CHARS: usize = 128;
+pub const MAX_DISPLAY_NAME_CHARS: usize = 128;
 
 pub fn username_valid(given: &String) -> bool {
     !given.is_empty() && check_control_whitespace(given, Some(MAX_USERNAME_CHARS))
 }
 
+pub fn display_name_valid(given: &String) -> bool {
+    !given.is_empty() && check_control_leading_trailing(given, Some(MAX_DISPLAY_NAME_CHARS))
+}
+
 pub fn email_valid(given: &String) -> bool {
     EmailAddress::is_valid(given)
 }
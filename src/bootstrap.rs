@@ -0,0 +1,105 @@
+use rfs_lib::ids;
+use rand::Rng;
+use rand::distributions::Alphanumeric;
+
+use crate::config;
+use crate::error::{self, Context};
+use crate::sec::authn::password::Password;
+use crate::sec::authz::permission::{Scope, Ability};
+use crate::state;
+
+/// length of the generated password for the initial user
+const PASSWORD_LEN: usize = 24;
+
+/// name of the role given to the initial user
+const ADMIN_ROLE: &str = "admin";
+
+/// creates the initial user with a generated password and assigns them a
+/// role with every ability for every scope. will refuse to run if any
+/// users already exist
+pub async fn admin(config: config::Config, username: String) -> error::Result<()> {
+    if !rfs_lib::users::username_valid(&username) {
+        return Err(error::Error::new()
+            .context("the username provided is invalid"));
+    }
+
+    let state = state::Shared::from_config(&config)?;
+    let mut conn = state.pool().get().await?;
+    let transaction = conn.transaction().await?;
+
+    // prevents another bootstrap from creating a user at the same time
+    transaction.execute("lock table users in exclusive mode", &[]).await?;
+
+    let exists: bool = transaction.query_one(
+        "select exists(select 1 from users)",
+        &[]
+    ).await?.get(0);
+
+    if exists {
+        return Err(error::Error::new()
+            .context("users already exist. the bootstrap can only be run on an empty database"));
+    }
+
+    let uid = ids::UserUid::gen();
+    let user_id: ids::UserId = transaction.query_one(
+        "\
+        insert into users (uid, username) \
+        values ($1, $2) \
+        returning id",
+        &[&uid, &username]
+    ).await?.get(0);
+
+    let password: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(PASSWORD_LEN)
+        .map(char::from)
+        .collect();
+
+    Password::create(
+        &transaction,
+        &user_id,
+        password.clone(),
+        state.sec().password_params(),
+        state.sec().peppers()
+    ).await.context("failed to create password for initial user")?;
+
+    let role_uid = ids::RoleUid::gen();
+    let role_id: ids::RoleId = if let Some(row) = transaction.query_opt(
+        "\
+        insert into authz_roles (uid, name) \
+        values ($1, $2) \
+        on conflict (name) do nothing \
+        returning id",
+        &[&role_uid, &ADMIN_ROLE]
+    ).await? {
+        row.get(0)
+    } else {
+        transaction.query_one(
+            "select id from authz_roles where name = $1",
+            &[&ADMIN_ROLE]
+        ).await?.get(0)
+    };
+
+    for scope in Scope::ALL {
+        for ability in Ability::ALL {
+            transaction.execute(
+                "\
+                insert into authz_permissions (role_id, scope, ability) \
+                values ($1, $2, $3) \
+                on conflict (role_id, scope, ability) do nothing",
+                &[&role_id, &scope, &ability]
+            ).await?;
+        }
+    }
+
+    transaction.execute(
+        "insert into user_roles (user_id, role_id) values ($1, $2)",
+        &[&user_id, &role_id]
+    ).await?;
+
+    transaction.commit().await?;
+
+    println!("created initial user. the password will not be shown again\nusername: {username}\npassword: {password}");
+
+    Ok(())
+}
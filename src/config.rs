@@ -25,7 +25,17 @@ pub trait TryDefault: Sized {
 pub struct CliArgs {
     /// a config path or directory to load file from
     #[arg(long)]
-    config: Vec<PathBuf>
+    config: Vec<PathBuf>,
+
+    /// validates the config and database connection then exits without
+    /// starting the server
+    #[arg(long)]
+    pub check_config: bool,
+
+    /// creates the initial user with the given username and a generated
+    /// password that has every permission. only runs if no users exist
+    #[arg(long, value_name = "USERNAME")]
+    pub bootstrap_admin: Option<String>,
 }
 
 #[derive(Debug)]
@@ -98,6 +108,14 @@ impl Config {
             }
         }
 
+        if let Some(listener) = &settings.metrics.listener {
+            if !settings.listeners.contains_key(listener) {
+                return Err(error::Error::new().context(format!(
+                    "settings.metrics.listener \"{listener}\" is not a known listener"
+                )));
+            }
+        }
+
         tracing::debug!("{settings:#?}");
 
         let kdf = hkdf::Hkdf::<sha3::Sha3_512>::new(None, settings.master_key.as_bytes());
@@ -211,6 +229,12 @@ pub struct Settings {
     pub assets: Assets,
     pub sec: Sec,
     pub db: Db,
+    pub metrics: Metrics,
+    pub rate_limit: RateLimit,
+    pub limits: Limits,
+    pub trash: Trash,
+    pub compression: Compression,
+    pub email: Email,
 }
 
 impl Settings {
@@ -260,6 +284,30 @@ impl Settings {
             self.db.merge(src, dot.push(&"db"), db)?;
         }
 
+        if let Some(metrics) = settings.metrics {
+            self.metrics.merge(src, dot.push(&"metrics"), metrics)?;
+        }
+
+        if let Some(rate_limit) = settings.rate_limit {
+            self.rate_limit.merge(src, dot.push(&"rate_limit"), rate_limit)?;
+        }
+
+        if let Some(limits) = settings.limits {
+            self.limits.merge(src, dot.push(&"limits"), limits)?;
+        }
+
+        if let Some(trash) = settings.trash {
+            self.trash.merge(src, dot.push(&"trash"), trash)?;
+        }
+
+        if let Some(compression) = settings.compression {
+            self.compression.merge(src, dot.push(&"compression"), compression)?;
+        }
+
+        if let Some(email) = settings.email {
+            self.email.merge(src, dot.push(&"email"), email)?;
+        }
+
         Ok(())
     }
 }
@@ -281,6 +329,12 @@ impl TryDefault for Settings {
             assets: Assets::default(),
             sec: Sec::default(),
             db: Db::default(),
+            metrics: Metrics::default(),
+            rate_limit: RateLimit::default(),
+            limits: Limits::default(),
+            trash: Trash::default(),
+            compression: Compression::default(),
+            email: Email::default(),
         })
     }
 }
@@ -394,6 +448,7 @@ impl TryDefault for Templates {
 pub struct Assets {
     pub files: HashMap<String, PathBuf>,
     pub directories: HashMap<String, PathBuf>,
+    pub cache_control: Option<String>,
 }
 
 impl Assets {
@@ -426,6 +481,17 @@ impl Assets {
             }
         }
 
+        if let Some(cache_control) = assets.cache_control {
+            if axum::http::HeaderValue::from_str(&cache_control).is_err() {
+                return Err(error::Error::new().context(format!(
+                    "{} is not a valid header value. file: {src}",
+                    dot.push(&"cache_control")
+                )));
+            }
+
+            self.cache_control = Some(cache_control);
+        }
+
         Ok(())
     }
 }
@@ -434,6 +500,7 @@ impl Default for Assets {
         Assets {
             files: HashMap::new(),
             directories: HashMap::new(),
+            cache_control: None,
         }
     }
 }
@@ -442,6 +509,9 @@ impl Default for Assets {
 pub struct Sec {
     pub session: Session,
     pub secrets: Secrets,
+    pub login: Login,
+    pub totp: Totp,
+    pub password: Password,
 }
 
 impl Sec {
@@ -450,6 +520,18 @@ impl Sec {
             self.session.merge(src, dot.push(&"session"), session)?;
         }
 
+        if let Some(login) = sec.login {
+            self.login.merge(src, dot.push(&"login"), login)?;
+        }
+
+        if let Some(totp) = sec.totp {
+            self.totp.merge(src, dot.push(&"totp"), totp)?;
+        }
+
+        if let Some(password) = sec.password {
+            self.password.merge(src, dot.push(&"password"), password)?;
+        }
+
         Ok(())
     }
 }
@@ -459,18 +541,46 @@ impl Default for Sec {
         Sec {
             session: Default::default(),
             secrets: Default::default(),
+            login: Default::default(),
+            totp: Default::default(),
+            password: Default::default(),
         }
     }
 }
 
+/// the longest a session can be valid for without being extended. 1 year
+const MAX_SESSION_LIFETIME: u64 = 60 * 60 * 24 * 365;
+
 #[derive(Debug)]
 pub struct Session {
     pub hash: Hash,
+    /// will always be true if same_site is None
     pub secure: bool,
+    /// name of the cookie that the session token is stored in
+    pub cookie_name: String,
+    pub same_site: SameSite,
+    /// optional domain to set on the session cookie
+    pub domain: Option<String>,
+    /// number of seconds a session is valid for when sliding is disabled
+    pub lifetime: u64,
+    /// number of seconds a session is valid for until it has been
+    /// authenticated and verified
+    pub pending_lifetime: u64,
+    /// extends the expiration of a session as it is used
+    pub sliding: bool,
+    /// number of seconds a session can go unused before it expires when
+    /// sliding is enabled
+    pub idle_timeout: u64,
+    /// max number of seconds a session can be extended to from when it was
+    /// issued when sliding is enabled
+    pub max_lifetime: u64,
+    /// number of seconds a session key is still accepted for after it has
+    /// been replaced by a newer key
+    pub key_grace: u64,
 }
 
 impl Session {
-    fn merge(&mut self, _src: &SrcFile<'_>, _dot: DotPath<'_>, session: shape::Session) -> error::Result<()> {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, session: shape::Session) -> error::Result<()> {
         if let Some(hash) = session.hash {
             self.hash = match hash {
                 shape::Hash::Blake3 => Hash::Blake3,
@@ -484,6 +594,106 @@ impl Session {
             self.secure = secure;
         }
 
+        if let Some(cookie_name) = session.cookie_name {
+            let valid = !cookie_name.is_empty() && cookie_name.chars().all(|ch| {
+                ch.is_ascii_graphic() && !"()<>@,;:\\\"/[]?={}".contains(ch)
+            });
+
+            if !valid {
+                return Err(error::Error::new().context(format!(
+                    "{} is not a valid cookie name. file: {src}", dot.push(&"cookie_name")
+                )));
+            }
+
+            self.cookie_name = cookie_name;
+        }
+
+        if let Some(same_site) = session.same_site {
+            self.same_site = match same_site {
+                shape::SameSite::Strict => SameSite::Strict,
+                shape::SameSite::Lax => SameSite::Lax,
+                shape::SameSite::None => SameSite::None,
+            };
+        }
+
+        if let Some(domain) = session.domain {
+            if domain.is_empty() {
+                return Err(error::Error::new().context(format!(
+                    "{} cannot be empty. file: {src}", dot.push(&"domain")
+                )));
+            }
+
+            self.domain = Some(domain);
+        }
+
+        // browsers will reject cookies with SameSite=None that are not
+        // also marked secure
+        if self.same_site == SameSite::None && !self.secure {
+            tracing::warn!(
+                "{} is None, forcing {} to true. file: {src}",
+                dot.push(&"same_site"),
+                dot.push(&"secure")
+            );
+
+            self.secure = true;
+        }
+
+        if let Some(lifetime) = session.lifetime {
+            if lifetime == 0 || lifetime > MAX_SESSION_LIFETIME {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0 and at most {MAX_SESSION_LIFETIME}. file: {src}",
+                    dot.push(&"lifetime")
+                )));
+            }
+
+            self.lifetime = lifetime;
+        }
+
+        if let Some(pending_lifetime) = session.pending_lifetime {
+            if pending_lifetime == 0 || pending_lifetime > MAX_SESSION_LIFETIME {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0 and at most {MAX_SESSION_LIFETIME}. file: {src}",
+                    dot.push(&"pending_lifetime")
+                )));
+            }
+
+            self.pending_lifetime = pending_lifetime;
+        }
+
+        if let Some(sliding) = session.sliding {
+            self.sliding = sliding;
+        }
+
+        if let Some(idle_timeout) = session.idle_timeout {
+            if idle_timeout == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"idle_timeout")
+                )));
+            }
+
+            self.idle_timeout = idle_timeout;
+        }
+
+        if let Some(max_lifetime) = session.max_lifetime {
+            if max_lifetime == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_lifetime")
+                )));
+            }
+
+            self.max_lifetime = max_lifetime;
+        }
+
+        if let Some(key_grace) = session.key_grace {
+            if key_grace == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"key_grace")
+                )));
+            }
+
+            self.key_grace = key_grace;
+        }
+
         Ok(())
     }
 }
@@ -493,10 +703,242 @@ impl Default for Session {
         Session {
             hash: Hash::Blake3,
             secure: true,
+            cookie_name: String::from("session_id"),
+            same_site: SameSite::Strict,
+            domain: None,
+            // 7 days
+            lifetime: 60 * 60 * 24 * 7,
+            // 10 minutes
+            pending_lifetime: 60 * 10,
+            sliding: false,
+            // 1 day
+            idle_timeout: 60 * 60 * 24,
+            // 30 days
+            max_lifetime: 60 * 60 * 24 * 30,
+            // 30 days
+            key_grace: 60 * 60 * 24 * 30,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Totp {
+    /// number of steps before the current one that a code will be accepted
+    /// for
+    pub window_before: u8,
+    /// number of steps after the current one that a code will be accepted
+    /// for
+    pub window_after: u8,
+    /// max number of recovery keys a single user can have
+    pub max_recovery_keys: u16,
+}
+
+impl Totp {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, totp: shape::Totp) -> error::Result<()> {
+        if let Some(window_before) = totp.window_before {
+            if !rfs_lib::sec::authn::totp::window_valid(&window_before) {
+                return Err(error::Error::new().context(format!(
+                    "{} must be between 0 and {}. file: {src}",
+                    dot.push(&"window_before"),
+                    rfs_lib::sec::authn::totp::MAX_WINDOW
+                )));
+            }
+
+            self.window_before = window_before;
+        }
+
+        if let Some(window_after) = totp.window_after {
+            if !rfs_lib::sec::authn::totp::window_valid(&window_after) {
+                return Err(error::Error::new().context(format!(
+                    "{} must be between 0 and {}. file: {src}",
+                    dot.push(&"window_after"),
+                    rfs_lib::sec::authn::totp::MAX_WINDOW
+                )));
+            }
+
+            self.window_after = window_after;
+        }
+
+        if let Some(max_recovery_keys) = totp.max_recovery_keys {
+            if max_recovery_keys == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_recovery_keys")
+                )));
+            }
+
+            self.max_recovery_keys = max_recovery_keys;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Totp {
+    fn default() -> Self {
+        Totp {
+            window_before: 1,
+            window_after: 1,
+            max_recovery_keys: 10,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Login {
+    /// number of failed attempts allowed within the window before the user
+    /// is locked out
+    pub max_attempts: u32,
+    /// number of seconds that failed attempts are counted for
+    pub window: u64,
+    /// number of seconds for the first lockout. each lockout after will be
+    /// double the previous
+    pub lockout: u64,
+    /// max number of seconds that a user can be locked out for
+    pub max_lockout: u64,
+}
+
+impl Login {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, login: shape::Login) -> error::Result<()> {
+        if let Some(max_attempts) = login.max_attempts {
+            if max_attempts == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_attempts")
+                )));
+            }
+
+            self.max_attempts = max_attempts;
+        }
+
+        if let Some(window) = login.window {
+            if window == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"window")
+                )));
+            }
+
+            self.window = window;
+        }
+
+        if let Some(lockout) = login.lockout {
+            if lockout == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"lockout")
+                )));
+            }
+
+            self.lockout = lockout;
+        }
+
+        if let Some(max_lockout) = login.max_lockout {
+            if max_lockout == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_lockout")
+                )));
+            }
+
+            self.max_lockout = max_lockout;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Login {
+    fn default() -> Self {
+        Login {
+            max_attempts: 5,
+            // 15 minutes
+            window: 60 * 15,
+            lockout: 30,
+            // 1 hour
+            max_lockout: 60 * 60,
+        }
+    }
+}
+
+/// argon2id parameters used when hashing new passwords. existing hashes
+/// store the parameters they were created with so changing these will not
+/// affect verifying them
+#[derive(Debug)]
+pub struct Password {
+    /// memory cost in KiB
+    pub mem_cost: u32,
+    /// number of passes over memory
+    pub time_cost: u32,
+    /// degree of parallelism
+    pub lanes: u32,
+    /// length of the resulting hash in bytes
+    pub hash_length: u32,
+}
+
+impl Password {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, password: shape::Password) -> error::Result<()> {
+        if let Some(time_cost) = password.time_cost {
+            if time_cost == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"time_cost")
+                )));
+            }
+
+            self.time_cost = time_cost;
+        }
+
+        if let Some(lanes) = password.lanes {
+            if lanes == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"lanes")
+                )));
+            }
+
+            self.lanes = lanes;
+        }
+
+        if let Some(hash_length) = password.hash_length {
+            if hash_length < 4 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be at least 4. file: {src}", dot.push(&"hash_length")
+                )));
+            }
+
+            self.hash_length = hash_length;
+        }
+
+        if let Some(mem_cost) = password.mem_cost {
+            self.mem_cost = mem_cost;
+        }
+
+        // argon2 requires at least 8 KiB of memory for each lane
+        if self.mem_cost < self.lanes.saturating_mul(8) {
+            return Err(error::Error::new().context(format!(
+                "{} must be at least 8 times {}. file: {src}",
+                dot.push(&"mem_cost"),
+                dot.push(&"lanes")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Password {
+    fn default() -> Self {
+        Password {
+            // 19 MiB
+            mem_cost: 19456,
+            time_cost: 2,
+            lanes: 1,
+            hash_length: 32,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
 #[derive(Debug)]
 pub enum Hash {
     Blake3,
@@ -522,11 +964,16 @@ pub struct Db {
     pub password: Option<String>,
     pub host: String,
     pub port: u16,
-    pub dbname: String
+    pub dbname: String,
+    /// maximum number of connections in the pool
+    pub max_size: usize,
+    /// seconds to wait when creating a new connection
+    pub connect_timeout: u64,
+    pub recycle: Recycle,
 }
 
 impl Db {
-    fn merge(&mut self, _src: &SrcFile<'_>, _dot: DotPath<'_>, db: shape::Db) -> error::Result<()> {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, db: shape::Db) -> error::Result<()> {
         if let Some(user) = db.user {
             self.user = user;
         }
@@ -547,6 +994,33 @@ impl Db {
             self.dbname = dbname;
         }
 
+        if let Some(max_size) = db.max_size {
+            if max_size == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_size")
+                )));
+            }
+
+            self.max_size = max_size;
+        }
+
+        if let Some(connect_timeout) = db.connect_timeout {
+            if connect_timeout == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"connect_timeout")
+                )));
+            }
+
+            self.connect_timeout = connect_timeout;
+        }
+
+        if let Some(recycle) = db.recycle {
+            self.recycle = match recycle {
+                shape::Recycle::Fast => Recycle::Fast,
+                shape::Recycle::Verified => Recycle::Verified,
+            };
+        }
+
         Ok(())
     }
 }
@@ -559,10 +1033,289 @@ impl Default for Db {
             host: "localhost".into(),
             port: 5432,
             dbname: "rfs".into(),
+            max_size: 4,
+            connect_timeout: 10,
+            recycle: Recycle::Fast,
+        }
+    }
+}
+
+/// how connections are checked before being given out by the pool
+#[derive(Debug)]
+pub enum Recycle {
+    /// only checks if the connection has been closed
+    Fast,
+    /// runs a test query on the connection
+    Verified,
+}
+
+#[derive(Debug)]
+pub struct Metrics {
+    pub enabled: bool,
+    pub listener: Option<String>,
+}
+
+impl Metrics {
+    fn merge(&mut self, _src: &SrcFile<'_>, _dot: DotPath<'_>, metrics: shape::Metrics) -> error::Result<()> {
+        if let Some(enabled) = metrics.enabled {
+            self.enabled = enabled;
+        }
+
+        if let Some(listener) = metrics.listener {
+            self.listener = Some(listener);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            enabled: false,
+            listener: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RateLimit {
+    pub enabled: bool,
+    /// max number of requests that can be made at once
+    pub capacity: u32,
+    /// number of requests restored every second
+    pub refill: u32,
+}
+
+impl RateLimit {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, rate_limit: shape::RateLimit) -> error::Result<()> {
+        if let Some(enabled) = rate_limit.enabled {
+            self.enabled = enabled;
+        }
+
+        if let Some(capacity) = rate_limit.capacity {
+            if capacity == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"capacity")
+                )));
+            }
+
+            self.capacity = capacity;
+        }
+
+        if let Some(refill) = rate_limit.refill {
+            if refill == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"refill")
+                )));
+            }
+
+            self.refill = refill;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        RateLimit {
+            enabled: false,
+            capacity: 120,
+            refill: 10,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Limits {
+    /// max number of bytes that can be uploaded for a single file
+    pub max_file_size: u64,
+    /// max number of bytes allowed for json request bodies
+    pub max_json_size: usize,
+    /// max number of path segments an fs item can be created at
+    pub max_depth: usize,
+    /// max number of items a single directory can contain
+    pub max_children: u64,
+    pub uploads: Uploads,
+}
+
+impl Limits {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, limits: shape::Limits) -> error::Result<()> {
+        if let Some(max_file_size) = limits.max_file_size {
+            if max_file_size == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_file_size")
+                )));
+            }
+
+            self.max_file_size = max_file_size;
+        }
+
+        if let Some(max_json_size) = limits.max_json_size {
+            if max_json_size == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_json_size")
+                )));
+            }
+
+            self.max_json_size = max_json_size;
+        }
+
+        if let Some(max_depth) = limits.max_depth {
+            if max_depth == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_depth")
+                )));
+            }
+
+            self.max_depth = max_depth;
+        }
+
+        if let Some(max_children) = limits.max_children {
+            if max_children == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"max_children")
+                )));
+            }
+
+            self.max_children = max_children;
+        }
+
+        if let Some(uploads) = limits.uploads {
+            self.uploads.merge(src, dot.push(&"uploads"), uploads)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            // 4 GiB
+            max_file_size: 4 * 1024 * 1024 * 1024,
+            // 2 MiB
+            max_json_size: 2 * 1024 * 1024,
+            max_depth: 64,
+            max_children: 100_000,
+            uploads: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Uploads {
+    /// max number of uploads that can be written to local storage mediums
+    /// at the same time
+    pub local: usize,
+    /// number of seconds an upload will wait for an open slot before the
+    /// server responds that it is busy
+    pub wait: u64,
+}
+
+impl Uploads {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, uploads: shape::Uploads) -> error::Result<()> {
+        if let Some(local) = uploads.local {
+            if local == 0 {
+                return Err(error::Error::new().context(format!(
+                    "{} must be greater than 0. file: {src}", dot.push(&"local")
+                )));
+            }
+
+            self.local = local;
+        }
+
+        if let Some(wait) = uploads.wait {
+            self.wait = wait;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Uploads {
+    fn default() -> Self {
+        Uploads {
+            local: 8,
+            wait: 30,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Trash {
+    /// number of days that an item will stay in the trash before it is
+    /// permanently removed
+    pub retention_days: u32,
+}
+
+impl Trash {
+    fn merge(&mut self, _src: &SrcFile<'_>, _dot: DotPath<'_>, trash: shape::Trash) -> error::Result<()> {
+        if let Some(retention_days) = trash.retention_days {
+            self.retention_days = retention_days;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Trash {
+    fn default() -> Self {
+        Trash {
+            retention_days: 30,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Compression {
+    /// if responses should be compressed when the client supports it
+    pub enabled: bool,
+    /// responses smaller than this number of bytes will not be compressed
+    pub min_size: u16,
+}
+
+impl Compression {
+    fn merge(&mut self, _src: &SrcFile<'_>, _dot: DotPath<'_>, compression: shape::Compression) -> error::Result<()> {
+        if let Some(enabled) = compression.enabled {
+            self.enabled = enabled;
+        }
+
+        if let Some(min_size) = compression.min_size {
+            self.min_size = min_size;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression {
+            enabled: true,
+            min_size: 1024,
         }
     }
 }
 
+#[derive(Debug, Default)]
+pub struct Email {
+    /// command that delivers email verification tokens. the email address
+    /// is given as the only argument and the token is written to stdin
+    pub command: Option<PathBuf>,
+}
+
+impl Email {
+    fn merge(&mut self, src: &SrcFile<'_>, dot: DotPath<'_>, email: shape::Email) -> error::Result<()> {
+        if let Some(command) = email.command {
+            self.command = Some(check_path(command, src, dot.push(&"command"), true)?);
+        }
+
+        Ok(())
+    }
+}
+
 fn check_path(given: PathBuf, src: &SrcFile<'_>, dot: DotPath<'_>, is_file: bool) -> error::Result<PathBuf> {
     let full = if given.is_absolute() {
         given
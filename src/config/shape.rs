@@ -13,6 +13,7 @@ pub struct Templates {
 pub struct Assets {
     pub files: Option<HashMap<String, PathBuf>>,
     pub directories: Option<HashMap<String, PathBuf>>,
+    pub cache_control: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,6 +23,22 @@ pub struct Db {
     pub host: Option<String>,
     pub port: Option<u16>,
     pub dbname: Option<String>,
+    pub max_size: Option<usize>,
+    pub connect_timeout: Option<u64>,
+    pub recycle: Option<Recycle>,
+}
+
+#[derive(Debug, Deserialize)]
+pub enum Recycle {
+    Fast,
+    Verified,
+}
+
+#[derive(Debug, Deserialize)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,6 +53,15 @@ pub enum Hash {
 pub struct Session {
     pub hash: Option<Hash>,
     pub secure: Option<bool>,
+    pub cookie_name: Option<String>,
+    pub same_site: Option<SameSite>,
+    pub domain: Option<String>,
+    pub lifetime: Option<u64>,
+    pub pending_lifetime: Option<u64>,
+    pub sliding: Option<bool>,
+    pub idle_timeout: Option<u64>,
+    pub max_lifetime: Option<u64>,
+    pub key_grace: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -44,10 +70,36 @@ pub enum Secrets {
     Local {}
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Totp {
+    pub window_before: Option<u8>,
+    pub window_after: Option<u8>,
+    pub max_recovery_keys: Option<u16>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Login {
+    pub max_attempts: Option<u32>,
+    pub window: Option<u64>,
+    pub lockout: Option<u64>,
+    pub max_lockout: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Password {
+    pub mem_cost: Option<u32>,
+    pub time_cost: Option<u32>,
+    pub lanes: Option<u32>,
+    pub hash_length: Option<u32>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Sec {
     pub session: Option<Session>,
     pub secrets: Option<Secrets>,
+    pub login: Option<Login>,
+    pub totp: Option<Totp>,
+    pub password: Option<Password>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,6 +114,50 @@ pub struct Listener {
     pub tls: Option<Tls>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Metrics {
+    pub enabled: Option<bool>,
+    pub listener: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RateLimit {
+    pub enabled: Option<bool>,
+    pub capacity: Option<u32>,
+    pub refill: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Trash {
+    pub retention_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Email {
+    pub command: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Compression {
+    pub enabled: Option<bool>,
+    pub min_size: Option<u16>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Limits {
+    pub max_file_size: Option<u64>,
+    pub max_json_size: Option<usize>,
+    pub max_depth: Option<usize>,
+    pub max_children: Option<u64>,
+    pub uploads: Option<Uploads>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Uploads {
+    pub local: Option<usize>,
+    pub wait: Option<u64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Settings {
     pub id: Option<i64>,
@@ -76,4 +172,10 @@ pub struct Settings {
 
     pub sec: Option<Sec>,
     pub db: Option<Db>,
+    pub metrics: Option<Metrics>,
+    pub rate_limit: Option<RateLimit>,
+    pub limits: Option<Limits>,
+    pub trash: Option<Trash>,
+    pub compression: Option<Compression>,
+    pub email: Option<Email>,
 }
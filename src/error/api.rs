@@ -62,7 +62,10 @@ impl IntoResponse for Error {
             tracing::error!("error when processing request\n{msg}");
         }
 
-        self.inner.into_response()
+        let mut inner = self.inner;
+        inner.set_request_id(crate::routing::current_request_id());
+
+        inner.into_response()
     }
 }
 
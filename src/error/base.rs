@@ -26,6 +26,14 @@ impl<I> Er<I> {
         self.src = Some(source.into());
         self
     }
+
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
 }
 
 impl<I> Default for Er<I>
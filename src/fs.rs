@@ -1,5 +1,10 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
 use rfs_lib::ids;
+use rfs_lib::query::{Limit, Offset};
 use deadpool_postgres::GenericClient;
+use futures::TryStreamExt;
 use tokio_postgres::Error as PgError;
 use chrono::{DateTime, Utc};
 
@@ -22,6 +27,14 @@ pub use file::File;
 
 pub mod backend;
 
+pub mod event;
+pub use event::{record_event, record_events};
+
+pub mod dir_hash;
+pub use dir_hash::{update_dir_hashes, update_many_dir_hashes};
+
+pub mod trash;
+
 #[derive(Debug)]
 pub enum Item {
     Root(Root),
@@ -36,7 +49,7 @@ impl Item {
         name: &str
     ) -> Result<Option<ids::FSId>, tokio_postgres::Error> {
         if let Some(row) = conn.query_opt(
-            "select id from fs where parent = $1 and basename = $2",
+            "select id from fs where parent = $1 and basename = $2 and deleted is null",
             &[parent, &name]
         ).await? {
             Ok(Some(row.get(0)))
@@ -53,6 +66,8 @@ impl Item {
 
         let id = ids::FSSet::new(row.get(0), row.get(1));
         let user = ids::UserSet::new(row.get(2), row.get(3));
+        let group = row.get::<usize, Option<ids::GroupId>>(20)
+            .map(|id| ids::GroupSet::new(id, row.get(21)));
         let storage = ids::StorageSet::new(row.get(4), row.get(5));
         let basename = row.get(8);
         let backend = sql::de_from_sql(row.get(15));
@@ -65,6 +80,7 @@ impl Item {
             consts::ROOT_TYPE => Item::Root(Root {
                 id,
                 user,
+                group,
                 storage,
                 basename,
                 backend,
@@ -77,6 +93,7 @@ impl Item {
             consts::FILE_TYPE => Item::File(File {
                 id,
                 user,
+                group,
                 storage,
                 parent: ids::FSSet::new(row.get(6), row.get(7)),
                 backend,
@@ -90,15 +107,19 @@ impl Item {
                 created,
                 updated,
                 deleted,
+                mtime: row.get(22),
             }),
             consts::DIR_TYPE => Item::Directory(Directory {
                 id,
                 user,
+                group,
                 storage,
                 backend,
                 parent: ids::FSSet::new(row.get(6), row.get(7)),
                 path: row.get(10),
                 basename,
+                hash: row.get::<usize, Option<Vec<u8>>>(14)
+                    .and_then(sql::try_blake3_hash_from_sql),
                 tags,
                 comment,
                 created,
@@ -134,10 +155,15 @@ impl Item {
                    fs.comment, \
                    fs.created, \
                    fs.updated, \
-                   fs.deleted \
+                   fs.deleted, \
+                   groups.id, \
+                   groups.uid, \
+                   fs.mtime \
             from fs \
             left join users on \
                 fs.user_id = users.id \
+            left join groups on \
+                fs.group_id = groups.id \
             left join storage on \
                 fs.storage_id = storage.id \
             left join fs as fs_parent on \
@@ -196,6 +222,56 @@ impl Item {
         }
     }
 
+    /// retrieves all of the given ids with one query for the records and
+    /// another for the tags. ids that are not found will not be in the
+    /// returned map
+    pub async fn retrieve_many(
+        conn: &impl GenericClient,
+        list: &[ids::FSId],
+    ) -> Result<HashMap<ids::FSId, Item>, PgError> {
+        if list.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let record_param: sql::ParamsArray<'_, 1> = [&list];
+        let tags_param: sql::ParamsArray<'_, 1> = [&list];
+        let record_query = format!("{} where fs.id = any($1)", Self::retrieve_base_query());
+
+        let record_fut = conn.query(&record_query, &record_param);
+        let tags_fut = conn.query_raw(
+            "\
+            select fs_tags.fs_id, \
+                   fs_tags.tag, \
+                   fs_tags.value \
+            from fs_tags \
+            where fs_tags.fs_id = any($1)",
+            tags_param
+        );
+
+        let (records, tags_stream) = tokio::try_join!(record_fut, tags_fut)?;
+
+        futures::pin_mut!(tags_stream);
+
+        let mut tags: HashMap<ids::FSId, tags::TagMap> = HashMap::new();
+
+        while let Some(row) = tags_stream.try_next().await? {
+            tags.entry(row.get(0))
+                .or_default()
+                .insert(row.get(1), row.get(2));
+        }
+
+        let mut rtn = HashMap::with_capacity(records.len());
+
+        for row in records {
+            let id: ids::FSId = row.get(0);
+            let item_tags = tags.remove(&id).unwrap_or_default();
+
+            rtn.insert(id, Self::query_to_item(row, item_tags)?);
+        }
+
+        Ok(rtn)
+    }
+
     pub fn id(&self) -> &ids::FSSet {
         match self {
             Self::Root(root) => &root.id,
@@ -212,6 +288,38 @@ impl Item {
         }
     }
 
+    pub fn group(&self) -> Option<&ids::GroupSet> {
+        match self {
+            Self::Root(root) => root.group.as_ref(),
+            Self::Directory(dir) => dir.group.as_ref(),
+            Self::File(file) => file.group.as_ref(),
+        }
+    }
+
+    pub fn backend(&self) -> &backend::Node {
+        match self {
+            Self::Root(root) => &root.backend,
+            Self::Directory(dir) => &dir.backend,
+            Self::File(file) => &file.backend,
+        }
+    }
+
+    pub fn basename(&self) -> &str {
+        match self {
+            Self::Root(root) => &root.basename,
+            Self::Directory(dir) => &dir.basename,
+            Self::File(file) => &file.basename,
+        }
+    }
+
+    pub fn deleted(&self) -> Option<&DateTime<Utc>> {
+        match self {
+            Self::Root(root) => root.deleted.as_ref(),
+            Self::Directory(dir) => dir.deleted.as_ref(),
+            Self::File(file) => file.deleted.as_ref(),
+        }
+    }
+
     pub fn as_container(&self) -> Option<&dyn traits::Container> {
         match self {
             Self::Root(root) => Some(root),
@@ -236,6 +344,30 @@ impl Item {
         }
     }
 
+    pub fn set_group(&mut self, group: Option<ids::GroupSet>) -> Option<ids::GroupSet> {
+        match self {
+            Self::Root(root) => std::mem::replace(&mut root.group, group),
+            Self::Directory(dir) => std::mem::replace(&mut dir.group, group),
+            Self::File(file) => std::mem::replace(&mut file.group, group),
+        }
+    }
+
+    pub fn set_updated(&mut self, updated: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Root(root) => std::mem::replace(&mut root.updated, updated),
+            Self::Directory(dir) => std::mem::replace(&mut dir.updated, updated),
+            Self::File(file) => std::mem::replace(&mut file.updated, updated),
+        }
+    }
+
+    pub fn tags_mut(&mut self) -> &mut tags::TagMap {
+        match self {
+            Self::Root(root) => &mut root.tags,
+            Self::Directory(dir) => &mut dir.tags,
+            Self::File(file) => &mut file.tags,
+        }
+    }
+
     pub fn set_tags(&mut self, tags: tags::TagMap) -> tags::TagMap {
         match self {
             Self::Root(root) => std::mem::replace(&mut root.tags, tags),
@@ -274,6 +406,13 @@ impl Item {
     pub fn into_schema(self) -> rfs_api::fs::Item {
         self.into()
     }
+
+    /// the schema used for lists of items. tags, comments, hashes and the
+    /// backend are left out so the full item is only sent when requested
+    /// directly
+    pub fn into_schema_shallow(self) -> rfs_api::fs::ItemMin {
+        self.into()
+    }
 }
 
 impl Common for Item {
@@ -373,6 +512,29 @@ impl From<Item> for rfs_api::fs::Item {
     }
 }
 
+impl From<Item> for rfs_api::fs::ItemMin {
+    fn from(item: Item) -> Self {
+        match item {
+            Item::Root(root) => rfs_api::fs::ItemMin::Root(root.into()),
+            Item::Directory(dir) => rfs_api::fs::ItemMin::Directory(dir.into()),
+            Item::File(file) => rfs_api::fs::ItemMin::File(file.into()),
+        }
+    }
+}
+
+/// the filters that can be applied when retrieving a list of storage
+/// mediums
+pub struct StorageFilter<'a> {
+    /// only include mediums that have all of the given tags
+    pub tags: &'a [tags::TagFilter],
+    /// include mediums that have been deleted
+    pub deleted: bool,
+    pub limit: Limit,
+    pub offset: Offset,
+    /// start after the given medium. the offset is ignored if this is set
+    pub last_id: Option<&'a ids::StorageUid>,
+}
+
 pub struct Storage {
     pub id: ids::StorageSet,
     pub name: String,
@@ -385,6 +547,26 @@ pub struct Storage {
 }
 
 impl Storage {
+    /// opens a node of this storage medium for reading
+    pub async fn open_reader(&self, node: &backend::Node) -> Result<backend::Reader, crate::error::api::Error> {
+        backend::Pair::match_up(&self.backend, node)?.open_reader().await
+    }
+
+    /// opens a node of this storage medium for writing
+    pub async fn open_writer(&self, node: &backend::Node) -> Result<tokio::fs::File, crate::error::api::Error> {
+        backend::Pair::match_up(&self.backend, node)?.open_writer().await
+    }
+
+    /// removes a file node from this storage medium
+    pub async fn remove(&self, node: &backend::Node) -> Result<(), crate::error::api::Error> {
+        backend::Pair::match_up(&self.backend, node)?.remove().await
+    }
+
+    /// checks if a node exists in this storage medium
+    pub async fn exists(&self, node: &backend::Node) -> Result<bool, crate::error::api::Error> {
+        backend::Pair::match_up(&self.backend, node)?.exists().await
+    }
+
     pub async fn name_check<N>(
         conn: &impl GenericClient,
         name: N
@@ -480,6 +662,114 @@ impl Storage {
         }
     }
 
+    /// counts all storage mediums owned by the user that match the given
+    /// filter. the pagination values of the filter are ignored
+    pub async fn count_all(
+        conn: &impl GenericClient,
+        user_id: &ids::UserId,
+        filter: &StorageFilter<'_>,
+    ) -> Result<i64, PgError> {
+        let mut params: sql::ParamsVec = vec![user_id];
+        let mut query = String::from("select count(*) from storage where storage.user_id = $1");
+
+        if !filter.deleted {
+            query.push_str(" and storage.deleted is null");
+        }
+
+        tags::write_filters(&mut query, &mut params, "storage_tags", "storage_id", "storage.id", filter.tags);
+
+        let row = conn.query_one(query.as_str(), params.as_slice()).await?;
+
+        Ok(row.get(0))
+    }
+
+    /// retrieves all storage mediums owned by the user that match the given
+    /// filter along with their tags
+    pub async fn retrieve_all(
+        conn: &impl GenericClient,
+        user_id: &ids::UserId,
+        filter: &StorageFilter<'_>,
+    ) -> Result<Vec<Self>, PgError> {
+        let offset_num = filter.limit.sql_offset(filter.offset);
+        let mut params: sql::ParamsVec = vec![user_id];
+        let mut query = format!("{} where storage.user_id = $1", Self::retrieve_base_query());
+
+        if !filter.deleted {
+            query.push_str(" and storage.deleted is null");
+        }
+
+        tags::write_filters(&mut query, &mut params, "storage_tags", "storage_id", "storage.id", filter.tags);
+
+        if let Some(last_id) = filter.last_id {
+            write!(
+                &mut query,
+                " and storage.id > (select storage.id from storage where storage.uid = ${}) \
+                order by storage.id \
+                limit ${}",
+                sql::push_param(&mut params, last_id),
+                sql::push_param(&mut params, &filter.limit),
+            ).unwrap();
+        } else {
+            write!(
+                &mut query,
+                " order by storage.id \
+                limit ${} \
+                offset ${}",
+                sql::push_param(&mut params, &filter.limit),
+                sql::push_param(&mut params, &offset_num),
+            ).unwrap();
+        }
+
+        let result = conn.query_raw(query.as_str(), params).await?;
+
+        futures::pin_mut!(result);
+
+        let mut rtn = Vec::with_capacity(filter.limit as usize);
+
+        while let Some(row) = result.try_next().await? {
+            rtn.push(Storage {
+                id: ids::StorageSet::new(row.get(0), row.get(1)),
+                user: ids::UserSet::new(row.get(2), row.get(3)),
+                name: row.get(4),
+                backend: sql::de_from_sql(row.get(5)),
+                tags: tags::TagMap::new(),
+                created: row.get(6),
+                updated: row.get(7),
+                deleted: row.get(8),
+            });
+        }
+
+        if rtn.is_empty() {
+            return Ok(rtn);
+        }
+
+        let storage_ids: Vec<ids::StorageId> = rtn.iter()
+            .map(|storage| *storage.id.local())
+            .collect();
+
+        let tag_rows = conn.query(
+            "\
+            select storage_tags.storage_id, \
+                   storage_tags.tag, \
+                   storage_tags.value \
+            from storage_tags \
+            where storage_tags.storage_id = any($1)",
+            &[&storage_ids]
+        ).await?;
+
+        let mut tag_maps: HashMap<ids::StorageId, tags::TagMap> = tags::group_rows(
+            tag_rows.into_iter().map(|row| (row.get(0), row.get(1), row.get(2)))
+        );
+
+        for storage in &mut rtn {
+            if let Some(tags) = tag_maps.remove(storage.id.local()) {
+                storage.tags = tags;
+            }
+        }
+
+        Ok(rtn)
+    }
+
     fn from_fs_base_query() -> &'static str {
             "\
             select storage.id, \
@@ -572,6 +862,17 @@ impl Storage {
     }
 }
 
+impl From<Storage> for rfs_api::fs::StorageMin {
+    fn from(storage: Storage) -> Self {
+        rfs_api::fs::StorageMin {
+            uid: storage.id.into_uid(),
+            name: storage.name,
+            user_uid: storage.user.into_uid(),
+            backend: storage.backend.into(),
+        }
+    }
+}
+
 impl From<Storage> for rfs_api::fs::Storage {
     fn from(storage: Storage) -> Self {
         rfs_api::fs::Storage {
@@ -587,29 +888,181 @@ impl From<Storage> for rfs_api::fs::Storage {
     }
 }
 
+/// takes a transaction level advisory lock on the basename in the given
+/// parent. concurrent requests creating the same basename will wait for the
+/// first transaction to finish so that the name check after the lock sees
+/// the committed item. the lock is released when the transaction ends
+pub async fn lock_basename(
+    conn: &impl GenericClient,
+    parent: &ids::FSId,
+    basename: &str,
+) -> Result<(), PgError> {
+    let _ = conn.execute(
+        "select pg_advisory_xact_lock(hashtextextended($2, $1))",
+        &[parent, &basename]
+    ).await?;
+
+    Ok(())
+}
+
+/// creates a basename that is not in use by the parent by adding a counter
+/// to the end of the stem. "file.txt" will be tried as "file (1).txt",
+/// "file (2).txt", etc. until one is found or the attempts run out
+pub async fn name_gen(
+    conn: &impl GenericClient,
+    parent: &ids::FSId,
+    name: &str,
+    attempts: usize,
+) -> Result<Option<String>, PgError> {
+    // a leading "." is part of the stem and not an extension
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+        _ => (name, None),
+    };
+
+    for count in 1..=attempts {
+        let candidate = if let Some(ext) = ext {
+            format!("{stem} ({count}).{ext}")
+        } else {
+            format!("{stem} ({count})")
+        };
+
+        if !rfs_lib::fs::basename_valid(&candidate) {
+            return Ok(None);
+        }
+
+        if Item::name_check(conn, parent, &candidate).await?.is_none() {
+            return Ok(Some(candidate));
+        }
+    }
+
+    Ok(None)
+}
+
 // ----------------------------------------------------------------------------
 
 use crate::error::{ApiError, ApiResult};
-use crate::error::api::{ApiErrorKind, Context};
+use crate::error::api::{ApiErrorKind, Context, Detail};
 use crate::sec::authn::initiator::Initiator;
 
+/// converts a violation of the unique basename index into an AlreadyExists
+/// error. any other error is passed through
+pub fn basename_conflict(err: PgError) -> ApiError {
+    if crate::sql::unique_constraint_error(&err) == Some(consts::BASENAME_INDEX) {
+        ApiError::from((
+            ApiErrorKind::AlreadyExists,
+            Detail::with_key("basename")
+        ))
+    } else {
+        err.into()
+    }
+}
+
+/// checks that an item created under the given parent path will not go past
+/// the max depth. the depth of the new item is the number of segments in the
+/// path of its parent plus itself
+pub fn check_depth(limits: &crate::state::Limits, parent_path: &str) -> ApiResult<()> {
+    let depth = parent_path.split('/').count() + 1;
+
+    if depth > limits.max_depth {
+        Err(ApiError::from((
+            ApiErrorKind::ValidationFailed,
+            Detail::with_key("depth"),
+            format!("the max depth of {} has been reached", limits.max_depth)
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// checks that the given parent is able to contain another item without
+/// going past the max number of children
+pub async fn check_children(
+    conn: &impl GenericClient,
+    limits: &crate::state::Limits,
+    parent: &ids::FSId,
+) -> ApiResult<()> {
+    let row = conn.query_one(
+        "select count(*) from fs where parent = $1 and deleted is null",
+        &[parent]
+    ).await.context("failed to count children of directory")?;
+
+    let count: i64 = row.get(0);
+
+    if count as u64 >= limits.max_children {
+        Err(ApiError::from((
+            ApiErrorKind::ValidationFailed,
+            Detail::with_key("children"),
+            format!("the directory has reached the max of {} items", limits.max_children)
+        )))
+    } else {
+        Ok(())
+    }
+}
+
 pub async fn fetch_item_uid(
     conn: &impl GenericClient,
     uid: &ids::FSUid,
     initiator: &Initiator,
+) -> ApiResult<Item> {
+    let item = fetch_any_item_uid(conn, uid, initiator).await?;
+
+    if item.deleted().is_some() {
+        Err(ApiError::from(ApiErrorKind::FileNotFound))
+    } else {
+        Ok(item)
+    }
+}
+
+/// retrieves the fs item even if it has been moved to the trash
+pub async fn fetch_any_item_uid(
+    conn: &impl GenericClient,
+    uid: &ids::FSUid,
+    initiator: &Initiator,
 ) -> ApiResult<Item> {
     let item = Item::retrieve_uid(conn, uid)
         .await
         .context("failed to retrieve fs item by uid")?
         .kind(ApiErrorKind::FileNotFound)?;
 
-    if initiator.user.id != *item.user_id() {
+    if !has_access(conn, initiator, &item).await? {
         Err(ApiError::from(ApiErrorKind::PermissionDenied))
     } else {
         Ok(item)
     }
 }
 
+/// checks if the initiator is able to access the fs item. the initiator must
+/// either own the item or be a member of the group attached to it
+pub async fn has_access(
+    conn: &impl GenericClient,
+    initiator: &Initiator,
+    item: &Item,
+) -> ApiResult<bool> {
+    if initiator.user.id == *item.user_id() {
+        return Ok(true);
+    }
+
+    let Some(group) = item.group() else {
+        return Ok(false);
+    };
+
+    is_group_member(conn, initiator.user.id.local(), group.local()).await
+}
+
+pub async fn is_group_member(
+    conn: &impl GenericClient,
+    user_id: &ids::UserId,
+    group_id: &ids::GroupId,
+) -> ApiResult<bool> {
+    let row = conn.query_one(
+        "select exists(select 1 from group_users where user_id = $1 and group_id = $2)",
+        &[user_id, group_id]
+    ).await.context("failed to check group membership")?;
+
+    Ok(row.get(0))
+}
+
 pub async fn fetch_storage_from_fs_uid(
     conn: &impl GenericClient,
     fs_uid: &ids::FSUid,
@@ -619,3 +1072,173 @@ pub async fn fetch_storage_from_fs_uid(
         .context("failed to retrieve storage item from fs uid")?
         .kind(ApiErrorKind::StorageNotFound)
 }
+
+/// walks the given path segments from the root of the storage medium to find
+/// the requested item. access is checked at every level and any failure is
+/// reported as not found so that the existence of items under a directory
+/// the initiator cannot read is not revealed
+pub async fn lookup_path(
+    conn: &impl GenericClient,
+    storage_uid: &ids::StorageUid,
+    segments: &[&str],
+    initiator: &Initiator,
+) -> ApiResult<Item> {
+    let storage = Storage::retrieve_uid(conn, storage_uid)
+        .await
+        .context("failed to retrieve storage item by uid")?
+        .kind(ApiErrorKind::StorageNotFound)?;
+
+    if storage.deleted.is_some() {
+        return Err(ApiError::from(ApiErrorKind::StorageNotFound));
+    }
+
+    let root_row = conn.query_opt(
+        "select id from fs where storage_id = $1 and fs_type = $2 and deleted is null",
+        &[storage.id.local(), &consts::ROOT_TYPE]
+    ).await.context("failed to retrieve storage root")?;
+
+    let root_id: ids::FSId = root_row.kind(ApiErrorKind::FileNotFound)?.get(0);
+
+    let mut current = Item::retrieve(conn, &root_id)
+        .await
+        .context("failed to retrieve fs item")?
+        .kind(ApiErrorKind::FileNotFound)?;
+
+    if !has_access(conn, initiator, &current).await? {
+        return Err(ApiError::from(ApiErrorKind::FileNotFound));
+    }
+
+    for segment in segments {
+        if current.as_container().is_none() {
+            return Err(ApiError::from(ApiErrorKind::FileNotFound));
+        }
+
+        let next_id = Item::name_check(conn, current.id().local(), segment)
+            .await
+            .context("failed to check for fs item name")?
+            .kind(ApiErrorKind::FileNotFound)?;
+
+        current = Item::retrieve(conn, &next_id)
+            .await
+            .context("failed to retrieve fs item")?
+            .kind(ApiErrorKind::FileNotFound)?;
+
+        if !has_access(conn, initiator, &current).await? {
+            return Err(ApiError::from(ApiErrorKind::FileNotFound));
+        }
+    }
+
+    Ok(current)
+}
+
+/// the items on disk for a storage medium. used to remove the contents of a
+/// medium after its records have been deleted
+pub struct StorageContents {
+    backend: backend::Config,
+    entries: Vec<(consts::FsType, backend::Node)>,
+}
+
+impl StorageContents {
+    /// retrieves the contents of all the storage mediums owned by the user.
+    /// only the items directly under the root of each medium are collected
+    /// since removing them will remove everything else
+    pub async fn retrieve_user(
+        conn: &impl GenericClient,
+        user_id: &ids::UserId,
+    ) -> ApiResult<Vec<Self>> {
+        let storage_rows = conn.query(
+            "select id, backend from storage where user_id = $1",
+            &[user_id]
+        ).await.context("failed to retrieve user storage")?;
+
+        if storage_rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut storage_ids: Vec<ids::StorageId> = Vec::with_capacity(storage_rows.len());
+        let mut contents: HashMap<ids::StorageId, StorageContents> = HashMap::with_capacity(storage_rows.len());
+
+        for row in storage_rows {
+            let storage_id: ids::StorageId = row.get(0);
+
+            storage_ids.push(storage_id);
+            contents.insert(storage_id, StorageContents {
+                backend: sql::de_from_sql(row.get(1)),
+                entries: Vec::new(),
+            });
+        }
+
+        let fs_rows = conn.query(
+            "\
+            select fs.storage_id, \
+                   fs.fs_type, \
+                   fs.backend \
+            from fs \
+            where fs.storage_id = any($1) and \
+                fs.parent in (\
+                    select fs_root.id \
+                    from fs fs_root \
+                    where fs_root.storage_id = any($1) and \
+                        fs_root.fs_type = $2\
+                )",
+            &[&storage_ids, &consts::ROOT_TYPE]
+        ).await.context("failed to retrieve storage contents")?;
+
+        for row in fs_rows {
+            let storage_id: ids::StorageId = row.get(0);
+
+            if let Some(found) = contents.get_mut(&storage_id) {
+                found.entries.push((row.get(1), sql::de_from_sql(row.get(2))));
+            }
+        }
+
+        Ok(contents.into_values().collect())
+    }
+
+    /// removes the items from disk along with the trash directory. the root
+    /// of the medium is left in place along with anything that was not
+    /// created by the server
+    pub async fn remove(&self) -> ApiResult<()> {
+        for (fs_type, node) in &self.entries {
+            let pair = backend::Pair::match_up(&self.backend, node)?;
+
+            if !pair.exists().await? {
+                continue;
+            }
+
+            if *fs_type != consts::DIR_TYPE {
+                pair.remove()
+                    .await
+                    .context("failed to remove file from storage")?;
+
+                continue;
+            }
+
+            match pair {
+                backend::Pair::Local((local, node_local)) => {
+                    let full = local.contained_path(&node_local.path).await?;
+
+                    tracing::debug!("removing storage path: \"{}\"", full.display());
+
+                    tokio::fs::remove_dir_all(&full)
+                        .await
+                        .context("failed to remove directory from storage")?;
+                }
+            }
+        }
+
+        match &self.backend {
+            backend::Config::Local(local) => {
+                let trash = local.path.join(backend::TRASH_DIR);
+
+                if crate::path::metadata(&trash)?.is_some() {
+                    tokio::fs::remove_dir_all(&trash)
+                        .await
+                        .context("failed to remove trash directory")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,16 +1,30 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use rfs_lib::ids;
 use serde::{Serialize, Deserialize};
+use async_compression::tokio::bufread::ZstdDecoder;
+use tokio::io::{AsyncRead, BufReader};
+
+use crate::error::api::ApiErrorKind;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NodeLocal {
     pub path: PathBuf,
+    /// the contents on disk are compressed with zstd. the size and hash of
+    /// the fs item are always of the original contents
+    #[serde(default)]
+    pub compressed: bool,
+    /// number of bytes the compressed contents take up on disk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored_size: Option<u64>,
 }
 
 impl From<NodeLocal> for rfs_api::fs::backend::NodeLocal {
     fn from(local: NodeLocal) -> Self {
         rfs_api::fs::backend::NodeLocal {
-            path: local.path
+            path: local.path,
+            compressed: local.compressed,
+            stored_size: local.stored_size,
         }
     }
 }
@@ -37,15 +51,93 @@ impl From<Node> for rfs_api::fs::backend::Node {
     }
 }
 
+/// name of the directory in the root of a local storage medium that holds
+/// items that have been moved to the trash
+pub const TRASH_DIR: &str = ".trash";
+
+/// name of the directory in the root of a local storage medium that uploads
+/// are written to when no tmp directory is configured
+pub const TMP_DIR: &str = ".tmp";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConfigLocal {
     pub path: PathBuf,
+    /// when enabled files with identical content will share the same data on
+    /// disk via hard links. the link count of the file acts as the reference
+    /// count so removing one item will not remove the data for the others
+    #[serde(default)]
+    pub dedup: bool,
+    /// directory that uploads are written to before being moved to their
+    /// final location. this should be on the same filesystem as the storage
+    /// root so that the move is a rename
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmp: Option<PathBuf>,
+    /// when enabled new file contents are compressed with zstd before being
+    /// written to disk. files already written are left as is
+    #[serde(default)]
+    pub compression: bool,
+}
+
+impl ConfigLocal {
+    /// joins the given path onto the storage root and verifies that the
+    /// canonical form of the result is still inside of the root. the path
+    /// must already exist on the system
+    ///
+    /// the returned path is the joined path and not the canonical one so
+    /// that it can still be stripped of the storage root
+    pub async fn contained_path<P>(&self, path: P) -> Result<PathBuf, crate::error::api::Error>
+    where
+        P: AsRef<Path>
+    {
+        let joined = self.path.join(path);
+        let root = tokio::fs::canonicalize(&self.path).await?;
+        let canonical = tokio::fs::canonicalize(&joined).await?;
+
+        if !canonical.starts_with(&root) {
+            tracing::warn!(
+                "path escapes storage root. root: \"{}\" path: \"{}\"",
+                root.display(),
+                canonical.display()
+            );
+
+            return Err(NotContained.into());
+        }
+
+        Ok(joined)
+    }
+
+    /// the path that the contents of a trashed item are moved to
+    pub fn trash_path(&self, uid: &ids::FSUid) -> PathBuf {
+        self.path.join(TRASH_DIR).join(uid.to_string())
+    }
+
+    /// the directory that in progress uploads are written to
+    pub fn tmp_dir(&self) -> PathBuf {
+        match &self.tmp {
+            Some(tmp) => tmp.clone(),
+            None => self.path.join(TMP_DIR),
+        }
+    }
+
+    /// the path that an in progress upload for the given item is written to
+    pub fn tmp_path(&self, uid: &ids::FSUid) -> PathBuf {
+        self.tmp_dir().join(format!("{uid}.tmp.rfs"))
+    }
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("path is not contained in the storage root")]
+pub struct NotContained;
+
+crate::error::api::simple_from!(NotContained, ApiErrorKind::ValidationFailed);
+
 impl From<ConfigLocal> for rfs_api::fs::backend::ConfigLocal {
     fn from(local: ConfigLocal) -> Self {
         rfs_api::fs::backend::ConfigLocal {
-            path: local.path
+            path: local.path,
+            dedup: local.dedup,
+            tmp: local.tmp,
+            compression: local.compression,
         }
     }
 }
@@ -66,6 +158,9 @@ impl From<Config> for rfs_api::fs::backend::Config {
 
 // ----------------------------------------------------------------------------
 
+/// reader for the original contents of a node
+pub type Reader = Box<dyn AsyncRead + Send + Unpin>;
+
 pub enum Pair<'a, 'b> {
     Local((&'a ConfigLocal, &'b NodeLocal))
 }
@@ -87,4 +182,78 @@ impl<'a, 'b> Pair<'a, 'b> {
             }
         }
     }
+
+    /// checks if the node exists in the storage medium
+    pub async fn exists(&self) -> Result<bool, crate::error::api::Error> {
+        match self {
+            Pair::Local((local, node_local)) => {
+                let joined = local.path.join(&node_local.path);
+
+                if crate::path::metadata(&joined)?.is_none() {
+                    return Ok(false);
+                }
+
+                local.contained_path(&node_local.path).await?;
+
+                Ok(true)
+            }
+        }
+    }
+
+    /// opens the node for reading. compressed contents are decompressed as
+    /// they are read
+    pub async fn open_reader(&self) -> Result<Reader, crate::error::api::Error> {
+        match self {
+            Pair::Local((local, node_local)) => {
+                let full = local.contained_path(&node_local.path).await?;
+
+                let file = tokio::fs::OpenOptions::new()
+                    .read(true)
+                    .open(full)
+                    .await?;
+
+                if node_local.compressed {
+                    Ok(Box::new(ZstdDecoder::new(BufReader::new(file))))
+                } else {
+                    Ok(Box::new(file))
+                }
+            }
+        }
+    }
+
+    /// opens the node for writing. the node will be created if it does not
+    /// exist and truncated if it does. the contents are written as given so
+    /// this should not be used for compressed nodes
+    pub async fn open_writer(&self) -> Result<tokio::fs::File, crate::error::api::Error> {
+        match self {
+            Pair::Local((local, node_local)) => {
+                let joined = local.path.join(&node_local.path);
+                let parent = joined.parent().ok_or(NotContained)?;
+
+                // the parent has to exist for the containment check since the
+                // node itself may not exist yet
+                local.contained_path(parent.strip_prefix(&local.path).map_err(|_| NotContained)?).await?;
+
+                Ok(tokio::fs::OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(joined)
+                    .await?)
+            }
+        }
+    }
+
+    /// removes a file node from the storage medium
+    pub async fn remove(&self) -> Result<(), crate::error::api::Error> {
+        match self {
+            Pair::Local((local, node_local)) => {
+                let full = local.contained_path(&node_local.path).await?;
+
+                tokio::fs::remove_file(&full).await?;
+
+                Ok(())
+            }
+        }
+    }
 }
@@ -4,3 +4,7 @@ pub const ROOT_TYPE: FsType = 0;
 pub const FILE_TYPE: FsType = 1;
 pub const DIR_TYPE: FsType = 2;
 
+/// unique index that prevents items that are not in the trash from sharing a
+/// basename in the same parent
+pub const BASENAME_INDEX: &str = "fs_parent_basename_unique";
+
@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+
+use rfs_lib::ids;
+use deadpool_postgres::GenericClient;
+use tokio_postgres::Error as PgError;
+
+use crate::sql;
+
+use super::consts;
+
+/// a single child of a directory that contributes to the hash of the
+/// directory
+struct Child {
+    basename: String,
+    fs_type: consts::FsType,
+    hash: Option<blake3::Hash>,
+}
+
+/// calculates the hash of a directory from its children. children are sorted
+/// by basename so the hash only changes when a child is added, removed,
+/// renamed or has its own hash changed
+fn hash_children(children: &mut [Child]) -> blake3::Hash {
+    children.sort_by(|a, b| a.basename.cmp(&b.basename));
+
+    let mut hasher = blake3::Hasher::new();
+
+    for child in children.iter() {
+        hasher.update(&(child.basename.len() as u64).to_le_bytes());
+        hasher.update(child.basename.as_bytes());
+        hasher.update(&child.fs_type.to_le_bytes());
+
+        // children without a hash, like directories that have not been
+        // changed since hashes were added, use a zeroed hash
+        match &child.hash {
+            Some(hash) => hasher.update(hash.as_bytes()),
+            None => hasher.update(&[0; blake3::OUT_LEN]),
+        };
+    }
+
+    hasher.finalize()
+}
+
+/// orders the given containers so that every container comes before its
+/// parent. the deepest containers are hashed first so that the hash of a
+/// parent includes the updated hashes of its children
+fn hash_order(parents: &HashMap<ids::FSId, Option<ids::FSId>>) -> Vec<ids::FSId> {
+    let mut depths: Vec<(usize, ids::FSId)> = parents.keys()
+        .map(|id| {
+            let mut depth = 0;
+            let mut current = parents.get(id).copied().flatten();
+
+            while let Some(parent) = current {
+                depth += 1;
+                current = parents.get(&parent).copied().flatten();
+            }
+
+            (depth, *id)
+        })
+        .collect();
+
+    depths.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    depths.into_iter()
+        .map(|(_, id)| id)
+        .collect()
+}
+
+/// retrieves the given containers and every container above them along
+/// with their parents
+async fn retrieve_ancestors(
+    conn: &impl GenericClient,
+    containers: &[ids::FSId],
+) -> Result<HashMap<ids::FSId, Option<ids::FSId>>, PgError> {
+    let rows = conn.query(
+        "\
+        with recursive ancestors as (\
+            select fs_item.id, \
+                   fs_item.parent \
+            from fs fs_item \
+            where fs_item.id = any($1) \
+            union \
+            select fs_above.id, \
+                   fs_above.parent \
+            from fs fs_above \
+            inner join ancestors on \
+                ancestors.parent = fs_above.id\
+        ) \
+        select id, parent from ancestors",
+        &[&containers]
+    ).await?;
+
+    Ok(rows.into_iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect())
+}
+
+/// recalculates the hash of the given container and every container above
+/// it, returning the new hash of the given container. this should be called
+/// with the same transaction that changed the children of the container so
+/// that the hashes stay consistent
+pub async fn update_dir_hashes(
+    conn: &impl GenericClient,
+    container: &ids::FSId,
+) -> Result<blake3::Hash, PgError> {
+    let mut hashes = update_many_dir_hashes(conn, std::slice::from_ref(container)).await?;
+
+    Ok(hashes.remove(container).expect("the given container was not hashed"))
+}
+
+/// recalculates the hashes of the given containers and every container above
+/// them, returning the new hashes of the given containers
+///
+/// every container is locked before any children are read so that a
+/// concurrent change cannot be missed. the locks are taken in order of id so
+/// that transactions updating overlapping containers cannot deadlock and are
+/// held until the transaction finishes which means every write in a storage
+/// medium will serialize on the row of the storage root
+pub async fn update_many_dir_hashes(
+    conn: &impl GenericClient,
+    containers: &[ids::FSId],
+) -> Result<HashMap<ids::FSId, blake3::Hash>, PgError> {
+    let mut locked: HashSet<ids::FSId> = HashSet::new();
+
+    // a container could be moved before it is locked so the ancestors are
+    // retrieved again until nothing new shows up
+    let parents = loop {
+        let parents = retrieve_ancestors(conn, containers).await?;
+        let missing: Vec<ids::FSId> = parents.keys()
+            .filter(|id| !locked.contains(*id))
+            .copied()
+            .collect();
+
+        if missing.is_empty() {
+            break parents;
+        }
+
+        conn.execute(
+            "select id from fs where id = any($1) order by id for update",
+            &[&missing]
+        ).await?;
+
+        locked.extend(missing);
+    };
+
+    let mut rtn = HashMap::with_capacity(containers.len());
+
+    for id in hash_order(&parents) {
+        let rows = conn.query(
+            "\
+            select fs.basename, \
+                   fs.fs_type, \
+                   fs.hash \
+            from fs \
+            where fs.parent = $1 and \
+                  fs.deleted is null",
+            &[&id]
+        ).await?;
+
+        let mut children: Vec<Child> = rows.into_iter()
+            .map(|row| Child {
+                basename: row.get(0),
+                fs_type: row.get(1),
+                hash: row.get::<usize, Option<Vec<u8>>>(2)
+                    .and_then(sql::try_blake3_hash_from_sql),
+            })
+            .collect();
+
+        let hash = hash_children(&mut children);
+
+        conn.execute(
+            "\
+            update fs \
+            set hash = $2 \
+            where id = $1 and fs_type != $3",
+            &[&id, &hash.as_bytes().as_slice(), &consts::FILE_TYPE]
+        ).await?;
+
+        if containers.contains(&id) {
+            rtn.insert(id, hash);
+        }
+    }
+
+    Ok(rtn)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn child(basename: &str, fs_type: consts::FsType, hash: Option<&[u8]>) -> Child {
+        Child {
+            basename: basename.to_owned(),
+            fs_type,
+            hash: hash.map(blake3::hash),
+        }
+    }
+
+    #[test]
+    fn order_independent() {
+        let mut a = vec![
+            child("a.txt", consts::FILE_TYPE, Some(b"a")),
+            child("b", consts::DIR_TYPE, None),
+        ];
+        let mut b = vec![
+            child("b", consts::DIR_TYPE, None),
+            child("a.txt", consts::FILE_TYPE, Some(b"a")),
+        ];
+
+        assert_eq!(hash_children(&mut a), hash_children(&mut b));
+    }
+
+    #[test]
+    fn child_changes() {
+        let mut original = vec![child("a.txt", consts::FILE_TYPE, Some(b"a"))];
+        let mut contents = vec![child("a.txt", consts::FILE_TYPE, Some(b"b"))];
+        let mut renamed = vec![child("b.txt", consts::FILE_TYPE, Some(b"a"))];
+        let mut empty = Vec::new();
+
+        let original = hash_children(&mut original);
+
+        assert_ne!(original, hash_children(&mut contents));
+        assert_ne!(original, hash_children(&mut renamed));
+        assert_ne!(original, hash_children(&mut empty));
+    }
+
+    #[test]
+    fn basename_boundaries() {
+        let mut a = vec![
+            child("ab", consts::FILE_TYPE, None),
+            child("c", consts::FILE_TYPE, None),
+        ];
+        let mut b = vec![
+            child("a", consts::FILE_TYPE, None),
+            child("bc", consts::FILE_TYPE, None),
+        ];
+
+        assert_ne!(hash_children(&mut a), hash_children(&mut b));
+    }
+
+    fn id(value: i64) -> ids::FSId {
+        ids::FSId::new(value).unwrap()
+    }
+
+    #[test]
+    fn children_before_parents() {
+        // 1 -> 5 -> 2 and 1 -> 3 -> 4
+        let parents = HashMap::from([
+            (id(1), None),
+            (id(5), Some(id(1))),
+            (id(2), Some(id(5))),
+            (id(3), Some(id(1))),
+            (id(4), Some(id(3))),
+        ]);
+
+        assert_eq!(hash_order(&parents), vec![id(2), id(4), id(3), id(5), id(1)]);
+    }
+}
@@ -9,11 +9,14 @@ use super::{traits, backend};
 pub struct Directory {
     pub id: ids::FSSet,
     pub user: ids::UserSet,
+    pub group: Option<ids::GroupSet>,
     pub storage: ids::StorageSet,
     pub backend: backend::Node,
     pub parent: ids::FSSet,
     pub basename: String,
     pub path: String,
+    /// hash of the children of the directory. see [`super::dir_hash`]
+    pub hash: Option<blake3::Hash>,
     pub tags: tags::TagMap,
     pub comment: Option<String>,
     pub created: DateTime<Utc>,
@@ -32,11 +35,13 @@ impl From<Directory> for rfs_api::fs::Directory {
         rfs_api::fs::Directory {
             uid: dir.id.into_uid(),
             user_uid: dir.user.into_uid(),
+            group_uid: dir.group.map(|group| group.into_uid()),
             storage_uid: dir.storage.into_uid(),
             backend: dir.backend.into(),
             parent: dir.parent.into_uid(),
             basename: dir.basename,
             path: dir.path,
+            hash: dir.hash.map(|hash| hash.as_bytes().to_vec()),
             tags: dir.tags,
             comment: dir.comment,
             created: dir.created,
@@ -46,6 +51,21 @@ impl From<Directory> for rfs_api::fs::Directory {
     }
 }
 
+impl From<Directory> for rfs_api::fs::DirectoryMin {
+    fn from(dir: Directory) -> Self {
+        rfs_api::fs::DirectoryMin {
+            uid: dir.id.into_uid(),
+            user_uid: dir.user.into_uid(),
+            storage_uid: dir.storage.into_uid(),
+            parent: dir.parent.into_uid(),
+            basename: dir.basename,
+            path: dir.path,
+            created: dir.created,
+            updated: dir.updated,
+        }
+    }
+}
+
 impl traits::Common for Directory {
     fn id(&self) -> &ids::FSId {
         self.id.local()
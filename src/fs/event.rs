@@ -0,0 +1,50 @@
+use rfs_lib::ids;
+use rfs_lib::fs::event::Action;
+use deadpool_postgres::GenericClient;
+use tokio_postgres::Error as PgError;
+
+/// records an action taken on an fs item. this should be called with the
+/// same transaction that performed the change so that the log will only
+/// contain actions that were actually committed
+pub async fn record_event(
+    conn: &impl GenericClient,
+    fs_id: &ids::FSId,
+    user_id: &ids::UserId,
+    action: Action,
+    detail: Option<serde_json::Value>,
+) -> Result<(), PgError> {
+    let created = chrono::Utc::now();
+
+    conn.execute(
+        "\
+        insert into fs_events (fs_id, user_id, action, detail, created) values \
+        ($1, $2, $3, $4, $5)",
+        &[fs_id, user_id, &action, &detail, &created]
+    ).await?;
+
+    Ok(())
+}
+
+/// records the same action for a list of fs items
+pub async fn record_events(
+    conn: &impl GenericClient,
+    fs_ids: &[ids::FSId],
+    user_id: &ids::UserId,
+    action: Action,
+) -> Result<(), PgError> {
+    if fs_ids.is_empty() {
+        return Ok(());
+    }
+
+    let created = chrono::Utc::now();
+
+    conn.execute(
+        "\
+        insert into fs_events (fs_id, user_id, action, created) \
+        select fs_id, $2, $3, $4 \
+        from unnest($1::bigint[]) as fs_id",
+        &[&fs_ids, user_id, &action, &created]
+    ).await?;
+
+    Ok(())
+}
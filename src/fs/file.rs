@@ -9,6 +9,7 @@ use super::{traits, backend};
 pub struct File {
     pub id: ids::FSSet,
     pub user: ids::UserSet,
+    pub group: Option<ids::GroupSet>,
     pub storage: ids::StorageSet,
     pub parent: ids::FSSet,
     pub path: String,
@@ -22,12 +23,27 @@ pub struct File {
     pub created: DateTime<Utc>,
     pub updated: Option<DateTime<Utc>>,
     pub deleted: Option<DateTime<Utc>>,
+    /// modification time of the file provided by the client
+    pub mtime: Option<DateTime<Utc>>,
 }
 
 impl File {
     pub fn into_schema(self) -> rfs_api::fs::File {
         self.into()
     }
+
+    /// strong etag built from the hash of the file contents
+    pub fn etag(&self) -> String {
+        format!("\"{}\"", self.hash)
+    }
+
+    /// the modification time from the client if one was given, otherwise
+    /// when the file was last changed on the server
+    pub fn last_modified(&self) -> &DateTime<Utc> {
+        self.mtime.as_ref()
+            .or(self.updated.as_ref())
+            .unwrap_or(&self.created)
+    }
 }
 
 impl From<File> for rfs_api::fs::File {
@@ -35,6 +51,7 @@ impl From<File> for rfs_api::fs::File {
         rfs_api::fs::File {
             uid: file.id.into_uid(),
             user_uid: file.user.into_uid(),
+            group_uid: file.group.map(|group| group.into_uid()),
             storage_uid: file.storage.into_uid(),
             parent: file.parent.into_uid(),
             basename: file.basename,
@@ -48,6 +65,24 @@ impl From<File> for rfs_api::fs::File {
             created: file.created,
             updated: file.updated,
             deleted: file.deleted,
+            mtime: file.mtime,
+        }
+    }
+}
+
+impl From<File> for rfs_api::fs::FileMin {
+    fn from(file: File) -> Self {
+        rfs_api::fs::FileMin {
+            uid: file.id.into_uid(),
+            user_uid: file.user.into_uid(),
+            storage_uid: file.storage.into_uid(),
+            parent: file.parent.into_uid(),
+            basename: file.basename,
+            path: file.path,
+            size: file.size,
+            mime: file.mime,
+            created: file.created,
+            updated: file.updated,
         }
     }
 }
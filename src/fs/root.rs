@@ -9,6 +9,7 @@ use super::{traits, backend};
 pub struct Root {
     pub id: ids::FSSet,
     pub user: ids::UserSet,
+    pub group: Option<ids::GroupSet>,
     pub storage: ids::StorageSet,
     pub basename: String,
     pub backend: backend::Node,
@@ -30,6 +31,7 @@ impl From<Root> for rfs_api::fs::Root {
         rfs_api::fs::Root {
             uid: root.id.into_uid(),
             user_uid: root.user.into_uid(),
+            group_uid: root.group.map(|group| group.into_uid()),
             storage_uid: root.storage.into_uid(),
             basename: root.basename,
             backend: root.backend.into(),
@@ -42,6 +44,19 @@ impl From<Root> for rfs_api::fs::Root {
     }
 }
 
+impl From<Root> for rfs_api::fs::RootMin {
+    fn from(root: Root) -> Self {
+        rfs_api::fs::RootMin {
+            uid: root.id.into_uid(),
+            user_uid: root.user.into_uid(),
+            storage_uid: root.storage.into_uid(),
+            basename: root.basename,
+            created: root.created,
+            updated: root.updated,
+        }
+    }
+}
+
 impl traits::Common for Root {
     fn id(&self) -> &ids::FSId {
         self.id.local()
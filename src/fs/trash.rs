@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::io::ErrorKind as StdIoErrorKind;
+use std::path::PathBuf;
+
+use rfs_lib::ids;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::GenericClient;
+use futures::TryStreamExt;
+
+use crate::error::{ApiError, ApiResult};
+use crate::error::api::{Detail, ApiErrorKind, Context};
+use crate::path;
+
+use super::{consts, backend, Storage};
+
+/// moves the contents of an item on disk into the trash directory of the
+/// storage medium
+pub async fn move_to_trash(
+    local: &backend::ConfigLocal,
+    node_local: &backend::NodeLocal,
+    uid: &ids::FSUid,
+) -> ApiResult<()> {
+    let full = local.contained_path(&node_local.path).await?;
+    let trash = local.trash_path(uid);
+
+    tokio::fs::create_dir_all(local.path.join(backend::TRASH_DIR))
+        .await
+        .context("failed to create trash directory")?;
+
+    tokio::fs::rename(&full, &trash)
+        .await
+        .context("failed to move item to trash")?;
+
+    Ok(())
+}
+
+/// moves the contents of an item from the trash directory back to its
+/// original location. the parent directory must still exist
+pub async fn restore_from_trash(
+    local: &backend::ConfigLocal,
+    node_local: &backend::NodeLocal,
+    uid: &ids::FSUid,
+) -> ApiResult<()> {
+    let full = local.path.join(&node_local.path);
+    let trash = local.trash_path(uid);
+
+    if let Some(parent) = node_local.path.parent() {
+        local.contained_path(parent).await?;
+    }
+
+    let result = path::metadata(&full)
+        .context("failed to retrieve metadata for restore path")?;
+
+    if result.is_some() {
+        return Err(ApiError::from((
+            ApiErrorKind::AlreadyExists,
+            Detail::with_key("basename"),
+            "an unknown file already exists in the original location"
+        )));
+    }
+
+    tokio::fs::rename(&trash, &full)
+        .await
+        .context("failed to move item out of trash")?;
+
+    Ok(())
+}
+
+/// a path in the trash directory that is left over after purging an item
+#[derive(Debug)]
+pub struct Purged {
+    path: PathBuf,
+    is_dir: bool,
+}
+
+/// permanently removes a trashed item and everything under it from the
+/// database
+///
+/// the returned entries are the paths in the trash directory that need to be
+/// removed with [`remove_purged`] once the transaction has been committed.
+/// any items under the given item that were trashed separately are included
+pub async fn purge(
+    conn: &impl GenericClient,
+    storage: &Storage,
+    id: &ids::FSId,
+) -> ApiResult<Vec<Purged>> {
+    let results = conn.query_raw(
+        "\
+        with recursive dir_tree as (\
+            select fs_root.id, \
+                   fs_root.parent, \
+                   fs_root.uid, \
+                   fs_root.fs_type, \
+                   fs_root.backend, \
+                   fs_root.deleted \
+            from fs fs_root \
+            where id = $1 \
+            union \
+            select fs_contents.id, \
+                   fs_contents.parent, \
+                   fs_contents.uid, \
+                   fs_contents.fs_type, \
+                   fs_contents.backend, \
+                   fs_contents.deleted \
+            from fs fs_contents \
+            inner join dir_tree on dir_tree.id = fs_contents.parent\
+        ) \
+        select * from dir_tree",
+        &[id]
+    ).await?;
+
+    futures::pin_mut!(results);
+
+    let mut deleted: HashMap<ids::FSId, Option<DateTime<Utc>>> = HashMap::new();
+    let mut records = Vec::new();
+
+    while let Some(row) = results.try_next().await? {
+        let item_id: ids::FSId = row.get(0);
+        let parent: Option<ids::FSId> = row.get(1);
+        let uid: ids::FSUid = row.get(2);
+        let fs_type: consts::FsType = row.get(3);
+        let node: backend::Node = crate::sql::de_from_sql(row.get(4));
+        let timestamp: Option<DateTime<Utc>> = row.get(5);
+
+        deleted.insert(item_id.clone(), timestamp);
+        records.push((item_id, parent, uid, fs_type, node, timestamp));
+    }
+
+    let mut purged = Vec::with_capacity(records.len());
+    let mut entries = Vec::new();
+
+    for (item_id, parent, uid, fs_type, node, timestamp) in records {
+        // an item is only in the trash directory if it was trashed separately
+        // from its parent
+        let parent_deleted = parent.as_ref()
+            .and_then(|parent_id| deleted.get(parent_id))
+            .copied()
+            .flatten();
+        let is_entry = item_id == *id || parent_deleted != timestamp;
+
+        purged.push(item_id);
+
+        if !is_entry {
+            continue;
+        }
+
+        match backend::Pair::match_up(&storage.backend, &node)? {
+            backend::Pair::Local((local, _node_local)) => {
+                entries.push(Purged {
+                    path: local.trash_path(&uid),
+                    is_dir: fs_type == consts::DIR_TYPE,
+                });
+            }
+        }
+    }
+
+    conn.execute("delete from fs_tags where fs_id = any($1)", &[&purged]).await?;
+    conn.execute("delete from fs_checksums where fs_id = any($1)", &[&purged]).await?;
+    conn.execute("delete from fs where id = any($1)", &[&purged]).await?;
+
+    Ok(entries)
+}
+
+/// removes the paths left over from [`purge`] from the trash directory. this
+/// should only be called after the purge has been committed since the files
+/// cannot be brought back. failures are logged as the database records are
+/// already gone
+pub async fn remove_purged(entries: Vec<Purged>) {
+    for entry in entries {
+        tracing::debug!("purging trash path: \"{}\"", entry.path.display());
+
+        let result = if entry.is_dir {
+            tokio::fs::remove_dir_all(&entry.path).await
+        } else {
+            tokio::fs::remove_file(&entry.path).await
+        };
+
+        if let Err(err) = result {
+            if err.kind() != StdIoErrorKind::NotFound {
+                tracing::error!("failed to remove item from trash. path: \"{}\" {err}", entry.path.display());
+            }
+        }
+    }
+}
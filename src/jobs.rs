@@ -16,6 +16,7 @@ use crate::state::ArcShared;
 use crate::error::{self, Context};
 
 mod session;
+mod trash;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct JobResults {
@@ -163,31 +164,6 @@ where
     Ok(())
 }
 
-fn get_jobs_dir(data: PathBuf) -> error::Result<PathBuf> {
-    let jobs_dir = data.join("jobs");
-
-    let metadata = match jobs_dir.metadata() {
-        Ok(m) => m,
-        Err(err) => match err.kind() {
-            ErrorKind::NotFound => {
-                std::fs::create_dir(&jobs_dir)?;
-
-                return Ok(jobs_dir);
-            },
-            _ => {
-                return Err(err.into());
-            }
-        }
-    };
-
-    if !metadata.is_dir() {
-        Err(error::Error::new()
-            .context("jobs data directory is not a directory"))
-    } else {
-        Ok(jobs_dir)
-    }
-}
-
 fn spawn_job<F, T>(
     jobs_dir: &PathBuf,
     state: &ArcShared,
@@ -227,8 +203,8 @@ where
 // sec  min   hour    day of month   month   day of week   year
 // 0    30    9,12,15     1,15       May-Aug  Mon,Wed,Fri  2018/2
 
-pub fn background(state: &ArcShared, data: PathBuf) -> error::Result<FuturesUnordered<JoinHandle<()>>> {
-    let jobs_dir = get_jobs_dir(data)?;
+pub fn background(state: &ArcShared) -> error::Result<FuturesUnordered<JoinHandle<()>>> {
+    let jobs_dir = state.data().jobs();
     let waiter = FuturesUnordered::new();
 
     waiter.push(spawn_job(
@@ -247,6 +223,14 @@ pub fn background(state: &ArcShared, data: PathBuf) -> error::Result<FuturesUnor
         false,
         session::rotate
     )?);
+    waiter.push(spawn_job(
+        &jobs_dir,
+        state,
+        "trash_purge",
+        "0 0 1 * * * *",
+        true,
+        trash::purge
+    )?);
 
     Ok(waiter)
 }
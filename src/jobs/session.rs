@@ -3,7 +3,7 @@ use futures::stream::TryStreamExt;
 use crate::state;
 use crate::error::{self, Context};
 use crate::sec::authn::session::token::SessionToken;
-use crate::sec::secrets::Key;
+use crate::sec::secrets::{self, Key};
 use crate::time;
 
 pub async fn cleanup(state: state::ArcShared) -> error::Result<()> {
@@ -42,6 +42,7 @@ pub async fn cleanup(state: state::ArcShared) -> error::Result<()> {
 
 pub async fn rotate(state: state::ArcShared) -> error::Result<()> {
     let wrapper = state.sec().session_info().keys();
+    let grace = *state.sec().session_info().key_grace();
     let data = Key::rand_key_data()?;
     let created = time::utc_now()
         .context("timestamp error for session key")?;
@@ -54,6 +55,12 @@ pub async fn rotate(state: state::ArcShared) -> error::Result<()> {
         };
 
         writer.push(key);
+
+        let removed = secrets::retain_session_keys(&mut writer, |_, replaced_by| {
+            secrets::within_grace(replaced_by, grace, created)
+        });
+
+        tracing::info!("retired {removed} session keys");
     }
 
     wrapper.save().context("failed to save session secret")?;
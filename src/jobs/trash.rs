@@ -0,0 +1,77 @@
+use rfs_lib::ids;
+use futures::stream::TryStreamExt;
+
+use crate::state;
+use crate::error::{self, Context};
+use crate::fs;
+use crate::sql;
+
+/// permanently removes items that have been in the trash longer than the
+/// configured retention
+pub async fn purge(state: state::ArcShared) -> error::Result<()> {
+    let cutoff = chrono::Utc::now() - *state.trash_retention();
+    let params: sql::ParamsArray<2> = [&cutoff, &fs::consts::ROOT_TYPE];
+    let mut conn = state.pool().get().await?;
+
+    // collected before purging since each item is purged in its own
+    // transaction
+    let found = {
+        let expired = conn.query_raw(
+            "\
+            select fs.id, \
+                   fs.storage_id \
+            from fs \
+            left join storage on \
+                fs.storage_id = storage.id \
+            left join fs as fs_parent on \
+                fs.parent = fs_parent.id \
+            where fs.deleted <= $1 and \
+                  fs.fs_type != $2 and \
+                  fs_parent.deleted is distinct from fs.deleted and \
+                  storage.deleted is null \
+            order by fs.deleted",
+            params
+        ).await?;
+
+        futures::pin_mut!(expired);
+
+        let mut found: Vec<(ids::FSId, ids::StorageId)> = Vec::new();
+
+        while let Some(row) = expired.try_next().await? {
+            found.push((row.get(0), row.get(1)));
+        }
+
+        found
+    };
+
+    let mut count = 0;
+
+    for (id, storage_id) in found {
+        let transaction = conn.transaction().await?;
+
+        let Some(storage) = fs::Storage::retrieve(&transaction, &storage_id).await? else {
+            continue;
+        };
+
+        let purged = match fs::trash::purge(&transaction, &storage, &id).await {
+            Ok(purged) => purged,
+            Err(err) => {
+                tracing::error!("failed to purge trashed item. id: {id} {err}");
+
+                continue;
+            }
+        };
+
+        transaction.commit()
+            .await
+            .context("failed to commit purged item")?;
+
+        fs::trash::remove_purged(purged).await;
+
+        count += 1;
+    }
+
+    tracing::info!("purged {count} trashed items");
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
 
+use axum_server::tls_rustls::RustlsConfig;
 use clap::Parser;
 use futures::StreamExt;
 use futures::stream::FuturesUnordered;
@@ -21,9 +23,14 @@ mod state;
 mod tags;
 mod routing;
 mod jobs;
+mod bootstrap;
+
+use error::Context;
 
 fn main() {
-    let args = config::CliArgs::parse();
+    let mut args = config::CliArgs::parse();
+    let check_config = args.check_config;
+    let bootstrap_admin = args.bootstrap_admin.take();
 
     FmtSubscriber::builder()
         .with_env_filter(EnvFilter::from_default_env())
@@ -33,6 +40,12 @@ fn main() {
     let config = match config::Config::from_args(args) {
         Ok(config) => config,
         Err(err) => {
+            if check_config {
+                println!("invalid config: {err}");
+
+                std::process::exit(1);
+            }
+
             tracing::error!("{err}");
 
             return;
@@ -55,21 +68,135 @@ fn main() {
         "started tokio runtime"
     );
 
+    if check_config {
+        if let Err(err) = rt.block_on(check(config)) {
+            println!("invalid config: {err}");
+
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    if let Some(username) = bootstrap_admin {
+        if let Err(err) = rt.block_on(bootstrap::admin(config, username)) {
+            println!("bootstrap failed: {err}");
+
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
     if let Err(err) = rt.block_on(init(config)) {
         tracing::error!("{err}");
     }
 }
 
+/// fail early if the database is not reachable instead of on the first
+/// request
+async fn check_db(state: &state::Shared) -> error::Result<()> {
+    if let Err(err) = state.pool().get().await {
+        return Err(error::Error::from(err)
+            .context("failed to acquire database connection. check the db config"));
+    }
+
+    Ok(())
+}
+
+/// runs the same setup as the server without binding any listeners and
+/// verifies that the storage mediums are available
+async fn check(config: config::Config) -> error::Result<()> {
+    let state = state::Shared::from_config(&config)?;
+
+    println!("config loaded");
+
+    for (key, listener) in &config.settings.listeners {
+        if let Some(tls) = &listener.tls {
+            RustlsConfig::from_pem_file(&tls.cert, &tls.key)
+                .await
+                .context(format!("\"{key}\" failed to load tls cert and key"))?;
+
+            println!("\"{key}\" tls cert and key ok");
+        }
+    }
+
+    check_db(&state).await?;
+
+    println!("database connection ok");
+
+    let conn = state.pool().get().await?;
+    let rows = conn.query(
+        "select name, backend from storage where deleted is null",
+        &[]
+    ).await?;
+    let mut unavailable = 0;
+
+    for row in rows {
+        let name: String = row.get(0);
+        let backend: fs::backend::Config = sql::de_from_sql(row.get(1));
+
+        match backend {
+            fs::backend::Config::Local(local) => {
+                if !local.path.is_dir() {
+                    println!("storage \"{name}\" directory not found: \"{}\"", local.path.display());
+
+                    unavailable += 1;
+                }
+            }
+        }
+    }
+
+    if unavailable > 0 {
+        return Err(error::Error::new()
+            .context(format!("{unavailable} storage medium(s) are unavailable")));
+    }
+
+    println!("config ok");
+
+    Ok(())
+}
+
 async fn init(config: config::Config) -> error::Result<()> {
     let state = Arc::new(state::Shared::from_config(&config)?);
+
+    check_db(&state).await?;
+
     let mut all_futs = FuturesUnordered::new();
 
-    all_futs.extend(jobs::background(&state, config.settings.data.clone())?);
+    all_futs.extend(jobs::background(&state)?);
 
     let router = routing::routes(&state);
+    let metrics_router = routing::metrics_routes(&state);
+
+    let has_tls = config.settings.listeners.values().any(|listener| listener.tls.is_some());
+
+    if config.settings.sec.session.secure && !has_tls {
+        tracing::warn!("sec.session.secure is enabled but no listener is using tls. browsers will not send session cookies over plain http");
+    }
 
     for (key, listener) in config.settings.listeners {
-        let instance_router = router.clone();
+        let tls_config = if let Some(tls) = &listener.tls {
+            let loaded = RustlsConfig::from_pem_file(&tls.cert, &tls.key)
+                .await
+                .context(format!(
+                    "\"{key}\" failed to load tls cert \"{}\" and key \"{}\"",
+                    tls.cert.display(),
+                    tls.key.display()
+                ))?;
+
+            Some(loaded)
+        } else {
+            None
+        };
+
+        let instance_router = if state.metrics().serve_on(&key) {
+            tracing::info!("\"{key}\" serving metrics");
+
+            metrics_router.clone().merge(router.clone())
+        } else {
+            router.clone()
+        };
 
         all_futs.push(tokio::spawn(async move {
             let tcp_listener = match std::net::TcpListener::bind(listener.addr) {
@@ -90,10 +217,21 @@ async fn init(config: config::Config) -> error::Result<()> {
                 }
             }
 
-            let fut = axum_server::from_tcp(tcp_listener)
-                .serve(instance_router.into_make_service());
+            let service = instance_router.into_make_service_with_connect_info::<SocketAddr>();
+
+            let result = if let Some(tls_config) = tls_config {
+                tracing::info!("\"{key}\" serving with tls");
+
+                axum_server::from_tcp_rustls(tcp_listener, tls_config)
+                    .serve(service)
+                    .await
+            } else {
+                axum_server::from_tcp(tcp_listener)
+                    .serve(service)
+                    .await
+            };
 
-            if let Err(err) = fut.await {
+            if let Err(err) = result {
                 tracing::error!("\"{key}\" server error: {err}");
             }
         }));
@@ -8,6 +8,7 @@ use chrono::{DateTime, Utc};
 use crate::error::ApiError;
 use crate::error::api::Context;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SameSite {
     Strict,
     Lax,
@@ -2,24 +2,64 @@ use std::time::Duration;
 
 use axum::Router;
 use axum::error_handling::HandleErrorLayer;
+use axum::extract::State;
 use axum::http::{header, StatusCode};
 use axum::routing::get;
 use axum::response::IntoResponse;
 use tower::ServiceBuilder;
+use tower_http::compression::CompressionLayer;
+use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::trace::TraceLayer;
 
-use crate::error::ApiError;
+use crate::error::{ApiError, ApiResult};
 use crate::state::ArcShared;
 
 mod query;
+mod conditional;
 mod layer;
 mod serve_file;
 
 mod api;
 mod auth;
+mod ws;
 
-async fn ping() -> (StatusCode, &'static str) {
-    (StatusCode::OK, "pong")
+pub use layer::current_request_id;
+
+/// responds with the time that the server processed the request in
+/// milliseconds since the unix epoch so that clients are able to estimate
+/// the skew between the two
+async fn ping() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [("x-server-time", chrono::Utc::now().timestamp_millis().to_string())],
+        "pong"
+    )
+}
+
+async fn health(
+    State(state): State<ArcShared>,
+) -> impl IntoResponse {
+    let status = state.pool().status();
+
+    axum::Json(serde_json::json!({
+        "db": {
+            "max_size": status.max_size,
+            "size": status.size,
+            "available": status.available,
+        }
+    }))
+}
+
+async fn metrics(
+    State(state): State<ArcShared>,
+) -> ApiResult<impl IntoResponse> {
+    let status = state.pool().status();
+    let body = state.metrics().render(&status)?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        body
+    ))
 }
 
 async fn handle_error<E>(error: E) -> impl IntoResponse
@@ -41,18 +81,36 @@ where
 
 pub fn routes(state: &ArcShared) -> Router {
     Router::new()
-        .nest("/auth", auth::routes())
-        .nest("/api", api::routes())
+        .nest("/auth", auth::routes()
+            .layer(RequestBodyLimitLayer::new(state.limits().max_json_size)))
+        .nest("/api", api::routes(state))
+        .route("/ws", get(ws::handle))
         .route("/ping", get(ping))
+        .route("/health", get(health))
         .fallback(serve_file::handle)
         .layer(ServiceBuilder::new()
             .layer(layer::RIDLayer::new())
+            .layer(layer::metrics::MetricsLayer::new(state.metrics().clone()))
             .layer(TraceLayer::new_for_http()
                 .make_span_with(layer::make_span_with)
                 .on_request(layer::on_request)
                 .on_response(layer::on_response)
                 .on_failure(layer::on_failure))
             .layer(HandleErrorLayer::new(handle_error))
+            // the rate limiter needs to be able to create its own responses
+            // so compression is added before it
+            .layer(CompressionLayer::new()
+                .compress_when(layer::compression::Compress::new(state.compression())))
+            .layer(layer::rate_limit::RateLimitLayer::new(state.clone()))
+            .layer(layer::session::SessionRefreshLayer::new())
             .layer(layer::TimeoutLayer::new(Duration::new(90, 0))))
         .with_state(state.clone())
 }
+
+/// routes that are only added to listeners that should expose server
+/// metrics
+pub fn metrics_routes(state: &ArcShared) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(state.clone())
+}
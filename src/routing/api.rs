@@ -1,6 +1,7 @@
 use axum::Router;
 use axum::error_handling::HandleErrorLayer;
 use tower::ServiceBuilder;
+use tower_http::limit::RequestBodyLimitLayer;
 
 use crate::state::ArcShared;
 use crate::error::ApiError;
@@ -8,7 +9,7 @@ use crate::error::api::ApiErrorKind;
 
 mod sec;
 mod user;
-mod fs;
+pub(super) mod fs;
 
 async fn not_found() -> ApiError {
     ApiError::from(ApiErrorKind::NotFound)
@@ -21,11 +22,13 @@ where
     error.into()
 }
 
-pub fn routes() -> Router<ArcShared> {
+pub fn routes(state: &ArcShared) -> Router<ArcShared> {
+    let json_limit = RequestBodyLimitLayer::new(state.limits().max_json_size);
+
     Router::new()
-        .nest("/sec", sec::routes())
-        .nest("/user", user::routes())
-        .nest("/fs", fs::routes())
+        .nest("/sec", sec::routes().layer(json_limit.clone()))
+        .nest("/user", user::routes().layer(json_limit.clone()))
+        .nest("/fs", fs::routes(json_limit))
         .fallback(not_found)
         .layer(ServiceBuilder::new()
             .layer(HandleErrorLayer::new(handle_error)))
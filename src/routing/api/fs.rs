@@ -1,8 +1,9 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::io::ErrorKind as StdIoErrorKind;
 
 use rfs_lib::ids;
+use rfs_lib::fs::event::Action;
 use rfs_api::fs::{
     DirectoryMin,
     FileMin,
@@ -12,45 +13,69 @@ use rfs_api::fs::{
 
 use axum::Router;
 use axum::body::Body;
-use axum::extract::{Path, Query};
-use axum::http::StatusCode;
-use axum::response::Response;
-use axum::routing::get;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::http::response::Builder;
+use axum::routing::{get, patch, post, put};
 use deadpool_postgres::GenericClient;
 use futures::TryStreamExt;
 use serde::Deserialize;
-use tokio::fs::OpenOptions;
 use tokio_util::io::ReaderStream;
+use tower_http::limit::RequestBodyLimitLayer;
 
 use crate::error::{ApiResult, ApiError};
 use crate::error::api::{Detail, Context, ApiErrorKind};
 use crate::fs::{self, backend};
-use crate::routing::query::PaginationQuery;
+use crate::routing::conditional;
+use crate::routing::query::{CursorQuery, PaginationQuery};
 use crate::sec::authn::initiator;
 use crate::sec::authz::permission;
 use crate::sql;
-use crate::state::ArcShared;
+use crate::state::{self, ArcShared};
 use crate::tags;
+use crate::user;
 use crate::db;
 
+mod batch;
+pub(in crate::routing) mod events;
+mod search;
+mod stats;
 mod storage;
+mod sync;
+mod trash;
 mod upload;
 
-pub fn routes() -> Router<ArcShared> {
+pub fn routes(json_limit: RequestBodyLimitLayer) -> Router<ArcShared> {
     Router::new()
         .route("/", get(retrieve))
         .route("/storage", get(storage::retrieve)
             .post(storage::create))
+        .route("/storage/search", get(storage::search))
         .route("/storage/:storage_uid", get(storage::retrieve_id)
             .patch(storage::update_id)
             .delete(storage::delete_id))
+        .route("/storage/:storage_uid/relocate", post(storage::relocate_id))
+        .route("/trash", get(trash::retrieve))
+        .route("/lookup", get(lookup))
+        .route("/search", get(search::retrieve))
+        .route("/stats", get(stats::retrieve))
+        .route("/batch", patch(batch::update))
+        .route("/events/stream", get(events::stream))
         .route("/:fs_uid", get(retrieve_id)
             .post(create_item)
-            .put(upload::upload_file)
             .patch(update_item)
             .delete(delete_item))
         .route("/:fs_uid/contents", get(retrieve_id_contents))
-        .route("/:fs_uid/download", get(download_id))
+        .route("/:fs_uid/download", get(download_id)
+            .head(download_id_head))
+        .route("/:fs_uid/events", get(retrieve_id_events))
+        .route("/:fs_uid/restore", post(trash::restore_id))
+        .route("/:fs_uid/sync-plan", post(sync::plan))
+        .layer(json_limit)
+        // uploads are added after the json limit since they have their own
+        // limit that is checked while streaming the body
+        .route("/:fs_uid", put(upload::upload_file))
 }
 
 #[derive(Deserialize)]
@@ -100,7 +125,8 @@ async fn retrieve(
                       from fs \
                       where fs.uid = $2\
                   ) and \
-                  fs.fs_type = $3 \
+                  fs.fs_type = $3 and \
+                  fs.deleted is null \
             order by fs.id \
             limit $4",
             params
@@ -125,7 +151,8 @@ async fn retrieve(
             left join storage on \
                 fs.storage_id = storage.id \
             where fs.user_id = $1 and \
-                  fs.fs_type = $2 \
+                  fs.fs_type = $2 and \
+                  fs.deleted is null \
             order by fs.id \
             limit $3 \
             offset $4",
@@ -171,13 +198,64 @@ pub async fn retrieve_id(
     Ok(rfs_api::Payload::new(fs::fetch_item_uid(&conn, &fs_uid, &initiator).await?.into()))
 }
 
+#[derive(Deserialize)]
+pub struct LookupQuery {
+    storage: ids::StorageUid,
+    path: String,
+}
+
+async fn lookup(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Query(LookupQuery { storage, path }): Query<LookupQuery>,
+) -> ApiResult<rfs_api::Payload<rfs_api::fs::Item>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read,
+    ).await?;
+
+    let mut segments = Vec::new();
+
+    for segment in path.split('/') {
+        if segment.is_empty() {
+            continue;
+        }
+
+        if !rfs_lib::fs::basename_valid(segment) {
+            return Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("path")
+            )));
+        }
+
+        segments.push(segment);
+    }
+
+    let item = fs::lookup_path(&conn, &storage, &segments, &initiator).await?;
+
+    Ok(rfs_api::Payload::new(item.into()))
+}
+
+#[derive(Deserialize)]
+pub struct CreateQuery {
+    /// treat the basename as a relative path and create any missing
+    /// directories along the way
+    #[serde(default)]
+    recursive: bool,
+}
+
 async fn create_item(
+    State(state): State<ArcShared>,
     db::Conn(mut conn): db::Conn,
     rbac: permission::Rbac,
     initiator: initiator::Initiator,
     Path(PathParams { fs_uid }): Path<PathParams>,
+    Query(CreateQuery { recursive }): Query<CreateQuery>,
     axum::Json(json): axum::Json<rfs_api::fs::CreateDir>,
-) -> ApiResult<(StatusCode, rfs_api::Payload<rfs_api::fs::Item>)> {
+) -> ApiResult<impl IntoResponse> {
     rbac.api_ability(
         &conn,
         &initiator,
@@ -191,11 +269,7 @@ async fn create_item(
     )?;
 
     let transaction = conn.transaction().await?;
-    let uid = ids::FSUid::gen();
     let user = initiator.user.id.clone();
-    let storage_id_set = storage.id.clone();
-    let created = chrono::Utc::now();
-    let basename = json.basename;
 
     let comment = if let Some(given) = json.comment {
         if !rfs_lib::fs::comment_valid(&given) {
@@ -210,35 +284,163 @@ async fn create_item(
         None
     };
 
-    if !rfs_lib::fs::basename_valid(&basename) {
+    let segments: Vec<&str> = if recursive {
+        json.basename.split('/').collect()
+    } else {
+        vec![json.basename.as_str()]
+    };
+
+    for segment in &segments {
+        if !rfs_lib::fs::basename_valid(segment) {
+            return Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("basename")
+            )));
+        }
+    }
+
+    let Some((basename, intermediate)) = segments.split_last() else {
         return Err(ApiError::from((
             ApiErrorKind::ValidationFailed,
             Detail::with_key("basename")
         )));
+    };
+
+    let mut guard = DirGuard::default();
+    let mut container = item;
+
+    for segment in intermediate {
+        if let fs::Item::File(_) = &container {
+            return Err(ApiError::from(ApiErrorKind::InvalidType));
+        }
+
+        let parent = container.id().local().clone();
+
+        fs::lock_basename(&transaction, &parent, segment).await?;
+
+        container = if let Some(id) = fs::Item::name_check(&transaction, &parent, segment).await? {
+            let existing = fs::Item::retrieve(&transaction, &id)
+                .await
+                .context("failed to retrieve existing directory")?
+                .kind(ApiErrorKind::FileNotFound)?;
+
+            if !fs::has_access(&transaction, &initiator, &existing).await? {
+                return Err(ApiError::from(ApiErrorKind::PermissionDenied));
+            }
+
+            existing
+        } else {
+            let created = create_dir(
+                &transaction,
+                &mut guard,
+                state.limits(),
+                &storage,
+                container,
+                &user,
+                segment.to_string(),
+                None
+            ).await?;
+
+            fs::Item::Directory(created)
+        };
+    }
+
+    let mut rtn = create_dir(
+        &transaction,
+        &mut guard,
+        state.limits(),
+        &storage,
+        container,
+        &user,
+        basename.to_string(),
+        comment,
+    ).await?;
+
+    if let Some(tags) = json.tags {
+        if !tags::validate_map(&tags) {
+            return Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("tags")
+            )));
+        }
+
+        tags::create_tags(&transaction, "fs_tags", "fs_id", rtn.id.local(), &tags).await?;
+
+        rtn.tags = tags;
     }
 
-    let Ok((parent, path, container_backend)) = item.try_into_parent_parts() else {
+    transaction.commit().await?;
+
+    guard.keep();
+
+    tracing::info!(
+        fs_id = %rtn.id.local(),
+        user_id = %user.local(),
+        action = "created",
+        "directory created"
+    );
+
+    let location = format!("/api/fs/{}", rtn.id.uid());
+
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, location)],
+        rfs_api::Payload::new(fs::Item::Directory(rtn).into())
+    ))
+}
+
+/// creates a single directory in the given container on disk and records it
+/// in the database. the directory on disk is added to the guard so that it
+/// can be removed if the transaction is not committed
+async fn create_dir(
+    conn: &impl GenericClient,
+    guard: &mut DirGuard,
+    limits: &state::Limits,
+    storage: &fs::Storage,
+    container: fs::Item,
+    user: &ids::UserSet,
+    basename: String,
+    comment: Option<String>,
+) -> ApiResult<fs::Directory> {
+    let uid = ids::FSUid::gen();
+    let created = chrono::Utc::now();
+
+    // items created in a group owned directory will share the same group
+    let group = container.group().cloned();
+
+    let Ok((parent, path, container_backend)) = container.try_into_parent_parts() else {
         return Err(ApiError::from(ApiErrorKind::InvalidType));
     };
 
-    if fs::Item::name_check(&transaction, parent.local(), &basename).await?.is_some() {
+    fs::lock_basename(conn, parent.local(), &basename).await?;
+
+    if fs::Item::name_check(conn, parent.local(), &basename).await?.is_some() {
         return Err(ApiError::from(ApiErrorKind::AlreadyExists));
     }
 
+    fs::check_depth(limits, &path)?;
+    fs::check_children(conn, limits, parent.local()).await?;
+
     let backend = match backend::Pair::match_up(&storage.backend, &container_backend)? {
         backend::Pair::Local((storage_local, container_local)) => {
-            let mut full = storage_local.path.join(&container_local.path);
+            let mut full = storage_local.contained_path(&container_local.path).await?;
             full.push(&basename);
 
             tracing::debug!("new directory path: {:?}", full.display());
 
             tokio::fs::create_dir(&full).await?;
 
-            backend::Node::Local(fs::backend::NodeLocal {
+            let node = backend::Node::Local(fs::backend::NodeLocal {
                 path: full.strip_prefix(&storage_local.path)
                     .unwrap()
-                    .to_owned()
-            })
+                    .to_owned(),
+                compressed: false,
+                stored_size: None,
+            });
+
+            guard.push(full);
+
+            node
         }
     };
 
@@ -247,11 +449,12 @@ async fn create_item(
     let id: ids::FSId = {
         let pg_backend = sql::ser_to_sql(&backend);
 
-        let row = transaction.query_one(
+        let row = conn.query_one(
             "\
             insert into fs(\
                 uid, \
                 user_id, \
+                group_id, \
                 storage_id, \
                 parent, \
                 basename, \
@@ -261,12 +464,13 @@ async fn create_item(
                 comment, \
                 created\
             ) values \
-            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) \
             returning id",
             &[
                 &uid,
                 user.local(),
-                storage_id_set.local(),
+                &group.as_ref().map(|group| group.local()),
+                storage.id.local(),
                 parent.local(),
                 &basename,
                 &fs::consts::DIR_TYPE,
@@ -275,44 +479,68 @@ async fn create_item(
                 &comment,
                 &created
             ]
-        ).await?;
+        ).await.map_err(fs::basename_conflict)?;
 
         row.get(0)
     };
 
-    let tags = if let Some(tags) = json.tags {
-        if !tags::validate_map(&tags) {
-            return Err(ApiError::from((
-                ApiErrorKind::ValidationFailed,
-                Detail::with_key("tags")
-            )));
-        }
-
-        tags::create_tags(&transaction, "fs_tags", "fs_id", &id, &tags).await?;
-
-        tags
-    } else {
-        Default::default()
-    };
+    fs::record_event(
+        conn,
+        &id,
+        user.local(),
+        Action::Created,
+        Some(serde_json::json!({ "basename": &basename })),
+    ).await?;
 
-    transaction.commit().await?;
+    let hash = fs::update_dir_hashes(conn, &id).await?;
 
-    let rtn = fs::Item::Directory(fs::Directory {
+    Ok(fs::Directory {
         id: ids::FSSet::new(id, uid),
-        user,
-        storage: storage_id_set,
+        user: user.clone(),
+        group,
+        storage: storage.id.clone(),
         backend,
         parent,
         basename,
         path,
-        tags,
+        hash: Some(hash),
+        tags: Default::default(),
         comment,
         created,
         updated: None,
         deleted: None
-    });
+    })
+}
+
+/// removes the directories created during a request when dropped unless
+/// they have been kept. directories are removed in reverse order so that
+/// nested directories are removed before their parents
+#[derive(Default)]
+struct DirGuard {
+    paths: Vec<std::path::PathBuf>,
+}
+
+impl DirGuard {
+    fn push(&mut self, path: std::path::PathBuf) {
+        self.paths.push(path);
+    }
 
-    Ok((StatusCode::CREATED, rfs_api::Payload::new(rtn.into())))
+    /// prevents the directories from being removed
+    fn keep(&mut self) {
+        self.paths.clear();
+    }
+}
+
+impl Drop for DirGuard {
+    fn drop(&mut self) {
+        while let Some(path) = self.paths.pop() {
+            if let Err(err) = std::fs::remove_dir(&path) {
+                if err.kind() != StdIoErrorKind::NotFound {
+                    tracing::error!("failed to remove created directory \"{}\": {err}", path.display());
+                }
+            }
+        }
+    }
 }
 
 async fn update_item(
@@ -335,35 +563,66 @@ async fn update_item(
 
     let mut item = fs::fetch_item_uid(&conn, &fs_uid, &initiator).await?;
 
+    let group = if let Some(maybe_uid) = &json.group_uid {
+        // group members are able to access the item but only the owner is
+        // able to decide who it is shared with
+        if initiator.user.id != *item.user_id() {
+            return Err(ApiError::from((
+                ApiErrorKind::PermissionDenied,
+                Detail::with_key("group_uid"),
+                "only the owner of an item is able to change its group"
+            )));
+        }
+
+        if let Some(group_uid) = maybe_uid {
+            let group = user::group::Group::retrieve_uid(&conn, group_uid)
+                .await?
+                .kind(ApiErrorKind::GroupNotFound)?;
+
+            if !fs::is_group_member(&conn, initiator.user.id.local(), group.id.local()).await? {
+                return Err(ApiError::from((
+                    ApiErrorKind::PermissionDenied,
+                    Detail::with_key("group_uid"),
+                    "you must be a member of the group to share an item with it"
+                )));
+            }
+
+            Some(Some(group.id))
+        } else {
+            Some(None)
+        }
+    } else {
+        None
+    };
+
     let transaction = conn.transaction().await?;
+    let detail = serde_json::json!({
+        "comment": json.comment.is_some(),
+        "tags": json.tags.is_some() || json.remove_tags.is_some(),
+        "group": json.group_uid.is_some(),
+    });
 
     {
         let local_id = *item.id().local();
         let updated = chrono::Utc::now();
-        let mut update_query = String::from("update fs set updated = $2");
-        let mut update_params = sql::ParamsVec::with_capacity(2);
-        update_params.push(&local_id);
-        update_params.push(&updated);
-
-        if let Some(comment) = &json.comment {
-            if comment.len() == 0 {
-                write!(&mut update_query, ", comment = null").unwrap();
-
-                item.set_comment(None);
-            } else {
-                write!(
-                    &mut update_query,
-                    ", comment = ${}",
-                    sql::push_param(&mut update_params, comment)
-                ).unwrap();
-
-                item.set_comment(Some(comment.clone()));
-            }
-        }
+        let (update_query, update_params) = update_statement(&local_id, &updated, &json, &group);
 
-        write!(&mut update_query, " where id = $1").unwrap();
+        let Some(row) = transaction.query_opt(update_query.as_str(), update_params.as_slice()).await? else {
+            // the item has been changed since the client last saw it
+            return Err(ApiError::from(ApiErrorKind::PreconditionFailed));
+        };
 
-        transaction.execute(update_query.as_str(), update_params.as_slice()).await?;
+        // the stored value is used since the database has a lower precision
+        // than the timestamp that was given
+        item.set_updated(Some(row.get(0)));
+    }
+
+    if let Some(comment) = &json.comment {
+        if comment.len() == 0 {
+            item.set_comment(None);
+        } else {
+            item.set_comment(Some(comment.clone()));
+        }
     }
 
     if let Some(tags) = json.tags {
@@ -382,16 +641,116 @@ async fn update_item(
         item.set_tags(tags);
     }
 
+    if let Some(remove_tags) = &json.remove_tags {
+        if !tags::validate_keys(remove_tags) {
+            return Err(ApiError::from(ApiErrorKind::InvalidTags));
+        }
+
+        tags::remove_tags(
+            &transaction,
+            "fs_tags",
+            "fs_id",
+            item.id().local(),
+            remove_tags
+        ).await?;
+
+        tags::remove_keys(item.tags_mut(), remove_tags);
+    }
+
+    if let Some(group) = group {
+        item.set_group(group);
+    }
+
+    fs::record_event(
+        &transaction,
+        item.id().local(),
+        initiator.user.id.local(),
+        Action::Updated,
+        Some(detail),
+    ).await?;
+
     transaction.commit().await?;
 
+    tracing::info!(
+        fs_id = %item.id().local(),
+        user_id = %initiator.user.id.local(),
+        action = "updated",
+        "fs item metadata updated"
+    );
+
     Ok(rfs_api::Payload::new(item.into()))
 }
 
+/// builds the statement for updating the comment and group of an item. the
+/// statement will return the new updated timestamp of the item and no rows
+/// if the item does not match the expected updated timestamp
+fn update_statement<'a>(
+    local_id: &'a ids::FSId,
+    updated: &'a chrono::DateTime<chrono::Utc>,
+    json: &'a rfs_api::fs::UpdateMetadata,
+    group: &'a Option<Option<ids::GroupSet>>,
+) -> (String, sql::ParamsVec<'a>) {
+    let mut update_query = String::from("update fs set updated = $2");
+    let mut update_params = sql::ParamsVec::with_capacity(2);
+    update_params.push(local_id);
+    update_params.push(updated);
+
+    if let Some(comment) = &json.comment {
+        if comment.len() == 0 {
+            write!(&mut update_query, ", comment = null").unwrap();
+        } else {
+            write!(
+                &mut update_query,
+                ", comment = ${}",
+                sql::push_param(&mut update_params, comment)
+            ).unwrap();
+        }
+    }
+
+    if let Some(maybe_group) = group {
+        if let Some(group) = maybe_group {
+            write!(
+                &mut update_query,
+                ", group_id = ${}",
+                sql::push_param(&mut update_params, group.local())
+            ).unwrap();
+        } else {
+            write!(&mut update_query, ", group_id = null").unwrap();
+        }
+    }
+
+    write!(&mut update_query, " where id = $1").unwrap();
+
+    if let Some(maybe_expected) = &json.expected_updated {
+        if let Some(expected) = maybe_expected {
+            write!(
+                &mut update_query,
+                " and updated = ${}",
+                sql::push_param(&mut update_params, expected)
+            ).unwrap();
+        } else {
+            write!(&mut update_query, " and updated is null").unwrap();
+        }
+    }
+
+    write!(&mut update_query, " returning updated").unwrap();
+
+    (update_query, update_params)
+}
+
+#[derive(Deserialize)]
+pub struct DeleteQuery {
+    /// permanently remove the item instead of moving it to the trash
+    #[serde(default)]
+    purge: bool,
+}
+
 async fn delete_item(
     db::Conn(mut conn): db::Conn,
     rbac: permission::Rbac,
     initiator: initiator::Initiator,
     Path(PathParams { fs_uid }): Path<PathParams>,
+    Query(DeleteQuery { purge }): Query<DeleteQuery>,
 ) -> ApiResult<StatusCode> {
     rbac.api_ability(
         &conn,
@@ -401,19 +760,34 @@ async fn delete_item(
     ).await?;
 
     let (item, storage) = tokio::try_join!(
-        fs::fetch_item_uid(&conn, &fs_uid, &initiator),
+        fs::fetch_any_item_uid(&conn, &fs_uid, &initiator),
         fs::fetch_storage_from_fs_uid(&conn, &fs_uid),
     )?;
 
+    if item.deleted().is_some() {
+        // items already in the trash can only be purged
+        if !purge {
+            return Err(ApiError::from(ApiErrorKind::FileNotFound));
+        }
+
+        trash::purge_item(&mut conn, &initiator, storage, item).await?;
+
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     match item {
         fs::Item::Root(_root) => {
             return Err(ApiError::from(ApiErrorKind::NotPermitted));
         },
-        fs::Item::Directory(dir) => {
-            delete_dir(&mut conn, storage, dir).await?;
+        fs::Item::Directory(dir) => if purge {
+            delete_dir(&mut conn, &initiator, storage, dir).await?;
+        } else {
+            trash::trash_dir(&mut conn, &initiator, storage, dir).await?;
         },
-        fs::Item::File(file) => {
-            delete_file(&mut conn, storage, file).await?;
+        fs::Item::File(file) => if purge {
+            delete_file(&mut conn, &initiator, storage, file).await?;
+        } else {
+            trash::trash_file(&mut conn, &initiator, storage, file).await?;
         }
     }
 
@@ -422,6 +796,7 @@ async fn delete_item(
 
 async fn delete_file(
     conn: &mut impl GenericClient,
+    initiator: &initiator::Initiator,
     storage: fs::Storage,
     file: fs::File,
 ) -> ApiResult<()> {
@@ -432,21 +807,34 @@ async fn delete_file(
         &[file.id.local()]
     ).await?;
 
-    match backend::Pair::match_up(&storage.backend, &file.backend)? {
-        backend::Pair::Local((local, node_local)) => {
-            let full_path = local.path.join(&node_local.path);
+    fs::record_event(
+        &transaction,
+        file.id.local(),
+        initiator.user.id.local(),
+        Action::Deleted,
+        Some(serde_json::json!({ "basename": &file.basename })),
+    ).await?;
 
-            tokio::fs::remove_file(&full_path).await?;
-        }
-    }
+    fs::update_dir_hashes(&transaction, file.parent.local()).await?;
+
+    storage.remove(&file.backend).await?;
 
     transaction.commit().await?;
 
+    tracing::info!(
+        fs_id = %file.id.local(),
+        user_id = %initiator.user.id.local(),
+        action = "deleted",
+        size = file.size,
+        "file deleted"
+    );
+
     Ok(())
 }
 
 async fn delete_dir(
     conn: &mut impl GenericClient,
+    initiator: &initiator::Initiator,
     storage: fs::Storage,
     directory: fs::Directory,
 ) -> ApiResult<()> {
@@ -460,7 +848,9 @@ async fn delete_dir(
                    fs_root.fs_type, \
                    fs_root.backend, \
                    1 as level, \
-                   fs_root.hash \
+                   fs_root.hash, \
+                   fs_root.uid, \
+                   fs_root.deleted \
             from fs fs_root \
             where id = $1 \
             union \
@@ -469,7 +859,9 @@ async fn delete_dir(
                    fs_contents.fs_type, \
                    fs_contents.backend, \
                    dir_tree.level + 1 as level, \
-                   fs_contents.hash \
+                   fs_contents.hash, \
+                   fs_contents.uid, \
+                   fs_contents.deleted \
             from fs fs_contents \
             inner join dir_tree on dir_tree.id = fs_contents.parent\
         ) \
@@ -485,6 +877,7 @@ async fn delete_dir(
     futures::pin_mut!(results);
 
     let mut skip_parents: HashSet<ids::FSId> = HashSet::new();
+    let mut parents: HashMap<ids::FSId, ids::FSId> = HashMap::new();
     let mut deleted: Vec<ids::FSId> = Vec::new();
     let mut failed: Vec<ids::FSId> = Vec::new();
     let mut skipped: Vec<ids::FSId> = Vec::new();
@@ -495,6 +888,10 @@ async fn delete_dir(
         let fs_type: fs::consts::FsType = row.get(2);
         let backend: fs::backend::Node = sql::de_from_sql(row.get(3));
         let level: i32 = row.get(4);
+        let uid: ids::FSUid = row.get(6);
+        let trashed = row.get::<_, Option<chrono::DateTime<chrono::Utc>>>(7).is_some();
+
+        parents.insert(id, parent);
 
         if skip_parents.contains(&id) {
             tracing::debug!("skipping fs item. id: {id}");
@@ -516,7 +913,14 @@ async fn delete_dir(
 
         match pair {
             backend::Pair::Local((local, node_local)) => {
-                let full_path = local.path.join(&node_local.path);
+                // items that were already trashed will have been moved out of
+                // the directory. anything under a trashed directory will not
+                // be found and is removed along with the directory
+                let full_path = if trashed {
+                    local.trash_path(&uid)
+                } else {
+                    local.path.join(&node_local.path)
+                };
 
                 tracing::debug!("deleting id: {id}\ndepth: {level}\npath: {}", full_path.display());
 
@@ -539,7 +943,13 @@ async fn delete_dir(
                         }
                     }
                     fs::consts::DIR_TYPE => {
-                        if let Err(err) = tokio::fs::remove_dir(&full_path).await {
+                        let result = if trashed {
+                            tokio::fs::remove_dir_all(&full_path).await
+                        } else {
+                            tokio::fs::remove_dir(&full_path).await
+                        };
+
+                        if let Err(err) = result {
                             match err.kind() {
                                 StdIoErrorKind::NotFound => {
                                     deleted.push(id);
@@ -573,8 +983,38 @@ async fn delete_dir(
 
     tracing::debug!("deleted: {del_result} skipped: {} failed: {}", skipped.len(), failed.len());
 
+    fs::record_events(
+        &transaction,
+        &deleted,
+        initiator.user.id.local(),
+        Action::Deleted,
+    ).await?;
+
+    // anything that failed to delete will still be around so the hashes of
+    // any remaining directories need to be updated
+    let removed: HashSet<&ids::FSId> = deleted.iter().collect();
+    let remaining: Vec<ids::FSId> = deleted.iter()
+        .filter_map(|id| parents.get(id))
+        .filter(|parent| !removed.contains(parent))
+        .copied()
+        .collect::<HashSet<ids::FSId>>()
+        .into_iter()
+        .collect();
+
+    fs::update_many_dir_hashes(&transaction, &remaining).await?;
+
     transaction.commit().await?;
 
+    tracing::info!(
+        fs_id = %directory.id.local(),
+        user_id = %initiator.user.id.local(),
+        action = "deleted",
+        deleted = deleted.len(),
+        skipped = skipped.len(),
+        failed = failed.len(),
+        "directory deleted"
+    );
+
     Ok(())
 }
 
@@ -584,6 +1024,7 @@ async fn retrieve_id_contents(
     initiator: initiator::Initiator,
     Path(PathParams { fs_uid }): Path<PathParams>,
     Query(PaginationQuery { limit, offset, last_id }): Query<PaginationQuery<ids::FSUid>>,
+    Query(CursorQuery { after }): Query<CursorQuery<ids::FSUid>>,
 ) -> ApiResult<rfs_api::Payload<Vec<ItemMin>>> {
     rbac.api_ability(
         &conn,
@@ -599,7 +1040,14 @@ async fn retrieve_id_contents(
 
     let mut pagination = rfs_api::Pagination::from(&limit);
 
-    let result = if let Some(last_id) = last_id {
+    let total: i64 = conn.query_one(
+        "select count(*) from fs where fs.parent = $1 and fs.deleted is null",
+        &[container.id()]
+    ).await?.get(0);
+
+    pagination.set_total(total as u64);
+
+    let result = if let Some(last_id) = after.or(last_id) {
         let params: sql::ParamsVec = vec![container.id(), &last_id, &limit];
 
         conn.query_raw(
@@ -627,7 +1075,8 @@ async fn retrieve_id_contents(
                 select fs.id \
                 from fs \
                 where fs.uid = $2\
-            ) \
+            ) and \
+            fs.deleted is null \
             order by fs.id \
             limit $3",
             params
@@ -659,7 +1108,8 @@ async fn retrieve_id_contents(
                 fs.storage_id = storage.id \
             left join fs as fs_parent on \
                 fs.parent = fs_parent.id \
-            where fs.parent = $1 \
+            where fs.parent = $1 and \
+                  fs.deleted is null \
             order by fs.id \
             limit $2 \
             offset $3",
@@ -672,60 +1122,88 @@ async fn retrieve_id_contents(
     let mut list = Vec::with_capacity(limit as usize);
 
     while let Some(row) = result.try_next().await? {
-        let fs_type = row.get(5);
-
-        let item = match fs_type {
-            fs::consts::ROOT_TYPE => {
-                ItemMin::Root(RootMin {
-                    uid: row.get(0),
-                    user_uid: row.get(1),
-                    storage_uid: row.get(2),
-                    basename: row.get(4),
-                    created: row.get(10),
-                    updated: row.get(11),
-                })
-            }
-            fs::consts::FILE_TYPE => {
-                ItemMin::File(FileMin {
-                    uid: row.get(0),
-                    user_uid: row.get(1),
-                    storage_uid: row.get(2),
-                    parent: row.get(3),
-                    basename: row.get(4),
-                    path: row.get(6),
-                    size: sql::u64_from_sql(row.get(7)),
-                    mime: sql::mime_from_sql(row.get(8), row.get(9)),
-                    created: row.get(10),
-                    updated: row.get(11),
-                })
-            }
-            fs::consts::DIR_TYPE => {
-                ItemMin::Directory(DirectoryMin {
-                    uid: row.get(0),
-                    user_uid: row.get(1),
-                    storage_uid: row.get(2),
-                    parent: row.get(3),
-                    basename: row.get(4),
-                    path: row.get(6),
-                    created: row.get(10),
-                    updated: row.get(11),
-                })
-            }
-            _ => {
-                panic!("unexpected fs_type when retrieving fs item. type: {fs_type}");
-            }
-        };
+        list.push(item_min_from_row(&row));
+    }
 
-        list.push(item);
+    if list.len() == limit as usize {
+        if let Some(last) = list.last() {
+            pagination.set_next(last.uid());
+        }
     }
 
     Ok(rfs_api::Payload::from((pagination, list)))
 }
 
+/// retrieves a page of the contents of a container starting after the given
+/// item
+pub(in crate::routing) async fn retrieve_contents(
+    conn: &impl GenericClient,
+    container: &ids::FSId,
+    after: Option<&ids::FSUid>,
+    limit: rfs_api::Limit,
+) -> ApiResult<Vec<ItemMin>> {
+    let mut params: sql::ParamsVec = vec![container, &limit];
+    let mut query = format!(
+        "{ITEM_MIN_QUERY} \
+        where fs.parent = $1 and \
+              fs.deleted is null"
+    );
+
+    if let Some(after) = after {
+        write!(
+            &mut query,
+            " and fs.id > (select fs.id from fs where fs.uid = ${})",
+            sql::push_param(&mut params, after)
+        ).unwrap();
+    }
+
+    query.push_str(" order by fs.id limit $2");
+
+    let rows = conn.query(query.as_str(), params.as_slice()).await?;
+
+    Ok(rows.iter().map(item_min_from_row).collect())
+}
+
+/// adds the headers that describe the contents of a file
+fn file_headers(builder: Builder, file: &fs::File) -> Builder {
+    builder.header(header::CONTENT_TYPE, file.mime.to_string())
+        .header(header::CONTENT_LENGTH, file.size)
+        .header(header::ETAG, file.etag())
+        .header(header::LAST_MODIFIED, conditional::http_date(file.last_modified()))
+        .header("x-checksum", format!("blake3:{}", file.hash))
+}
+
+async fn download_id_head(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(PathParams { fs_uid }): Path<PathParams>,
+) -> ApiResult<Response<Body>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read
+    ).await?;
+
+    let item = fs::fetch_item_uid(&conn, &fs_uid, &initiator).await?;
+
+    let Ok(file): Result<fs::File, _> = item.try_into() else {
+        return Err(ApiError::from(ApiErrorKind::NotFile));
+    };
+
+    let builder = Response::builder()
+        .status(StatusCode::OK);
+
+    Ok(file_headers(builder, &file).body(Body::empty())?)
+}
+
 async fn download_id(
+    State(state): State<ArcShared>,
     db::Conn(conn): db::Conn,
     rbac: permission::Rbac,
     initiator: initiator::Initiator,
+    headers: HeaderMap,
     Path(PathParams { fs_uid }): Path<PathParams>,
 ) -> ApiResult<Response<Body>> {
     rbac.api_ability(
@@ -744,22 +1222,253 @@ async fn download_id(
         return Err(ApiError::from(ApiErrorKind::NotFile));
     };
 
-    let builder = Response::builder()
+    let etag = file.etag();
+
+    if conditional::etag_matches(&headers, header::IF_NONE_MATCH, &etag) {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, etag)
+            .header(header::LAST_MODIFIED, conditional::http_date(file.last_modified()))
+            .body(Body::empty())?);
+    }
+
+    let builder = file_headers(Response::builder(), &file)
         .status(StatusCode::OK)
-        .header("content-disposition", format!("attachment; filename=\"{}\"", file.basename))
-        .header("content-type", file.mime.to_string())
-        .header("content-length", file.size)
-        .header("x-checksum", format!("blake3:{}", file.hash));
-
-    match backend::Pair::match_up(&storage.backend, &file.backend)? {
-        backend::Pair::Local((local, node_local)) => {
-            let full = local.path.join(&node_local.path);
-            let stream = ReaderStream::new(OpenOptions::new()
-                .read(true)
-                .open(full)
-                .await?);
-
-            Ok(builder.body(Body::from_stream(stream))?)
+        .header("content-disposition", format!("attachment; filename=\"{}\"", file.basename));
+
+    let stream = ReaderStream::new(storage.open_reader(&file.backend).await?);
+
+    state.metrics().add_downloaded(file.size);
+
+    Ok(builder.body(Body::from_stream(stream))?)
+}
+
+async fn retrieve_id_events(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(PathParams { fs_uid }): Path<PathParams>,
+    Query(PaginationQuery { limit, offset, last_id }): Query<PaginationQuery<i64>>,
+    Query(CursorQuery { after }): Query<CursorQuery<i64>>,
+) -> ApiResult<rfs_api::Payload<Vec<rfs_api::fs::Event>>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read,
+    ).await?;
+
+    let item = fs::fetch_item_uid(&conn, &fs_uid, &initiator).await?;
+
+    let mut pagination = rfs_api::Pagination::from(&limit);
+
+    let result = if let Some(last_id) = after.or(last_id) {
+        let params: sql::ParamsVec = vec![item.id().local(), &last_id, &limit];
+
+        conn.query_raw(
+            "\
+            select fs_events.id, \
+                   users.uid, \
+                   fs_events.action, \
+                   fs_events.detail, \
+                   fs_events.created \
+            from fs_events \
+            left join users on \
+                fs_events.user_id = users.id \
+            where fs_events.fs_id = $1 and \
+                  fs_events.id > $2 \
+            order by fs_events.id \
+            limit $3",
+            params
+        ).await?
+    } else {
+        pagination.set_offset(offset);
+
+        let offset_num = limit.sql_offset(offset);
+        let params: sql::ParamsVec = vec![item.id().local(), &limit, &offset_num];
+
+        conn.query_raw(
+            "\
+            select fs_events.id, \
+                   users.uid, \
+                   fs_events.action, \
+                   fs_events.detail, \
+                   fs_events.created \
+            from fs_events \
+            left join users on \
+                fs_events.user_id = users.id \
+            where fs_events.fs_id = $1 \
+            order by fs_events.id \
+            limit $2 \
+            offset $3",
+            params
+        ).await?
+    };
+
+    futures::pin_mut!(result);
+
+    let mut list = Vec::with_capacity(limit as usize);
+
+    while let Some(row) = result.try_next().await? {
+        list.push(rfs_api::fs::Event {
+            id: row.get(0),
+            fs_uid: item.id().uid().clone(),
+            user_uid: row.get(1),
+            action: row.get(2),
+            detail: row.get(3),
+            created: row.get(4),
+        });
+    }
+
+    if list.len() == limit as usize {
+        if let Some(last) = list.last() {
+            pagination.set_next(last.id);
+        }
+    }
+
+    Ok(rfs_api::Payload::from((pagination, list)))
+}
+
+/// selects the columns needed for [`item_min_from_row`]
+const ITEM_MIN_QUERY: &str = "\
+    select fs.uid, \
+           users.uid, \
+           storage.uid, \
+           fs_parent.uid, \
+           fs.basename, \
+           fs.fs_type, \
+           fs.fs_path, \
+           fs.fs_size, \
+           fs.mime_type, \
+           fs.mime_subtype, \
+           fs.created, \
+           fs.updated \
+    from fs \
+    left join users on \
+        fs.user_id = users.id \
+    left join storage on \
+        fs.storage_id = storage.id \
+    left join fs as fs_parent on \
+        fs.parent = fs_parent.id";
+
+/// creates an [`ItemMin`] from a row with the columns selected by
+/// [`ITEM_MIN_QUERY`]
+fn item_min_from_row(row: &tokio_postgres::Row) -> ItemMin {
+    let fs_type = row.get(5);
+
+    match fs_type {
+        fs::consts::ROOT_TYPE => {
+            ItemMin::Root(RootMin {
+                uid: row.get(0),
+                user_uid: row.get(1),
+                storage_uid: row.get(2),
+                basename: row.get(4),
+                created: row.get(10),
+                updated: row.get(11),
+            })
+        }
+        fs::consts::FILE_TYPE => {
+            ItemMin::File(FileMin {
+                uid: row.get(0),
+                user_uid: row.get(1),
+                storage_uid: row.get(2),
+                parent: row.get(3),
+                basename: row.get(4),
+                path: row.get(6),
+                size: sql::u64_from_sql(row.get(7)),
+                mime: sql::mime_from_sql(row.get(8), row.get(9)),
+                created: row.get(10),
+                updated: row.get(11),
+            })
+        }
+        fs::consts::DIR_TYPE => {
+            ItemMin::Directory(DirectoryMin {
+                uid: row.get(0),
+                user_uid: row.get(1),
+                storage_uid: row.get(2),
+                parent: row.get(3),
+                basename: row.get(4),
+                path: row.get(6),
+                created: row.get(10),
+                updated: row.get(11),
+            })
+        }
+        _ => {
+            panic!("unexpected fs_type when retrieving fs item. type: {fs_type}");
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn directory() -> fs::Item {
+        fs::Item::Directory(fs::Directory {
+            id: ids::FSSet::new(ids::FSId::new(2).unwrap(), ids::FSUid::gen()),
+            user: ids::UserSet::new(ids::UserId::new(1).unwrap(), ids::UserUid::gen()),
+            group: None,
+            storage: ids::StorageSet::new(ids::StorageId::new(1).unwrap(), ids::StorageUid::gen()),
+            backend: backend::Node::Local(backend::NodeLocal {
+                path: "dir".into(),
+                compressed: false,
+                stored_size: None,
+            }),
+            parent: ids::FSSet::new(ids::FSId::new(1).unwrap(), ids::FSUid::gen()),
+            basename: String::from("dir"),
+            path: String::from("/"),
+            hash: None,
+            tags: Default::default(),
+            comment: None,
+            created: chrono::Utc::now(),
+            updated: None,
+            deleted: None,
+        })
+    }
+
+    fn metadata(expected_updated: Option<chrono::DateTime<chrono::Utc>>) -> rfs_api::fs::UpdateMetadata {
+        rfs_api::fs::UpdateMetadata {
+            tags: None,
+            remove_tags: None,
+            comment: Some(String::from("comment")),
+            group_uid: None,
+            expected_updated: Some(expected_updated),
+        }
+    }
+
+    fn response_updated(item: fs::Item) -> Option<chrono::DateTime<chrono::Utc>> {
+        match rfs_api::fs::Item::from(item) {
+            rfs_api::fs::Item::Directory(dir) => dir.updated,
+            _ => panic!("item is not a directory"),
+        }
+    }
+
+    #[test]
+    fn chained_updates() {
+        let mut item = directory();
+        let local_id = *item.id().local();
+        let group = None;
+
+        let first = chrono::Utc::now();
+        let json = metadata(None);
+        let (query, params) = update_statement(&local_id, &first, &json, &group);
+
+        assert!(query.ends_with(" where id = $1 and updated is null returning updated"));
+        assert_eq!(params.len(), 3);
+
+        // the value returned by the database
+        item.set_updated(Some(first));
+
+        // the client sends back what it received from the first update
+        let expected = response_updated(item);
+
+        assert_eq!(expected, Some(first));
+
+        let second = chrono::Utc::now();
+        let json = metadata(expected);
+        let (query, params) = update_statement(&local_id, &second, &json, &group);
+
+        assert!(query.ends_with(" where id = $1 and updated = $4 returning updated"));
+        assert_eq!(format!("{:?}", params[3]), format!("{:?}", first));
+    }
+}
@@ -0,0 +1,115 @@
+use rfs_lib::fs::event::Action;
+use rfs_api::Validator;
+use rfs_api::fs::{BatchUpdate, BatchResult};
+
+use crate::error::ApiResult;
+use crate::error::api::ApiErrorKind;
+use crate::fs;
+use crate::sec::authn::initiator;
+use crate::sec::authz::permission;
+use crate::tags;
+use crate::db;
+
+/// applies the same metadata changes to all of the items provided. items
+/// that are not found or that the initiator cannot access are reported in
+/// the results without stopping the rest of the batch
+pub async fn update(
+    db::Conn(mut conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    axum::Json(json): axum::Json<BatchUpdate>,
+) -> ApiResult<rfs_api::Payload<Vec<BatchResult>>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Write,
+    ).await?;
+
+    json.assert_ok()?;
+
+    let transaction = conn.transaction().await?;
+    let updated = chrono::Utc::now();
+    let comment = json.comment.as_ref()
+        .map(|comment| if comment.is_empty() { None } else { Some(comment) });
+    let detail = serde_json::json!({
+        "comment": comment.is_some(),
+        "tags": json.add_tags.is_some() || json.remove_tags.is_some(),
+        "batch": true,
+    });
+
+    let mut results = Vec::with_capacity(json.ids.len());
+    let mut count = 0usize;
+
+    for uid in json.ids {
+        let item = match fs::Item::retrieve_uid(&transaction, &uid).await? {
+            Some(item) if item.deleted().is_none() => item,
+            _ => {
+                results.push(BatchResult {
+                    id: uid,
+                    error: Some(rfs_api::ApiError::from(ApiErrorKind::FileNotFound)),
+                });
+
+                continue;
+            }
+        };
+
+        if !fs::has_access(&transaction, &initiator, &item).await? {
+            results.push(BatchResult {
+                id: uid,
+                error: Some(rfs_api::ApiError::from(ApiErrorKind::PermissionDenied)),
+            });
+
+            continue;
+        }
+
+        let local_id = item.id().local();
+
+        if let Some(comment) = &comment {
+            transaction.execute(
+                "update fs set comment = $2, updated = $3 where id = $1",
+                &[local_id, comment, &updated]
+            ).await?;
+        } else {
+            transaction.execute(
+                "update fs set updated = $2 where id = $1",
+                &[local_id, &updated]
+            ).await?;
+        }
+
+        if let Some(add_tags) = &json.add_tags {
+            tags::add_tags(&transaction, "fs_tags", "fs_id", local_id, add_tags).await?;
+        }
+
+        if let Some(remove_tags) = &json.remove_tags {
+            tags::remove_tags(&transaction, "fs_tags", "fs_id", local_id, remove_tags).await?;
+        }
+
+        fs::record_event(
+            &transaction,
+            local_id,
+            initiator.user.id.local(),
+            Action::Updated,
+            Some(detail.clone()),
+        ).await?;
+
+        count += 1;
+
+        results.push(BatchResult {
+            id: uid,
+            error: None,
+        });
+    }
+
+    transaction.commit().await?;
+
+    tracing::info!(
+        user_id = %initiator.user.id.local(),
+        action = "updated",
+        count,
+        failed = results.len() - count,
+        "fs items batch updated"
+    );
+
+    Ok(rfs_api::Payload::new(results))
+}
@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::time::Duration;
+
+use rfs_lib::ids;
+
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use deadpool_postgres::GenericClient;
+use futures::Stream;
+use serde::Deserialize;
+
+use crate::error::ApiResult;
+use crate::error::api::{ApiErrorKind, Context};
+use crate::fs;
+use crate::sec::authn::initiator;
+use crate::sec::authz::permission;
+use crate::state::ArcShared;
+use crate::db;
+
+/// how long to wait between checking the event log for new events
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// max number of events retrieved from the event log at once
+const POLL_LIMIT: i64 = 100;
+
+/// how often the initiator is checked while the stream is open so that it
+/// does not outlive a session
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+pub struct StreamQuery {
+    /// the directory to receive events for
+    dir: ids::FSUid,
+    /// only send events after the given event id. the Last-Event-ID header
+    /// sent by reconnecting clients takes precedence
+    after: Option<i64>,
+}
+
+/// what is needed to keep checking the event log for a single client
+pub(in crate::routing) struct Subscription {
+    state: ArcShared,
+    dir: ids::FSId,
+    user: ids::UserId,
+    last_id: i64,
+    pending: VecDeque<rfs_api::fs::Event>,
+    first: bool,
+}
+
+impl Subscription {
+    /// creates a subscription for a directory the user has access to. without
+    /// a starting point only events that happen after subscribing are sent
+    pub(in crate::routing) async fn new(
+        conn: &impl GenericClient,
+        state: ArcShared,
+        dir: ids::FSId,
+        user: ids::UserId,
+        after: Option<i64>,
+    ) -> ApiResult<Self> {
+        let last_id = match after {
+            Some(id) => id,
+            None => conn.query_one(
+                "select coalesce(max(id), 0) from fs_events",
+                &[]
+            ).await?.get(0),
+        };
+
+        Ok(Subscription {
+            state,
+            dir,
+            user,
+            last_id,
+            pending: VecDeque::new(),
+            first: true,
+        })
+    }
+
+    /// retrieves the events recorded after the last one that was sent for
+    /// items beneath the directory that the user has access to
+    async fn poll(&mut self) -> ApiResult<()> {
+        let conn = self.state.pool().get().await?;
+
+        let rows = conn.query(
+            "\
+            select fs_events.id, \
+                   fs.uid, \
+                   users.uid, \
+                   fs_events.action, \
+                   fs_events.detail, \
+                   fs_events.created \
+            from fs_events \
+            inner join fs on \
+                fs_events.fs_id = fs.id \
+            left join users on \
+                fs_events.user_id = users.id \
+            where fs_events.id > $2 and \
+                  (fs.user_id = $3 or fs.group_id in (\
+                      select group_users.group_id \
+                      from group_users \
+                      where group_users.user_id = $3\
+                  )) and \
+                  exists (\
+                      with recursive ancestors as (\
+                          select fs_item.id, \
+                                 fs_item.parent \
+                          from fs fs_item \
+                          where fs_item.id = fs.id \
+                          union all \
+                          select fs_above.id, \
+                                 fs_above.parent \
+                          from fs fs_above \
+                          inner join ancestors on \
+                              ancestors.parent = fs_above.id and \
+                              ancestors.id != $1\
+                      ) \
+                      select 1 \
+                      from ancestors \
+                      where ancestors.id = $1\
+                  ) \
+            order by fs_events.id \
+            limit $4",
+            &[&self.dir, &self.last_id, &self.user, &POLL_LIMIT]
+        ).await?;
+
+        for row in rows {
+            let event = rfs_api::fs::Event {
+                id: row.get(0),
+                fs_uid: row.get(1),
+                user_uid: row.get(2),
+                action: row.get(3),
+                detail: row.get(4),
+                created: row.get(5),
+            };
+
+            self.last_id = event.id;
+            self.pending.push_back(event);
+        }
+
+        Ok(())
+    }
+
+    /// waits for the next event. the event log is only checked once the
+    /// previous events have been sent so a slow client will not have events
+    /// pile up in memory. None is returned if the event log cannot be read
+    pub(in crate::routing) async fn next(&mut self) -> Option<rfs_api::fs::Event> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+
+            if self.first {
+                self.first = false;
+            } else {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+
+            if let Err(err) = self.poll().await {
+                tracing::error!("failed to retrieve fs events for stream: {err}");
+
+                return None;
+            }
+        }
+    }
+}
+
+/// checks that the initiator is still authenticated. the initiator is only
+/// looked up when the stream is started so a session could have expired or
+/// been revoked since then
+async fn check_initiator(state: &ArcShared, initiator: &initiator::Initiator) -> ApiResult<()> {
+    let conn = state.pool().get().await?;
+
+    initiator::check_mechanism(&conn, initiator).await?;
+
+    Ok(())
+}
+
+/// streams the changes made to items beneath a directory as server-sent
+/// events. the stream ends when the client disconnects or the initiator is
+/// no longer authenticated. clients can resume from the last event they
+/// received with the Last-Event-ID header
+pub async fn stream(
+    State(state): State<ArcShared>,
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    headers: HeaderMap,
+    Query(StreamQuery { dir, after }): Query<StreamQuery>,
+) -> ApiResult<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read,
+    ).await?;
+
+    let item = fs::fetch_item_uid(&conn, &dir, &initiator).await?;
+
+    let container = item.as_container()
+        .kind(ApiErrorKind::NotDirectory)?;
+
+    let last_event_id = match headers.get("last-event-id") {
+        Some(value) => {
+            let id = value.to_str()
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .kind_context(
+                    ApiErrorKind::InvalidHeaderValue,
+                    "last-event-id is not a valid event id"
+                )?;
+
+            Some(id)
+        }
+        None => after,
+    };
+
+    let subscription = Subscription::new(
+        &conn,
+        state,
+        container.id().clone(),
+        initiator.user.id.local().clone(),
+        last_event_id,
+    ).await?;
+
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    check.reset();
+
+    let stream = futures::stream::unfold((subscription, check, initiator), |(mut subscription, mut check, initiator)| {
+        let state = subscription.state.clone();
+
+        async move {
+            // waiting on the subscription can be cancelled since events are
+            // only taken from the pending queue once they are returned
+            let event = loop {
+                tokio::select! {
+                    event = subscription.next() => break event?,
+                    _ = check.tick() => {
+                        if let Err(err) = check_initiator(&state, &initiator).await {
+                            tracing::debug!("closing fs event stream: {err}");
+
+                            return None;
+                        }
+                    }
+                }
+            };
+
+            let id = event.id;
+
+            let sse = match SseEvent::default().json_data(&event) {
+                Ok(sse) => sse.id(id.to_string()),
+                Err(err) => {
+                    tracing::error!("failed to serialize fs event {id}: {err}");
+
+                    return None;
+                }
+            };
+
+            Some((Ok(sse), (subscription, check, initiator)))
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,167 @@
+use std::fmt::Write;
+
+use rfs_lib::ids;
+use rfs_api::fs::ItemMin;
+
+use axum::extract::Query;
+use futures::TryStreamExt;
+use serde::Deserialize;
+
+use crate::error::{ApiError, ApiResult};
+use crate::error::api::{ApiErrorKind, Detail};
+use crate::routing::query::{self, PaginationQuery};
+use crate::sec::authn::initiator;
+use crate::sec::authz::permission;
+use crate::sql;
+use crate::tags;
+use crate::db;
+
+use super::{ITEM_MIN_QUERY, item_min_from_row};
+
+/// max length of the name that can be searched for
+const MAX_NAME_LEN: usize = 255;
+
+#[derive(Deserialize)]
+pub struct NameQuery {
+    name: Option<String>,
+    storage: Option<ids::StorageUid>,
+}
+
+/// retrieves all fs items that have the tags provided and/or contain the
+/// given name in their basename. only items that the initiator owns or that
+/// belong to a group the initiator is a member of will be returned. when
+/// searching by name every directory above the item must also be accessible
+/// so that the contents of directories the initiator cannot read are not
+/// revealed
+pub async fn retrieve(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Query(PaginationQuery { limit, offset, last_id }): Query<PaginationQuery<ids::FSUid>>,
+    Query(NameQuery { name, storage }): Query<NameQuery>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> ApiResult<rfs_api::Payload<Vec<ItemMin>>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read,
+    ).await?;
+
+    let name_pattern = if let Some(name) = &name {
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("name")
+            )));
+        }
+
+        Some(format!("%{}%", tags::escape_like(name)))
+    } else {
+        None
+    };
+
+    let has_tags = pairs.iter()
+        .any(|(key, _)| key == "tag" || key == "tag_prefix");
+
+    // tags are optional when searching by name
+    let filters = if has_tags || name_pattern.is_none() {
+        query::tag_filters(pairs)?
+    } else {
+        Vec::new()
+    };
+
+    let mut pagination = rfs_api::Pagination::from(&limit);
+    let mut params: sql::ParamsVec = vec![initiator.user.id.local()];
+    let mut search_query = format!(
+        "{ITEM_MIN_QUERY} \
+        where (\
+            fs.user_id = $1 or \
+            fs.group_id in (\
+                select group_users.group_id \
+                from group_users \
+                where group_users.user_id = $1\
+            )\
+        ) and \
+        fs.deleted is null"
+    );
+
+    if let Some(pattern) = &name_pattern {
+        write!(
+            &mut search_query,
+            " and fs.basename ilike ${} \
+            and not exists (\
+                with recursive ancestors as (\
+                    select fs_parent.id, \
+                           fs_parent.parent, \
+                           fs_parent.user_id, \
+                           fs_parent.group_id \
+                    from fs fs_parent \
+                    where fs_parent.id = fs.parent \
+                    union all \
+                    select fs_above.id, \
+                           fs_above.parent, \
+                           fs_above.user_id, \
+                           fs_above.group_id \
+                    from fs fs_above \
+                    inner join ancestors on ancestors.parent = fs_above.id\
+                ) \
+                select 1 \
+                from ancestors \
+                where ancestors.user_id != $1 and \
+                      (ancestors.group_id is null or ancestors.group_id not in (\
+                          select group_users.group_id \
+                          from group_users \
+                          where group_users.user_id = $1\
+                      ))\
+            )",
+            sql::push_param(&mut params, pattern)
+        ).unwrap();
+    }
+
+    if let Some(storage) = &storage {
+        write!(
+            &mut search_query,
+            " and storage.uid = ${}",
+            sql::push_param(&mut params, storage)
+        ).unwrap();
+    }
+
+    tags::write_filters(&mut search_query, &mut params, "fs_tags", "fs_id", "fs.id", &filters);
+
+    let offset_num = limit.sql_offset(offset);
+
+    if let Some(last_id) = &last_id {
+        write!(
+            &mut search_query,
+            " and fs.id > (select fs.id from fs where fs.uid = ${}) \
+            order by fs.id \
+            limit ${}",
+            sql::push_param(&mut params, last_id),
+            sql::push_param(&mut params, &limit),
+        ).unwrap();
+    } else {
+        pagination.set_offset(offset);
+
+        write!(
+            &mut search_query,
+            " order by fs.id \
+            limit ${} \
+            offset ${}",
+            sql::push_param(&mut params, &limit),
+            sql::push_param(&mut params, &offset_num),
+        ).unwrap();
+    }
+
+    let result = conn.query_raw(search_query.as_str(), params).await?;
+
+    futures::pin_mut!(result);
+
+    let mut list = Vec::with_capacity(limit as usize);
+
+    while let Some(row) = result.try_next().await? {
+        list.push(item_min_from_row(&row));
+    }
+
+    Ok(rfs_api::Payload::from((pagination, list)))
+}
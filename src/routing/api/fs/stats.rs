@@ -0,0 +1,54 @@
+use rfs_api::fs::UserStats;
+
+use crate::error::ApiResult;
+use crate::sec::authn::initiator;
+use crate::sec::authz::permission;
+use crate::db;
+
+/// summarizes the fs items owned by each user across all storage mediums.
+/// users are ordered by the amount of space used with the largest first
+pub async fn retrieve(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+) -> ApiResult<rfs_api::Payload<Vec<UserStats>>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read,
+    ).await?;
+
+    // other users data is exposed so the initiator must be able to see them
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::User,
+        permission::Ability::Read,
+    ).await?;
+
+    let rows = conn.query(
+        "\
+        select users.uid, \
+               users.username, \
+               count(fs.id), \
+               coalesce(sum(fs.fs_size), 0)::bigint as total_size \
+        from users \
+        left join fs on \
+            users.id = fs.user_id \
+        group by users.id \
+        order by total_size desc, users.id",
+        &[]
+    ).await?;
+
+    let list = rows.into_iter()
+        .map(|row| UserStats {
+            user_uid: row.get(0),
+            username: row.get(1),
+            count: row.get::<usize, i64>(2) as u64,
+            size: row.get::<usize, i64>(3) as u64,
+        })
+        .collect();
+
+    Ok(rfs_api::Payload::new(list))
+}
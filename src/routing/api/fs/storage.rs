@@ -1,20 +1,19 @@
 use std::fmt::Write;
 use std::path::PathBuf;
 
-use rfs_api::fs::{CreateStorage, StorageMin, UpdateStorage};
+use rfs_api::fs::{CreateStorage, RelocateStorage, StorageMin, UpdateStorage};
 use rfs_api::fs::backend::{CreateConfig, UpdateConfig};
 use rfs_lib::ids;
 
 use axum::extract::{Path, Query};
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::response::IntoResponse;
-use futures::TryStreamExt;
 use serde::Deserialize;
 
 use crate::error::{ApiError, ApiResult};
 use crate::error::api::{Context, Detail, ApiErrorKind};
 use crate::fs;
-use crate::routing::query::PaginationQuery;
+use crate::routing::query::{self, PaginationQuery};
 use crate::sec::authn::initiator;
 use crate::sec::authz::permission;
 use crate::sql;
@@ -26,6 +25,37 @@ pub struct PathParams {
     storage_uid: ids::StorageUid,
 }
 
+/// number of files checked when verifying a relocated storage medium
+const RELOCATE_SAMPLE: i64 = 20;
+
+/// the path of a local storage medium must be an absolute path to an existing
+/// directory
+fn check_local_path(path: &std::path::Path) -> ApiResult<()> {
+    if !path.is_absolute() {
+        return Err(ApiError::from(ApiErrorKind::NotAbsolutePath));
+    }
+
+    let metadata = match path.metadata() {
+        Ok(m) => m,
+        Err(err) => {
+            match err.kind() {
+                std::io::ErrorKind::NotFound => {
+                    return Err(ApiError::from(ApiErrorKind::DirNotFound));
+                },
+                _ => {
+                    return Err(err.into())
+                }
+            }
+        }
+    };
+
+    if !metadata.is_dir() {
+        return Err(ApiError::from(ApiErrorKind::NotDirectory));
+    }
+
+    Ok(())
+}
+
 pub async fn retrieve(
     db::Conn(conn): db::Conn,
     rbac: permission::Rbac,
@@ -41,63 +71,78 @@ pub async fn retrieve(
 
     let mut pagination = rfs_api::Pagination::from(&limit);
 
-    let result = if let Some(last_id) = last_id {
-        let params: sql::ParamsArray<3> = [initiator.user.id.local(), &last_id, &limit];
-
-        conn.query_raw(
-            "\
-            select storage.uid, \
-                   storage.name, \
-                   users.uid, \
-                   storage.backend \
-            from storage \
-                join users on storage.user_id = users.id \
-            where storage.user_id = $1 and \
-                  storage.id > (\
-                      select storage.id \
-                      from storage \
-                      where storage.uid = $2\
-                  ) \
-            order by storage.id \
-            limit $3",
-            params,
-        ).await?
-    } else {
+    if last_id.is_none() {
         pagination.set_offset(offset);
+    }
 
-        let offset_num = limit.sql_offset(offset);
-        let params: sql::ParamsArray<3> = [initiator.user.id.local(), &limit, &offset_num];
-
-        conn.query_raw(
-            "\
-            select storage.uid, \
-                   storage.name, \
-                   users.uid, \
-                   storage.backend \
-            from storage \
-                join users on storage.user_id = users.id \
-            where storage.user_id = $1 \
-            order by storage.id \
-            limit $2 \
-            offset $3",
-            params,
-        ).await?
+    let filter = fs::StorageFilter {
+        tags: &[],
+        deleted: true,
+        limit,
+        offset,
+        last_id: last_id.as_ref(),
     };
 
-    futures::pin_mut!(result);
+    let (list, total) = tokio::try_join!(
+        fs::Storage::retrieve_all(&conn, initiator.user.id.local(), &filter),
+        fs::Storage::count_all(&conn, initiator.user.id.local(), &filter),
+    )?;
+
+    pagination.set_total(total as u64);
+
+    Ok(rfs_api::Payload::from((
+        pagination,
+        list.into_iter()
+            .map(StorageMin::from)
+            .collect::<Vec<_>>()
+    )))
+}
+
+/// retrieves all storage mediums owned by the initiator that have the tags
+/// provided
+pub async fn search(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Query(PaginationQuery { limit, offset, last_id }): Query<PaginationQuery<ids::StorageUid>>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> ApiResult<impl IntoResponse> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Storage,
+        permission::Ability::Read,
+    ).await?;
+
+    let filters = query::tag_filters(pairs)?;
 
-    let mut list = Vec::new();
+    let mut pagination = rfs_api::Pagination::from(&limit);
 
-    while let Some(row) = result.try_next().await? {
-        list.push(StorageMin {
-            uid: row.get(0),
-            name: row.get(1),
-            user_uid: row.get(2),
-            backend: sql::de_from_sql(row.get(3)),
-        });
+    if last_id.is_none() {
+        pagination.set_offset(offset);
     }
 
-    Ok(rfs_api::Payload::from((pagination, list)))
+    let filter = fs::StorageFilter {
+        tags: &filters,
+        deleted: false,
+        limit,
+        offset,
+        last_id: last_id.as_ref(),
+    };
+
+    let (list, total) = tokio::try_join!(
+        fs::Storage::retrieve_all(&conn, initiator.user.id.local(), &filter),
+        fs::Storage::count_all(&conn, initiator.user.id.local(), &filter),
+    )?;
+
+    pagination.set_total(total as u64);
+
+    Ok(rfs_api::Payload::from((
+        pagination,
+        list.into_iter()
+            .map(StorageMin::from)
+            .collect::<Vec<_>>()
+    )))
 }
 
 pub async fn create(
@@ -114,32 +159,25 @@ pub async fn create(
     ).await?;
 
     let backend = match json.backend {
-        CreateConfig::Local { path } => {
-            if !path.is_absolute() {
-                return Err(ApiError::from(ApiErrorKind::NotAbsolutePath));
-            }
+        CreateConfig::Local { path, dedup, tmp, compression } => {
+            check_local_path(&path)?;
 
-            let metadata = match path.metadata() {
-                Ok(m) => m,
-                Err(err) => {
-                    match err.kind() {
-                        std::io::ErrorKind::NotFound => {
-                            return Err(ApiError::from(ApiErrorKind::DirNotFound));
-                        },
-                        _ => {
-                            return Err(err.into())
-                        }
-                    }
-                }
-            };
+            tokio::fs::create_dir_all(&path).await?;
 
-            if !metadata.is_dir() {
-                return Err(ApiError::from(ApiErrorKind::NotDirectory));
+            if let Some(tmp) = &tmp {
+                if !tmp.is_absolute() {
+                    return Err(ApiError::from((
+                        ApiErrorKind::NotAbsolutePath,
+                        Detail::with_key("tmp")
+                    )));
+                }
             }
 
-            tokio::fs::create_dir_all(&path).await?;
+            let local = fs::backend::ConfigLocal { path, dedup, tmp, compression };
 
-            fs::backend::Config::Local(fs::backend::ConfigLocal { path })
+            tokio::fs::create_dir_all(local.tmp_dir()).await?;
+
+            fs::backend::Config::Local(local)
         }
     };
 
@@ -196,7 +234,9 @@ pub async fn create(
     let created = chrono::Utc::now();
     let uid = ids::FSUid::gen();
     let backend = fs::backend::Node::Local(fs::backend::NodeLocal {
-        path: PathBuf::new()
+        path: PathBuf::new(),
+        compressed: false,
+        stored_size: None,
     });
 
     {
@@ -220,7 +260,13 @@ pub async fn create(
 
     transaction.commit().await?;
 
-    Ok((StatusCode::CREATED, rfs_api::Payload::new(storage.into_schema())))
+    let location = format!("/api/fs/storage/{}", storage.id.uid());
+
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, location)],
+        rfs_api::Payload::new(storage.into_schema())
+    ))
 }
 
 pub async fn retrieve_id(
@@ -285,6 +331,7 @@ pub async fn update_id(
     let local_id = *storage.id.local();
 
     if json.name.is_some() || json.backend.is_some() {
+        let pg_backend;
         let updated = chrono::Utc::now();
         let mut update_query = String::from("update storage set updated = $2");
         let mut update_params = sql::ParamsVec::with_capacity(2);
@@ -312,18 +359,34 @@ pub async fn update_id(
 
             write!(
                 &mut update_query,
-                "name = ${} ",
+                ", name = ${}",
                 sql::push_param(&mut update_params, &storage.name)
             ).unwrap();
         }
 
         if let Some(backend) = &json.backend {
-            match backend {
-                UpdateConfig::Local {..} => {}
+            match (backend, &mut storage.backend) {
+                (UpdateConfig::Local { dedup, compression }, fs::backend::Config::Local(local)) => {
+                    if let Some(dedup) = dedup {
+                        local.dedup = *dedup;
+                    }
+
+                    if let Some(compression) = compression {
+                        local.compression = *compression;
+                    }
+                }
             }
+
+            pg_backend = sql::ser_to_sql(&storage.backend);
+
+            write!(
+                &mut update_query,
+                ", backend = ${}",
+                sql::push_param(&mut update_params, &pg_backend)
+            ).unwrap();
         }
 
-        write!(&mut update_query, "where storage_id = $1").unwrap();
+        write!(&mut update_query, " where id = $1").unwrap();
 
         transaction.execute(update_query.as_str(), update_params.as_slice()).await?;
     }
@@ -340,11 +403,115 @@ pub async fn update_id(
         storage.tags = tags;
     }
 
+    if let Some(remove_tags) = &json.remove_tags {
+        if !tags::validate_keys(remove_tags) {
+            return Err(ApiError::from(ApiErrorKind::InvalidTags));
+        }
+
+        tags::remove_tags(
+            &transaction,
+            "storage_tags",
+            "storage_id",
+            &local_id,
+            remove_tags
+        ).await?;
+
+        tags::remove_keys(&mut storage.tags, remove_tags);
+    }
+
     transaction.commit().await?;
 
     Ok(rfs_api::Payload::new(storage.into_schema()))
 }
 
+/// updates the path of a local storage medium after its directory has been
+/// moved on the server. nothing is moved on disk
+pub async fn relocate_id(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(PathParams { storage_uid }): Path<PathParams>,
+    axum::Json(json): axum::Json<RelocateStorage>,
+) -> ApiResult<impl IntoResponse> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Storage,
+        permission::Ability::Write,
+    ).await?;
+
+    let mut storage = fs::Storage::retrieve_uid(&conn, &storage_uid)
+        .await?
+        .kind(ApiErrorKind::StorageNotFound)?;
+
+    if storage.deleted.is_some() {
+        return Err(ApiError::from(ApiErrorKind::StorageNotFound));
+    }
+
+    if storage.user != initiator.user.id {
+        return Err(ApiError::from(ApiErrorKind::PermissionDenied));
+    }
+
+    check_local_path(&json.path)?;
+
+    let previous = match &mut storage.backend {
+        fs::backend::Config::Local(local) => {
+            std::mem::replace(&mut local.path, json.path.clone())
+        }
+    };
+
+    if json.verify {
+        let rows = conn.query(
+            "\
+            select fs.backend \
+            from fs \
+            where fs.storage_id = $1 and \
+                  fs.fs_type = $2 and \
+                  fs.deleted is null \
+            order by random() \
+            limit $3",
+            &[storage.id.local(), &fs::consts::FILE_TYPE, &RELOCATE_SAMPLE]
+        ).await?;
+
+        for row in rows {
+            let node: fs::backend::Node = sql::de_from_sql(row.get(0));
+
+            let exists = match (&node, &storage.backend) {
+                (fs::backend::Node::Local(node_local), fs::backend::Config::Local(local)) => {
+                    local.path.join(&node_local.path).try_exists()?
+                }
+            };
+
+            if !exists {
+                return Err(ApiError::from((
+                    ApiErrorKind::FileNotFound,
+                    Detail::with_key("path")
+                )));
+            }
+        }
+    }
+
+    let pg_backend = sql::ser_to_sql(&storage.backend);
+    let updated = chrono::Utc::now();
+
+    conn.execute(
+        "update storage set backend = $2, updated = $3 where id = $1",
+        &[storage.id.local(), &pg_backend, &updated]
+    ).await?;
+
+    storage.updated = Some(updated);
+
+    tracing::warn!(
+        storage_id = %storage.id.local(),
+        user_id = %initiator.user.id.local(),
+        previous = %previous.display(),
+        path = %json.path.display(),
+        "storage medium relocated"
+    );
+
+    Ok(rfs_api::Payload::new(storage.into_schema()))
+}
+
 pub async fn delete_id(
     db::Conn(mut conn): db::Conn,
     rbac: permission::Rbac,
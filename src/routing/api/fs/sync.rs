@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+
+use rfs_lib::ids;
+use rfs_api::Validator;
+use rfs_api::fs::sync::{SyncManifest, SyncMode, SyncItem, SyncPlan};
+
+use axum::extract::Path;
+
+use crate::error::ApiResult;
+use crate::error::api::{ApiErrorKind, Context};
+use crate::fs;
+use crate::sec::authn::initiator;
+use crate::sec::authz::permission;
+use crate::sql;
+use crate::db;
+
+use super::PathParams;
+
+/// an item under the directory being synced
+struct Remote {
+    uid: ids::FSUid,
+    fs_type: fs::consts::FsType,
+    hash: Option<blake3::Hash>,
+    size: u64,
+    accessible: bool,
+}
+
+/// compares the manifest of the client against the contents of the
+/// directory and returns the changes needed to make the server match
+pub async fn plan(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(PathParams { fs_uid }): Path<PathParams>,
+    axum::Json(json): axum::Json<SyncManifest>,
+) -> ApiResult<rfs_api::Payload<SyncPlan>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read,
+    ).await?;
+
+    json.validate()?;
+
+    let item = fs::fetch_item_uid(&conn, &fs_uid, &initiator).await?;
+
+    let container = item.as_container()
+        .kind(ApiErrorKind::NotDirectory)?;
+
+    let groups: HashSet<ids::GroupId> = conn.query(
+        "select group_id from group_users where user_id = $1",
+        &[initiator.user.id.local()]
+    ).await?
+        .into_iter()
+        .map(|row| row.get(0))
+        .collect();
+
+    let rows = conn.query(
+        "\
+        with recursive dir_tree as (\
+            select fs.id, \
+                   fs.uid, \
+                   fs.fs_type, \
+                   fs.hash, \
+                   fs.fs_size, \
+                   fs.user_id, \
+                   fs.group_id, \
+                   fs.basename::text as rel_path \
+            from fs \
+            where fs.parent = $1 and \
+                  fs.deleted is null \
+            union all \
+            select fs_contents.id, \
+                   fs_contents.uid, \
+                   fs_contents.fs_type, \
+                   fs_contents.hash, \
+                   fs_contents.fs_size, \
+                   fs_contents.user_id, \
+                   fs_contents.group_id, \
+                   dir_tree.rel_path || '/' || fs_contents.basename \
+            from fs fs_contents \
+            inner join dir_tree on dir_tree.id = fs_contents.parent \
+            where fs_contents.deleted is null\
+        ) \
+        select uid, fs_type, hash, fs_size, user_id, group_id, rel_path \
+        from dir_tree",
+        &[container.id()]
+    ).await?;
+
+    let mut remote: HashMap<String, Remote> = HashMap::with_capacity(rows.len());
+    let mut remote_order: Vec<String> = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let user_id: ids::UserId = row.get(4);
+        let group_id: Option<ids::GroupId> = row.get(5);
+        let path: String = row.get(6);
+
+        let accessible = user_id == *initiator.user.id.local() ||
+            group_id.map(|id| groups.contains(&id)).unwrap_or(false);
+
+        remote_order.push(path.clone());
+        remote.insert(path, Remote {
+            uid: row.get(0),
+            fs_type: row.get(1),
+            hash: row.get::<usize, Option<Vec<u8>>>(2)
+                .and_then(sql::try_blake3_hash_from_sql),
+            size: sql::u64_from_sql(row.get(3)),
+            accessible,
+        });
+    }
+
+    // sorting by bytes makes sure that a directory is always before its
+    // contents
+    remote_order.sort();
+
+    let plan = match json.mode {
+        SyncMode::Push => plan_push(&json, &remote, &remote_order),
+    };
+
+    Ok(rfs_api::Payload::new(plan))
+}
+
+/// creates a plan that will make the server match the client
+fn plan_push(
+    manifest: &SyncManifest,
+    remote: &HashMap<String, Remote>,
+    remote_order: &[String],
+) -> SyncPlan {
+    let mut plan = SyncPlan {
+        mode: SyncMode::Push,
+        upload: Vec::new(),
+        delete: Vec::new(),
+        conflicts: Vec::new(),
+        unchanged: 0,
+    };
+
+    let mut local: HashSet<&str> = HashSet::with_capacity(manifest.entries.len());
+    let mut local_dirs: HashSet<&str> = HashSet::new();
+
+    for entry in &manifest.entries {
+        local.insert(entry.path.as_str());
+
+        let mut path = entry.path.as_str();
+
+        while let Some((parent, _)) = path.rsplit_once('/') {
+            local_dirs.insert(parent);
+            path = parent;
+        }
+
+        let Some(found) = remote.get(&entry.path) else {
+            plan.upload.push(SyncItem {
+                path: entry.path.clone(),
+                uid: None,
+            });
+
+            continue;
+        };
+
+        if found.fs_type != fs::consts::FILE_TYPE || !found.accessible {
+            plan.conflicts.push(entry.path.clone());
+
+            continue;
+        }
+
+        let matches = found.size == entry.size && found.hash
+            .map(|hash| hash.to_hex().as_str() == entry.hash.to_ascii_lowercase())
+            .unwrap_or(false);
+
+        if matches {
+            plan.unchanged += 1;
+        } else {
+            plan.upload.push(SyncItem {
+                path: entry.path.clone(),
+                uid: Some(found.uid.clone()),
+            });
+        }
+    }
+
+    let mut deleted_dirs: Vec<&str> = Vec::new();
+
+    for path in remote_order {
+        let found = &remote[path];
+
+        if local.contains(path.as_str()) {
+            continue;
+        }
+
+        if local_dirs.contains(path.as_str()) {
+            // the client has files under a path that is a file on the server
+            if found.fs_type == fs::consts::FILE_TYPE {
+                plan.conflicts.push(path.clone());
+            }
+
+            continue;
+        }
+
+        let under_deleted = deleted_dirs.iter()
+            .any(|dir| path.strip_prefix(dir).map(|rest| rest.starts_with('/')).unwrap_or(false));
+
+        if under_deleted || !found.accessible {
+            continue;
+        }
+
+        if found.fs_type == fs::consts::DIR_TYPE {
+            deleted_dirs.push(path.as_str());
+        }
+
+        plan.delete.push(SyncItem {
+            path: path.clone(),
+            uid: Some(found.uid.clone()),
+        });
+    }
+
+    plan
+}
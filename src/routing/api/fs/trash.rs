@@ -0,0 +1,429 @@
+use rfs_lib::ids;
+use rfs_lib::fs::event::Action;
+use rfs_api::fs::{
+    DirectoryMin,
+    FileMin,
+    ItemMin,
+    Trashed,
+};
+
+use axum::extract::{Path, Query};
+use deadpool_postgres::GenericClient;
+use futures::TryStreamExt;
+
+use crate::error::{ApiError, ApiResult};
+use crate::error::api::{Detail, ApiErrorKind};
+use crate::fs::{self, backend};
+use crate::routing::query::PaginationQuery;
+use crate::sec::authn::initiator;
+use crate::sec::authz::permission;
+use crate::sql;
+use crate::db;
+
+use super::PathParams;
+
+pub async fn retrieve(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Query(PaginationQuery { limit, offset, last_id }): Query<PaginationQuery<ids::FSUid>>,
+) -> ApiResult<rfs_api::Payload<Vec<Trashed>>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read,
+    ).await?;
+
+    let mut pagination = rfs_api::Pagination::from(&limit);
+
+    // only items that were trashed directly are listed. anything that was
+    // trashed along with its parent will have the same deleted timestamp
+    let result = if let Some(last_id) = last_id {
+        let params: sql::ParamsVec = vec![initiator.user.id.local(), &last_id, &limit];
+
+        conn.query_raw(
+            "\
+            select fs.uid, \
+                   users.uid, \
+                   storage.uid, \
+                   fs_parent.uid, \
+                   fs.basename, \
+                   fs.fs_type, \
+                   fs.fs_path, \
+                   fs.fs_size, \
+                   fs.mime_type, \
+                   fs.mime_subtype, \
+                   fs.created, \
+                   fs.updated, \
+                   fs.deleted \
+            from fs \
+            left join users on \
+                fs.user_id = users.id \
+            left join storage on \
+                fs.storage_id = storage.id \
+            left join fs as fs_parent on \
+                fs.parent = fs_parent.id \
+            where fs.user_id = $1 and \
+                  fs.deleted is not null and \
+                  fs_parent.deleted is distinct from fs.deleted and \
+                  storage.deleted is null and \
+                  fs.id > (\
+                      select fs.id \
+                      from fs \
+                      where fs.uid = $2\
+                  ) \
+            order by fs.id \
+            limit $3",
+            params
+        ).await?
+    } else {
+        pagination.set_offset(offset);
+
+        let offset_num = limit.sql_offset(offset);
+        let params: sql::ParamsVec = vec![initiator.user.id.local(), &limit, &offset_num];
+
+        conn.query_raw(
+            "\
+            select fs.uid, \
+                   users.uid, \
+                   storage.uid, \
+                   fs_parent.uid, \
+                   fs.basename, \
+                   fs.fs_type, \
+                   fs.fs_path, \
+                   fs.fs_size, \
+                   fs.mime_type, \
+                   fs.mime_subtype, \
+                   fs.created, \
+                   fs.updated, \
+                   fs.deleted \
+            from fs \
+            left join users on \
+                fs.user_id = users.id \
+            left join storage on \
+                fs.storage_id = storage.id \
+            left join fs as fs_parent on \
+                fs.parent = fs_parent.id \
+            where fs.user_id = $1 and \
+                  fs.deleted is not null and \
+                  fs_parent.deleted is distinct from fs.deleted and \
+                  storage.deleted is null \
+            order by fs.id \
+            limit $2 \
+            offset $3",
+            params
+        ).await?
+    };
+
+    futures::pin_mut!(result);
+
+    let mut list = Vec::with_capacity(limit as usize);
+
+    while let Some(row) = result.try_next().await? {
+        let fs_type = row.get(5);
+
+        let item = match fs_type {
+            fs::consts::FILE_TYPE => {
+                ItemMin::File(FileMin {
+                    uid: row.get(0),
+                    user_uid: row.get(1),
+                    storage_uid: row.get(2),
+                    parent: row.get(3),
+                    basename: row.get(4),
+                    path: row.get(6),
+                    size: sql::u64_from_sql(row.get(7)),
+                    mime: sql::mime_from_sql(row.get(8), row.get(9)),
+                    created: row.get(10),
+                    updated: row.get(11),
+                })
+            }
+            fs::consts::DIR_TYPE => {
+                ItemMin::Directory(DirectoryMin {
+                    uid: row.get(0),
+                    user_uid: row.get(1),
+                    storage_uid: row.get(2),
+                    parent: row.get(3),
+                    basename: row.get(4),
+                    path: row.get(6),
+                    created: row.get(10),
+                    updated: row.get(11),
+                })
+            }
+            _ => {
+                // roots are only marked deleted when their storage is deleted
+                // and those are filtered out above
+                continue;
+            }
+        };
+
+        list.push(Trashed {
+            item,
+            deleted: row.get(12),
+        });
+    }
+
+    Ok(rfs_api::Payload::from((pagination, list)))
+}
+
+pub async fn restore_id(
+    db::Conn(mut conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(PathParams { fs_uid }): Path<PathParams>,
+) -> ApiResult<rfs_api::Payload<rfs_api::fs::Item>> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Write,
+    ).await?;
+
+    let (item, storage) = tokio::try_join!(
+        fs::fetch_any_item_uid(&conn, &fs_uid, &initiator),
+        fs::fetch_storage_from_fs_uid(&conn, &fs_uid),
+    )?;
+
+    let Some(deleted) = item.deleted().cloned() else {
+        return Err(ApiError::from(ApiErrorKind::NoOp));
+    };
+
+    let Some(parent) = fs::traits::Common::parent(&item) else {
+        return Err(ApiError::from(ApiErrorKind::NotPermitted));
+    };
+
+    let parent_deleted: Option<chrono::DateTime<chrono::Utc>> = conn.query_one(
+        "select deleted from fs where id = $1",
+        &[parent]
+    ).await?.get(0);
+
+    if parent_deleted.is_some() {
+        return Err(ApiError::from((
+            ApiErrorKind::NotPermitted,
+            Detail::with_key("parent"),
+            "the parent of this item is in the trash"
+        )));
+    }
+
+    if fs::Item::name_check(&conn, parent, item.basename()).await?.is_some() {
+        return Err(ApiError::from((
+            ApiErrorKind::AlreadyExists,
+            Detail::with_key("basename")
+        )));
+    }
+
+    let transaction = conn.transaction().await?;
+
+    // only the items that were trashed along with this item will be restored
+    let params: sql::ParamsArray<2> = [item.id().local(), &deleted];
+    let result = transaction.query_raw(
+        "\
+        with recursive dir_tree as (\
+            select fs_root.id \
+            from fs fs_root \
+            where id = $1 \
+            union \
+            select fs_contents.id \
+            from fs fs_contents \
+            inner join dir_tree on dir_tree.id = fs_contents.parent \
+            where fs_contents.deleted = $2\
+        ) \
+        update fs \
+        set deleted = null \
+        where id in (select id from dir_tree) \
+        returning id",
+        params
+    ).await.map_err(fs::basename_conflict)?;
+
+    futures::pin_mut!(result);
+
+    let mut restored: Vec<ids::FSId> = Vec::new();
+
+    while let Some(row) = result.try_next().await.map_err(fs::basename_conflict)? {
+        restored.push(row.get(0));
+    }
+
+    fs::record_events(
+        &transaction,
+        &restored,
+        initiator.user.id.local(),
+        Action::Restored,
+    ).await?;
+
+    fs::update_dir_hashes(&transaction, parent).await?;
+
+    match backend::Pair::match_up(&storage.backend, item.backend())? {
+        backend::Pair::Local((local, node_local)) => {
+            fs::trash::restore_from_trash(local, node_local, item.id().uid()).await?;
+
+            if let Err(err) = transaction.commit().await {
+                if let Err(err) = fs::trash::move_to_trash(local, node_local, item.id().uid()).await {
+                    tracing::error!("failed to move item back to trash after failed commit. id: {} {err}", item.id().local());
+                }
+
+                return Err(ApiError::from(err));
+            }
+        }
+    }
+
+    Ok(rfs_api::Payload::new(fs::fetch_item_uid(&conn, &fs_uid, &initiator).await?.into()))
+}
+
+/// marks the file as deleted and moves it into the trash directory of the
+/// storage medium
+pub async fn trash_file(
+    conn: &mut impl GenericClient,
+    initiator: &initiator::Initiator,
+    storage: fs::Storage,
+    file: fs::File,
+) -> ApiResult<()> {
+    let transaction = conn.transaction().await?;
+    let deleted = chrono::Utc::now();
+
+    transaction.execute(
+        "update fs set deleted = $2 where id = $1",
+        &[file.id.local(), &deleted]
+    ).await?;
+
+    fs::record_event(
+        &transaction,
+        file.id.local(),
+        initiator.user.id.local(),
+        Action::Deleted,
+        Some(serde_json::json!({ "basename": &file.basename, "trash": true })),
+    ).await?;
+
+    fs::update_dir_hashes(&transaction, file.parent.local()).await?;
+
+    match backend::Pair::match_up(&storage.backend, &file.backend)? {
+        backend::Pair::Local((local, node_local)) => {
+            fs::trash::move_to_trash(local, node_local, file.id.uid()).await?;
+
+            if let Err(err) = transaction.commit().await {
+                if let Err(err) = fs::trash::restore_from_trash(local, node_local, file.id.uid()).await {
+                    tracing::error!("failed to restore file after failed commit. id: {} {err}", file.id.local());
+                }
+
+                return Err(ApiError::from(err));
+            }
+        }
+    }
+
+    tracing::info!(
+        fs_id = %file.id.local(),
+        user_id = %initiator.user.id.local(),
+        action = "trashed",
+        size = file.size,
+        "file moved to trash"
+    );
+
+    Ok(())
+}
+
+/// marks the directory and everything under it as deleted and moves the
+/// directory into the trash directory of the storage medium. everything
+/// under the directory will share the same deleted timestamp so that it can
+/// be restored along with the directory
+pub async fn trash_dir(
+    conn: &mut impl GenericClient,
+    initiator: &initiator::Initiator,
+    storage: fs::Storage,
+    directory: fs::Directory,
+) -> ApiResult<()> {
+    let transaction = conn.transaction().await?;
+    let deleted = chrono::Utc::now();
+    let params: sql::ParamsArray<2> = [directory.id.local(), &deleted];
+
+    let result = transaction.query_raw(
+        "\
+        with recursive dir_tree as (\
+            select fs_root.id \
+            from fs fs_root \
+            where id = $1 \
+            union \
+            select fs_contents.id \
+            from fs fs_contents \
+            inner join dir_tree on dir_tree.id = fs_contents.parent \
+            where fs_contents.deleted is null\
+        ) \
+        update fs \
+        set deleted = $2 \
+        where id in (select id from dir_tree) \
+        returning id",
+        params
+    ).await?;
+
+    futures::pin_mut!(result);
+
+    let mut trashed: Vec<ids::FSId> = Vec::new();
+
+    while let Some(row) = result.try_next().await? {
+        trashed.push(row.get(0));
+    }
+
+    fs::record_events(
+        &transaction,
+        &trashed,
+        initiator.user.id.local(),
+        Action::Deleted,
+    ).await?;
+
+    fs::update_dir_hashes(&transaction, directory.parent.local()).await?;
+
+    match backend::Pair::match_up(&storage.backend, &directory.backend)? {
+        backend::Pair::Local((local, node_local)) => {
+            fs::trash::move_to_trash(local, node_local, directory.id.uid()).await?;
+
+            if let Err(err) = transaction.commit().await {
+                if let Err(err) = fs::trash::restore_from_trash(local, node_local, directory.id.uid()).await {
+                    tracing::error!("failed to restore directory after failed commit. id: {} {err}", directory.id.local());
+                }
+
+                return Err(ApiError::from(err));
+            }
+        }
+    }
+
+    tracing::info!(
+        fs_id = %directory.id.local(),
+        user_id = %initiator.user.id.local(),
+        action = "trashed",
+        trashed = trashed.len(),
+        "directory moved to trash"
+    );
+
+    Ok(())
+}
+
+/// permanently removes an item that is already in the trash
+pub async fn purge_item(
+    conn: &mut impl GenericClient,
+    initiator: &initiator::Initiator,
+    storage: fs::Storage,
+    item: fs::Item,
+) -> ApiResult<()> {
+    let transaction = conn.transaction().await?;
+
+    let purged = fs::trash::purge(&transaction, &storage, item.id().local()).await?;
+
+    fs::record_event(
+        &transaction,
+        item.id().local(),
+        initiator.user.id.local(),
+        Action::Deleted,
+        Some(serde_json::json!({ "basename": item.basename(), "purged": true })),
+    ).await?;
+
+    transaction.commit().await?;
+
+    fs::trash::remove_purged(purged).await;
+
+    tracing::info!(
+        fs_id = %item.id().local(),
+        user_id = %initiator.user.id.local(),
+        action = "purged",
+        "fs item purged from trash"
+    );
+
+    Ok(())
+}
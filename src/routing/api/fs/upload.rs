@@ -1,23 +1,31 @@
+use std::fmt::Write;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use rfs_lib::ids;
+use rfs_lib::fs::event::Action;
+use rfs_api::fs::OnConflict;
 
 use axum::body::Body;
-use axum::extract::{Path, Query};
-use axum::http::HeaderMap;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
 use deadpool_postgres::GenericClient;
 use futures::StreamExt;
 use serde::Deserialize;
+use async_compression::tokio::write::ZstdEncoder;
 use tokio::io::{AsyncWriteExt, BufWriter};
 
 use crate::error::{ApiResult, ApiError};
 use crate::error::api::{Detail, Context, ApiErrorKind};
 use crate::fs::{self, backend};
+use crate::routing::conditional;
 use crate::sec::authn::initiator;
 use crate::sec::authz::permission;
 use crate::sql;
 use crate::path;
 use crate::db;
+use crate::state::ArcShared;
 
 #[derive(Deserialize)]
 pub struct PathParams {
@@ -27,9 +35,68 @@ pub struct PathParams {
 #[derive(Deserialize)]
 pub struct UploadQuery {
     basename: Option<String>,
+    #[serde(default)]
+    overwrite: bool,
+    on_conflict: Option<OnConflict>,
+    mime: Option<String>,
+    #[serde(default)]
+    allow_mime_change: bool,
+}
+
+impl UploadQuery {
+    /// "overwrite=true" is the same as "on_conflict=overwrite" but cannot be
+    /// combined with a different option
+    fn on_conflict(&self) -> ApiResult<OnConflict> {
+        match (self.overwrite, self.on_conflict) {
+            (false, on_conflict) => Ok(on_conflict.unwrap_or_default()),
+            (true, None | Some(OnConflict::Overwrite)) => Ok(OnConflict::Overwrite),
+            (true, Some(_)) => Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key("on_conflict")
+            ))),
+        }
+    }
+}
+
+/// max number of names to try when renaming a conflicting upload
+const RENAME_ATTEMPTS: usize = 100;
+
+/// what an upload into a directory will do with the requested basename
+#[derive(Debug, PartialEq)]
+enum Target {
+    /// nothing has the basename so a new file is created
+    Create,
+    /// the existing item with the basename is replaced
+    Overwrite(ids::FSId),
+    /// an unused basename needs to be found for a new file
+    Rename,
+}
+
+/// decides what to do with an upload given the item that already has the
+/// requested basename. a create only request (If-None-Match: *) is rejected
+/// for any existing item regardless of the conflict option
+fn resolve_target(
+    existing: Option<ids::FSId>,
+    create_only: bool,
+    on_conflict: OnConflict,
+) -> ApiResult<Target> {
+    let Some(existing_id) = existing else {
+        return Ok(Target::Create);
+    };
+
+    if create_only {
+        return Err(ApiError::from(ApiErrorKind::PreconditionFailed));
+    }
+
+    match on_conflict {
+        OnConflict::Error => Err(ApiError::from(ApiErrorKind::AlreadyExists)),
+        OnConflict::Overwrite => Ok(Target::Overwrite(existing_id)),
+        OnConflict::Rename => Ok(Target::Rename),
+    }
 }
 
 pub async fn upload_file(
+    State(state): State<ArcShared>,
     db::Conn(mut conn): db::Conn,
     rbac: permission::Rbac,
     initiator: initiator::Initiator,
@@ -37,7 +104,7 @@ pub async fn upload_file(
     Path(PathParams { fs_uid }): Path<PathParams>,
     Query(upload_query): Query<UploadQuery>,
     stream: Body,
-) -> ApiResult<rfs_api::Payload<rfs_api::fs::Item>> {
+) -> ApiResult<Response> {
     rbac.api_ability(
         &conn,
         &initiator,
@@ -50,27 +117,100 @@ pub async fn upload_file(
         fs::fetch_storage_from_fs_uid(&conn, &fs_uid),
     )?;
 
-    let mime = get_mime(&headers)?;
+    let max_size = state.limits().max_file_size;
+
+    check_content_length(&headers, max_size)?;
+
+    let mime = get_mime(&headers, &upload_query)?;
     let maybe_validate = get_validation_hash(&headers)?;
+    let mtime = get_mtime(&headers)?;
+
+    // held until the upload has finished
+    let _permit = match state.uploads().acquire(&storage.backend).await {
+        Ok(permit) => permit,
+        Err(wait) => return Ok(server_busy(wait)),
+    };
+
     let transaction = conn.transaction().await?;
 
+    let mut item = item;
+    let mut new_basename = None;
+
+    if item.as_container().is_some() {
+        let on_conflict = upload_query.on_conflict()?;
+        let mut basename = get_basename(&headers, &upload_query)?;
+
+        // held until the transaction finishes so that a concurrent upload
+        // with the same basename will see this file once committed
+        fs::lock_basename(&transaction, item.id().local(), &basename).await?;
+
+        let existing = fs::Item::name_check(&transaction, item.id().local(), &basename).await?;
+
+        // the client only wanted to create the file if it did not already
+        // exist. any other value is checked against the existing file if it
+        // is overwritten
+        let create_only = conditional::is_wildcard(&headers, header::IF_NONE_MATCH);
+
+        match resolve_target(existing, create_only, on_conflict)? {
+            Target::Create => {}
+            Target::Overwrite(existing_id) => {
+                let existing = fs::Item::retrieve(&transaction, &existing_id)
+                    .await?
+                    .kind(ApiErrorKind::FileNotFound)?;
+
+                if !fs::has_access(&transaction, &initiator, &existing).await? {
+                    return Err(ApiError::from(ApiErrorKind::PermissionDenied));
+                }
+
+                if existing.as_container().is_some() {
+                    return Err(ApiError::from((
+                        ApiErrorKind::AlreadyExists,
+                        Detail::with_key("basename"),
+                        "a directory with the given basename already exists"
+                    )));
+                }
+
+                item = existing;
+            }
+            Target::Rename => {
+                let Some(found) = fs::name_gen(&transaction, item.id().local(), &basename, RENAME_ATTEMPTS).await? else {
+                    return Err(ApiError::from((
+                        ApiErrorKind::AlreadyExists,
+                        Detail::with_key("basename"),
+                        "failed to find an unused basename"
+                    )));
+                };
+
+                fs::lock_basename(&transaction, item.id().local(), &found).await?;
+
+                basename = found;
+            }
+        }
+
+        if item.as_container().is_some() {
+            new_basename = Some(basename);
+        }
+    }
+
+    // files created in a group owned directory will share the same group
+    let group = item.group().cloned();
+
     let rtn = match item.try_into_parent_parts() {
         Ok((parent, path, container_backend)) => {
             let uid = ids::FSUid::gen();
             let user = initiator.user.id.clone();
             let storage_id = storage.id.clone();
             let created = chrono::Utc::now();
-            let basename = get_basename(&headers, &upload_query)?;
+            let basename = new_basename.context("missing basename for new file")?;
 
-            if fs::Item::name_check(&transaction, parent.local(), &basename).await?.is_some() {
-                return Err(ApiError::from(ApiErrorKind::AlreadyExists));
-            }
+            fs::check_depth(state.limits(), &path)?;
+            fs::check_children(&transaction, state.limits(), parent.local()).await?;
 
             match backend::Pair::match_up(&storage.backend, &container_backend)? {
                 backend::Pair::Local((local, node_local)) => {
-                    let dir = local.path.join(&node_local.path);
+                    let dir = local.contained_path(&node_local.path).await?;
                     let full = dir.join(&basename);
-                    let tmp = dir.join(format!("{}.tmp.rfs", uid));
+                    let tmp = create_tmp_path(local, &uid).await?;
 
                     tracing::debug!("tmp path: \"{}\"", tmp.display());
 
@@ -80,34 +220,31 @@ pub async fn upload_file(
                     if result.is_some() {
                         return Err(ApiError::from((
                             ApiErrorKind::AlreadyExists,
+                            Detail::with_key("basename"),
                             "an unknown file already exists in this location"
                         )));
                     }
 
-                    let tmp_file = create_file(&tmp).await?;
+                    let (mut guard, size, hash) = write_tmp(&tmp, local.compression, maybe_validate, stream, max_size).await?;
 
-                    let (size, hash) = match write_body(tmp_file, maybe_validate, stream).await {
-                        Ok(result) => result,
-                        Err(err) => {
-                            tokio::fs::remove_file(&tmp)
-                                .await
-                                .context("failed removing tmp file after failed hash validation")?;
+                    state.metrics().add_uploaded(size);
 
-                            return Err(err);
-                        }
-                    };
+                    if local.dedup {
+                        dedup_local(&transaction, local, &storage_id, None, size, &hash, &tmp).await?;
+                    }
 
-                    let backend = backend::Node::Local(fs::backend::NodeLocal {
-                        path: full.strip_prefix(&local.path)
-                            .unwrap()
-                            .to_owned()
-                    });
+                    let backend = local_node(
+                        local,
+                        full.strip_prefix(&local.path).unwrap().to_owned(),
+                        &tmp
+                    ).await?;
 
                     let tmp_id = ids::FSId::try_from(1).unwrap();
 
                     let mut file = fs::File {
                         id: ids::FSSet::new(tmp_id, uid),
                         user,
+                        group,
                         storage: storage_id,
                         backend,
                         parent,
@@ -121,32 +258,41 @@ pub async fn upload_file(
                         created,
                         updated: None,
                         deleted: None,
+                        mtime,
                     };
 
-                    if let Err(err) = insert_file(&mut file, &transaction).await {
-                        tokio::fs::remove_file(&tmp)
-                            .await
-                            .context("failed removing tmp file after inserting database")?;
+                    insert_file(&mut file, &transaction).await?;
 
-                        return Err(err);
-                    }
+                    fs::record_event(
+                        &transaction,
+                        file.id.local(),
+                        file.user.local(),
+                        Action::Created,
+                        Some(serde_json::json!({
+                            "basename": &file.basename,
+                            "size": file.size,
+                        })),
+                    ).await?;
 
-                    if let Err(err) = tokio::fs::rename(&tmp, &full).await {
-                        tokio::fs::remove_file(&tmp)
-                            .await
-                            .context("failed removing tmp file after failed hash validation")?;
+                    move_file(&tmp, &full)
+                        .await
+                        .context("failed to move tmp file to full path")?;
 
-                        return Err(ApiError::from(err)
-                            .context("failed to move tmp file to full path"));
-                    }
+                    // the file is now in its final location so that is what
+                    // needs to be removed if the commit fails
+                    guard.moved(full);
 
-                    if let Err(err) = transaction.commit().await {
-                        tokio::fs::remove_file(&full)
-                            .await
-                            .context("failed to remove full after committing database")?;
+                    transaction.commit().await?;
 
-                        return Err(ApiError::from(err));
-                    }
+                    guard.keep();
+
+                    tracing::info!(
+                        fs_id = %file.id.local(),
+                        user_id = %initiator.user.id.local(),
+                        action = "created",
+                        size = file.size,
+                        "file uploaded"
+                    );
 
                     fs::Item::File(file)
                 }
@@ -154,6 +300,13 @@ pub async fn upload_file(
         }
         Err(item) => {
             let mut file = item.into_file();
+
+            check_preconditions(&headers, &file)?;
+
+            if file.mime.essence_str() != mime.essence_str() && !upload_query.allow_mime_change {
+                return Err(ApiError::from(ApiErrorKind::MimeMismatch));
+            }
+
             file.mime = mime;
 
             match backend::Pair::match_up(&storage.backend, &file.backend)? {
@@ -161,7 +314,7 @@ pub async fn upload_file(
                     let full = local.path.join(&node_local.path);
                     let parent_dir = full.parent()
                         .context("failed to retrieve parent directory of file?")?;
-                    let tmp = parent_dir.join(format!("{}.tmp.rfs", file.id.uid()));
+                    let tmp = create_tmp_path(local, file.id.uid()).await?;
                     let prev = parent_dir.join(format!("{}.prev.rfs", file.id.uid()));
 
                     tracing::debug!("tmp path: \"{}\"", tmp.display());
@@ -174,62 +327,59 @@ pub async fn upload_file(
                         return Err(ApiError::from(ApiErrorKind::FileNotFound));
                     }
 
-                    let tmp_file = create_file(&tmp).await?;
+                    local.contained_path(&node_local.path).await?;
 
-                    let (size, hash) = match write_body(tmp_file, maybe_validate, stream).await {
-                        Ok(result) => result,
-                        Err(err) => {
-                            tokio::fs::remove_file(&tmp)
-                                .await
-                                .context("failed removing tmp file after failed hash validation")?;
+                    let node_path = node_local.path.clone();
 
-                            return Err(err);
-                        }
-                    };
+                    let (mut guard, size, hash) = write_tmp(&tmp, local.compression, maybe_validate, stream, max_size).await?;
+
+                    state.metrics().add_uploaded(size);
 
+                    if local.dedup {
+                        dedup_local(&transaction, local, &file.storage, Some(file.id.local()), size, &hash, &tmp).await?;
+                    }
+
+                    // the new contents follow the current compression setting
+                    // of the storage medium and not the previous contents
+                    file.backend = local_node(local, node_path, &tmp).await?;
                     file.size = size;
                     file.hash = hash;
                     file.updated = Some(chrono::Utc::now());
+                    file.mtime = mtime;
 
-                    if let Err(err) = update_file(&file, &transaction).await {
-                        tokio::fs::remove_file(&tmp)
-                            .await
-                            .context("failed removing tmp file after updating database")?;
+                    update_file(&file, &transaction).await?;
 
-                        return Err(err);
-                    }
+                    fs::record_event(
+                        &transaction,
+                        file.id.local(),
+                        initiator.user.id.local(),
+                        Action::Updated,
+                        Some(serde_json::json!({ "size": file.size })),
+                    ).await?;
 
-                    // now begins the dance of file updates
+                    // now begins the dance of file updates. the tmp file will
+                    // be removed by the guard if any of these fail
 
                     // first move the current file to the prev
-                    if let Err(err) = tokio::fs::rename(&full, &prev).await {
-                        // try to remove the tmp file
-                        tokio::fs::remove_file(&tmp)
-                            .await
-                            .context("failed removing tmp after failed moving full to prev")?;
-
-                        return Err(ApiError::from(err)
-                            .context("failed to move full to prev"));
-                    }
+                    tokio::fs::rename(&full, &prev)
+                        .await
+                        .context("failed to move full to prev")?;
 
                     // then move the tmp file to full
-                    if let Err(err) = tokio::fs::rename(&tmp, &full).await {
+                    if let Err(err) = move_file(&tmp, &full).await {
                         // try to move the prev file back to the original
                         tokio::fs::rename(&prev, &full)
                             .await
                             .context("failed to move prev to full after moving tmp to full")?;
 
-                        // try to remove the tmp file, since this operation did
-                        // not succeed the tmp file should still be in its
-                        // original path
-                        tokio::fs::remove_file(&tmp)
-                            .await
-                            .context("failed removing tmp after failed moving full to prev")?;
-
                         return Err(ApiError::from(err)
                             .context("failed to move tmp to full"));
                     }
 
+                    // the tmp file no longer exists so there is nothing left
+                    // for the guard to remove
+                    guard.keep();
+
                     // commit to the database
                     if let Err(err) = transaction.commit().await {
                         // since the tmp file was moved to the full path we
@@ -250,17 +400,54 @@ pub async fn upload_file(
                         .await
                         .context("failed to remove prev file")?;
 
+                    tracing::info!(
+                        fs_id = %file.id.local(),
+                        user_id = %initiator.user.id.local(),
+                        action = "updated",
+                        size = file.size,
+                        "file contents updated"
+                    );
+
                     fs::Item::File(file)
                 }
             }
         }
     };
 
-    Ok(rfs_api::Payload::new(rtn.into()))
+    Ok(rfs_api::Payload::new(rfs_api::fs::Item::from(rtn)).into_response())
+}
+
+/// the client should wait at least as long as the upload waited for an open
+/// slot before trying again
+fn server_busy(wait: std::time::Duration) -> Response {
+    let mut response = ApiError::from(ApiErrorKind::ServerBusy).into_response();
+
+    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(wait.as_secs().max(1)));
+    response
+}
+
+/// checks the conditional headers against the current hash of the file to
+/// prevent overwriting changes that the client has not seen
+fn check_preconditions(headers: &HeaderMap, file: &fs::File) -> ApiResult<()> {
+    let etag = file.etag();
+
+    if headers.contains_key(header::IF_MATCH) &&
+        !conditional::etag_matches_strong(headers, header::IF_MATCH, &etag)
+    {
+        return Err(ApiError::from(ApiErrorKind::PreconditionFailed));
+    }
+
+    if conditional::etag_matches(headers, header::IF_NONE_MATCH, &etag) {
+        return Err(ApiError::from(ApiErrorKind::PreconditionFailed));
+    }
+
+    Ok(())
 }
 
+/// retrieves the expected hash of the upload. x-hash is still accepted for
+/// older clients
 fn get_validation_hash(headers: &HeaderMap) -> ApiResult<Option<blake3::Hash>> {
-    if let Some(hash) = headers.get("x-hash") {
+    if let Some(hash) = headers.get("x-content-hash").or_else(|| headers.get("x-hash")) {
         let hash_str = hash.to_str()
             .kind(ApiErrorKind::InvalidHeaderValue)?;
 
@@ -283,6 +470,33 @@ fn get_validation_hash(headers: &HeaderMap) -> ApiResult<Option<blake3::Hash>> {
     }
 }
 
+/// retrieves the modification time of the file from the client. the value
+/// can either be an RFC 3339 timestamp or the number of seconds since the
+/// unix epoch
+fn get_mtime(headers: &HeaderMap) -> ApiResult<Option<chrono::DateTime<chrono::Utc>>> {
+    let Some(value) = headers.get("x-mtime") else {
+        return Ok(None);
+    };
+
+    let value_str = value.to_str().kind_context(
+        ApiErrorKind::InvalidHeaderValue,
+        "x-mtime contains invalid utf8 characters"
+    )?;
+
+    let mtime = if let Ok(secs) = i64::from_str(value_str) {
+        chrono::DateTime::from_timestamp(secs, 0)
+    } else {
+        chrono::DateTime::parse_from_rfc3339(value_str)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    };
+
+    mtime.map(Some).kind_context(
+        ApiErrorKind::InvalidHeaderValue,
+        "x-mtime is not a valid timestamp"
+    )
+}
+
 fn get_basename(headers: &HeaderMap, query: &UploadQuery) -> ApiResult<String> {
     let found = if let Some(value) = &query.basename {
         value.clone()
@@ -308,8 +522,15 @@ fn get_basename(headers: &HeaderMap, query: &UploadQuery) -> ApiResult<String> {
     Ok(found)
 }
 
-fn get_mime(headers: &HeaderMap) -> ApiResult<mime::Mime> {
-    if let Some(value) = headers.get("content-type") {
+/// the mime provided in the query takes precedence over the content-type
+/// header
+fn get_mime(headers: &HeaderMap, query: &UploadQuery) -> ApiResult<mime::Mime> {
+    if let Some(value) = &query.mime {
+        mime::Mime::from_str(value).kind_context(
+            ApiErrorKind::InvalidMimeType,
+            "mime is not a valid mime format"
+        )
+    } else if let Some(value) = headers.get("content-type") {
         let content_type = value.to_str().kind_context(
             ApiErrorKind::InvalidHeaderValue,
             "content-type contains invalid utf8 characters"
@@ -324,6 +545,66 @@ fn get_mime(headers: &HeaderMap) -> ApiResult<mime::Mime> {
     }
 }
 
+/// rejects the upload if the content-length provided by the client is larger
+/// than the allowed max size. the body is still checked while it is streamed
+/// since the header is not guaranteed to be present or accurate
+fn check_content_length(headers: &HeaderMap, max_size: u64) -> ApiResult<()> {
+    let Some(value) = headers.get("content-length") else {
+        return Ok(());
+    };
+
+    let length = value.to_str()
+        .ok()
+        .and_then(|v| u64::from_str(v).ok())
+        .kind_context(
+            ApiErrorKind::InvalidHeaderValue,
+            "content-length is not a valid number"
+        )?;
+
+    if length > max_size {
+        return Err(ApiError::from(ApiErrorKind::MaxSize));
+    }
+
+    Ok(())
+}
+
+/// removes the file at the given path when dropped unless it has been kept.
+/// this makes sure that partially written files are cleaned up when the
+/// request errors or the client disconnects in the middle of an upload
+struct FileGuard {
+    path: Option<PathBuf>,
+}
+
+impl FileGuard {
+    fn new(path: PathBuf) -> Self {
+        FileGuard { path: Some(path) }
+    }
+
+    /// updates the path to remove after the file has been renamed
+    fn moved(&mut self, path: PathBuf) {
+        self.path = Some(path);
+    }
+
+    /// prevents the file from being removed
+    fn keep(&mut self) {
+        self.path = None;
+    }
+}
+
+impl Drop for FileGuard {
+    fn drop(&mut self) {
+        let Some(path) = self.path.take() else {
+            return;
+        };
+
+        if let Err(err) = std::fs::remove_file(&path) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                tracing::error!("failed to remove partial file \"{}\": {err}", path.display());
+            }
+        }
+    }
+}
+
 async fn create_file(path: &std::path::Path) -> ApiResult<BufWriter<tokio::fs::File>> {
     let file = tokio::fs::OpenOptions::new()
         .write(true)
@@ -335,15 +616,99 @@ async fn create_file(path: &std::path::Path) -> ApiResult<BufWriter<tokio::fs::F
     Ok(BufWriter::new(file))
 }
 
+/// retrieves the path to write an upload to, creating the tmp directory of
+/// the storage medium if it does not exist
+async fn create_tmp_path(local: &backend::ConfigLocal, uid: &ids::FSUid) -> ApiResult<PathBuf> {
+    tokio::fs::create_dir_all(local.tmp_dir())
+        .await
+        .context("failed to create tmp directory")?;
+
+    Ok(local.tmp_path(uid))
+}
+
+/// moves the tmp file to its final location. if the tmp directory is on a
+/// different filesystem then the rename will fail so the file is copied and
+/// the tmp file removed instead
+async fn move_file(tmp: &std::path::Path, full: &std::path::Path) -> std::io::Result<()> {
+    let Err(err) = tokio::fs::rename(tmp, full).await else {
+        return Ok(());
+    };
+
+    if err.kind() == std::io::ErrorKind::NotFound {
+        return Err(err);
+    }
+
+    tracing::debug!("rename failed, falling back to copy. {err}");
+
+    if let Err(err) = tokio::fs::copy(tmp, full).await {
+        // do not leave a partial file at the final location
+        let _ = tokio::fs::remove_file(full).await;
+
+        return Err(err);
+    }
+
+    tokio::fs::remove_file(tmp).await
+}
+
+/// creates the tmp file and writes the body to it. the returned guard will
+/// remove the tmp file when dropped so nothing is left behind in the storage
+/// medium if the upload does not complete
+///
+/// when compressing, the size and hash returned are still of the original
+/// body and not of what was written to disk
+async fn write_tmp(
+    tmp: &std::path::Path,
+    compress: bool,
+    validate: Option<blake3::Hash>,
+    stream: Body,
+    max_size: u64,
+) -> ApiResult<(FileGuard, u64, blake3::Hash)> {
+    let tmp_file = create_file(tmp).await?;
+    let guard = FileGuard::new(tmp.to_owned());
+
+    let (size, hash) = if compress {
+        write_body(ZstdEncoder::new(tmp_file), validate, stream, max_size).await?
+    } else {
+        write_body(tmp_file, validate, stream, max_size).await?
+    };
+
+    Ok((guard, size, hash))
+}
+
+/// creates the node for contents written to the tmp file. the stored size is
+/// retrieved after any dedup since the tmp file may have been replaced
+async fn local_node(
+    local: &backend::ConfigLocal,
+    path: PathBuf,
+    tmp: &std::path::Path,
+) -> ApiResult<backend::Node> {
+    let stored_size = if local.compression {
+        let metadata = tokio::fs::metadata(tmp)
+            .await
+            .context("failed to retrieve metadata for tmp file")?;
+
+        Some(metadata.len())
+    } else {
+        None
+    };
+
+    Ok(backend::Node::Local(fs::backend::NodeLocal {
+        path,
+        compressed: local.compression,
+        stored_size,
+    }))
+}
+
 async fn write_body<T>(
     mut writer: T,
     validate: Option<blake3::Hash>,
     stream: Body,
+    max_size: u64,
 ) -> ApiResult<(u64, blake3::Hash)>
 where
     T: tokio::io::AsyncWrite + Unpin,
 {
-    let mut written: usize = 0;
+    let mut written: u64 = 0;
     let mut hasher = blake3::Hasher::new();
 
     let mut stream = stream.into_data_stream();
@@ -352,29 +717,113 @@ where
         let bytes = result?;
         let slice = bytes.as_ref();
 
-        hasher.update(slice);
+        written = written.checked_add(slice.len() as u64)
+            .kind(ApiErrorKind::MaxSize)?;
 
-        let wrote = writer.write(slice).await?;
+        if written > max_size {
+            return Err(ApiError::from(ApiErrorKind::MaxSize));
+        }
 
-        written = written.checked_add(wrote)
-            .kind(ApiErrorKind::MaxSize)?;
+        hasher.update(slice);
+
+        writer.write_all(slice).await?;
     }
 
-    writer.flush().await?;
+    // shutdown instead of flush so that encoders finish writing their output
+    writer.shutdown().await?;
 
-    let size = written.try_into()
-        .kind(ApiErrorKind::MaxSize)?;
+    let size = written;
     let hash = hasher.finalize();
 
     if let Some(validate) = validate {
+        // the tmp file guard and the uncommitted transaction will clean up
+        // everything that was done for the upload
         if validate != hash {
-            return Err(ApiError::from(ApiErrorKind::InvalidHash));
+            return Err(ApiError::from(ApiErrorKind::ChecksumMismatch));
         }
     }
 
     Ok((size, hash))
 }
 
+/// searches the storage medium for an existing file with the same size and
+/// hash. if one is found then the tmp file is swapped out for a hard link to
+/// the existing file so that the content is only stored once on disk.
+///
+/// failing to create the link is not considered an error as the tmp file
+/// will still contain the uploaded contents. only files that were stored with
+/// the same compression setting are considered
+async fn dedup_local(
+    conn: &impl GenericClient,
+    local: &backend::ConfigLocal,
+    storage: &ids::StorageSet,
+    exclude: Option<&ids::FSId>,
+    size: u64,
+    hash: &blake3::Hash,
+    tmp: &std::path::Path,
+) -> ApiResult<()> {
+    let pg_hash = hash.as_bytes().as_slice();
+    let pg_size: i64 = TryFrom::try_from(size)
+        .kind_context(ApiErrorKind::MaxSize, "total bytes written exceeds i64")?;
+
+    let mut query = String::from(
+        "\
+        select fs.backend \
+        from fs \
+        where fs.storage_id = $1 and \
+              fs.fs_type = $2 and \
+              fs.fs_size = $3 and \
+              fs.hash = $4 and \
+              coalesce((fs.backend->>'compressed')::boolean, false) = $5 and \
+              fs.deleted is null"
+    );
+    let mut params: sql::ParamsVec = vec![
+        storage.local(),
+        &fs::consts::FILE_TYPE,
+        &pg_size,
+        &pg_hash,
+        &local.compression,
+    ];
+
+    if let Some(id) = exclude {
+        write!(&mut query, " and fs.id != ${}", sql::push_param(&mut params, id)).unwrap();
+    }
+
+    query.push_str(" limit 1");
+
+    let Some(row) = conn.query_opt(query.as_str(), params.as_slice()).await? else {
+        return Ok(());
+    };
+
+    let found: backend::Node = sql::de_from_sql(row.get(0));
+
+    let Some(node_local) = found.as_local() else {
+        return Ok(());
+    };
+
+    let existing = local.path.join(&node_local.path);
+    let link = tmp.with_extension("link.rfs");
+
+    tracing::debug!("dedup existing path: \"{}\"", existing.display());
+
+    if let Err(err) = tokio::fs::hard_link(&existing, &link).await {
+        tracing::warn!("failed to create dedup link. existing: \"{}\" {err}", existing.display());
+
+        return Ok(());
+    }
+
+    if let Err(err) = tokio::fs::rename(&link, tmp).await {
+        tokio::fs::remove_file(&link)
+            .await
+            .context("failed removing dedup link after failed rename")?;
+
+        return Err(ApiError::from(err)
+            .context("failed to move dedup link to tmp path"));
+    }
+
+    Ok(())
+}
+
 async fn insert_file(file: &mut fs::File, conn: &impl GenericClient) -> ApiResult<()> {
     let id = {
         let pg_backend = sql::ser_to_sql(&file.backend);
@@ -389,6 +838,7 @@ async fn insert_file(file: &mut fs::File, conn: &impl GenericClient) -> ApiResul
             insert into fs(\
                 uid, \
                 user_id, \
+                group_id, \
                 storage_id, \
                 parent, \
                 basename, \
@@ -399,13 +849,15 @@ async fn insert_file(file: &mut fs::File, conn: &impl GenericClient) -> ApiResul
                 backend, \
                 mime_type, \
                 mime_subtype, \
-                created\
+                created, \
+                mtime\
             ) values \
-            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) \
-            returing id",
+            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15) \
+            returning id",
             &[
                 file.id.uid(),
                 file.user.local(),
+                &file.group.as_ref().map(|group| group.local()),
                 file.storage.local(),
                 file.parent.local(),
                 &file.basename,
@@ -416,19 +868,23 @@ async fn insert_file(file: &mut fs::File, conn: &impl GenericClient) -> ApiResul
                 &pg_backend,
                 &pg_mime_type,
                 &pg_mime_subtype,
-                &file.created
+                &file.created,
+                &file.mtime
             ]
-        ).await?;
+        ).await.map_err(fs::basename_conflict)?;
 
         result.get(0)
     };
 
     file.id = ids::FSSet::new(id, file.id.uid().clone());
 
+    fs::update_dir_hashes(conn, file.parent.local()).await?;
+
     Ok(())
 }
 
 async fn update_file(file: &fs::File, conn: &impl GenericClient) -> ApiResult<()> {
+    let pg_backend = sql::ser_to_sql(&file.backend);
     let pg_hash = file.hash.as_bytes().as_slice();
     let pg_size: i64 = TryFrom::try_from(file.size)
         .kind_context(ApiErrorKind::MaxSize, "total bytes written exceeds i64")?;
@@ -438,10 +894,223 @@ async fn update_file(file: &fs::File, conn: &impl GenericClient) -> ApiResult<()
         update fs \
         set fs_size = $2, \
             hash = $3, \
-            updated = $4 \
+            updated = $4, \
+            mtime = $5, \
+            backend = $6 \
         where fs.id = $1",
-        &[file.id.local(), &pg_size, &pg_hash, &file.updated]
+        &[file.id.local(), &pg_size, &pg_hash, &file.updated, &file.mtime, &pg_backend]
     ).await?;
 
+    fs::update_dir_hashes(conn, file.parent.local()).await?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn write_tmp_over_max_size() {
+        let max_size = 1024;
+        let tmp = std::env::temp_dir()
+            .join(format!("{}.tmp.rfs", ids::FSUid::gen()));
+
+        let stream = Body::from(vec![0u8; max_size as usize + 1]);
+
+        let Err(err) = write_tmp(&tmp, false, None, stream, max_size).await else {
+            panic!("body larger than max size was written");
+        };
+
+        assert_eq!(*err.inner().kind(), ApiErrorKind::MaxSize);
+        assert!(!tmp.exists(), "tmp file was not removed");
+    }
+
+    #[tokio::test]
+    async fn write_tmp_at_max_size() {
+        let max_size = 1024;
+        let tmp = std::env::temp_dir()
+            .join(format!("{}.tmp.rfs", ids::FSUid::gen()));
+
+        let stream = Body::from(vec![0u8; max_size as usize]);
+
+        let (guard, size, _hash) = write_tmp(&tmp, false, None, stream, max_size)
+            .await
+            .expect("body at max size failed to write");
+
+        assert_eq!(size, max_size);
+        assert!(tmp.exists(), "tmp file was not created");
+
+        drop(guard);
+
+        assert!(!tmp.exists(), "tmp file was not removed by guard");
+    }
+
+    #[tokio::test]
+    async fn write_tmp_compressed() {
+        use tokio::io::AsyncReadExt;
+
+        let tmp = std::env::temp_dir()
+            .join(format!("{}.tmp.rfs", ids::FSUid::gen()));
+        let contents = b"compressed contents ".repeat(256);
+
+        let stream = Body::from(contents.clone());
+
+        let (guard, size, hash) = write_tmp(&tmp, true, None, stream, 1024 * 1024)
+            .await
+            .expect("failed to write compressed body");
+
+        assert_eq!(size, contents.len() as u64);
+        assert_eq!(hash, blake3::hash(&contents));
+
+        let stored = tokio::fs::read(&tmp).await.unwrap();
+
+        assert!(stored.len() < contents.len(), "contents were not compressed");
+
+        let mut decoder = async_compression::tokio::bufread::ZstdDecoder::new(stored.as_slice());
+        let mut decoded = Vec::new();
+
+        decoder.read_to_end(&mut decoded).await.unwrap();
+
+        assert_eq!(decoded, contents);
+
+        drop(guard);
+    }
+
+    fn parse_query(query: &str) -> UploadQuery {
+        let uri: axum::http::Uri = format!("/api/fs/uid?{query}").parse().unwrap();
+
+        Query::<UploadQuery>::try_from_uri(&uri)
+            .expect("failed to parse upload query")
+            .0
+    }
+
+    #[test]
+    fn on_conflict_defaults_to_error() {
+        let query = parse_query("basename=file.txt");
+
+        assert_eq!(query.on_conflict().unwrap(), OnConflict::Error);
+    }
+
+    #[test]
+    fn on_conflict_overwrite() {
+        let query = parse_query("overwrite=true");
+
+        assert_eq!(query.on_conflict().unwrap(), OnConflict::Overwrite);
+
+        let query = parse_query("overwrite=true&on_conflict=overwrite");
+
+        assert_eq!(query.on_conflict().unwrap(), OnConflict::Overwrite);
+
+        let query = parse_query("on_conflict=rename");
+
+        assert_eq!(query.on_conflict().unwrap(), OnConflict::Rename);
+    }
+
+    #[test]
+    fn on_conflict_mismatch() {
+        let query = parse_query("overwrite=true&on_conflict=rename");
+
+        let Err(err) = query.on_conflict() else {
+            panic!("overwrite with rename was accepted");
+        };
+
+        assert_eq!(*err.inner().kind(), ApiErrorKind::ValidationFailed);
+    }
+
+    #[test]
+    fn target_create_without_existing() {
+        for on_conflict in [OnConflict::Error, OnConflict::Overwrite, OnConflict::Rename] {
+            for create_only in [false, true] {
+                let target = resolve_target(None, create_only, on_conflict)
+                    .expect("upload without an existing item was rejected");
+
+                assert_eq!(target, Target::Create);
+            }
+        }
+    }
+
+    #[test]
+    fn target_reject_on_conflict() {
+        let existing = ids::FSId::new(1).unwrap();
+
+        let Err(err) = resolve_target(Some(existing), false, OnConflict::Error) else {
+            panic!("conflicting upload was accepted");
+        };
+
+        assert_eq!(*err.inner().kind(), ApiErrorKind::AlreadyExists);
+    }
+
+    #[test]
+    fn target_reject_create_only() {
+        let existing = ids::FSId::new(1).unwrap();
+
+        for on_conflict in [OnConflict::Error, OnConflict::Overwrite, OnConflict::Rename] {
+            let Err(err) = resolve_target(Some(existing), true, on_conflict) else {
+                panic!("create only upload was accepted for an existing item");
+            };
+
+            assert_eq!(*err.inner().kind(), ApiErrorKind::PreconditionFailed);
+        }
+    }
+
+    #[test]
+    fn target_overwrite_existing() {
+        let existing = ids::FSId::new(1).unwrap();
+
+        let target = resolve_target(Some(existing), false, OnConflict::Overwrite)
+            .expect("overwrite was rejected");
+
+        assert_eq!(target, Target::Overwrite(existing));
+
+        let target = resolve_target(Some(existing), false, OnConflict::Rename)
+            .expect("rename was rejected");
+
+        assert_eq!(target, Target::Rename);
+    }
+
+    #[test]
+    fn mtime_formats() {
+        let expected = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut headers = HeaderMap::new();
+
+        assert_eq!(get_mtime(&headers).unwrap(), None);
+
+        headers.insert("x-mtime", HeaderValue::from_static("1700000000"));
+
+        assert_eq!(get_mtime(&headers).unwrap(), Some(expected));
+
+        headers.insert("x-mtime", HeaderValue::from_static("2023-11-14T22:13:20Z"));
+
+        assert_eq!(get_mtime(&headers).unwrap(), Some(expected));
+
+        headers.insert("x-mtime", HeaderValue::from_static("2023-11-14T23:13:20+01:00"));
+
+        assert_eq!(get_mtime(&headers).unwrap(), Some(expected));
+
+        headers.insert("x-mtime", HeaderValue::from_static("yesterday"));
+
+        let Err(err) = get_mtime(&headers) else {
+            panic!("invalid mtime was accepted");
+        };
+
+        assert_eq!(*err.inner().kind(), ApiErrorKind::InvalidHeaderValue);
+    }
+
+    #[tokio::test]
+    async fn move_file_replaces_tmp() {
+        let tmp = std::env::temp_dir()
+            .join(format!("{}.tmp.rfs", ids::FSUid::gen()));
+        let full = std::env::temp_dir()
+            .join(format!("{}.full.rfs", ids::FSUid::gen()));
+
+        tokio::fs::write(&tmp, b"contents").await.unwrap();
+
+        move_file(&tmp, &full).await.expect("failed to move tmp file");
+
+        assert!(!tmp.exists(), "tmp file still exists");
+        assert_eq!(tokio::fs::read(&full).await.unwrap(), b"contents");
+
+        tokio::fs::remove_file(&full).await.unwrap();
+    }
+}
@@ -1,10 +1,11 @@
 use axum::Router;
-use axum::routing::get;
+use axum::routing::{get, post, delete};
 
 use crate::state::ArcShared;
 
 mod secrets;
 mod roles;
+mod check;
 
 pub fn routes() -> Router<ArcShared> {
     Router::new()
@@ -15,6 +16,7 @@ pub fn routes() -> Router<ArcShared> {
         .route("/secrets/session", get(secrets::session_retrieve)
             .post(secrets::session_create)
             .delete(secrets::session_delete))
+        .route("/secrets/session/:id", delete(secrets::session_retire))
         .route("/roles", get(roles::retrieve)
             .post(roles::create))
         .route("/roles/:role_uid", get(roles::retrieve_id)
@@ -26,4 +28,5 @@ pub fn routes() -> Router<ArcShared> {
         .route("/roles/:role_uid/groups", get(roles::retrieve_id_groups)
             .post(roles::add_id_groups)
             .delete(roles::remove_id_groups))
+        .route("/check", post(check::check))
 }
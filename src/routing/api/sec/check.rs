@@ -0,0 +1,35 @@
+use axum::response::IntoResponse;
+
+use crate::error::{ApiError, ApiResult};
+use crate::error::api::ApiErrorKind;
+use crate::sec::authn::initiator;
+use crate::sec::authz::permission::Rbac;
+use crate::db;
+
+/// checks which of the given permissions the initiator has. no specific
+/// permission is required as the initiator is only able to check their own
+pub async fn check(
+    db::Conn(conn): db::Conn,
+    rbac: Rbac,
+    initiator: initiator::Initiator,
+    axum::Json(json): axum::Json<rfs_api::sec::check::CheckPermissions>,
+) -> ApiResult<impl IntoResponse> {
+    if json.permissions.is_empty() {
+        return Err(ApiError::from(ApiErrorKind::NoWork));
+    }
+
+    let abilities = rbac.abilities(&conn, &initiator).await?;
+    let mut results = Vec::with_capacity(json.permissions.len());
+
+    for permission in json.permissions {
+        let granted = abilities.has_ability(&permission.scope, &permission.ability);
+
+        results.push(rfs_api::sec::check::PermissionCheck {
+            scope: permission.scope,
+            ability: permission.ability,
+            granted,
+        });
+    }
+
+    Ok(rfs_api::Payload::new(results))
+}
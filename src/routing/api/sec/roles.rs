@@ -3,7 +3,7 @@ use std::fmt::Write;
 
 use rfs_lib::ids;
 
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::extract::{Path, Query};
 use axum::response::IntoResponse;
 use deadpool_postgres::GenericClient;
@@ -34,6 +34,12 @@ pub async fn retrieve(
 
     let mut pagination = rfs_api::Pagination::from(&limit);
 
+    let total: i64 = conn.query_one("select count(*) from authz_roles", &[])
+        .await?
+        .get(0);
+
+    pagination.set_total(total as u64);
+
     let result = if let Some(last_id) = last_id {
         let params: sql::ParamsArray<2> = [&last_id, &limit];
 
@@ -42,7 +48,11 @@ pub async fn retrieve(
             select authz_roles.uid, \
                    authz_roles.name \
             from authz_roles \
-            where authz_roles.id > $1 \
+            where authz_roles.id > (\
+                select authz_roles.id \
+                from authz_roles \
+                where authz_roles.uid = $1\
+            ) \
             order by authz_roles.id \
             limit $2",
             params
@@ -120,12 +130,14 @@ pub async fn create(
     };
 
     let role_id: ids::RoleId = result.get(0);
+    let location = format!("/api/sec/roles/{uid}");
 
     if json.permissions.len() == 0 {
         transaction.commit().await?;
 
         return Ok((
             StatusCode::CREATED,
+            [(header::LOCATION, location)],
             rfs_api::Payload::new(rfs_api::sec::roles::Role {
                 uid,
                 name: json.name,
@@ -179,6 +191,7 @@ pub async fn create(
 
     Ok((
         StatusCode::CREATED,
+        [(header::LOCATION, location)],
         rfs_api::Payload::new(rfs_api::sec::roles::Role {
             uid,
             name: json.name,
@@ -336,9 +349,11 @@ pub async fn update_id(
         }
     }
 
+    let affected = role_users(&transaction, original.id.local()).await?;
+
     transaction.commit().await?;
 
-    clear_via_role(&rbac, &conn, original.id.local()).await?;
+    clear_role_users(&rbac, original.id.local(), &affected);
 
     let permissions = if let Some(changed) = changed_permissions {
         changed.into_iter()
@@ -386,8 +401,14 @@ pub async fn delete_id(
         .await?
         .kind(ApiErrorKind::RoleNotFound)?;
 
+    // the users need to be collected before the role is removed since
+    // the records that attach them to the role will be gone afterwards
+    let affected = role_users(&transaction, original.id.local()).await?;
+
     let query_params: sql::ParamsArray<1> = [original.id.local()];
 
+    // tokio_postgres pipelines queries sent on the same connection so these
+    // will run one after the other in the transaction
     let result = tokio::try_join!(
         transaction.execute(
             "delete from authz_permissions where role_id = $1",
@@ -400,6 +421,10 @@ pub async fn delete_id(
         transaction.execute(
             "delete from user_roles where role_id = $1",
             &query_params
+        ),
+        transaction.execute(
+            "delete from bots where role_id = $1",
+            &query_params
         )
     );
 
@@ -414,7 +439,7 @@ pub async fn delete_id(
 
     transaction.commit().await?;
 
-    clear_via_role(&rbac, &conn, original.id.local()).await?;
+    clear_role_users(&rbac, original.id.local(), &affected);
 
     Ok(StatusCode::OK)
 }
@@ -831,11 +856,12 @@ async fn users(
     )))
 }
 
-async fn clear_via_role(
-    rbac: &Rbac,
+/// retrieves all users that are attached to the role either directly or
+/// through a group
+async fn role_users(
     conn: &impl GenericClient,
     role_id: &ids::RoleId
-) -> ApiResult<()> {
+) -> ApiResult<Vec<ids::UserId>> {
     let (users, users_via_groups) = tokio::try_join!(
         users(conn, role_id),
         users_via_groups(conn, role_id),
@@ -844,13 +870,49 @@ async fn clear_via_role(
     futures::pin_mut!(users);
     futures::pin_mut!(users_via_groups);
 
+    let mut rtn = Vec::new();
+
     while let Some(user_id) = users.try_next().await? {
-        rbac.clear_id(&user_id);
+        rtn.push(user_id);
     }
 
     while let Some(user_id) = users_via_groups.try_next().await? {
-        rbac.clear_id(&user_id);
+        rtn.push(user_id);
     }
 
-    Ok(())
+    Ok(rtn)
+}
+
+/// clears the cached abilities of the users attached to the role along with
+/// any bots using it
+fn clear_role_users(rbac: &Rbac, role_id: &ids::RoleId, users: &[ids::UserId]) {
+    for user_id in users {
+        rbac.clear_id(user_id);
+    }
+
+    rbac.clear_role(role_id);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clear_role_users_drops_cached() {
+        let rbac = Rbac::new();
+        let role_id = ids::RoleId::new(1).unwrap();
+        let attached = ids::UserId::new(1).unwrap();
+        let via_group = ids::UserId::new(2).unwrap();
+        let unrelated = ids::UserId::new(3).unwrap();
+
+        rbac.cache_empty(&attached);
+        rbac.cache_empty(&via_group);
+        rbac.cache_empty(&unrelated);
+
+        clear_role_users(&rbac, &role_id, &[attached, via_group]);
+
+        assert!(!rbac.is_cached(&attached), "attached user is still cached");
+        assert!(!rbac.is_cached(&via_group), "group user is still cached");
+        assert!(rbac.is_cached(&unrelated), "unrelated user was cleared");
+    }
 }
@@ -11,9 +11,9 @@ use tokio_postgres::types::ToSql;
 use base64::{Engine, engine::general_purpose::STANDARD};
 
 use crate::error::{ApiError, ApiResult};
-use crate::error::api::{ApiErrorKind, Context};
+use crate::error::api::{Detail, ApiErrorKind, Context};
 use crate::state::ArcShared;
-use crate::sec::secrets::{Key, PeppersManager};
+use crate::sec::secrets::{self, Key, PeppersManager};
 use crate::sec::authn::initiator;
 use crate::sec::authz::permission;
 use crate::time;
@@ -39,13 +39,16 @@ pub async fn password_retrieve(
 
         known_versions.reserve(reader.len());
 
+        let newest = reader.latest_version().map(|(version, _)| *version);
+
         for (version, key) in reader.iter() {
             let created = time::utc_to_chrono_datetime(key.created())
                 .context("timestamp error for password key")?;
 
             known_versions.push(rfs_api::sec::secrets::PasswordListItem {
                 version: *version,
-                created
+                created,
+                newest: newest == Some(*version),
             });
         }
     }
@@ -301,11 +304,18 @@ pub async fn session_retrieve(
 
         known_keys = Vec::with_capacity(reader.stored());
 
+        let newest = reader.newest().map(|key| key.id());
+
         for key in reader.iter() {
             let created = time::utc_to_chrono_datetime(key.created())
                 .context("timestamp error for session key")?;
+            let id = key.id();
 
-            known_keys.push(rfs_api::sec::secrets::SessionListItem { created });
+            known_keys.push(rfs_api::sec::secrets::SessionListItem {
+                newest: newest.as_ref() == Some(&id),
+                id,
+                created,
+            });
         }
     }
 
@@ -326,6 +336,7 @@ pub async fn session_create(
     ).await?;
 
     let wrapper = state.sec().session_info().keys();
+    let grace = *state.sec().session_info().key_grace();
     let data = Key::rand_key_data()?;
     let created = time::utc_now().context("timestamp error for session key")?;
 
@@ -336,7 +347,13 @@ pub async fn session_create(
             return Err(ApiError::new().source("session keys rwlock poisoned"));
         };
 
+        // the new key will be used for creating sessions while the previous
+        // keys are still accepted until they are outside the grace period
         writer.push(key);
+
+        secrets::retain_session_keys(&mut writer, |_, replaced_by| {
+            secrets::within_grace(replaced_by, grace, created)
+        });
     }
 
     wrapper.save().context("failed to save session secret")?;
@@ -391,3 +408,55 @@ pub async fn session_delete(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[derive(Deserialize)]
+pub struct SessionKeyParams {
+    id: String,
+}
+
+/// removes a specific session key. any sessions that were created with the
+/// key will no longer be valid
+pub async fn session_retire(
+    State(state): State<ArcShared>,
+    initiator: initiator::Initiator,
+    Path(SessionKeyParams { id }): Path<SessionKeyParams>,
+) -> ApiResult<impl IntoResponse> {
+    let conn = state.pool().get().await?;
+
+    state.sec().rbac().api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::SecSecrets,
+        permission::Ability::Write
+    ).await?;
+
+    let wrapper = state.sec().session_info().keys();
+
+    {
+        let Ok(mut writer) = wrapper.inner().write() else {
+            return Err(ApiError::new().source("session keys rwlock poisoned"));
+        };
+
+        let Some(newest) = writer.newest() else {
+            return Err(ApiError::from(ApiErrorKind::SecretNotFound));
+        };
+
+        if newest.id() == id {
+            return Err(ApiError::from((
+                ApiErrorKind::NoOp,
+                Detail::with_key("id"),
+                "cannot retire the newest session key. create a new key first"
+            )));
+        }
+
+        let removed = secrets::retain_session_keys(&mut writer, |key, _| key.id() != id);
+
+        if removed == 0 {
+            return Err(ApiError::from(ApiErrorKind::SecretNotFound));
+        }
+    }
+
+    wrapper.save().context("failed to save session secret")?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
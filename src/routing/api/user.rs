@@ -4,7 +4,7 @@ use rfs_lib::ids;
 use rfs_api::Validator;
 
 use axum::Router;
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::extract::{State, Query, Path};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
@@ -14,6 +14,7 @@ use serde::Deserialize;
 use crate::error::{ApiResult, ApiError};
 use crate::error::api::{ApiErrorKind, Detail, Context};
 use crate::state::ArcShared;
+use crate::fs;
 use crate::sec::authn::{self, initiator, session};
 use crate::sec::authz::permission;
 use crate::sql;
@@ -21,7 +22,10 @@ use crate::user;
 use crate::routing::query::PaginationQuery;
 use crate::db;
 
+mod bot;
+mod email;
 mod group;
+mod me;
 mod password;
 mod totp;
 
@@ -42,6 +46,10 @@ pub fn routes() -> Router<ArcShared> {
         .route("/group/:group_uid/users", get(group::retrieve_users)
             .post(group::add_users)
             .delete(group::delete_users))
+        .route("/me", get(me::retrieve)
+            .patch(me::update))
+        .route("/email", post(email::update))
+        .route("/email/verify", post(email::verify))
         .route("/password", post(password::update))
         .route("/totp", get(totp::retrieve)
             .post(totp::create)
@@ -52,9 +60,16 @@ pub fn routes() -> Router<ArcShared> {
         .route("/totp/recovery/:key_id", get(totp::retrieve_recovery_key)
             .patch(totp::update_recovery_key)
             .delete(totp::delete_recovery_key))
+        .route("/totp/recovery/:key_id/regen", post(totp::regen_recovery_key))
         .route("/:user_uid", get(retrieve_id)
             .patch(update_id)
             .delete(delete_id))
+        .route("/:user_uid/usage", get(retrieve_id_usage))
+        .route("/:user_uid/bot", get(bot::retrieve)
+            .post(bot::create))
+        .route("/:user_uid/bot/:bot_uid", get(bot::retrieve_id)
+            .put(bot::rotate_id)
+            .delete(bot::delete_id))
 }
 
 async fn retrieve(
@@ -163,9 +178,7 @@ async fn create(
 
         Some(email)
     } else {
-        let username_id = user::check_username(&transaction, &username).await?;
-
-        if username_id.is_some() {
+        if user::User::retrieve_by_username(&transaction, &username).await?.is_some() {
             return Err(ApiError::from((
                 ApiErrorKind::AlreadyExists,
                 Detail::with_key("username")
@@ -175,17 +188,46 @@ async fn create(
         None
     };
 
-    let row = transaction.query_one(
+    // the checks above can race with another request creating the same user
+    // so the constraints of the table are the final say
+    let row = match transaction.query_one(
         "\
         insert into users (uid, username, email) \
         values ($1, $2, $3) \
         returning id",
         &[&uid, &username, &email]
-    ).await?;
+    ).await {
+        Ok(row) => row,
+        Err(err) => {
+            if let Some(constraint) = sql::unique_constraint_error(&err) {
+                if constraint == "users_username_key" {
+                    return Err(ApiError::from((
+                        ApiErrorKind::AlreadyExists,
+                        Detail::with_key("username")
+                    )));
+                }
+
+                if constraint == "users_email_key" {
+                    return Err(ApiError::from((
+                        ApiErrorKind::AlreadyExists,
+                        Detail::with_key("email")
+                    )));
+                }
+            }
+
+            return Err(err.into());
+        }
+    };
 
     let id = row.get(0);
 
-    authn::password::Password::create(&transaction, &id, json.password, state.sec().peppers()).await?;
+    authn::password::Password::create(
+        &transaction,
+        &id,
+        json.password,
+        state.sec().password_params(),
+        state.sec().peppers()
+    ).await?;
 
     transaction.commit().await?;
 
@@ -194,12 +236,17 @@ async fn create(
         verified: false
     });
 
+    let location = format!("/api/user/{uid}");
+
     Ok((
         StatusCode::CREATED,
+        [(header::LOCATION, location)],
         rfs_api::Payload::new(rfs_api::users::User {
             uid,
             username,
-            email
+            email,
+            display_name: None,
+            preferences: serde_json::Value::Object(Default::default()),
         })
     ))
 }
@@ -221,16 +268,62 @@ async fn retrieve_id(
         .await?
         .kind(ApiErrorKind::UserNotFound)?;
 
-    let email = user.email.map(|e| rfs_api::users::Email {
-        email: e.email,
-        verified: e.verified
-    });
+    Ok(rfs_api::Payload::new(rfs_api::users::User::from(user)))
+}
 
-    Ok(rfs_api::Payload::new(rfs_api::users::User {
-        uid: user.id.into(),
-        username: user.username,
-        email
-    }))
+/// counts the fs items and bytes owned by the user for each fs type
+async fn retrieve_id_usage(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(PathParams { user_uid }): Path<PathParams>,
+) -> ApiResult<impl IntoResponse> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::User,
+        permission::Ability::Read,
+    ).await?;
+
+    let user = user::User::retrieve_uid(&conn, &user_uid)
+        .await?
+        .kind(ApiErrorKind::UserNotFound)?;
+
+    let rows = conn.query(
+        "\
+        select fs.fs_type, \
+               count(*), \
+               coalesce(sum(fs.fs_size), 0)::bigint \
+        from fs \
+        where fs.user_id = $1 \
+        group by fs.fs_type",
+        &[user.id.local()]
+    ).await?;
+
+    let mut rtn = rfs_api::fs::UserUsage {
+        user_uid: user.id.uid().clone(),
+        roots: Default::default(),
+        directories: Default::default(),
+        files: Default::default(),
+    };
+
+    for row in rows {
+        let fs_type: fs::consts::FsType = row.get(0);
+        let count: i64 = row.get(1);
+        let size: i64 = row.get(2);
+
+        let usage = match fs_type {
+            fs::consts::ROOT_TYPE => &mut rtn.roots,
+            fs::consts::DIR_TYPE => &mut rtn.directories,
+            fs::consts::FILE_TYPE => &mut rtn.files,
+            _ => continue,
+        };
+
+        usage.count = count as u64;
+        usage.size = size as u64;
+    }
+
+    Ok(rfs_api::Payload::new(rtn))
 }
 
 async fn update_id(
@@ -340,22 +433,25 @@ async fn update_id(
         transaction.execute(update_query.as_str(), update_params.as_slice()).await?;
     }
 
-    let email = user.email.map(|e| rfs_api::users::Email {
-        email: e.email,
-        verified: e.verified
-    });
+    Ok(rfs_api::Payload::new(rfs_api::users::User::from(user)))
+}
 
-    Ok(rfs_api::Payload::new(rfs_api::users::User {
-        uid: user.id.into_uid(),
-        username: user.username,
-        email
-    }))
+#[derive(Deserialize)]
+struct DeleteQuery {
+    #[serde(default)]
+    purge: bool,
 }
 
+/// deletes the user along with their sessions, authentication methods, roles,
+/// group memberships, and bots. if the user owns any storage then the request
+/// is refused unless purge is specified which will also remove all of the
+/// data in the storage. fs items created by the user in storage owned by
+/// someone else are given to the owner of that storage
 async fn delete_id(
     State(state): State<ArcShared>,
     initiator: initiator::Initiator,
     Path(PathParams { user_uid }): Path<PathParams>,
+    Query(DeleteQuery { purge }): Query<DeleteQuery>,
 ) -> ApiResult<impl IntoResponse> {
     let mut conn = state.pool().get().await?;
     let rbac = state.sec().rbac();
@@ -364,19 +460,98 @@ async fn delete_id(
         &conn,
         &initiator,
         permission::Scope::User,
-        permission::Ability::Write
+        permission::Ability::Delete
     ).await?;
 
     let user = user::User::retrieve_uid(&conn, &user_uid)
         .await?
         .kind(ApiErrorKind::UserNotFound)?;
 
-    if *user.id.uid() == user_uid {
+    if user.id == initiator.user.id {
         return Err(ApiError::from(ApiErrorKind::NoOp));
     }
 
     let transaction = conn.transaction().await?;
 
+    // prevents the last two admins from deleting each other at the same time
+    transaction.execute(
+        "lock table user_roles, group_users in share row exclusive mode",
+        &[]
+    ).await?;
+
+    let admins = permission::retrieve_users_with_ability(
+        &transaction,
+        permission::Scope::User,
+        permission::Ability::Delete
+    ).await?;
+
+    if admins.contains(user.id.local()) && admins.len() == 1 {
+        return Err(ApiError::from(ApiErrorKind::LastAdmin));
+    }
+
+    // items created by the user in storage owned by someone else, such as a
+    // directory shared through a group, are given to the owner of the
+    // storage. the group of the item is left as is so it stays shared
+    transaction.execute(
+        "\
+        update fs \
+        set user_id = storage.user_id \
+        from storage \
+        where fs.storage_id = storage.id and \
+              fs.user_id = $1 and \
+              storage.user_id != $1",
+        &[user.id.local()]
+    ).await?;
+
+    let owns_data = transaction.query_one(
+        "select exists(select 1 from storage where user_id = $1)",
+        &[user.id.local()]
+    ).await?;
+
+    let owns_data: bool = owns_data.get(0);
+
+    if owns_data && !purge {
+        return Err(ApiError::from(ApiErrorKind::UserOwnsData));
+    }
+
+    let contents = if purge {
+        fs::StorageContents::retrieve_user(&transaction, user.id.local()).await?
+    } else {
+        Vec::new()
+    };
+
+    if purge {
+        let fs_ids = "\
+            select fs.id \
+            from fs \
+            where fs.storage_id in (select storage.id from storage where storage.user_id = $1)";
+
+        transaction.execute(
+            format!("delete from fs_tags where fs_id in ({fs_ids})").as_str(),
+            &[user.id.local()]
+        ).await?;
+        transaction.execute(
+            format!("delete from fs_checksums where fs_id in ({fs_ids})").as_str(),
+            &[user.id.local()]
+        ).await?;
+        transaction.execute(
+            "\
+            delete from fs \
+            where fs.storage_id in (select storage.id from storage where storage.user_id = $1)",
+            &[user.id.local()]
+        ).await?;
+        transaction.execute(
+            "\
+            delete from storage_tags \
+            where storage_id in (select storage.id from storage where storage.user_id = $1)",
+            &[user.id.local()]
+        ).await?;
+        transaction.execute(
+            "delete from storage where user_id = $1",
+            &[user.id.local()]
+        ).await?;
+    }
+
     let session = state.sec().session_info().cache();
 
     let session_tokens = session::Session::delete_user_sessions(
@@ -387,11 +562,43 @@ async fn delete_id(
 
     futures::pin_mut!(session_tokens);
 
+    let mut removed_tokens = Vec::new();
+
     while let Some(token) = session_tokens.try_next().await? {
+        removed_tokens.push(token);
+    }
+
+    // fs_events are kept for the history of fs items. the database will set
+    // the user_id of any events made by the user to null
+    transaction.execute("delete from auth_totp_hash where user_id = $1", &[user.id.local()]).await?;
+    transaction.execute("delete from auth_totp where user_id = $1", &[user.id.local()]).await?;
+    transaction.execute("delete from auth_password where user_id = $1", &[user.id.local()]).await?;
+    transaction.execute("delete from user_roles where user_id = $1", &[user.id.local()]).await?;
+    transaction.execute("delete from group_users where user_id = $1", &[user.id.local()]).await?;
+    transaction.execute("delete from bots where user_id = $1", &[user.id.local()]).await?;
+    transaction.execute("delete from users where id = $1", &[user.id.local()]).await?;
+
+    transaction.commit().await?;
+
+    for token in removed_tokens {
         session.remove(&token);
     }
 
     rbac.clear_id(user.id.local());
 
+    // the records are gone at this point so a failure is only logged
+    for storage in contents {
+        if let Err(err) = storage.remove().await {
+            tracing::error!("failed to remove storage contents for deleted user. id: {} {err}", user.id.local());
+        }
+    }
+
+    tracing::info!(
+        user_id = %user.id.local(),
+        action = "deleted",
+        purge,
+        "user deleted"
+    );
+
     Ok(StatusCode::NO_CONTENT)
 }
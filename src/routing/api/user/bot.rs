@@ -0,0 +1,340 @@
+use rfs_lib::ids;
+use rfs_api::Validator;
+use rfs_api::users::bots::{Bot, BotToken, CreateBot, ListItem};
+
+use axum::http::{header, StatusCode};
+use axum::extract::{Path, Query, State};
+use axum::response::IntoResponse;
+use deadpool_postgres::GenericClient;
+use futures::TryStreamExt;
+use serde::Deserialize;
+
+use crate::error::{ApiError, ApiResult};
+use crate::error::api::{ApiErrorKind, Detail, Context};
+use crate::state::ArcShared;
+use crate::sec::authn::{bot, initiator};
+use crate::sec::authn::session::token::SessionToken;
+use crate::sec::authz::permission;
+use crate::sql;
+use crate::routing::query::PaginationQuery;
+use crate::user;
+use crate::db;
+
+#[derive(Deserialize)]
+pub struct Params {
+    pub user_uid: ids::UserUid,
+}
+
+#[derive(Deserialize)]
+pub struct BotParams {
+    pub user_uid: ids::UserUid,
+    pub bot_uid: ids::BotUid,
+}
+
+/// retrieves the owner of the bots being managed. users are able to manage
+/// their own bots but will need the ability to manage other users in order
+/// to manage theirs. bots are not allowed to manage other bots
+async fn retrieve_owner(
+    conn: &impl GenericClient,
+    rbac: &permission::Rbac,
+    initiator: &initiator::Initiator,
+    user_uid: &ids::UserUid,
+    ability: permission::Ability,
+) -> ApiResult<user::User> {
+    if let initiator::Mechanism::Bot(_) = &initiator.mechanism {
+        return Err(ApiError::from((
+            ApiErrorKind::NotPermitted,
+            Detail::with_key("bot"),
+            "bots are not allowed to manage bots"
+        )));
+    }
+
+    if initiator.user.id == *user_uid {
+        return Ok(initiator.user.clone());
+    }
+
+    rbac.api_ability(
+        conn,
+        initiator,
+        permission::Scope::User,
+        ability,
+    ).await?;
+
+    user::User::retrieve_uid(conn, user_uid)
+        .await?
+        .kind(ApiErrorKind::UserNotFound)
+}
+
+fn bot_token(
+    state: &ArcShared,
+    token: &SessionToken,
+) -> String {
+    bot::encode_token(state.sec(), token)
+}
+
+fn into_api(owner: &user::User, bot: bot::Bot) -> Bot {
+    Bot {
+        uid: bot.id.into_uid(),
+        user_uid: owner.id.uid().clone(),
+        role_uid: bot.role.into_uid(),
+        name: bot.name,
+        created: bot.created,
+        updated: bot.updated,
+    }
+}
+
+pub async fn retrieve(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(Params { user_uid }): Path<Params>,
+    Query(PaginationQuery { limit, offset, last_id }): Query<PaginationQuery<ids::BotUid>>,
+) -> ApiResult<impl IntoResponse> {
+    let owner = retrieve_owner(
+        &conn,
+        &rbac,
+        &initiator,
+        &user_uid,
+        permission::Ability::Read
+    ).await?;
+
+    let mut pagination = rfs_api::Pagination::from(&limit);
+
+    let result = if let Some(last_id) = last_id {
+        let params: sql::ParamsVec = vec![owner.id.local(), &last_id, &limit];
+
+        conn.query_raw(
+            "\
+            select bots.uid, \
+                   bots.name \
+            from bots \
+            where bots.user_id = $1 and \
+                  bots.id > (\
+                      select bots.id \
+                      from bots \
+                      where bots.uid = $2\
+                  ) \
+            order by bots.id \
+            limit $3",
+            params
+        ).await?
+    } else {
+        pagination.set_offset(offset);
+
+        let offset_num = limit.sql_offset(offset);
+        let params: sql::ParamsVec = vec![owner.id.local(), &limit, &offset_num];
+
+        conn.query_raw(
+            "\
+            select bots.uid, \
+                   bots.name \
+            from bots \
+            where bots.user_id = $1 \
+            order by bots.id \
+            limit $2 \
+            offset $3",
+            params
+        ).await?
+    };
+
+    futures::pin_mut!(result);
+
+    let mut list = Vec::with_capacity(limit as usize);
+
+    while let Some(row) = result.try_next().await? {
+        list.push(ListItem {
+            uid: row.get(0),
+            name: row.get(1),
+        });
+    }
+
+    Ok(rfs_api::Payload::from((pagination, list)))
+}
+
+pub async fn create(
+    State(state): State<ArcShared>,
+    db::Conn(mut conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(Params { user_uid }): Path<Params>,
+    axum::Json(json): axum::Json<CreateBot>,
+) -> ApiResult<impl IntoResponse> {
+    let owner = retrieve_owner(
+        &conn,
+        &rbac,
+        &initiator,
+        &user_uid,
+        permission::Ability::Write
+    ).await?;
+
+    json.validate()?;
+
+    let role = permission::Role::retrieve_uid(&conn, &json.role_uid)
+        .await?
+        .kind(ApiErrorKind::RoleNotFound)?;
+
+    let transaction = conn.transaction().await?;
+
+    let existing = transaction.query_opt(
+        "select id from bots where user_id = $1 and name = $2",
+        &[owner.id.local(), &json.name]
+    ).await?;
+
+    if existing.is_some() {
+        return Err(ApiError::from((
+            ApiErrorKind::AlreadyExists,
+            Detail::with_key("name")
+        )));
+    }
+
+    let (token, hash) = bot::unique_token(&transaction, 10)
+        .await?
+        .context("ran out of token attempts")?;
+
+    let uid = ids::BotUid::gen();
+    let created = chrono::Utc::now();
+
+    let row = transaction.query_one(
+        "\
+        insert into bots (uid, user_id, role_id, name, token_hash, created) \
+        values ($1, $2, $3, $4, $5, $6) \
+        returning id",
+        &[
+            &uid,
+            owner.id.local(),
+            role.id.local(),
+            &json.name,
+            &hash.as_bytes().as_slice(),
+            &created,
+        ]
+    ).await?;
+
+    let token = bot_token(&state, &token);
+
+    transaction.commit().await?;
+
+    bot::clear_cached_tokens(state.sec(), bot.id.local());
+
+    let bot = bot::Bot {
+        id: ids::BotSet::new(row.get(0), uid),
+        user_id: owner.id.local().clone(),
+        role: role.id,
+        name: json.name,
+        created,
+        updated: None,
+    };
+
+    let location = format!("/api/user/{}/bot/{}", owner.id.uid(), bot.id.uid());
+
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, location)],
+        rfs_api::Payload::new(BotToken {
+            bot: into_api(&owner, bot),
+            token,
+        })
+    ))
+}
+
+pub async fn retrieve_id(
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(BotParams { user_uid, bot_uid }): Path<BotParams>,
+) -> ApiResult<impl IntoResponse> {
+    let owner = retrieve_owner(
+        &conn,
+        &rbac,
+        &initiator,
+        &user_uid,
+        permission::Ability::Read
+    ).await?;
+
+    let bot = bot::Bot::retrieve_uid(&conn, owner.id.local(), &bot_uid)
+        .await?
+        .kind(ApiErrorKind::BotNotFound)?;
+
+    Ok(rfs_api::Payload::new(into_api(&owner, bot)))
+}
+
+/// creates a new token for the bot. the previous token will no longer be
+/// valid once this completes
+pub async fn rotate_id(
+    State(state): State<ArcShared>,
+    db::Conn(mut conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(BotParams { user_uid, bot_uid }): Path<BotParams>,
+) -> ApiResult<impl IntoResponse> {
+    let owner = retrieve_owner(
+        &conn,
+        &rbac,
+        &initiator,
+        &user_uid,
+        permission::Ability::Write
+    ).await?;
+
+    let mut bot = bot::Bot::retrieve_uid(&conn, owner.id.local(), &bot_uid)
+        .await?
+        .kind(ApiErrorKind::BotNotFound)?;
+
+    let transaction = conn.transaction().await?;
+
+    let (token, hash) = bot::unique_token(&transaction, 10)
+        .await?
+        .context("ran out of token attempts")?;
+
+    let updated = chrono::Utc::now();
+
+    transaction.execute(
+        "update bots set token_hash = $2, updated = $3 where id = $1",
+        &[bot.id.local(), &hash.as_bytes().as_slice(), &updated]
+    ).await?;
+
+    let token = bot_token(&state, &token);
+
+    transaction.commit().await?;
+
+    bot::clear_cached_tokens(state.sec(), bot.id.local());
+
+    bot.updated = Some(updated);
+
+    Ok(rfs_api::Payload::new(BotToken {
+        bot: into_api(&owner, bot),
+        token,
+    }))
+}
+
+pub async fn delete_id(
+    State(state): State<ArcShared>,
+    db::Conn(mut conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    Path(BotParams { user_uid, bot_uid }): Path<BotParams>,
+) -> ApiResult<impl IntoResponse> {
+    let owner = retrieve_owner(
+        &conn,
+        &rbac,
+        &initiator,
+        &user_uid,
+        permission::Ability::Write
+    ).await?;
+
+    let bot = bot::Bot::retrieve_uid(&conn, owner.id.local(), &bot_uid)
+        .await?
+        .kind(ApiErrorKind::BotNotFound)?;
+
+    let transaction = conn.transaction().await?;
+
+    transaction.execute(
+        "delete from bots where id = $1",
+        &[bot.id.local()]
+    ).await?;
+
+    transaction.commit().await?;
+
+    rbac.clear_bot(bot.id.local());
+    bot::clear_cached_tokens(state.sec(), bot.id.local());
+
+    Ok(rfs_api::Payload::new(into_api(&owner, bot)))
+}
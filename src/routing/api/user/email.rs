@@ -0,0 +1,169 @@
+use rfs_lib::ids;
+use rfs_api::Validator;
+use rfs_api::users::email::{SetEmail, VerifyEmail};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use deadpool_postgres::GenericClient;
+
+use crate::error::{ApiResult, ApiError};
+use crate::error::api::{Detail, Context, ApiErrorKind};
+use crate::sec::authn::initiator::{Initiator, Mechanism};
+use crate::sql;
+use crate::state::ArcShared;
+use crate::state::mailer::Mailer;
+use crate::user;
+use crate::user::email::Verification;
+use crate::db;
+
+/// updates the email of the user and creates a new verification for it. the
+/// email will be unverified until the token created is given back. the token
+/// is delivered before returning so a failed delivery will leave the
+/// transaction uncommitted
+pub(super) async fn start_verification(
+    conn: &impl GenericClient,
+    mailer: &Mailer,
+    user_id: &ids::UserId,
+    email: String,
+) -> ApiResult<()> {
+    if !mailer.is_configured() {
+        return Err(ApiError::from((
+            ApiErrorKind::EmailNotConfigured,
+            Detail::with_key("email"),
+            "the server is not able to deliver email verification tokens"
+        )));
+    }
+
+    // the address is given to the delivery command as an argument
+    if email.starts_with('-') {
+        return Err(ApiError::from((
+            ApiErrorKind::ValidationFailed,
+            Detail::with_key("email"),
+            "email addresses starting with \"-\" are not supported"
+        )));
+    }
+
+    if let Some(found_id) = user::check_email(conn, &email).await? {
+        if found_id != *user_id {
+            return Err(ApiError::from((
+                ApiErrorKind::AlreadyExists,
+                Detail::with_key("email")
+            )));
+        }
+    }
+
+    let (token, hash) = user::email::gen_token()?;
+
+    if let Err(err) = conn.execute(
+        "update users set email = $2, email_verified = false where id = $1",
+        &[user_id, &email]
+    ).await {
+        if sql::unique_constraint_error(&err) == Some("users_email_key") {
+            return Err(ApiError::from((
+                ApiErrorKind::AlreadyExists,
+                Detail::with_key("email")
+            )));
+        }
+
+        return Err(err.into());
+    }
+
+    let verification = Verification::create(conn, user_id, email, &hash).await?;
+
+    mailer.send_verification(&verification.email, &token)
+        .await
+        .context("failed to deliver email verification token")?;
+
+    tracing::debug!(
+        user_id = %verification.user_id,
+        expires = %verification.expires,
+        "email verification token delivered"
+    );
+
+    Ok(())
+}
+
+/// sets the email of the initiator. the email will be unverified until the
+/// token that was created is given back
+pub async fn update(
+    State(state): State<ArcShared>,
+    db::Conn(mut conn): db::Conn,
+    initiator: Initiator,
+    axum::Json(json): axum::Json<SetEmail>,
+) -> ApiResult<impl IntoResponse> {
+    let Mechanism::Session(_) = &initiator.mechanism else {
+        return Err(ApiError::from((
+            ApiErrorKind::NotPermitted,
+            Detail::with_key("bot"),
+            "bots are not allowed to update the email of a user"
+        )));
+    };
+
+    json.validate()?;
+
+    let transaction = conn.transaction().await?;
+
+    start_verification(&transaction, state.mailer(), initiator.user.id.local(), json.email).await?;
+
+    transaction.commit().await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// marks the email of the initiator as verified if the token given matches
+/// the pending verification
+pub async fn verify(
+    db::Conn(mut conn): db::Conn,
+    initiator: Initiator,
+    axum::Json(json): axum::Json<VerifyEmail>,
+) -> ApiResult<impl IntoResponse> {
+    let Mechanism::Session(_) = &initiator.mechanism else {
+        return Err(ApiError::from((
+            ApiErrorKind::NotPermitted,
+            Detail::with_key("bot"),
+            "bots are not allowed to verify the email of a user"
+        )));
+    };
+
+    json.validate()?;
+
+    let Some(hash) = user::email::decode_token(&json.token) else {
+        return Err(ApiError::from(ApiErrorKind::InvalidToken));
+    };
+
+    let transaction = conn.transaction().await?;
+
+    let Some(verification) = Verification::retrieve_hash(
+        &transaction,
+        initiator.user.id.local(),
+        &hash
+    ).await? else {
+        return Err(ApiError::from(ApiErrorKind::InvalidToken));
+    };
+
+    if verification.is_expired() {
+        verification.delete(&transaction).await?;
+
+        transaction.commit().await?;
+
+        return Err(ApiError::from(ApiErrorKind::InvalidToken));
+    }
+
+    // the email could have been changed by an admin since the token was
+    // created
+    let count = transaction.execute(
+        "update users set email_verified = true where id = $1 and email = $2",
+        &[&verification.user_id, &verification.email]
+    ).await?;
+
+    verification.delete(&transaction).await?;
+
+    transaction.commit().await?;
+
+    if count == 0 {
+        return Err(ApiError::from(ApiErrorKind::InvalidToken));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
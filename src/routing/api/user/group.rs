@@ -1,6 +1,6 @@
 use rfs_lib::ids;
 
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::extract::{Path, Query, State};
 use axum::response::IntoResponse;
 use futures::TryStreamExt;
@@ -143,8 +143,11 @@ pub async fn create(
 
     transaction.commit().await?;
 
+    let location = format!("/api/user/group/{}", rtn.uid);
+
     Ok((
         StatusCode::CREATED,
+        [(header::LOCATION, location)],
         rfs_api::Payload::new(rtn)
     ))
 }
@@ -320,7 +323,7 @@ pub async fn retrieve_users(
             join group_users on \
                 users.id = group_users.user_id \
             join groups on \
-                group_user.group_id = groups.id \
+                group_users.group_id = groups.id \
             where groups.uid = $1 and \
                   users.id > (\
                       select users.id \
@@ -347,10 +350,23 @@ pub async fn retrieve_users(
 
     let group_fut = user::group::Group::retrieve_uid(&conn, &group_uid);
     let users_fut = conn.query_raw(query, params);
-
-    let result = match tokio::try_join!(group_fut, users_fut) {
-        Ok((Some(_), rows)) => rows,
-        Ok((None, _)) => {
+    let total_fut = conn.query_one(
+        "\
+        select count(*) \
+        from group_users \
+        join groups on \
+            group_users.group_id = groups.id \
+        where groups.uid = $1",
+        &[&group_uid]
+    );
+
+    let result = match tokio::try_join!(group_fut, users_fut, total_fut) {
+        Ok((Some(_), rows, total)) => {
+            pagination.set_total(total.get::<usize, i64>(0) as u64);
+
+            rows
+        },
+        Ok((None, _, _)) => {
             return Err(ApiError::from(ApiErrorKind::GroupNotFound));
         },
         Err(err) => {
@@ -431,7 +447,7 @@ pub async fn delete_users(
     initiator: initiator::Initiator,
     Path(Params { group_uid }): Path<Params>,
     axum::Json(json): axum::Json<rfs_api::users::groups::DropUsers>
-) -> ApiResult<impl IntoResponse> {
+) -> ApiResult<rfs_api::Payload<Vec<ids::UserUid>>> {
     let mut conn = state.pool().get().await?;
 
     state.sec().rbac().api_ability(
@@ -451,28 +467,35 @@ pub async fn delete_users(
 
     let transaction = conn.transaction().await?;
 
+    // users that are not members of the group are ignored and will not be
+    // in the returned list
     let params: sql::ParamsArray<2> = [group.id.local(), &json.uids];
     let result = transaction.query_raw(
         "\
         delete from group_users \
         using users \
         where group_users.user_id = users.id and \
-              users.uid <> all($2) and \
-              group_id = $1",
+              users.uid = any($2) and \
+              group_users.group_id = $1 \
+        returning users.id, \
+                  users.uid",
         params
     ).await?;
 
-    transaction.commit().await?;
-
     futures::pin_mut!(result);
 
     let rbac = state.sec().rbac();
+    let mut dropped = Vec::with_capacity(json.uids.len());
 
     while let Some(row) = result.try_next().await? {
         let user_id = row.get(0);
 
         rbac.clear_id(&user_id);
+
+        dropped.push(row.get(1));
     }
 
-    Ok(StatusCode::NO_CONTENT)
+    transaction.commit().await?;
+
+    Ok(rfs_api::Payload::new(dropped))
 }
@@ -0,0 +1,70 @@
+use rfs_api::Validator;
+use rfs_api::users::UpdateMe;
+
+use axum::extract::State;
+
+use crate::error::{ApiResult, ApiError};
+use crate::error::api::{Detail, Context, ApiErrorKind};
+use crate::sec::authn::initiator::{Initiator, Mechanism};
+use crate::state::ArcShared;
+use crate::user;
+use crate::db;
+
+/// retrieves the record of the initiator. no scope is required as a user is
+/// always able to view their own record
+pub async fn retrieve(
+    initiator: Initiator,
+) -> ApiResult<rfs_api::Payload<rfs_api::users::User>> {
+    Ok(rfs_api::Payload::new(initiator.user.into()))
+}
+
+/// updates the non privileged fields of the initiator. no scope is required
+/// as a user is always able to update these fields on their own record.
+/// anything else still has to go through the user scope
+pub async fn update(
+    State(state): State<ArcShared>,
+    db::Conn(mut conn): db::Conn,
+    initiator: Initiator,
+    axum::Json(json): axum::Json<UpdateMe>,
+) -> ApiResult<rfs_api::Payload<rfs_api::users::User>> {
+    json.assert_ok()?;
+
+    if json.email.is_some() {
+        let Mechanism::Session(_) = &initiator.mechanism else {
+            return Err(ApiError::from((
+                ApiErrorKind::NotPermitted,
+                Detail::with_key("bot"),
+                "bots are not allowed to update the email of a user"
+            )));
+        };
+    }
+
+    let user_id = initiator.user.id.local();
+    let transaction = conn.transaction().await?;
+
+    if let Some(display_name) = &json.display_name {
+        transaction.execute(
+            "update users set display_name = $2 where id = $1",
+            &[user_id, display_name]
+        ).await?;
+    }
+
+    if let Some(preferences) = &json.preferences {
+        transaction.execute(
+            "update users set preferences = $2 where id = $1",
+            &[user_id, preferences]
+        ).await?;
+    }
+
+    if let Some(email) = json.email {
+        super::email::start_verification(&transaction, state.mailer(), user_id, email).await?;
+    }
+
+    let user = user::User::retrieve(&transaction, user_id)
+        .await?
+        .context("failed to retrieve initiator user record")?;
+
+    transaction.commit().await?;
+
+    Ok(rfs_api::Payload::new(user.into()))
+}
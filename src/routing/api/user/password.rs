@@ -17,6 +17,14 @@ pub async fn update(
     initiator: Initiator,
     axum::Json(json): axum::Json<CreatePassword>,
 ) -> ApiResult<impl IntoResponse> {
+    let Mechanism::Session(session) = &initiator.mechanism else {
+        return Err(ApiError::from((
+            ApiErrorKind::NotPermitted,
+            Detail::with_key("bot"),
+            "bots are not allowed to update the password of a user"
+        )));
+    };
+
     let mut conn = state.pool().get().await?;
 
     json.validate()?;
@@ -29,27 +37,30 @@ pub async fn update(
 
     if !password.verify(&json.current, state.sec().peppers())? {
         return Err(ApiError::from((
-            ApiErrorKind::InvalidData,
-            Detail::Keys(vec![String::from("password")])
+            ApiErrorKind::InvalidPassword,
+            Detail::with_key("current")
         )));
     }
 
-    password.update(&transaction, json.updated, state.sec().peppers()).await?;
+    password.update(
+        &transaction,
+        json.updated,
+        state.sec().password_params(),
+        state.sec().peppers()
+    ).await?;
 
-    match initiator.mechanism {
-        Mechanism::Session(session) => {
-            let cache = state.sec().session_info().cache();
-            let session_tokens = session::Session::delete_user_sessions(
-                &transaction,
-                initiator.user.id.local(),
-                Some(&session.token)
-            ).await?;
+    {
+        let cache = state.sec().session_info().cache();
+        let session_tokens = session::Session::delete_user_sessions(
+            &transaction,
+            initiator.user.id.local(),
+            Some(&session.token)
+        ).await?;
 
-            futures::pin_mut!(session_tokens);
+        futures::pin_mut!(session_tokens);
 
-            while let Some(token) = session_tokens.try_next().await? {
-                cache.invalidate(&token);
-            }
+        while let Some(token) = session_tokens.try_next().await? {
+            cache.invalidate(&token);
         }
     }
 
@@ -7,7 +7,7 @@ use futures::TryStreamExt;
 use serde::Deserialize;
 
 use crate::error::{ApiError, ApiResult};
-use crate::error::api::{Context, ApiErrorKind};
+use crate::error::api::{Detail, Context, ApiErrorKind};
 use crate::state::ArcShared;
 use crate::sec::authn::initiator::Initiator;
 use crate::sec::authn::totp;
@@ -31,7 +31,9 @@ pub async fn retrieve(
         algo: totp.algo.get().to_string(),
         secret: totp.secret.into(),
         digits: totp.digits.into(),
-        step: totp.step.into()
+        step: totp.step.into(),
+        window_before: state.sec().totp_window().before,
+        window_after: state.sec().totp_window().after,
     }))
 }
 
@@ -81,7 +83,9 @@ pub async fn create(
             algo: algo.to_string(),
             secret,
             digits,
-            step
+            step,
+            window_before: state.sec().totp_window().before,
+            window_after: state.sec().totp_window().after,
         })
     ))
 }
@@ -129,7 +133,9 @@ pub async fn update(
         algo: totp.algo.get().to_string(),
         secret: totp.secret.into(),
         digits: totp.digits.into(),
-        step: totp.step.into()
+        step: totp.step.into(),
+        window_before: state.sec().totp_window().before,
+        window_after: state.sec().totp_window().after,
     }))
 }
 
@@ -186,7 +192,11 @@ pub async fn retrieve_recovery(
         });
     }
 
-    Ok(rfs_api::Payload::new(rtn))
+    Ok(rfs_api::Payload::new(rfs_api::users::totp::TotpRecoveryList {
+        count: rtn.len(),
+        max: state.sec().totp_max_recovery(),
+        keys: rtn,
+    }))
 }
 
 pub async fn create_recovery(
@@ -206,6 +216,22 @@ pub async fn create_recovery(
 
     let transaction = conn.transaction().await?;
 
+    // locking the user row keeps concurrent requests from going over the
+    // limit between the count and the insert
+    transaction.execute(
+        "select id from users where id = $1 for update",
+        &[initiator.user.id.local()]
+    ).await?;
+
+    let count: i64 = transaction.query_one(
+        "select count(*) from auth_totp_hash where user_id = $1",
+        &[initiator.user.id.local()]
+    ).await?.get(0);
+
+    if count >= i64::from(state.sec().totp_max_recovery()) {
+        return Err(ApiError::from(ApiErrorKind::TotpRecoveryLimit));
+    }
+
     transaction.execute(
         "\
         insert into auth_totp_hash (user_id, key, hash, used) values \
@@ -267,8 +293,8 @@ pub async fn update_recovery_key(
         hash.set_key(new_key);
     }
 
-    if json.regen {
-        hash.regen_hash()?;
+    if json.regen && !hash.regen_hash()? {
+        return Err(used_key_regen());
     }
 
     let transaction = conn.transaction().await?;
@@ -285,6 +311,48 @@ pub async fn update_recovery_key(
     }))
 }
 
+/// the error for attempting to regenerate a recovery key that was used
+fn used_key_regen() -> ApiError {
+    ApiError::from((
+        ApiErrorKind::NotPermitted,
+        Detail::with_key("key_id"),
+        "used recovery keys cannot be regenerated"
+    ))
+}
+
+pub async fn regen_recovery_key(
+    State(state): State<ArcShared>,
+    initiator: Initiator,
+    Path(RecoveryKeyPath { key_id }): Path<RecoveryKeyPath>,
+) -> ApiResult<impl IntoResponse> {
+    let mut conn = state.pool().get().await?;
+    let transaction = conn.transaction().await?;
+
+    let mut hash = totp::recovery::Hash::retrieve_key_for_update(
+        &transaction,
+        initiator.user.id.local(),
+        &key_id
+    )
+        .await?
+        .kind(ApiErrorKind::TotpRecoveryNotFound)?;
+
+    // a used key has served its purpose and should be deleted instead
+    if !hash.regen_hash()? {
+        return Err(used_key_regen());
+    }
+
+    hash.update(&transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(rfs_api::Payload::new(rfs_api::users::totp::TotpRecovery {
+        user_uid: initiator.user.id.into_uid(),
+        key: hash.key.into(),
+        hash: hash.hash.into(),
+        used: hash.used.into(),
+    }))
+}
+
 pub async fn delete_recovery_key(
     State(state): State<ArcShared>,
     initiator: Initiator,
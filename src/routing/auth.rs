@@ -1,5 +1,5 @@
 use axum::Router;
-use axum::routing::{post, delete};
+use axum::routing::{get, post, delete};
 
 use crate::state::ArcShared;
 
@@ -10,5 +10,8 @@ pub fn routes() -> Router<ArcShared> {
         .route("/session/request", post(session::request))
         .route("/session/submit", post(session::submit))
         .route("/session/verify", post(session::verify))
+        .route("/session", get(session::retrieve)
+            .delete(session::delete_all))
         .route("/session/drop", delete(session::drop))
+        .route("/session/:session_id", delete(session::delete_id))
 }
@@ -3,11 +3,13 @@ use rfs_api::auth::session::SubmittedVerify;
 
 use axum::debug_handler;
 use axum::http::{StatusCode, HeaderMap};
-use axum::extract::State;
+use axum::extract::{State, Path};
 use axum::response::IntoResponse;
+use futures::TryStreamExt;
+use serde::Deserialize;
 
 use crate::error::{ApiError, ApiResult};
-use crate::error::api::{Context, ApiErrorKind};
+use crate::error::api::{Detail, Context, ApiErrorKind};
 use crate::state::ArcShared;
 use crate::user;
 use crate::sec::authn::{totp, password, Authenticate, Verify};
@@ -36,11 +38,14 @@ pub async fn request(
         }
     }
 
-    let user = user::User::query_with_username(&mut conn, &json.username)
+    let user = user::User::retrieve_by_username(&conn, &json.username)
         .await?
         .kind(ApiErrorKind::UserNotFound)?;
 
     let mut builder = session::Session::builder(user.id().clone());
+
+    builder.duration(*state.sec().session_info().pending_lifetime());
+
     let transaction = conn.transaction().await?;
 
     let auth_method = Authenticate::retrieve_primary(&transaction, user.id())
@@ -109,6 +114,14 @@ pub async fn submit(
         }
     };
 
+    // the session can only be created for users that exist so checking the
+    // lockout here will not reveal anything about a given username
+    let login_attempts = state.login_attempts();
+
+    if login_attempts.check(&session.user_id).is_err() {
+        return Err(ApiError::from(ApiErrorKind::TooManyAttempts));
+    }
+
     match json {
         SubmittedAuth::Password(given) => match session.auth_method {
             AuthMethod::Password => {
@@ -117,9 +130,19 @@ pub async fn submit(
                     .context("session required user password but user password was not found")?;
 
                 if !user_password.verify(&given, state.sec().peppers())? {
+                    if let Some(duration) = login_attempts.failed(&session.user_id) {
+                        tracing::info!(
+                            "user locked out after too many failed login attempts. user: {} duration: {}s",
+                            session.user_id,
+                            duration.as_secs()
+                        );
+                    }
+
                     return Err(ApiError::from(ApiErrorKind::InvalidPassword));
                 }
 
+                login_attempts.reset(&session.user_id);
+
                 session.authenticated = true;
             },
         }
@@ -128,9 +151,13 @@ pub async fn submit(
     match session.verify_method {
         VerifyMethod::None => {
             session.verified = true;
+            session.set_verified_expires(state.sec().session_info(), chrono::Utc::now())?;
 
             session.update(&transaction).await?;
 
+            let session_cookie = session::create_session_cookie(state.sec(), &session)
+                .context("session keys rwlock poisoned")?;
+
             let user = user::User::retrieve(&transaction, &session.user_id)
                 .await?
                 .kind(ApiErrorKind::UserNotFound)?;
@@ -142,7 +169,7 @@ pub async fn submit(
 
             transaction.commit().await?;
 
-            Ok(StatusCode::NO_CONTENT.into_response())
+            Ok((StatusCode::NO_CONTENT, session_cookie).into_response())
         },
         VerifyMethod::Totp => {
             session.update(&transaction).await?;
@@ -206,7 +233,7 @@ pub async fn verify(
                     .await?
                     .context("session required totp verify but user totp was not found")?;
 
-                let result = totp.verify(&code)?;
+                let result = totp.verify(&code, state.sec().totp_window())?;
 
                 match result {
                     VerifyResult::Valid => {},
@@ -232,9 +259,11 @@ pub async fn verify(
                     return Err(ApiError::api(ApiErrorKind::InvalidTotpHash));
                 }
 
-                totp_hash.set_used();
-
-                totp_hash.update(&transaction).await?;
+                // another request could have used the hash after it was
+                // retrieved so the update only succeeds if it is still unused
+                if !totp_hash.set_used() || !totp_hash.consume(&transaction).await? {
+                    return Err(ApiError::api(ApiErrorKind::InvalidTotpHash));
+                }
             },
             _ => {
                 return Err(ApiError::from(ApiErrorKind::InvalidAuthMethod));
@@ -243,9 +272,13 @@ pub async fn verify(
     }
 
     session.verified = true;
+    session.set_verified_expires(state.sec().session_info(), chrono::Utc::now())?;
 
     session.update(&transaction).await?;
 
+    let session_cookie = session::create_session_cookie(state.sec(), &session)
+        .context("session keys rwlock poisoned")?;
+
     let user = user::User::retrieve(&transaction, &session.user_id)
         .await?
         .kind(ApiErrorKind::UserNotFound)?;
@@ -257,7 +290,7 @@ pub async fn verify(
 
     transaction.commit().await?;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok((StatusCode::NO_CONTENT, session_cookie))
 }
 
 pub async fn drop(
@@ -269,6 +302,13 @@ pub async fn drop(
     let session = match initiator::lookup_header_map(state.sec(), &conn, &headers).await {
         Ok(initiator) => match initiator.mechanism {
             Mechanism::Session(session) => session,
+            Mechanism::Bot(_) => {
+                return Err(ApiError::from((
+                    ApiErrorKind::NotPermitted,
+                    Detail::with_key("bot"),
+                    "bots do not have a session to drop"
+                )));
+            }
         }
         Err(err) => match err {
             LookupError::SessionNotFound => {
@@ -281,7 +321,10 @@ pub async fn drop(
             LookupError::SessionUnauthenticated(session) |
             LookupError::SessionUnverified(session) => session,
             LookupError::UserNotFound(mechanism) => match mechanism {
-                Mechanism::Session(session) => session
+                Mechanism::Session(session) => session,
+                Mechanism::Bot(_) => {
+                    return Err(ApiError::from(ApiErrorKind::UserNotFound));
+                }
             }
             err => {
                 return Err(err.into());
@@ -305,3 +348,116 @@ pub async fn drop(
         session::expire_session_cookie(state.sec())
     ))
 }
+
+/// retrieves the session of the initiator. bots do not have sessions
+fn initiator_session(initiator: &initiator::Initiator) -> ApiResult<&session::Session> {
+    let Mechanism::Session(session) = &initiator.mechanism else {
+        return Err(ApiError::from((
+            ApiErrorKind::NotPermitted,
+            Detail::with_key("bot"),
+            "bots do not have sessions"
+        )));
+    };
+
+    Ok(session)
+}
+
+/// lists the active sessions of the initiator
+pub async fn retrieve(
+    State(state): State<ArcShared>,
+    initiator: initiator::Initiator,
+) -> ApiResult<impl IntoResponse> {
+    let conn = state.pool().get().await?;
+    let current = initiator_session(&initiator)?;
+
+    let sessions = session::Session::retrieve_user_active(&conn, initiator.user.id.local()).await?;
+
+    let list: Vec<rfs_api::auth::session::SessionInfo> = sessions.into_iter()
+        .map(|found| rfs_api::auth::session::SessionInfo {
+            id: found.token.public_id(),
+            issued_on: found.issued_on,
+            expires: found.expires,
+            current: found.token == current.token,
+        })
+        .collect();
+
+    Ok(rfs_api::Payload::new(list))
+}
+
+#[derive(Deserialize)]
+pub struct SessionParams {
+    session_id: String,
+}
+
+/// revokes a single session of the initiator. the current session cannot be
+/// revoked here and must be dropped instead
+pub async fn delete_id(
+    State(state): State<ArcShared>,
+    initiator: initiator::Initiator,
+    Path(SessionParams { session_id }): Path<SessionParams>,
+) -> ApiResult<impl IntoResponse> {
+    let mut conn = state.pool().get().await?;
+    let current = initiator_session(&initiator)?;
+
+    let sessions = session::Session::retrieve_user_active(&conn, initiator.user.id.local()).await?;
+
+    let found = sessions.into_iter()
+        .find(|found| found.token.public_id() == session_id)
+        .kind(ApiErrorKind::SessionNotFound)?;
+
+    if found.token == current.token {
+        return Err(ApiError::from((
+            ApiErrorKind::NoOp,
+            Detail::with_key("session_id"),
+            "the current session cannot be revoked. drop the session instead"
+        )));
+    }
+
+    let transaction = conn.transaction().await?;
+
+    found.delete(&transaction).await?;
+
+    transaction.commit().await?;
+
+    state.sec()
+        .session_info()
+        .cache()
+        .invalidate(&found.token);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// revokes all sessions of the initiator except for the current one
+pub async fn delete_all(
+    State(state): State<ArcShared>,
+    initiator: initiator::Initiator,
+) -> ApiResult<impl IntoResponse> {
+    let mut conn = state.pool().get().await?;
+    let current = initiator_session(&initiator)?;
+
+    let transaction = conn.transaction().await?;
+
+    let session_tokens = session::Session::delete_user_sessions(
+        &transaction,
+        initiator.user.id.local(),
+        Some(&current.token),
+    ).await?;
+
+    futures::pin_mut!(session_tokens);
+
+    let mut removed = Vec::new();
+
+    while let Some(token) = session_tokens.try_next().await? {
+        removed.push(token);
+    }
+
+    transaction.commit().await?;
+
+    let cache = state.sec().session_info().cache();
+
+    for token in removed {
+        cache.invalidate(&token);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,85 @@
+use axum::http::{header, HeaderMap, HeaderName};
+use chrono::{DateTime, Utc};
+
+/// checks if the given etag is found in the list of etags provided by the
+/// specified header. a wildcard will match any etag. weak etags are compared
+/// by their value only
+pub fn etag_matches(headers: &HeaderMap, name: HeaderName, etag: &str) -> bool {
+    find_etag(headers, name, etag, true)
+}
+
+/// checks if the given etag is found in the list of etags provided by the
+/// specified header. a wildcard will match any etag. weak etags will never
+/// match
+pub fn etag_matches_strong(headers: &HeaderMap, name: HeaderName, etag: &str) -> bool {
+    find_etag(headers, name, etag, false)
+}
+
+/// checks if the specified header contains the wildcard value. used when a
+/// resource should only be created if nothing currently exists
+pub fn is_wildcard(headers: &HeaderMap, name: HeaderName) -> bool {
+    for value in headers.get_all(name) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+
+        if value.split(',').any(|tag| tag.trim() == "*") {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn find_etag(headers: &HeaderMap, name: HeaderName, etag: &str, weak: bool) -> bool {
+    for value in headers.get_all(name) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+
+        for tag in value.split(',') {
+            let tag = tag.trim();
+
+            if tag == "*" {
+                return true;
+            }
+
+            let tag = match tag.strip_prefix("W/") {
+                Some(stripped) if weak => stripped,
+                Some(_) => continue,
+                None => tag,
+            };
+
+            if tag == etag {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// checks if the given timestamp is after the date provided by the
+/// if-modified-since header. if the header is missing or invalid then it is
+/// assumed to be modified
+pub fn modified_since(headers: &HeaderMap, last_modified: &DateTime<Utc>) -> bool {
+    let Some(value) = headers.get(header::IF_MODIFIED_SINCE) else {
+        return true;
+    };
+
+    let Ok(value) = value.to_str() else {
+        return true;
+    };
+
+    let Ok(since) = DateTime::parse_from_rfc2822(value) else {
+        return true;
+    };
+
+    // http dates only have second precision
+    last_modified.timestamp() > since.timestamp()
+}
+
+/// formats the given timestamp for use in http headers
+pub fn http_date(value: &DateTime<Utc>) -> String {
+    value.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
@@ -5,7 +5,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::pin::Pin;
 use std::future::Future;
 
-use axum::http::{Request, Response, Extensions};
+use axum::http::{HeaderName, HeaderValue, Request, Response, Extensions};
 use axum::body::Body;
 use pin_project::pin_project;
 use tokio::time::Sleep;
@@ -15,6 +15,11 @@ use tracing::Span;
 
 use crate::error;
 
+pub mod compression;
+pub mod metrics;
+pub mod rate_limit;
+pub mod session;
+
 pub fn make_span_with(request: &Request<Body>) -> Span {
     let req_id = RequestId::from_request(request).expect("missing request id");
 
@@ -51,6 +56,18 @@ pub fn on_failure(error: ServerErrorsFailureClass, latency: Duration, _span: &Sp
 
 type Counter = Arc<AtomicU64>;
 
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: u64;
+}
+
+/// retrieves the id of the request currently being processed. will only be
+/// available to code that is running in the same task as the request
+pub fn current_request_id() -> Option<u64> {
+    CURRENT_REQUEST_ID.try_with(|id| *id).ok()
+}
+
 #[derive(Debug, Clone)]
 pub struct RequestId {
     id: u64,
@@ -70,6 +87,38 @@ impl RequestId {
     }
 }
 
+#[pin_project]
+pub struct RIDFuture<F> {
+    #[pin]
+    response: F,
+    id: u64,
+}
+
+impl<F, B, Error> Future for RIDFuture<F>
+where
+    F: Future<Output = Result<Response<B>, Error>>,
+{
+    type Output = Result<Response<B>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let id = *this.id;
+        let response = this.response;
+
+        // the id is made available while the inner future is polled so that
+        // errors are able to include it in their responses
+        let mut response = match CURRENT_REQUEST_ID.sync_scope(id, || response.poll(cx)) {
+            Poll::Ready(Ok(response)) => response,
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        response.headers_mut().insert(REQUEST_ID_HEADER, HeaderValue::from(id));
+
+        Poll::Ready(Ok(response))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RIDService<S> {
     inner: S,
@@ -85,13 +134,13 @@ impl<S> RIDService<S> {
     }
 }
 
-impl<S, B> Service<Request<B>> for RIDService<S>
+impl<S, B, ResBody> Service<Request<B>> for RIDService<S>
 where
-    S: Service<Request<B>>
+    S: Service<Request<B>, Response = Response<ResBody>>
 {
     type Response = S::Response;
     type Error = S::Error;
-    type Future = S::Future;
+    type Future = RIDFuture<S::Future>;
 
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         self.inner.poll_ready(cx).map_err(Into::into)
@@ -105,7 +154,10 @@ where
             extensions.insert(RequestId { id });
         }
 
-        self.inner.call(request)
+        RIDFuture {
+            response: self.inner.call(request),
+            id,
+        }
     }
 }
 
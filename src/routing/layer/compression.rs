@@ -0,0 +1,55 @@
+use axum::body::HttpBody;
+use axum::http::Response;
+use tower_http::compression::predicate::{Predicate, SizeAbove, NotForContentType};
+
+use crate::state::Compression;
+
+/// content types that are already compressed or are streamed and would not
+/// benefit from being compressed again
+const SKIP: [NotForContentType; 11] = [
+    NotForContentType::GRPC,
+    NotForContentType::IMAGES,
+    NotForContentType::SSE,
+    NotForContentType::const_new("audio/"),
+    NotForContentType::const_new("video/"),
+    NotForContentType::const_new("application/zip"),
+    NotForContentType::const_new("application/gzip"),
+    NotForContentType::const_new("application/zstd"),
+    NotForContentType::const_new("application/x-7z-compressed"),
+    NotForContentType::const_new("application/x-bzip2"),
+    NotForContentType::const_new("application/x-xz"),
+];
+
+/// decides if a response should be compressed based on the server config,
+/// size of the response, and its content type
+#[derive(Debug, Clone)]
+pub struct Compress {
+    enabled: bool,
+    min_size: u16,
+}
+
+impl Compress {
+    pub fn new(compression: &Compression) -> Self {
+        Compress {
+            enabled: compression.enabled,
+            min_size: compression.min_size,
+        }
+    }
+}
+
+impl Predicate for Compress {
+    fn should_compress<B>(&self, response: &Response<B>) -> bool
+    where
+        B: HttpBody
+    {
+        if !self.enabled {
+            return false;
+        }
+
+        if !SizeAbove::new(self.min_size).should_compress(response) {
+            return false;
+        }
+
+        SKIP.iter().all(|skip| skip.should_compress(response))
+    }
+}
@@ -0,0 +1,106 @@
+use std::task::{Context, Poll};
+use std::sync::Arc;
+use std::pin::Pin;
+use std::future::Future;
+use std::time::Instant;
+
+use axum::extract::MatchedPath;
+use axum::http::{Request, Response};
+use pin_project::pin_project;
+use tower::{Layer, Service};
+
+use crate::state::metrics::Metrics;
+
+#[pin_project]
+pub struct MetricsFuture<F> {
+    #[pin]
+    response: F,
+    metrics: Arc<Metrics>,
+    route: String,
+    start: Instant,
+}
+
+impl<F, B, Error> Future for MetricsFuture<F>
+where
+    F: Future<Output = Result<Response<B>, Error>>,
+{
+    type Output = Result<Response<B>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let result = match this.response.poll(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        let status = match &result {
+            Ok(res) => res.status().as_u16(),
+            Err(_) => 500,
+        };
+
+        this.metrics.record_request(this.route, status, this.start.elapsed());
+
+        Poll::Ready(result)
+    }
+}
+
+/// records the status and duration of requests using the route that was
+/// matched by the router
+#[derive(Debug, Clone)]
+pub struct MetricsService<S> {
+    inner: S,
+    metrics: Arc<Metrics>,
+}
+
+impl<S> MetricsService<S> {
+    pub fn new(inner: S, metrics: Arc<Metrics>) -> Self {
+        MetricsService { inner, metrics }
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for MetricsService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = MetricsFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let route = request.extensions()
+            .get::<MatchedPath>()
+            .map(|matched| matched.as_str().to_owned())
+            .unwrap_or_else(|| String::from("fallback"));
+
+        MetricsFuture {
+            response: self.inner.call(request),
+            metrics: self.metrics.clone(),
+            route,
+            start: Instant::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsLayer {
+    metrics: Arc<Metrics>,
+}
+
+impl MetricsLayer {
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        MetricsLayer { metrics }
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        MetricsService::new(service, self.metrics.clone())
+    }
+}
@@ -0,0 +1,120 @@
+use std::net::SocketAddr;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use rfs_lib::ids;
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{header, HeaderMap, HeaderValue, Request, Response};
+use axum::response::IntoResponse;
+use futures::future::{Either, Ready, ready};
+use tower::{Layer, Service};
+
+use crate::error::api::ApiErrorKind;
+use crate::sec::authn::{bot, initiator, session};
+use crate::state::ArcShared;
+use crate::state::rate_limit::Key;
+
+/// attempts to find the user of a request without going to the database.
+/// the bearer token is checked first since it takes precedence over the
+/// session cookie. if the token or session has not been cached yet then the
+/// request will be limited by the ip address instead
+fn request_user(state: &ArcShared, headers: &HeaderMap) -> Option<ids::UserId> {
+    if let Some(given) = initiator::find_bearer_token(headers).ok()? {
+        let hash = bot::decode_token(state.sec(), given).ok()?;
+        let (_bot_id, user_id) = state.sec().bot_tokens().get(&hash)?;
+
+        return Some(user_id);
+    }
+
+    let found = initiator::find_session_id(
+        headers.get_all("cookie"),
+        state.sec().session_info().cookie_name()
+    ).ok()??;
+    let (token, _hash) = session::decode_base64(state.sec(), found).ok()?;
+    let (_session, user) = state.sec().session_info().cache().get(&token)?;
+
+    Some(user.id.local().clone())
+}
+
+fn request_key<B>(state: &ArcShared, request: &Request<B>) -> Option<Key> {
+    if let Some(user_id) = request_user(state, request.headers()) {
+        return Some(Key::User(user_id));
+    }
+
+    request.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| Key::Addr(addr.ip()))
+}
+
+fn too_many_requests(wait: Duration) -> Response<Body> {
+    let mut secs = wait.as_secs();
+
+    if wait.subsec_nanos() > 0 {
+        secs += 1;
+    }
+
+    let mut response = rfs_api::error::ApiError::from(ApiErrorKind::TooManyRequests)
+        .into_response();
+
+    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs.max(1)));
+    response
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    state: ArcShared,
+}
+
+impl<S> RateLimit<S> {
+    pub fn new(inner: S, state: ArcShared) -> Self {
+        RateLimit { inner, state }
+    }
+}
+
+impl<S, B> Service<Request<B>> for RateLimit<S>
+where
+    S: Service<Request<B>, Response = Response<Body>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Response<Body>, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let limiter = self.state.rate_limiter();
+
+        if limiter.enabled() {
+            if let Some(key) = request_key(&self.state, &request) {
+                if let Err(wait) = limiter.check(key) {
+                    return Either::Left(ready(Ok(too_many_requests(wait))));
+                }
+            }
+        }
+
+        Either::Right(self.inner.call(request))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    state: ArcShared,
+}
+
+impl RateLimitLayer {
+    pub fn new(state: ArcShared) -> Self {
+        RateLimitLayer { state }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RateLimit::new(service, self.state.clone())
+    }
+}
@@ -0,0 +1,104 @@
+use std::task::{Context, Poll};
+use std::pin::Pin;
+use std::future::Future;
+
+use axum::http::{header, Request, Response};
+use pin_project::pin_project;
+use tower::{Layer, Service};
+
+use crate::sec::authn::session::CookieSlot;
+
+#[pin_project]
+pub struct SessionRefreshFuture<F> {
+    #[pin]
+    response: F,
+    slot: CookieSlot,
+}
+
+impl<F, B, Error> Future for SessionRefreshFuture<F>
+where
+    F: Future<Output = Result<Response<B>, Error>>,
+{
+    type Output = Result<Response<B>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let mut response = match this.response.poll(cx) {
+            Poll::Ready(Ok(response)) => response,
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        if let Some(cookie) = this.slot.take() {
+            // the handler has already modified the cookies for the request
+            // so its changes are kept
+            if !response.headers().contains_key(header::SET_COOKIE) {
+                match cookie.into_header_value() {
+                    Ok(value) => {
+                        response.headers_mut().append(header::SET_COOKIE, value);
+                    }
+                    Err(err) => {
+                        tracing::error!("failed to create refreshed session cookie. {err}");
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(response))
+    }
+}
+
+/// provides a slot for the request that a refreshed session cookie can be
+/// placed in. the cookie will then be added to the response
+#[derive(Debug, Clone)]
+pub struct SessionRefreshService<S> {
+    inner: S,
+}
+
+impl<S> SessionRefreshService<S> {
+    pub fn new(inner: S) -> Self {
+        SessionRefreshService { inner }
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SessionRefreshService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = SessionRefreshFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let slot = CookieSlot::default();
+
+        request.extensions_mut().insert(slot.clone());
+
+        SessionRefreshFuture {
+            response: self.inner.call(request),
+            slot,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionRefreshLayer;
+
+impl SessionRefreshLayer {
+    pub fn new() -> Self {
+        SessionRefreshLayer
+    }
+}
+
+impl<S> Layer<S> for SessionRefreshLayer {
+    type Service = SessionRefreshService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        SessionRefreshService::new(service)
+    }
+}
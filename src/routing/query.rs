@@ -2,6 +2,10 @@ use rfs_lib::query::{Limit, Offset};
 
 use serde::Deserialize;
 
+use crate::error::{ApiError, ApiResult};
+use crate::error::api::{ApiErrorKind, Detail};
+use crate::tags;
+
 #[derive(Deserialize)]
 pub struct PaginationQuery<T> {
     #[serde(default)]
@@ -12,3 +16,60 @@ pub struct PaginationQuery<T> {
 
     pub last_id: Option<T>,
 }
+
+/// keyset pagination that starts after the given id. unlike offsets the
+/// results will not shift if items are added while paging through them
+#[derive(Deserialize)]
+pub struct CursorQuery<T> {
+    pub after: Option<T>,
+}
+
+/// collects all "tag" and "tag_prefix" parameters from the query. at least
+/// one is required. "ignore_case" will apply to the values of all the tags
+pub fn tag_filters(pairs: Vec<(String, String)>) -> ApiResult<Vec<tags::TagFilter>> {
+    let mut rtn = Vec::new();
+    let mut ignore_case = false;
+
+    for (key, value) in pairs {
+        let filter = match key.as_str() {
+            "tag" => tags::TagFilter::parse(&value),
+            "tag_prefix" => tags::TagFilter::parse(&value)
+                .map(tags::TagFilter::into_prefix),
+            "ignore_case" => {
+                let Ok(flag) = value.parse() else {
+                    return Err(ApiError::from((
+                        ApiErrorKind::ValidationFailed,
+                        Detail::with_key("ignore_case")
+                    )));
+                };
+
+                ignore_case = flag;
+
+                continue;
+            }
+            _ => continue,
+        };
+
+        let Some(filter) = filter else {
+            return Err(ApiError::from((
+                ApiErrorKind::ValidationFailed,
+                Detail::with_key(key)
+            )));
+        };
+
+        rtn.push(filter);
+    }
+
+    if rtn.is_empty() {
+        return Err(ApiError::from((
+            ApiErrorKind::MissingData,
+            Detail::with_key("tag")
+        )));
+    }
+
+    for filter in &mut rtn {
+        filter.ignore_case = ignore_case;
+    }
+
+    Ok(rtn)
+}
@@ -1,8 +1,10 @@
 use axum::debug_handler;
 use axum::body::Body;
 use axum::extract::State;
-use axum::http::{header, Method, Uri, StatusCode};
+use axum::http::{header, HeaderMap, HeaderValue, Method, Uri, StatusCode};
+use axum::http::response::Builder;
 use axum::response::Response;
+use chrono::{DateTime, Utc};
 use tokio::fs::OpenOptions;
 use tokio_util::io::ReaderStream;
 
@@ -10,6 +12,7 @@ use std::path::Path;
 
 use crate::error::trace_error;
 use crate::path;
+use crate::routing::conditional;
 use crate::state::ArcShared;
 
 fn get_asset_dir<'a, 'b>(state: &'a ArcShared, uri_path: &'b str) -> Option<(&'a Path, &'b str)> {
@@ -56,7 +59,31 @@ fn method_not_allowed() -> Response<Body> {
         .unwrap()
 }
 
-async fn send_file(path: &Path) -> Response<Body> {
+/// adds the caching headers for the asset
+fn cache_headers(
+    mut builder: Builder,
+    etag: &str,
+    last_modified: Option<&DateTime<Utc>>,
+    cache_control: Option<&HeaderValue>,
+) -> Builder {
+    builder = builder.header(header::ETAG, etag);
+
+    if let Some(last_modified) = last_modified {
+        builder = builder.header(header::LAST_MODIFIED, conditional::http_date(last_modified));
+    }
+
+    if let Some(cache_control) = cache_control {
+        builder = builder.header(header::CACHE_CONTROL, cache_control);
+    }
+
+    builder
+}
+
+async fn send_file(
+    path: &Path,
+    headers: &HeaderMap,
+    cache_control: Option<&HeaderValue>
+) -> Response<Body> {
     tracing::debug!("attempting to send file: \"{}\"", path.display());
 
     let mime = path::mime_from_ext(path.extension());
@@ -78,6 +105,41 @@ async fn send_file(path: &Path) -> Response<Body> {
         return bad_request();
     }
 
+    let last_modified: Option<DateTime<Utc>> = metadata.modified()
+        .ok()
+        .map(|time| time.into());
+
+    // the size and modified time are used instead of hashing the contents
+    // so the file does not have to be read for every request
+    let etag = format!(
+        "\"{:x}-{:x}\"",
+        metadata.len(),
+        last_modified.as_ref().map(|time| time.timestamp()).unwrap_or(0)
+    );
+
+    let not_modified = if headers.contains_key(header::IF_NONE_MATCH) {
+        conditional::etag_matches(headers, header::IF_NONE_MATCH, &etag)
+    } else if let Some(last_modified) = &last_modified {
+        !conditional::modified_since(headers, last_modified)
+    } else {
+        false
+    };
+
+    if not_modified {
+        let result = cache_headers(Response::builder(), &etag, last_modified.as_ref(), cache_control)
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty());
+
+        return match result {
+            Ok(res) => res,
+            Err(err) => {
+                trace_error("error when creating asset response", &err);
+
+                server_error()
+            }
+        };
+    }
+
     let file = match OpenOptions::new()
         .read(true)
         .open(path)
@@ -91,7 +153,7 @@ async fn send_file(path: &Path) -> Response<Body> {
     };
 
     let stream = ReaderStream::new(file);
-    let result = Response::builder()
+    let result = cache_headers(Response::builder(), &etag, last_modified.as_ref(), cache_control)
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, mime.to_string())
         .header(header::CONTENT_LENGTH, metadata.len())
@@ -111,16 +173,18 @@ async fn send_file(path: &Path) -> Response<Body> {
 pub async fn handle(
     State(state): State<ArcShared>,
     method: Method,
-    uri: Uri
+    uri: Uri,
+    headers: HeaderMap,
 ) -> Response<Body> {
     if method != Method::GET {
         return method_not_allowed();
     }
 
     let uri_path = uri.path();
+    let cache_control = state.assets().cache_control.as_ref();
 
     if let Some(asset) = state.assets().files.get(uri_path) {
-        send_file(asset).await
+        send_file(asset, &headers, cache_control).await
     } else if let Some((dir, stripped)) = get_asset_dir(&state, uri_path) {
         let parts = stripped.split('/');
         let mut working = dir.to_path_buf();
@@ -133,7 +197,7 @@ pub async fn handle(
             }
         }
 
-        send_file(&working).await
+        send_file(&working, &headers, cache_control).await
     } else {
         not_found()
     }
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rfs_api::ws::{Request, Operation, Message};
+
+use axum::extract::State;
+use axum::extract::ws::{WebSocket, WebSocketUpgrade, Message as WsMessage};
+use axum::response::Response;
+use futures::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::error::{ApiError, ApiResult};
+use crate::error::api::{ApiErrorKind, Context};
+use crate::fs;
+use crate::sec::authn::initiator;
+use crate::sec::authz::permission;
+use crate::state::ArcShared;
+use crate::db;
+
+use super::api::fs::events::Subscription;
+use super::api::fs::retrieve_contents;
+
+/// max number of messages waiting to be sent to the client. subscriptions
+/// will wait for room before checking for more events
+const OUTGOING_LIMIT: usize = 64;
+
+/// how often the initiator is checked while no requests are being sent so
+/// that subscriptions do not outlive a session
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// everything a connection needs to handle requests from the client
+struct Connection {
+    state: ArcShared,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    outgoing: mpsc::Sender<Message>,
+    subscriptions: HashMap<u64, JoinHandle<()>>,
+}
+
+impl Connection {
+    /// checks that the initiator is still authenticated. the initiator is
+    /// only looked up with the upgrade request so a session could have
+    /// expired or been revoked since then
+    async fn check_initiator(&self) -> ApiResult<()> {
+        let conn = self.state.pool().get().await?;
+
+        initiator::check_mechanism(&conn, &self.initiator).await?;
+
+        Ok(())
+    }
+
+    /// sends the error to the client before the socket is closed
+    async fn closing(&self, err: ApiError) {
+        tracing::debug!("closing websocket: {err}");
+
+        let _ = self.outgoing.send(Message::Error {
+            id: None,
+            error: err.into_inner(),
+        }).await;
+    }
+
+    async fn handle(&mut self, request: Request) -> ApiResult<Message> {
+        let conn = self.state.pool().get().await?;
+
+        self.rbac.api_ability(
+            &conn,
+            &self.initiator,
+            permission::Scope::Fs,
+            permission::Ability::Read,
+        ).await?;
+
+        match request.op {
+            Operation::Retrieve { uid } => {
+                let item = fs::fetch_item_uid(&conn, &uid, &self.initiator).await?;
+
+                Ok(Message::Item {
+                    id: request.id,
+                    item: item.into_schema(),
+                })
+            }
+            Operation::Contents { uid, after, limit } => {
+                let item = fs::fetch_item_uid(&conn, &uid, &self.initiator).await?;
+
+                let container = item.as_container()
+                    .kind(ApiErrorKind::NotDirectory)?;
+
+                let items = retrieve_contents(&conn, container.id(), after.as_ref(), limit).await?;
+
+                Ok(Message::Contents {
+                    id: request.id,
+                    items,
+                })
+            }
+            Operation::Subscribe { uid, after } => {
+                if self.subscriptions.contains_key(&request.id) {
+                    return Err(ApiError::from(ApiErrorKind::AlreadyExists));
+                }
+
+                let item = fs::fetch_item_uid(&conn, &uid, &self.initiator).await?;
+
+                let container = item.as_container()
+                    .kind(ApiErrorKind::NotDirectory)?;
+
+                let mut subscription = Subscription::new(
+                    &conn,
+                    self.state.clone(),
+                    container.id().clone(),
+                    self.initiator.user.id.local().clone(),
+                    after,
+                ).await?;
+
+                let id = request.id;
+                let outgoing = self.outgoing.clone();
+
+                let handle = tokio::spawn(async move {
+                    while let Some(event) = subscription.next().await {
+                        let msg = Message::Event {
+                            subscription: id,
+                            event,
+                        };
+
+                        if outgoing.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+
+                self.subscriptions.insert(id, handle);
+
+                Ok(Message::Subscribed { id })
+            }
+            Operation::Unsubscribe { subscription } => {
+                let handle = self.subscriptions.remove(&subscription)
+                    .kind(ApiErrorKind::NotFound)?;
+
+                handle.abort();
+
+                Ok(Message::Unsubscribed { id: request.id })
+            }
+        }
+    }
+}
+
+/// upgrades the request to a websocket that handles json requests for fs
+/// items. the initiator is authenticated with the upgrade request and is
+/// checked again along with abilities for every request. the socket is
+/// closed once the initiator is no longer authenticated
+pub async fn handle(
+    State(state): State<ArcShared>,
+    db::Conn(conn): db::Conn,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+    upgrade: WebSocketUpgrade,
+) -> ApiResult<Response> {
+    rbac.api_ability(
+        &conn,
+        &initiator,
+        permission::Scope::Fs,
+        permission::Ability::Read,
+    ).await?;
+
+    Ok(upgrade.on_upgrade(move |socket| run(socket, state, rbac, initiator)))
+}
+
+async fn run(
+    socket: WebSocket,
+    state: ArcShared,
+    rbac: permission::Rbac,
+    initiator: initiator::Initiator,
+) {
+    let (mut sender, mut receiver) = socket.split();
+    let (outgoing, mut pending) = mpsc::channel::<Message>(OUTGOING_LIMIT);
+
+    let writer = tokio::spawn(async move {
+        while let Some(msg) = pending.recv().await {
+            let text = match serde_json::to_string(&msg) {
+                Ok(text) => text,
+                Err(err) => {
+                    tracing::error!("failed to serialize websocket message: {err}");
+
+                    continue;
+                }
+            };
+
+            if sender.send(WsMessage::Text(text)).await.is_err() {
+                break;
+            }
+        }
+
+        let _ = sender.close().await;
+    });
+
+    let mut connection = Connection {
+        state,
+        rbac,
+        initiator,
+        outgoing,
+        subscriptions: HashMap::new(),
+    };
+
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    check.reset();
+
+    loop {
+        let result = tokio::select! {
+            result = receiver.next() => match result {
+                Some(result) => result,
+                None => break,
+            },
+            _ = check.tick() => {
+                if let Err(err) = connection.check_initiator().await {
+                    connection.closing(err).await;
+
+                    break;
+                }
+
+                continue;
+            }
+        };
+
+        let text = match result {
+            Ok(WsMessage::Text(text)) => text,
+            Ok(WsMessage::Close(_)) => break,
+            // pings are answered by axum and everything else is ignored
+            Ok(_) => continue,
+            Err(err) => {
+                tracing::debug!("websocket receive error: {err}");
+
+                break;
+            }
+        };
+
+        if let Err(err) = connection.check_initiator().await {
+            connection.closing(err).await;
+
+            break;
+        }
+
+        check.reset();
+
+        let msg = match serde_json::from_str::<Request>(&text) {
+            Ok(request) => {
+                let id = request.id;
+
+                match connection.handle(request).await {
+                    Ok(msg) => msg,
+                    Err(err) => {
+                        tracing::debug!("websocket request {id} failed: {err}");
+
+                        Message::Error {
+                            id: Some(id),
+                            error: err.into_inner(),
+                        }
+                    }
+                }
+            }
+            Err(_) => Message::Error {
+                id: None,
+                error: rfs_api::ApiError::from(ApiErrorKind::InvalidData),
+            },
+        };
+
+        if connection.outgoing.send(msg).await.is_err() {
+            break;
+        }
+    }
+
+    for (_, handle) in connection.subscriptions.drain() {
+        handle.abort();
+    }
+
+    // the writer finishes once every sender has been dropped
+    drop(connection);
+
+    let _ = writer.await;
+}
@@ -3,6 +3,7 @@ use tokio_postgres::{Error as PgError};
 use deadpool_postgres::GenericClient;
 
 pub mod session;
+pub mod bot;
 pub mod password;
 pub mod totp;
 pub mod initiator;
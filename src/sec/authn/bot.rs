@@ -0,0 +1,244 @@
+use rfs_lib::ids;
+use rfs_lib::sec::chacha;
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use chrono::{DateTime, Utc};
+use deadpool_postgres::GenericClient;
+use rand::RngCore;
+use tokio_postgres::{Error as PgError};
+
+use crate::sec::state;
+
+use super::session::{self, token};
+
+pub type Hash = blake3::Hash;
+
+/// the bot and user of recently used tokens so that a request can be
+/// attributed to a user without going to the database
+pub type TokenCache = moka::sync::Cache<Hash, (ids::BotId, ids::UserId)>;
+
+/// a service account that is owned by a user. the bot is only able to
+/// perform actions that are allowed by its role and the user that owns it
+#[derive(Debug, Clone)]
+pub struct Bot {
+    pub id: ids::BotSet,
+    pub user_id: ids::UserId,
+    pub role: ids::RoleSet,
+    pub name: String,
+    pub created: DateTime<Utc>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+impl Bot {
+    pub async fn retrieve_uid(
+        conn: &impl GenericClient,
+        user_id: &ids::UserId,
+        uid: &ids::BotUid,
+    ) -> Result<Option<Self>, PgError> {
+        Ok(conn.query_opt(
+            "\
+            select bots.id, \
+                   authz_roles.id, \
+                   authz_roles.uid, \
+                   bots.name, \
+                   bots.created, \
+                   bots.updated \
+            from bots \
+            join authz_roles on \
+                bots.role_id = authz_roles.id \
+            where bots.uid = $1 and \
+                  bots.user_id = $2",
+            &[uid, user_id]
+        ).await?.map(|row| Bot {
+            id: ids::BotSet::new(row.get(0), uid.clone()),
+            user_id: user_id.clone(),
+            role: ids::RoleSet::new(row.get(1), row.get(2)),
+            name: row.get(3),
+            created: row.get(4),
+            updated: row.get(5),
+        }))
+    }
+
+    pub async fn retrieve_token_hash(
+        conn: &impl GenericClient,
+        hash: &Hash,
+    ) -> Result<Option<Self>, PgError> {
+        Ok(conn.query_opt(
+            "\
+            select bots.id, \
+                   bots.uid, \
+                   bots.user_id, \
+                   authz_roles.id, \
+                   authz_roles.uid, \
+                   bots.name, \
+                   bots.created, \
+                   bots.updated \
+            from bots \
+            join authz_roles on \
+                bots.role_id = authz_roles.id \
+            where bots.token_hash = $1",
+            &[&hash.as_bytes().as_slice()]
+        ).await?.map(|row| Bot {
+            id: ids::BotSet::new(row.get(0), row.get(1)),
+            user_id: row.get(2),
+            role: ids::RoleSet::new(row.get(3), row.get(4)),
+            name: row.get(5),
+            created: row.get(6),
+            updated: row.get(7),
+        }))
+    }
+}
+
+/// creates a new token for a bot along with the hash that will be stored in
+/// the database. the token itself is never stored so it is only available
+/// when it is created
+///
+/// bot tokens use the same format as session tokens
+pub async fn unique_token(
+    conn: &impl GenericClient,
+    mut attempts: usize
+) -> Result<Option<(token::SessionToken, Hash)>, token::UniqueError> {
+    let mut rtn = [0; token::SESSION_ID_BYTES];
+    let mut count;
+
+    while attempts > 0 {
+        rand::thread_rng().try_fill_bytes(&mut rtn)?;
+
+        let hash = blake3::hash(&rtn);
+
+        count = conn.execute(
+            "select id from bots where token_hash = $1",
+            &[&hash.as_bytes().as_slice()]
+        ).await?;
+
+        if count == 0 {
+            return Ok(Some((token::SessionToken::from_vec(rtn.to_vec()), hash)));
+        } else {
+            rtn.fill(0);
+        }
+
+        attempts -= 1;
+    }
+
+    Ok(None)
+}
+
+/// removes any cached tokens of the given bot. this should be called when
+/// the token of the bot changes or the bot is deleted
+pub fn clear_cached_tokens(auth: &state::Sec, bot_id: &ids::BotId) {
+    let bot_id = bot_id.clone();
+
+    if let Err(err) = auth.bot_tokens().invalidate_entries_if(
+        move |_, (cached, _)| *cached == bot_id
+    ) {
+        tracing::error!("failed to clear cached bot tokens. {err}");
+    }
+}
+
+/// encodes the token into the string that will be given to the user. the
+/// token is signed with the bot key instead of the session keys since the
+/// session keys are rotated and retired long before a bot token is revoked
+pub fn encode_token(auth: &state::Sec, token: &token::SessionToken) -> String {
+    encode_with_key(auth.bot_key(), token)
+}
+
+/// decodes the given token string and returns the hash that can be used to
+/// find the bot
+pub fn decode_token<T>(auth: &state::Sec, given: T) -> Result<Hash, session::DecodeError>
+where
+    T: AsRef<[u8]>
+{
+    decode_with_key(auth.bot_key(), given)
+}
+
+fn encode_with_key(key: &chacha::Key, token: &token::SessionToken) -> String {
+    let hash = blake3::keyed_hash(key, token.as_slice());
+
+    session::encode_base64(token, hash)
+}
+
+fn decode_with_key<T>(key: &chacha::Key, given: T) -> Result<Hash, session::DecodeError>
+where
+    T: AsRef<[u8]>
+{
+    let Ok(mut bytes) = URL_SAFE.decode(given) else {
+        return Err(session::DecodeError::InvalidString);
+    };
+
+    if bytes.len() != token::SESSION_ID_BYTES + blake3::OUT_LEN {
+        return Err(session::DecodeError::InvalidLength);
+    }
+
+    let token = token::SessionToken::drain_vec(&mut bytes);
+    let given: [u8; blake3::OUT_LEN] = bytes.try_into()
+        .expect("remaining bytes does not match expected length");
+
+    // blake3::Hash comparisons are constant time
+    if blake3::Hash::from(given) != blake3::keyed_hash(key, token.as_slice()) {
+        return Err(session::DecodeError::InvalidHash);
+    }
+
+    Ok(blake3::hash(token.as_slice()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use rust_lib_history::list::fixed::Fixed;
+
+    use super::*;
+    use crate::sec::secrets::{self, Key};
+
+    fn rand_token() -> token::SessionToken {
+        let mut bytes = vec![0; token::SESSION_ID_BYTES];
+        rand::thread_rng().fill_bytes(&mut bytes);
+
+        token::SessionToken::from_vec(bytes)
+    }
+
+    #[test]
+    fn token_survives_session_key_rotation() {
+        let bot_key = Key::rand_key_data().unwrap();
+        let grace = Duration::from_secs(30 * 24 * 60 * 60);
+        let month = Duration::from_secs(31 * 24 * 60 * 60);
+
+        let mut session_keys: Fixed<Key, { secrets::MAX_SESSION_KEYS }> = Fixed::default();
+        session_keys.push(Key::new(Key::rand_key_data().unwrap(), Duration::ZERO));
+
+        let original = session_keys.newest().unwrap().id();
+        let token = rand_token();
+        let encoded = encode_with_key(&bot_key, &token);
+
+        // two monthly rotations will retire the key that was the newest when
+        // the token was created
+        for count in 1..=2 {
+            let now = month * count;
+
+            session_keys.push(Key::new(Key::rand_key_data().unwrap(), now));
+
+            secrets::retain_session_keys(&mut session_keys, |_, replaced_by| {
+                secrets::within_grace(replaced_by, grace, now)
+            });
+        }
+
+        assert!(
+            session_keys.iter().all(|key| key.id() != original),
+            "original session key was not retired"
+        );
+
+        let hash = decode_with_key(&bot_key, &encoded)
+            .expect("bot token failed to verify after rotating session keys");
+
+        assert_eq!(hash, blake3::hash(token.as_slice()));
+    }
+
+    #[test]
+    fn token_rejects_other_key() {
+        let token = rand_token();
+        let encoded = encode_with_key(&Key::rand_key_data().unwrap(), &token);
+
+        let result = decode_with_key(&Key::rand_key_data().unwrap(), &encoded);
+
+        assert!(matches!(result, Err(session::DecodeError::InvalidHash)));
+    }
+}
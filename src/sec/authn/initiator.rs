@@ -1,7 +1,7 @@
 use std::pin::Pin;
 use std::future::Future;
 
-use axum::http::header::{HeaderMap, HeaderValue, GetAll};
+use axum::http::header::{AUTHORIZATION, HeaderMap, HeaderValue, GetAll};
 use axum::http::request::Parts;
 use axum::extract::FromRequestParts;
 use deadpool_postgres::GenericClient;
@@ -12,12 +12,13 @@ use crate::sec::state;
 use crate::user;
 use crate::state::ArcShared;
 
-use super::session;
+use super::{session, bot};
 
 // not sure what to call this
 #[derive(Debug)]
 pub enum Mechanism {
     Session(session::Session),
+    Bot(bot::Bot),
 }
 
 pub struct Initiator {
@@ -39,6 +40,12 @@ pub enum LookupError {
     #[error("session is unverified")]
     SessionUnverified(session::Session),
 
+    #[error("bot was not found")]
+    BotNotFound,
+
+    #[error("provided bot token is invalid")]
+    TokenDecode(session::DecodeError),
+
     #[error("user was not found")]
     UserNotFound(Mechanism),
 
@@ -63,6 +70,14 @@ impl From<LookupError> for ApiError {
             LookupError::SessionUnauthenticated(_session) => ApiError::from(ApiErrorKind::SessionUnauthenticated),
             LookupError::SessionUnverified(_session) => ApiError::from(ApiErrorKind::SessionUnverified),
 
+            LookupError::BotNotFound => ApiError::from(ApiErrorKind::InvalidToken),
+            LookupError::TokenDecode(err) => match err {
+                session::DecodeError::InvalidString |
+                session::DecodeError::InvalidLength |
+                session::DecodeError::InvalidHash => ApiError::from(ApiErrorKind::InvalidToken),
+                err => ApiError::new().source(err)
+            },
+
             LookupError::UserNotFound(_authorization) => ApiError::from(ApiErrorKind::UserNotFound),
 
             LookupError::MechanismNotFound => ApiError::from(ApiErrorKind::MechanismNotFound),
@@ -139,12 +154,66 @@ where
     }
 }
 
-fn find_session_id<'a>(cookies: GetAll<'a, HeaderValue>) -> Result<Option<&'a str>, LookupError> {
+pub async fn lookup_bot_token<T>(
+    auth: &state::Sec,
+    conn: &impl GenericClient,
+    token: T
+) -> Result<Initiator, LookupError>
+where
+    T: AsRef<[u8]>
+{
+    let hash = bot::decode_token(auth, token)
+        .map_err(LookupError::TokenDecode)?;
+
+    let Some(bot) = bot::Bot::retrieve_token_hash(conn, &hash).await? else {
+        return Err(LookupError::BotNotFound);
+    };
+
+    auth.bot_tokens().insert(hash, (bot.id.local().clone(), bot.user_id.clone()));
+
+    if let Some(user) = user::User::retrieve(conn, &bot.user_id).await? {
+        Ok(Initiator {
+            user,
+            mechanism: Mechanism::Bot(bot),
+        })
+    } else {
+        Err(LookupError::UserNotFound(Mechanism::Bot(bot)))
+    }
+}
+
+/// checks that the mechanism of the initiator has not been revoked or expired
+/// since it was looked up. the cache is skipped so that any changes to the
+/// session are seen
+pub async fn check_mechanism(
+    conn: &impl GenericClient,
+    initiator: &Initiator,
+) -> Result<(), LookupError> {
+    match &initiator.mechanism {
+        Mechanism::Session(session) => {
+            let Some(current) = session::Session::retrieve_token(conn, &session.token).await? else {
+                return Err(LookupError::SessionNotFound);
+            };
+
+            if current.dropped || current.expires < chrono::Utc::now() {
+                return Err(LookupError::SessionExpired(current));
+            }
+        }
+        Mechanism::Bot(bot) => {
+            if bot::Bot::retrieve_uid(conn, &bot.user_id, bot.id.uid()).await?.is_none() {
+                return Err(LookupError::BotNotFound);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn find_session_id<'a>(cookies: GetAll<'a, HeaderValue>, cookie_name: &str) -> Result<Option<&'a str>, LookupError> {
     for value in cookies {
         let value_str = value.to_str()?;
 
         if let Some((name, value)) = value_str.split_once('=') {
-            if name == "session_id" {
+            if name == cookie_name {
                 return Ok(Some(value));
             }
         }
@@ -153,20 +222,77 @@ fn find_session_id<'a>(cookies: GetAll<'a, HeaderValue>) -> Result<Option<&'a st
     Ok(None)
 }
 
+/// searches for a bearer token in the authorization header
+pub fn find_bearer_token(headers: &HeaderMap) -> Result<Option<&str>, LookupError> {
+    let Some(value) = headers.get(AUTHORIZATION) else {
+        return Ok(None);
+    };
+
+    let value_str = value.to_str()?;
+
+    if let Some((scheme, token)) = value_str.split_once(' ') {
+        if scheme.eq_ignore_ascii_case("bearer") {
+            return Ok(Some(token.trim()));
+        }
+    }
+
+    Ok(None)
+}
+
 pub async fn lookup_header_map(
     auth: &state::Sec,
     conn: &impl GenericClient,
     headers: &HeaderMap
 ) -> Result<Initiator, LookupError> {
+    // a bearer token is explicitly provided for the request so it will take
+    // priority over any session cookie that may have been sent along with it
+    if let Some(found) = find_bearer_token(headers)? {
+        return lookup_bot_token(auth, conn, found.as_bytes()).await;
+    }
+
     let cookies = headers.get_all("cookie");
 
-    if let Some(found) = find_session_id(cookies)? {
+    if let Some(found) = find_session_id(cookies, auth.session_info().cookie_name())? {
         return lookup_session_id(auth, conn, found.as_bytes()).await;
     }
 
     Err(LookupError::MechanismNotFound)
 }
 
+/// extends the expiration of the session if sliding sessions are enabled.
+/// the refreshed cookie is placed in the given slot so that it can be sent
+/// back with the response
+pub async fn refresh_session(
+    auth: &state::Sec,
+    conn: &impl GenericClient,
+    initiator: &mut Initiator,
+    slot: &session::CookieSlot,
+) -> Result<(), LookupError> {
+    let Some(sliding) = auth.session_info().sliding() else {
+        return Ok(());
+    };
+
+    let Mechanism::Session(session) = &mut initiator.mechanism else {
+        return Ok(());
+    };
+
+    if !session.slide(sliding, chrono::Utc::now()) {
+        return Ok(());
+    }
+
+    session.update(conn).await?;
+
+    auth.session_info()
+        .cache()
+        .insert(session.token.clone(), (session.clone(), initiator.user.clone()));
+
+    if let Some(cookie) = session::create_session_cookie(auth, session) {
+        slot.set(cookie);
+    }
+
+    Ok(())
+}
+
 impl FromRequestParts<ArcShared> for Initiator {
     type Rejection = ApiError;
 
@@ -189,7 +315,15 @@ impl FromRequestParts<ArcShared> for Initiator {
                     .context("failed to retrieve database connection"))
             };
 
-            Ok(lookup_header_map(sec, &conn, &parts.headers).await?)
+            let mut initiator = lookup_header_map(sec, &conn, &parts.headers).await?;
+
+            // the cookie can only be refreshed if there is somewhere to put
+            // it for the response
+            if let Some(slot) = parts.extensions.get::<session::CookieSlot>() {
+                refresh_session(sec, &conn, &mut initiator, slot).await?;
+            }
+
+            Ok(initiator)
         })
     }
 }
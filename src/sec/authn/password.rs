@@ -7,6 +7,7 @@ use deadpool_postgres::GenericClient;
 use argon2::Variant;
 use rand::RngCore;
 
+use crate::config;
 use crate::error;
 use crate::sec::secrets::{PeppersManager, PMError};
 use crate::sql;
@@ -58,9 +59,36 @@ pub fn gen_salt() -> Result<Salt, rand::Error> {
     Ok(salt)
 }
 
-pub fn gen_hash(password: &str, salt: &[u8]) -> Result<String, argon2::Error> {
+/// the argon2id parameters used when creating new hashes. the parameters
+/// are encoded into the hash so previous hashes can still be verified if
+/// these change
+#[derive(Debug, Clone)]
+pub struct HashParams {
+    pub mem_cost: u32,
+    pub time_cost: u32,
+    pub lanes: u32,
+    pub hash_length: u32,
+}
+
+impl HashParams {
+    pub fn from_config(config: &config::Config) -> Self {
+        let password = &config.settings.sec.password;
+
+        HashParams {
+            mem_cost: password.mem_cost,
+            time_cost: password.time_cost,
+            lanes: password.lanes,
+            hash_length: password.hash_length,
+        }
+    }
+}
+
+pub fn gen_hash(password: &str, salt: &[u8], params: &HashParams) -> Result<String, argon2::Error> {
     let mut config = argon2::Config::default();
-    config.mem_cost = 19456;
+    config.mem_cost = params.mem_cost;
+    config.time_cost = params.time_cost;
+    config.lanes = params.lanes;
+    config.hash_length = params.hash_length;
     config.variant = Variant::Argon2id;
 
     Ok(argon2::hash_encoded(
@@ -118,11 +146,12 @@ impl Password {
         conn: &impl GenericClient,
         user_id: &ids::UserId,
         password: String,
+        params: &HashParams,
         manager: &PeppersManager,
     ) -> Result<Self, PasswordError> {
         let salt = gen_salt()?;
         let (version, encrypted) = gen_encrypted(
-            gen_hash(&password, &salt)?,
+            gen_hash(&password, &salt, params)?,
             manager
         )?;
 
@@ -148,11 +177,12 @@ impl Password {
         &mut self,
         conn: &impl GenericClient,
         update: String,
+        params: &HashParams,
         manager: &PeppersManager,
     ) -> Result<(), PasswordError> {
         let salt = gen_salt()?;
         let (version, encrypted) = gen_encrypted(
-            gen_hash(&update, &salt)?,
+            gen_hash(&update, &salt, params)?,
             manager
         )?;
 
@@ -198,3 +228,25 @@ impl Password {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hash_encodes_params() {
+        let salt = gen_salt().unwrap();
+        let params = HashParams {
+            mem_cost: 64,
+            time_cost: 1,
+            lanes: 2,
+            hash_length: 16,
+        };
+
+        let hash = gen_hash("password", &salt, &params).unwrap();
+
+        assert!(hash.contains("m=64,t=1,p=2"), "params not in hash: {hash}");
+        assert!(argon2::verify_encoded(&hash, b"password").unwrap());
+        assert!(!argon2::verify_encoded(&hash, b"wrong").unwrap());
+    }
+}
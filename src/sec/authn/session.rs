@@ -1,3 +1,5 @@
+use std::sync::{Arc, Mutex};
+
 use rfs_lib::ids;
 use chrono::Utc;
 use base64::{Engine, engine::general_purpose::URL_SAFE};
@@ -7,7 +9,7 @@ use futures::{TryStream, StreamExt};
 use moka::sync::Cache;
 
 use crate::error::ApiError;
-use crate::net::cookie::{SameSite, SetCookie};
+use crate::net::cookie::SetCookie;
 use crate::sec::state;
 use crate::sql;
 use crate::user::User;
@@ -100,11 +102,20 @@ impl From<BuilderError> for ApiError {
 
 pub struct SessionBuilder {
     user_id: ids::UserId,
+    duration: chrono::Duration,
     auth_method: AuthMethod,
     verify_method: Option<VerifyMethod>
 }
 
 impl SessionBuilder {
+    /// how long the session is valid for until it has been authenticated and
+    /// verified. the session should be given its full lifetime with
+    /// [`Session::set_verified_expires`] once that happens
+    pub fn duration(&mut self, duration: chrono::Duration) -> &mut Self {
+        self.duration = duration;
+        self
+    }
+
     pub fn auth_method(&mut self, method: AuthMethod) -> &mut Self {
         self.auth_method = method;
         self
@@ -119,7 +130,7 @@ impl SessionBuilder {
         let user_id = self.user_id;
         let dropped = false;
         let issued_on = Utc::now();
-        let duration = chrono::Duration::days(7);
+        let duration = self.duration;
         let authenticated = false;
         let auth_method = self.auth_method;
 
@@ -200,6 +211,7 @@ impl Session {
     pub fn builder(user_id: ids::UserId) -> SessionBuilder {
         SessionBuilder {
             user_id,
+            duration: chrono::Duration::minutes(10),
             auth_method: AuthMethod::Password,
             verify_method: None,
         }
@@ -242,6 +254,48 @@ impl Session {
         }
     }
 
+    /// retrieves all sessions for the user that have not been dropped or
+    /// expired
+    pub async fn retrieve_user_active(
+        conn: &impl GenericClient,
+        user_id: &ids::UserId,
+    ) -> Result<Vec<Session>, PgError> {
+        let rows = conn.query(
+            "\
+            select auth_session.token, \
+                   auth_session.user_id, \
+                   auth_session.dropped, \
+                   auth_session.issued_on, \
+                   auth_session.expires, \
+                   auth_session.authenticated, \
+                   auth_session.verified, \
+                   auth_session.auth_method, \
+                   auth_session.verify_method \
+            from auth_session \
+            where auth_session.user_id = $1 and \
+                  auth_session.dropped = false and \
+                  auth_session.expires > $2 \
+            order by auth_session.issued_on",
+            &[user_id, &Utc::now()]
+        ).await?;
+
+        Ok(rows.into_iter()
+            .map(|row| Session {
+                token: token::SessionToken::from_vec(row.get(0)),
+                user_id: row.get(1),
+                dropped: row.get(2),
+                issued_on: row.get(3),
+                expires: row.get(4),
+                authenticated: row.get(5),
+                verified: row.get(6),
+                auth_method: AuthMethod::from_i16(row.get(7))
+                    .expect("invalid auth method returned from database for session"),
+                verify_method: VerifyMethod::from_i16(row.get(8))
+                    .expect("invalid verify method returned from database for session"),
+            })
+            .collect())
+    }
+
     pub async fn delete_user_sessions(
         conn: &impl GenericClient,
         id: &ids::UserId,
@@ -307,6 +361,61 @@ impl Session {
         Ok(())
     }
 
+    /// moves the expiration of the session forward by the idle timeout
+    /// without going past the max lifetime of the session. to avoid updating
+    /// the session on every request the expiration is only moved once more
+    /// than a quarter of the idle timeout has elapsed. returns true if the
+    /// expiration was changed
+    pub fn slide(&mut self, sliding: &state::Sliding, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let Some(mut target) = now.checked_add_signed(sliding.idle_timeout) else {
+            return false;
+        };
+
+        if let Some(max) = self.issued_on.checked_add_signed(sliding.max_lifetime) {
+            if target > max {
+                target = max;
+            }
+        }
+
+        if target - self.expires <= sliding.idle_timeout / 4 {
+            return false;
+        }
+
+        self.expires = target;
+
+        true
+    }
+
+    /// replaces the pending expiration of a session that has just been
+    /// authenticated and verified with the full lifetime of a session. when
+    /// sliding is enabled the session will start with the idle timeout
+    pub fn set_verified_expires(
+        &mut self,
+        session_info: &state::SessionInfo,
+        now: chrono::DateTime<chrono::Utc>
+    ) -> Result<(), BuilderError> {
+        let duration = match session_info.sliding() {
+            Some(sliding) => sliding.idle_timeout,
+            None => *session_info.lifetime(),
+        };
+
+        let Some(mut expires) = now.checked_add_signed(duration) else {
+            return Err(BuilderError::UtcOverflow);
+        };
+
+        if let Some(sliding) = session_info.sliding() {
+            if let Some(max) = self.issued_on.checked_add_signed(sliding.max_lifetime) {
+                if expires > max {
+                    expires = max;
+                }
+            }
+        }
+
+        self.expires = expires;
+
+        Ok(())
+    }
+
     pub async fn delete(&self, conn: &impl GenericClient) -> Result<(), PgError> {
         let _ = conn.execute(
             "delete from auth_session where token = $1",
@@ -414,12 +523,12 @@ pub fn create_session_cookie(auth: &state::Sec, session: &Session) -> Option<Set
     };
     let encoded_token = encode_base64(&session.token, hash);
 
-    let mut cookie = SetCookie::new("session_id", encoded_token)
+    let mut cookie = SetCookie::new(auth.session_info().cookie_name(), encoded_token)
         .with_expires(session.expires.clone())
         .with_path("/")
         .with_http_only(true)
         .with_secure(*auth.session_info().secure())
-        .with_same_site(SameSite::Strict);
+        .with_same_site(*auth.session_info().same_site());
 
     if let Some(domain) = auth.session_info().domain() {
         cookie.set_domain(domain);
@@ -428,13 +537,38 @@ pub fn create_session_cookie(auth: &state::Sec, session: &Session) -> Option<Set
     Some(cookie)
 }
 
+/// holds a refreshed session cookie that will be added to the response of a
+/// request
+#[derive(Clone, Default)]
+pub struct CookieSlot(Arc<Mutex<Option<SetCookie>>>);
+
+impl CookieSlot {
+    pub fn set(&self, cookie: SetCookie) {
+        let mut slot = match self.0.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        *slot = Some(cookie);
+    }
+
+    pub fn take(&self) -> Option<SetCookie> {
+        let mut slot = match self.0.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        slot.take()
+    }
+}
+
 pub fn expire_session_cookie(auth: &state::Sec) -> SetCookie {
-    let mut cookie = SetCookie::new("session_id", "")
+    let mut cookie = SetCookie::new(auth.session_info().cookie_name(), "")
         .with_max_age(std::time::Duration::new(0, 0))
         .with_path("/")
         .with_http_only(true)
         .with_secure(*auth.session_info().secure())
-        .with_same_site(SameSite::Strict);
+        .with_same_site(*auth.session_info().same_site());
 
     if let Some(domain) = auth.session_info().domain() {
         cookie.set_domain(domain);
@@ -72,6 +72,14 @@ impl SessionToken {
     pub fn as_slice(&self) -> &[u8] {
         self.0.as_slice()
     }
+
+    /// an identifier for the session that can be given to the user without
+    /// revealing the token itself
+    pub fn public_id(&self) -> String {
+        let hash = blake3::hash(self.0.as_slice());
+
+        hash.to_hex()[..32].to_owned()
+    }
 }
 
 impl AsRef<[u8]> for SessionToken {
@@ -6,6 +6,7 @@ use tokio_postgres::{Error as PgError};
 use deadpool_postgres::GenericClient;
 use rand::RngCore;
 
+use crate::sec::state;
 use crate::sql;
 
 pub mod algo;
@@ -103,7 +104,7 @@ impl Totp {
         Ok(())
     }
 
-    pub fn verify<C>(&self, code: C) -> rust_otp::error::Result<rust_otp::VerifyResult>
+    pub fn verify<C>(&self, code: C, window: &state::TotpWindow) -> rust_otp::error::Result<rust_otp::VerifyResult>
     where
         C: AsRef<str>
     {
@@ -115,8 +116,8 @@ impl Totp {
             secret,
             digits: *self.digits.get(),
             step: *self.step.get(),
-            window_before: 1,
-            window_after: 1,
+            window_before: window.before.into(),
+            window_after: window.after.into(),
             now: None,
         };
 
@@ -108,6 +108,36 @@ impl Hash {
         }
     }
 
+    /// same as [`Hash::retrieve_key`] but the row is locked until the
+    /// transaction finishes so that it cannot be changed or deleted by
+    /// another request in the meantime
+    pub async fn retrieve_key_for_update<K>(
+        conn: &impl GenericClient,
+        user_id: &ids::UserId,
+        key: K
+    ) -> Result<Option<Self>, PgError>
+    where
+        K: AsRef<str>
+    {
+        Ok(conn.query_opt(
+            "\
+            select auth_totp_hash.user_id, \
+                   auth_totp_hash.key, \
+                   auth_totp_hash.hash, \
+                   auth_totp_hash.used \
+            from auth_totp_hash \
+            where auth_totp_hash.user_id = $1 and \
+                  auth_totp_hash.key = $2 \
+            for update",
+            &[user_id, &key.as_ref()]
+        ).await?.map(|row| Hash {
+            user_id: row.get(0),
+            key: HistoryField::new(row.get(1)),
+            hash: HistoryField::new(row.get(2)),
+            used: HistoryField::new(row.get(3)),
+        }))
+    }
+
     pub fn used(&self) -> &bool {
         self.used.get()
     }
@@ -128,11 +158,16 @@ impl Hash {
         }
     }
 
-    pub fn regen_hash(&mut self) -> Result<(), rand::Error> {
+    /// creates a new hash for the key. used keys cannot be regenerated and
+    /// will return false
+    pub fn regen_hash(&mut self) -> Result<bool, rand::Error> {
+        if *self.used.get() {
+            return Ok(false);
+        }
+
         self.hash.set(create_hash()?);
-        self.used.set(false);
 
-        Ok(())
+        Ok(true)
     }
 
     pub fn verify<V>(&self, verify: V) -> bool
@@ -142,9 +177,11 @@ impl Hash {
         self.hash.as_str() == verify.as_ref()
     }
 
-    pub async fn update(&mut self, conn: &impl GenericClient) -> Result<bool, PgError> {
+    /// creates the update statement for the fields that have changed. None
+    /// is returned if nothing has changed
+    fn update_query(&self) -> Option<(String, sql::ParamsVec<'_>)> {
         if !self.key.is_updated() && !self.hash.is_updated() && !self.used.is_updated() {
-            return Ok(false);
+            return None;
         }
 
         let mut update_query = String::from("update auth_totp_hash set");
@@ -187,15 +224,49 @@ impl Hash {
 
         update_query.push_str(" where user_id = $1 and key = $2");
 
-        let _ = conn.execute(update_query.as_str(), update_params.as_slice()).await?;
+        Some((update_query, update_params))
+    }
 
+    /// marks the changed fields as saved
+    fn commit(&mut self) {
         self.key.commit();
         self.hash.commit();
         self.used.commit();
+    }
+
+    pub async fn update(&mut self, conn: &impl GenericClient) -> Result<bool, PgError> {
+        let Some((update_query, update_params)) = self.update_query() else {
+            return Ok(false);
+        };
+
+        let _ = conn.execute(update_query.as_str(), update_params.as_slice()).await?;
+
+        self.commit();
 
         Ok(true)
     }
 
+    /// marks the hash as used in the database only if it has not already
+    /// been used. checking in the same statement prevents concurrent requests
+    /// from using the same hash more than once. returns false if the hash was
+    /// used by someone else first
+    pub async fn consume(&mut self, conn: &impl GenericClient) -> Result<bool, PgError> {
+        let result = conn.execute(
+            "\
+            update auth_totp_hash \
+            set used = true \
+            where user_id = $1 and \
+                  key = $2 and \
+                  used = false",
+            &[&self.user_id, self.key.original()]
+        ).await?;
+
+        self.used.set(true);
+        self.used.commit();
+
+        Ok(result == 1)
+    }
+
     pub async fn delete(&self, conn: &impl GenericClient) -> Result<(), PgError> {
         conn.execute(
             "delete from auth_totp_hash where key = $1 and user_id = $2",
@@ -205,3 +276,70 @@ impl Hash {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn unused_hash() -> Hash {
+        Hash {
+            user_id: ids::UserId::new(1).unwrap(),
+            key: HistoryField::new(String::from("primary")),
+            hash: HistoryField::new(create_hash().unwrap()),
+            used: HistoryField::new(false),
+        }
+    }
+
+    #[test]
+    fn used_hash_fails_second_attempt() {
+        let mut hash = unused_hash();
+
+        assert!(hash.set_used(), "first use should succeed");
+        assert!(*hash.used());
+        assert!(!hash.set_used(), "second use should fail");
+    }
+
+    #[test]
+    fn regen_hash_unused() {
+        let mut hash = unused_hash();
+        let original = hash.hash.get().clone();
+
+        assert!(hash.regen_hash().unwrap(), "unused hash was not regenerated");
+        assert!(!*hash.used());
+        assert_ne!(hash.hash.get(), &original);
+    }
+
+    #[test]
+    fn regen_hash_rejects_used() {
+        let mut hash = unused_hash();
+        let original = hash.hash.get().clone();
+
+        hash.set_used();
+
+        assert!(!hash.regen_hash().unwrap(), "used hash was regenerated");
+        assert!(*hash.used());
+        assert_eq!(hash.hash.get(), &original);
+    }
+
+    #[test]
+    fn saved_use_fails_second_attempt() {
+        let mut hash = unused_hash();
+
+        assert!(hash.set_used(), "first use should succeed");
+
+        {
+            let (query, params) = hash.update_query()
+                .expect("used flag was not included in the update");
+
+            assert_eq!(query, "update auth_totp_hash set used = $3 where user_id = $1 and key = $2");
+            assert_eq!(params.len(), 3);
+        }
+
+        hash.commit();
+
+        assert!(hash.update_query().is_none(), "saved hash still has changes");
+        assert!(!hash.set_used(), "second use after saving should fail");
+        assert!(hash.update_query().is_none(), "failed use created changes");
+        assert!(!hash.regen_hash().unwrap(), "saved used hash was regenerated");
+    }
+}
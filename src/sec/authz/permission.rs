@@ -165,9 +165,19 @@ impl Abilities {
     }
 }
 
+/// the abilities of a bot along with the user and role they were derived
+/// from so that they can be cleared if either of them change
+#[derive(Debug, Clone)]
+struct BotAbilities {
+    user_id: ids::UserId,
+    role_id: ids::RoleId,
+    abilities: Arc<Abilities>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Rbac {
     cache: moka::sync::Cache<ids::UserId, Arc<Abilities>>,
+    bots: moka::sync::Cache<ids::BotId, BotAbilities>,
 }
 
 impl Rbac {
@@ -177,41 +187,106 @@ impl Rbac {
                 .name("rbac")
                 .max_capacity(1_000)
                 .build(),
+            bots: Cache::builder()
+                .name("rbac_bots")
+                .max_capacity(1_000)
+                .support_invalidation_closures()
+                .build(),
         }
     }
 
     pub fn clear_id(&self, user_id: &ids::UserId) {
         self.cache.invalidate(user_id);
+
+        let user_id = user_id.clone();
+
+        if let Err(err) = self.bots.invalidate_entries_if(
+            move |_, cached| cached.user_id == user_id
+        ) {
+            tracing::error!("failed to clear bot abilities for user. {err}");
+        }
     }
 
-    pub async fn api_ability(
+    pub fn clear_role(&self, role_id: &ids::RoleId) {
+        let role_id = role_id.clone();
+
+        if let Err(err) = self.bots.invalidate_entries_if(
+            move |_, cached| cached.role_id == role_id
+        ) {
+            tracing::error!("failed to clear bot abilities for role. {err}");
+        }
+    }
+
+    pub fn clear_bot(&self, bot_id: &ids::BotId) {
+        self.bots.invalidate(bot_id);
+    }
+
+    /// caches an empty set of abilities for the user
+    #[cfg(test)]
+    pub fn cache_empty(&self, user_id: &ids::UserId) {
+        self.cache.insert(user_id.clone(), Arc::new(Abilities(HashMap::new())));
+    }
+
+    #[cfg(test)]
+    pub fn is_cached(&self, user_id: &ids::UserId) -> bool {
+        self.cache.contains_key(user_id)
+    }
+
+    /// retrieves the abilities of the initiator. the abilities are cached
+    /// until they are cleared
+    pub async fn abilities(
         &self,
         conn: &impl GenericClient,
         initiator: &Initiator,
-        scope: Scope,
-        ability: Ability,
-    ) -> ApiResult<()> {
+    ) -> ApiResult<Arc<Abilities>> {
         match &initiator.mechanism {
             Mechanism::Session(_) => {
                 if let Some(abilities) = self.cache.get(&initiator.user.id.local()) {
-                    if !abilities.has_ability(&scope, &ability) {
-                        return Err(ApiError::from(ApiErrorKind::PermissionDenied));
-                    }
-                } else {
-                    let abilities = retrieve_abilities(conn, &initiator.user.id.local())
-                        .await
-                        .context("failed to retrieve user abilities")?;
+                    return Ok(abilities);
+                }
 
-                    let result = abilities.has_ability(&scope, &ability);
+                let abilities = retrieve_abilities(conn, &initiator.user.id.local())
+                    .await
+                    .context("failed to retrieve user abilities")?;
+                let abilities = Arc::new(abilities);
 
-                    self.cache.insert(initiator.user.id.local().clone(), Arc::new(abilities));
+                self.cache.insert(initiator.user.id.local().clone(), abilities.clone());
 
-                    if !result {
-                        return Err(ApiError::from(ApiErrorKind::PermissionDenied));
-                    }
+                Ok(abilities)
+            }
+            Mechanism::Bot(bot) => {
+                if let Some(cached) = self.bots.get(bot.id.local()) {
+                    return Ok(cached.abilities);
                 }
+
+                let abilities = retrieve_bot_abilities(conn, &bot.user_id, bot.role.local())
+                    .await
+                    .context("failed to retrieve bot abilities")?;
+                let abilities = Arc::new(abilities);
+
+                self.bots.insert(bot.id.local().clone(), BotAbilities {
+                    user_id: bot.user_id.clone(),
+                    role_id: bot.role.local().clone(),
+                    abilities: abilities.clone(),
+                });
+
+                Ok(abilities)
             }
         }
+    }
+
+    pub async fn api_ability(
+        &self,
+        conn: &impl GenericClient,
+        initiator: &Initiator,
+        scope: Scope,
+        ability: Ability,
+    ) -> ApiResult<()> {
+        let abilities = self.abilities(conn, initiator).await?;
+
+        if !abilities.has_ability(&scope, &ability) {
+            return Err(ApiError::from(ApiErrorKind::PermissionDenied));
+        }
 
         Ok(())
     }
@@ -281,3 +356,83 @@ pub async fn retrieve_abilities(
 
     Ok(Abilities(scopes))
 }
+
+/// retrieves the abilities of a bot. a bot is only given the abilities of its
+/// role that the user who owns it also has
+pub async fn retrieve_bot_abilities(
+    conn: &impl GenericClient,
+    user_id: &ids::UserId,
+    role_id: &ids::RoleId,
+) -> Result<Abilities, PgError> {
+    let (owner, role) = tokio::try_join!(
+        retrieve_abilities(conn, user_id),
+        conn.query(
+            "\
+            select authz_permissions.scope, \
+                   authz_permissions.ability \
+            from authz_permissions \
+            where authz_permissions.role_id = $1",
+            &[role_id]
+        )
+    )?;
+
+    let mut scopes: HashMap<Scope, HashSet<Ability>> = HashMap::new();
+
+    for row in role {
+        let scope = Scope::from_str(row.get(0))
+            .expect("invalid scope value from database");
+        let ability = Ability::from_str(row.get(1))
+            .expect("invalid ability value from database");
+
+        if !owner.has_ability(&scope, &ability) {
+            continue;
+        }
+
+        if let Some(abilities) = scopes.get_mut(&scope) {
+            abilities.insert(ability);
+        } else {
+            scopes.insert(scope, HashSet::from([ability]));
+        }
+    }
+
+    Ok(Abilities(scopes))
+}
+
+/// retrieves the ids of all users that have the given ability either
+/// directly through their roles or through the groups they are a member of
+pub async fn retrieve_users_with_ability(
+    conn: &impl GenericClient,
+    scope: Scope,
+    ability: Ability,
+) -> Result<HashSet<ids::UserId>, PgError> {
+    let params: sql::ParamsArray<2> = [&scope, &ability];
+    let result = conn.query_raw(
+        "\
+        select user_roles.user_id \
+        from user_roles \
+        join authz_permissions on \
+            user_roles.role_id = authz_permissions.role_id \
+        where authz_permissions.scope = $1 and \
+            authz_permissions.ability = $2 \
+        union \
+        select group_users.user_id \
+        from group_users \
+        join group_roles on \
+            group_users.group_id = group_roles.group_id \
+        join authz_permissions on \
+            group_roles.role_id = authz_permissions.role_id \
+        where authz_permissions.scope = $1 and \
+            authz_permissions.ability = $2",
+        params
+    ).await?;
+
+    futures::pin_mut!(result);
+
+    let mut users = HashSet::new();
+
+    while let Some(row) = result.try_next().await? {
+        users.insert(row.get(0));
+    }
+
+    Ok(users)
+}
@@ -56,6 +56,16 @@ impl Key {
     pub fn into_tuple(self) -> (chacha::Key, Duration) {
         (self.data, self.created)
     }
+
+    /// a public identifier for the key that is derived from a hash of the
+    /// key data
+    pub fn id(&self) -> String {
+        let hash = blake3::hash(self.data.as_slice());
+        let mut rtn = hash.to_hex().to_string();
+        rtn.truncate(16);
+
+        rtn
+    }
 }
 
 impl Clone for Key {
@@ -79,6 +89,59 @@ impl std::convert::AsRef<chacha::Key> for Key {
     }
 }
 
+/// removes session keys that fail the provided check. the check is given the
+/// key and the key that replaced it. the newest key is always kept so that
+/// new sessions can still be created. returns the number of keys removed
+pub fn retain_session_keys<F>(keys: &mut Fixed<Key, MAX_SESSION_KEYS>, keep: F) -> usize
+where
+    F: FnMut(&Key, &Key) -> bool
+{
+    let mut all = Vec::with_capacity(keys.stored());
+
+    while let Some(key) = keys.pop() {
+        all.push(key);
+    }
+
+    let total = all.len();
+    let kept = filter_session_keys(all, keep);
+    let removed = total - kept.len();
+
+    for key in kept {
+        keys.push(key);
+    }
+
+    removed
+}
+
+/// checks if a session key that was replaced is still within the grace
+/// period and can be used to verify existing sessions
+pub fn within_grace(replaced_by: &Key, grace: Duration, now: Duration) -> bool {
+    now.saturating_sub(replaced_by.created) < grace
+}
+
+fn filter_session_keys<F>(mut all: Vec<Key>, mut keep: F) -> Vec<Key>
+where
+    F: FnMut(&Key, &Key) -> bool
+{
+    all.sort_by(|a, b| a.created.cmp(&b.created));
+
+    let mut rtn = Vec::with_capacity(all.len());
+    let mut iter = all.into_iter().peekable();
+
+    while let Some(key) = iter.next() {
+        let retained = match iter.peek() {
+            Some(replaced_by) => keep(&key, replaced_by),
+            None => true,
+        };
+
+        if retained {
+            rtn.push(key);
+        }
+    }
+
+    rtn
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum PMError {
     #[error("failed to retrieve reader from rwlock")]
@@ -159,3 +222,36 @@ impl PeppersManager {
         Ok(self.0.save()?)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(created: u64) -> Key {
+        Key::new(chacha::empty_key(), Duration::from_secs(created))
+    }
+
+    #[test]
+    fn keeps_keys_within_grace() {
+        let keys = vec![key(300), key(100), key(200)];
+        let grace = Duration::from_secs(150);
+        let now = Duration::from_secs(400);
+
+        let kept = filter_session_keys(keys, |_, replaced_by| within_grace(replaced_by, grace, now));
+        let created: Vec<u64> = kept.iter()
+            .map(|key| key.created().as_secs())
+            .collect();
+
+        assert_eq!(created, vec![200, 300]);
+    }
+
+    #[test]
+    fn always_keeps_newest() {
+        let keys = vec![key(100), key(200)];
+
+        let kept = filter_session_keys(keys, |_, _| false);
+
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].created().as_secs(), 200);
+    }
+}
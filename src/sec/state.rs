@@ -2,17 +2,34 @@ use rfs_lib::sec::chacha;
 
 use crate::error::{self, Context};
 use crate::config;
+use crate::net::cookie::SameSite;
+use crate::state::data::DataDir;
 
 use super::secrets;
 use super::authn::session::SessionCache;
+use super::authn::bot::TokenCache;
 use super::authz::permission::Rbac;
+use super::authn::password::HashParams;
+
+/// settings for extending the expiration of a session as it is used
+#[derive(Debug)]
+pub struct Sliding {
+    pub idle_timeout: chrono::TimeDelta,
+    pub max_lifetime: chrono::TimeDelta,
+}
 
 #[derive(Debug)]
 pub struct SessionInfo {
     manager: secrets::SessionWrapper,
     cache: SessionCache,
+    cookie_name: String,
+    same_site: SameSite,
     domain: Option<String>,
     secure: bool,
+    lifetime: chrono::TimeDelta,
+    pending_lifetime: chrono::TimeDelta,
+    sliding: Option<Sliding>,
+    key_grace: std::time::Duration,
 }
 
 impl SessionInfo {
@@ -27,7 +44,7 @@ impl SessionInfo {
                 .context("failed to expand session key for secrets manager"));
         }
 
-        let secrets_file = config.settings.data.join("sec/secrets/session.data");
+        let secrets_file = DataDir::new(config.settings.data.clone()).session_secrets();
 
         let manager = secrets::SessionWrapper::load_create(secrets_file, session_key)
             .kind("SessionWrapperFailed")
@@ -38,11 +55,31 @@ impl SessionInfo {
             .max_capacity(1_000)
             .build();
 
+        let session_config = &config.settings.sec.session;
+        let sliding = if session_config.sliding {
+            Some(Sliding {
+                idle_timeout: seconds_duration(session_config.idle_timeout)?,
+                max_lifetime: seconds_duration(session_config.max_lifetime)?,
+            })
+        } else {
+            None
+        };
+
         Ok(SessionInfo {
             manager,
             cache,
-            domain: None,
-            secure: config.settings.sec.session.secure
+            cookie_name: session_config.cookie_name.clone(),
+            same_site: match session_config.same_site {
+                config::SameSite::Strict => SameSite::Strict,
+                config::SameSite::Lax => SameSite::Lax,
+                config::SameSite::None => SameSite::None,
+            },
+            domain: session_config.domain.clone(),
+            secure: session_config.secure,
+            lifetime: seconds_duration(session_config.lifetime)?,
+            pending_lifetime: seconds_duration(session_config.pending_lifetime)?,
+            sliding,
+            key_grace: std::time::Duration::from_secs(session_config.key_grace),
         })
     }
 
@@ -54,6 +91,14 @@ impl SessionInfo {
         &self.cache
     }
 
+    pub fn cookie_name(&self) -> &str {
+        &self.cookie_name
+    }
+
+    pub fn same_site(&self) -> &SameSite {
+        &self.same_site
+    }
+
     pub fn domain(&self) -> Option<&String> {
         self.domain.as_ref()
     }
@@ -61,13 +106,52 @@ impl SessionInfo {
     pub fn secure(&self) -> &bool {
         &self.secure
     }
+
+    /// how long a new session is valid for when sliding is disabled
+    pub fn lifetime(&self) -> &chrono::TimeDelta {
+        &self.lifetime
+    }
+
+    /// how long a new session is valid for until it has been authenticated
+    /// and verified
+    pub fn pending_lifetime(&self) -> &chrono::TimeDelta {
+        &self.pending_lifetime
+    }
+
+    pub fn sliding(&self) -> Option<&Sliding> {
+        self.sliding.as_ref()
+    }
+
+    /// how long a replaced session key is still accepted for
+    pub fn key_grace(&self) -> &std::time::Duration {
+        &self.key_grace
+    }
+}
+
+fn seconds_duration(secs: u64) -> error::Result<chrono::TimeDelta> {
+    i64::try_from(secs)
+        .ok()
+        .and_then(chrono::TimeDelta::try_seconds)
+        .context("session duration is too large")
+}
+
+/// number of steps around the current one that a totp code is accepted for
+#[derive(Debug, Clone)]
+pub struct TotpWindow {
+    pub before: u8,
+    pub after: u8,
 }
 
 #[derive(Debug)]
 pub struct Sec {
     session_info: SessionInfo,
     peppers: secrets::PeppersManager,
+    bot_key: chacha::Key,
+    bot_tokens: TokenCache,
     rbac: Rbac,
+    totp_window: TotpWindow,
+    totp_max_recovery: u16,
+    password_params: HashParams,
 }
 
 impl Sec {
@@ -82,18 +166,43 @@ impl Sec {
                 .context("failed to expand passwords key for secrets manager"));
         }
 
-        let secrets_file = config.settings.data.join("sec/secrets/passwords.data");
+        let secrets_file = DataDir::new(config.settings.data.clone()).password_secrets();
 
         let peppers = secrets::PeppersManager::load(secrets_file, password_key.into())
             .kind("PepperManagerFailed")
             .context("failed to create PeppersManager")?;
 
+        // bot tokens are long lived so they are signed with a key that is
+        // not rotated along with the session keys
+        let mut bot_key = chacha::empty_key();
+
+        if let Err(_err) = config.kdf.expand(rfs_lib::sec::secrets::BOTS_KEY_INFO, &mut bot_key) {
+            return Err(error::Error::default()
+                .kind("KDFExpandFailed")
+                .context("failed to expand bot token key"));
+        }
+
+        let bot_tokens = TokenCache::builder()
+            .name("bot_token_cache")
+            .max_capacity(1_000)
+            .time_to_idle(std::time::Duration::from_secs(60 * 60))
+            .support_invalidation_closures()
+            .build();
+
         let rbac = Rbac::new();
 
         Ok(Sec {
             session_info: SessionInfo::from_config(config)?,
             peppers,
-            rbac
+            bot_key,
+            bot_tokens,
+            rbac,
+            totp_window: TotpWindow {
+                before: config.settings.sec.totp.window_before,
+                after: config.settings.sec.totp.window_after,
+            },
+            totp_max_recovery: config.settings.sec.totp.max_recovery_keys,
+            password_params: HashParams::from_config(config),
         })
     }
 
@@ -105,8 +214,30 @@ impl Sec {
         &self.peppers
     }
 
+    /// the key used to sign bot tokens
+    pub fn bot_key(&self) -> &chacha::Key {
+        &self.bot_key
+    }
+
+    pub fn bot_tokens(&self) -> &TokenCache {
+        &self.bot_tokens
+    }
+
     pub fn rbac(&self) -> &Rbac {
         &self.rbac
     }
+
+    pub fn totp_window(&self) -> &TotpWindow {
+        &self.totp_window
+    }
+
+    /// max number of recovery keys a single user can have
+    pub fn totp_max_recovery(&self) -> u16 {
+        self.totp_max_recovery
+    }
+
+    pub fn password_params(&self) -> &HashParams {
+        &self.password_params
+    }
 }
 
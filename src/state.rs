@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use axum::http::HeaderValue;
 use deadpool_postgres::Pool;
 
 use crate::error;
@@ -9,16 +10,31 @@ use crate::config;
 use crate::template;
 use crate::sec;
 
+pub mod data;
 pub mod db;
+pub mod login_attempts;
+pub mod mailer;
+pub mod metrics;
+pub mod rate_limit;
+pub mod uploads;
 
 #[derive(Debug)]
 pub struct Shared {
     assets: Assets,
     pages: PathBuf,
     tmp: PathBuf,
+    data: data::DataDir,
     pool: Pool,
     templates: template::state::Templates,
     sec: sec::state::Sec,
+    metrics: Arc<metrics::Metrics>,
+    rate_limiter: rate_limit::RateLimiter,
+    login_attempts: login_attempts::LoginAttempts,
+    uploads: uploads::Uploads,
+    mailer: mailer::Mailer,
+    limits: Limits,
+    compression: Compression,
+    trash_retention: chrono::TimeDelta,
 }
 
 pub type ArcShared = Arc<Shared>;
@@ -27,16 +43,39 @@ impl Shared {
     pub fn from_config(config: &config::Config) -> error::Result<Shared> {
         tracing::debug!("creating Shared state");
 
+        // the layout needs to exist before anything tries to load files from
+        // the data directory
+        let data = data::DataDir::init(config.settings.data.clone())?;
+
         Ok(Shared {
             assets: Assets {
                 files: config.settings.assets.files.clone(),
                 directories: config.settings.assets.directories.clone(),
+                cache_control: config.settings.assets.cache_control.as_ref()
+                    .and_then(|value| HeaderValue::from_str(value).ok()),
             },
             pages: PathBuf::new(),
             tmp: config.settings.tmp.clone(),
+            data,
             pool: db::from_config(config)?,
             templates: template::state::Templates::from_config(config)?,
             sec: sec::state::Sec::from_config(config)?,
+            metrics: Arc::new(metrics::Metrics::from_config(config)),
+            rate_limiter: rate_limit::RateLimiter::from_config(config),
+            login_attempts: login_attempts::LoginAttempts::from_config(config),
+            uploads: uploads::Uploads::from_config(config),
+            mailer: mailer::Mailer::from_config(config),
+            limits: Limits {
+                max_file_size: config.settings.limits.max_file_size,
+                max_json_size: config.settings.limits.max_json_size,
+                max_depth: config.settings.limits.max_depth,
+                max_children: config.settings.limits.max_children,
+            },
+            compression: Compression {
+                enabled: config.settings.compression.enabled,
+                min_size: config.settings.compression.min_size,
+            },
+            trash_retention: chrono::TimeDelta::days(config.settings.trash.retention_days.into()),
         })
     }
 
@@ -52,6 +91,10 @@ impl Shared {
         &self.tmp
     }
 
+    pub fn data(&self) -> &data::DataDir {
+        &self.data
+    }
+
     pub fn pool(&self) -> &Pool {
         &self.pool
     }
@@ -63,6 +106,38 @@ impl Shared {
     pub fn sec(&self) -> &sec::state::Sec {
         &self.sec
     }
+
+    pub fn metrics(&self) -> &Arc<metrics::Metrics> {
+        &self.metrics
+    }
+
+    pub fn rate_limiter(&self) -> &rate_limit::RateLimiter {
+        &self.rate_limiter
+    }
+
+    pub fn login_attempts(&self) -> &login_attempts::LoginAttempts {
+        &self.login_attempts
+    }
+
+    pub fn uploads(&self) -> &uploads::Uploads {
+        &self.uploads
+    }
+
+    pub fn mailer(&self) -> &mailer::Mailer {
+        &self.mailer
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    pub fn compression(&self) -> &Compression {
+        &self.compression
+    }
+
+    pub fn trash_retention(&self) -> &chrono::TimeDelta {
+        &self.trash_retention
+    }
 }
 
 impl AsRef<Pool> for Shared {
@@ -87,4 +162,19 @@ impl AsRef<template::state::Templates> for Shared {
 pub struct Assets {
     pub files: HashMap<String, PathBuf>,
     pub directories: HashMap<String, PathBuf>,
+    pub cache_control: Option<HeaderValue>,
+}
+
+#[derive(Debug)]
+pub struct Limits {
+    pub max_file_size: u64,
+    pub max_json_size: usize,
+    pub max_depth: usize,
+    pub max_children: u64,
+}
+
+#[derive(Debug)]
+pub struct Compression {
+    pub enabled: bool,
+    pub min_size: u16,
 }
@@ -0,0 +1,134 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::error::{self, Context};
+
+/// directories that will be created in the data directory. paths are
+/// relative to the root of the data directory
+const DIRECTORIES: [&str; 3] = [
+    "sec",
+    "sec/secrets",
+    "jobs",
+];
+
+/// files written by earlier versions outside of the current layout. each
+/// file is moved to the second path if it does not already exist
+const LEGACY: [(&str, &str); 2] = [
+    ("session.data", "sec/secrets/session.data"),
+    ("passwords.data", "sec/secrets/passwords.data"),
+];
+
+/// the layout of the data directory. anything the server writes to the data
+/// directory should be placed under one of the paths provided here so that
+/// backups only need to know about this layout
+///
+/// ```text
+/// {data}/
+///   sec/secrets/session.data   encrypted session keys
+///   sec/secrets/passwords.data encrypted password peppers
+///   jobs/                      last run information for background jobs
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataDir {
+    root: PathBuf,
+}
+
+impl DataDir {
+    pub fn new(root: PathBuf) -> Self {
+        DataDir { root }
+    }
+
+    /// creates any missing directories of the layout and moves legacy
+    /// files into place
+    pub fn init(root: PathBuf) -> error::Result<Self> {
+        let data = DataDir::new(root);
+
+        for dir in DIRECTORIES {
+            create_dir(&data.root.join(dir))?;
+        }
+
+        for (legacy, current) in LEGACY {
+            migrate_file(&data.root.join(legacy), &data.root.join(current))?;
+        }
+
+        Ok(data)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn session_secrets(&self) -> PathBuf {
+        self.root.join("sec/secrets/session.data")
+    }
+
+    pub fn password_secrets(&self) -> PathBuf {
+        self.root.join("sec/secrets/passwords.data")
+    }
+
+    pub fn jobs(&self) -> PathBuf {
+        self.root.join("jobs")
+    }
+}
+
+/// creates the directory if it does not exist. directories are only
+/// accessible by the user running the server since they contain secrets
+fn create_dir(path: &Path) -> error::Result<()> {
+    match path.metadata() {
+        Ok(meta) => {
+            if !meta.is_dir() {
+                return Err(error::Error::new()
+                    .kind("InvalidDataDir")
+                    .context(format!("data path is not a directory. {}", path.display())));
+            }
+
+            return Ok(());
+        }
+        Err(err) => if err.kind() != ErrorKind::NotFound {
+            return Err(error::Error::new()
+                .context(format!("failed to retrieve metadata for data path. {}", path.display()))
+                .source(err));
+        }
+    }
+
+    let mut builder = std::fs::DirBuilder::new();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+
+        builder.mode(0o700);
+    }
+
+    builder.create(path)
+        .context(format!("failed to create data directory. {}", path.display()))?;
+
+    tracing::debug!("created data directory \"{}\"", path.display());
+
+    Ok(())
+}
+
+/// moves a legacy file to its current location. if a file already exists at
+/// the current location then the legacy file is left alone
+fn migrate_file(legacy: &Path, current: &Path) -> error::Result<()> {
+    if !legacy.try_exists().context("failed to check for legacy data file")? {
+        return Ok(());
+    }
+
+    if current.try_exists().context("failed to check for data file")? {
+        tracing::warn!(
+            "legacy data file \"{}\" was not moved since \"{}\" already exists",
+            legacy.display(),
+            current.display()
+        );
+
+        return Ok(());
+    }
+
+    std::fs::rename(legacy, current)
+        .context(format!("failed to move legacy data file. {}", legacy.display()))?;
+
+    tracing::info!("moved legacy data file \"{}\" to \"{}\"", legacy.display(), current.display());
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
+use std::time::Duration;
+
 use tokio_postgres::{Config, NoTls};
-use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
+use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod, Runtime};
 
 use crate::config;
 
@@ -18,13 +20,24 @@ pub fn from_config(config: &config::Config) -> Result<Pool, deadpool_postgres::B
     pg_config.port(config.settings.db.port);
     pg_config.dbname(config.settings.db.dbname.as_str());
 
+    let connect_timeout = Duration::from_secs(config.settings.db.connect_timeout);
+
+    pg_config.connect_timeout(connect_timeout);
+
+    let recycling_method = match config.settings.db.recycle {
+        config::Recycle::Fast => RecyclingMethod::Fast,
+        config::Recycle::Verified => RecyclingMethod::Verified,
+    };
+
     let manager_config = ManagerConfig {
-        recycling_method: RecyclingMethod::Fast,
+        recycling_method,
     };
 
     let manager = Manager::from_config(pg_config, NoTls, manager_config);
 
     Pool::builder(manager)
-        .max_size(4)
+        .max_size(config.settings.db.max_size)
+        .create_timeout(Some(connect_timeout))
+        .runtime(Runtime::Tokio1)
         .build()
 }
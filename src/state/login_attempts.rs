@@ -0,0 +1,147 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rfs_lib::ids;
+use moka::sync::Cache;
+
+use crate::config;
+
+#[derive(Debug)]
+struct Attempts {
+    failures: u32,
+    window_start: Instant,
+    lockouts: u32,
+    locked_until: Option<Instant>,
+}
+
+/// tracks failed login attempts for users. once a user has failed too many
+/// times within the window they will be locked out with each lockout being
+/// longer than the last
+#[derive(Debug)]
+pub struct LoginAttempts {
+    max_attempts: u32,
+    window: Duration,
+    lockout: Duration,
+    max_lockout: Duration,
+    users: Cache<ids::UserId, Arc<Mutex<Attempts>>>,
+}
+
+impl LoginAttempts {
+    pub fn from_config(config: &config::Config) -> Self {
+        let login = &config.settings.sec.login;
+        let window = Duration::from_secs(login.window);
+        let max_lockout = Duration::from_secs(login.max_lockout);
+
+        LoginAttempts {
+            max_attempts: login.max_attempts,
+            window,
+            lockout: Duration::from_secs(login.lockout),
+            max_lockout,
+            // once a user has been idle longer than the window and any
+            // lockout they would have had then the backoff is reset
+            users: Cache::builder()
+                .name("login_attempts")
+                .max_capacity(10_000)
+                .time_to_idle(window.max(max_lockout))
+                .build(),
+        }
+    }
+
+    /// checks if the user is currently locked out. if they are then the
+    /// amount of time remaining is returned
+    pub fn check(&self, user_id: &ids::UserId) -> Result<(), Duration> {
+        let Some(entry) = self.users.get(user_id) else {
+            return Ok(());
+        };
+
+        let attempts = match entry.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if let Some(locked_until) = attempts.locked_until {
+            let now = Instant::now();
+
+            if locked_until > now {
+                return Err(locked_until - now);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// records a failed attempt for the user. returns the lockout duration
+    /// if the user was locked out by this attempt
+    pub fn failed(&self, user_id: &ids::UserId) -> Option<Duration> {
+        let now = Instant::now();
+        let entry = self.users.get_with(user_id.clone(), || Arc::new(Mutex::new(Attempts {
+            failures: 0,
+            window_start: now,
+            lockouts: 0,
+            locked_until: None,
+        })));
+
+        let mut attempts = match entry.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if now.saturating_duration_since(attempts.window_start) > self.window {
+            attempts.failures = 0;
+            attempts.window_start = now;
+        }
+
+        attempts.failures += 1;
+
+        if attempts.failures < self.max_attempts {
+            return None;
+        }
+
+        let duration = lockout_duration(self.lockout, self.max_lockout, attempts.lockouts);
+
+        attempts.failures = 0;
+        attempts.window_start = now;
+        attempts.lockouts = attempts.lockouts.saturating_add(1);
+        attempts.locked_until = Some(now + duration);
+
+        Some(duration)
+    }
+
+    /// clears any failed attempts for the user
+    pub fn reset(&self, user_id: &ids::UserId) {
+        self.users.invalidate(user_id);
+    }
+}
+
+/// calculates the duration of a lockout by doubling the base for each
+/// previous lockout without going over the max
+fn lockout_duration(base: Duration, max: Duration, previous: u32) -> Duration {
+    2u32.checked_pow(previous)
+        .and_then(|multiplier| base.checked_mul(multiplier))
+        .map(|duration| duration.min(max))
+        .unwrap_or(max)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lockout_doubles() {
+        let base = Duration::from_secs(30);
+        let max = Duration::from_secs(60 * 60);
+
+        assert_eq!(lockout_duration(base, max, 0), Duration::from_secs(30));
+        assert_eq!(lockout_duration(base, max, 1), Duration::from_secs(60));
+        assert_eq!(lockout_duration(base, max, 2), Duration::from_secs(120));
+    }
+
+    #[test]
+    fn lockout_capped_at_max() {
+        let base = Duration::from_secs(30);
+        let max = Duration::from_secs(60 * 60);
+
+        assert_eq!(lockout_duration(base, max, 10), max);
+        assert_eq!(lockout_duration(base, max, 40), max);
+    }
+}
@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::config;
+
+/// delivers email verification tokens to users. the server does not have a
+/// mail transport of its own so the token is handed to the command given in
+/// the config
+#[derive(Debug)]
+pub struct Mailer {
+    command: Option<PathBuf>,
+}
+
+impl Mailer {
+    pub fn from_config(config: &config::Config) -> Self {
+        Mailer {
+            command: config.settings.email.command.clone(),
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.command.is_some()
+    }
+
+    /// runs the configured command with the email address as the only
+    /// argument and writes the token to stdin. the token is never logged.
+    /// addresses starting with "-" are rejected so that they cannot be
+    /// mistaken for options by the command
+    pub async fn send_verification(&self, email: &str, token: &str) -> std::io::Result<()> {
+        let Some(command) = &self.command else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "no email command is configured"
+            ));
+        };
+
+        if email.starts_with('-') {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "email address cannot start with \"-\""
+            ));
+        }
+
+        let mut child = Command::new(command)
+            .arg(email)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(token.as_bytes()).await?;
+            stdin.shutdown().await?;
+        }
+
+        let status = child.wait().await?;
+
+        if !status.success() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("email command exited with {status}")
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    use std::os::unix::fs::PermissionsExt;
+
+    /// creates an executable shell script in the temp directory with the
+    /// given body
+    fn create_script(name: &str, body: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join(format!("rfs_mailer_{name}_{}.sh", std::process::id()));
+
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        path
+    }
+
+    #[tokio::test]
+    async fn delivers_token_on_stdin() {
+        let output = std::env::temp_dir()
+            .join(format!("rfs_mailer_output_{}.txt", std::process::id()));
+        let script = create_script(
+            "deliver",
+            &format!("echo \"$1\" > \"{}\"\ncat >> \"{}\"", output.display(), output.display())
+        );
+        let mailer = Mailer { command: Some(script.clone()) };
+
+        mailer.send_verification("user@example.com", "token")
+            .await
+            .expect("failed to deliver verification token");
+
+        let written = std::fs::read_to_string(&output).unwrap();
+
+        std::fs::remove_file(&script).unwrap();
+        std::fs::remove_file(&output).unwrap();
+
+        assert_eq!(written, "user@example.com\ntoken");
+    }
+
+    #[tokio::test]
+    async fn command_exits_non_zero() {
+        let script = create_script("fail", "cat > /dev/null\nexit 3");
+        let mailer = Mailer { command: Some(script.clone()) };
+
+        let result = mailer.send_verification("user@example.com", "token").await;
+
+        std::fs::remove_file(&script).unwrap();
+
+        let err = result.expect_err("non-zero exit was treated as delivered");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::Other);
+    }
+
+    #[tokio::test]
+    async fn rejects_option_like_email() {
+        let mailer = Mailer { command: Some(PathBuf::from("/bin/false")) };
+
+        let err = mailer.send_verification("--help", "token")
+            .await
+            .expect_err("option like email was passed to the command");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
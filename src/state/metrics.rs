@@ -0,0 +1,171 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use dashmap::DashMap;
+
+/// upper bounds in seconds for the request duration histogram. these are the
+/// same as the default buckets used by the prometheus client libraries
+const DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0
+];
+
+#[derive(Debug, Default)]
+struct Histogram {
+    buckets: [u64; DURATION_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        if let Some(index) = DURATION_BUCKETS.iter().position(|le| secs <= *le) {
+            self.buckets[index] += 1;
+        }
+
+        self.count += 1;
+        self.sum += secs;
+    }
+}
+
+/// collected server metrics that will be output in the prometheus text
+/// format
+#[derive(Debug)]
+pub struct Metrics {
+    enabled: bool,
+    listener: Option<String>,
+    requests: DashMap<(String, u16), u64>,
+    durations: DashMap<String, Histogram>,
+    uploaded: AtomicU64,
+    downloaded: AtomicU64,
+}
+
+impl Metrics {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Metrics {
+            enabled: config.settings.metrics.enabled,
+            listener: config.settings.metrics.listener.clone(),
+            requests: DashMap::new(),
+            durations: DashMap::new(),
+            uploaded: AtomicU64::new(0),
+            downloaded: AtomicU64::new(0),
+        }
+    }
+
+    /// checks if the metrics endpoint should be available for the given
+    /// listener
+    pub fn serve_on(&self, listener: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        match &self.listener {
+            Some(key) => key == listener,
+            None => true
+        }
+    }
+
+    pub fn record_request(&self, route: &str, status: u16, duration: Duration) {
+        *self.requests.entry((route.to_owned(), status)).or_insert(0) += 1;
+
+        self.durations.entry(route.to_owned())
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn add_uploaded(&self, bytes: u64) {
+        self.uploaded.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn add_downloaded(&self, bytes: u64) {
+        self.downloaded.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn render(&self, pool: &deadpool_postgres::Status) -> Result<String, std::fmt::Error> {
+        let mut output = String::new();
+
+        write!(
+            &mut output,
+            "# HELP rfs_http_requests_total total number of http requests by route and status\n\
+            # TYPE rfs_http_requests_total counter\n"
+        )?;
+
+        for entry in self.requests.iter() {
+            let (route, status) = entry.key();
+
+            write!(
+                &mut output,
+                "rfs_http_requests_total{{route=\"{}\",status=\"{status}\"}} {}\n",
+                escape_label(route),
+                entry.value()
+            )?;
+        }
+
+        write!(
+            &mut output,
+            "# HELP rfs_http_request_duration_seconds duration of http requests by route\n\
+            # TYPE rfs_http_request_duration_seconds histogram\n"
+        )?;
+
+        for entry in self.durations.iter() {
+            let route = escape_label(entry.key());
+            let histogram = entry.value();
+            let mut cumulative = 0;
+
+            for (le, count) in DURATION_BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+
+                write!(
+                    &mut output,
+                    "rfs_http_request_duration_seconds_bucket{{route=\"{route}\",le=\"{le}\"}} {cumulative}\n"
+                )?;
+            }
+
+            write!(
+                &mut output,
+                "rfs_http_request_duration_seconds_bucket{{route=\"{route}\",le=\"+Inf\"}} {}\n\
+                rfs_http_request_duration_seconds_sum{{route=\"{route}\"}} {}\n\
+                rfs_http_request_duration_seconds_count{{route=\"{route}\"}} {}\n",
+                histogram.count,
+                histogram.sum,
+                histogram.count
+            )?;
+        }
+
+        write!(
+            &mut output,
+            "# HELP rfs_fs_uploaded_bytes_total total bytes written by file uploads\n\
+            # TYPE rfs_fs_uploaded_bytes_total counter\n\
+            rfs_fs_uploaded_bytes_total {}\n\
+            # HELP rfs_fs_downloaded_bytes_total total bytes sent by file downloads\n\
+            # TYPE rfs_fs_downloaded_bytes_total counter\n\
+            rfs_fs_downloaded_bytes_total {}\n",
+            self.uploaded.load(Ordering::Relaxed),
+            self.downloaded.load(Ordering::Relaxed),
+        )?;
+
+        write!(
+            &mut output,
+            "# HELP rfs_db_pool_size current number of connections in the pool\n\
+            # TYPE rfs_db_pool_size gauge\n\
+            rfs_db_pool_size {}\n\
+            # HELP rfs_db_pool_available number of idle connections in the pool\n\
+            # TYPE rfs_db_pool_available gauge\n\
+            rfs_db_pool_available {}\n\
+            # HELP rfs_db_pool_max_size maximum number of connections in the pool\n\
+            # TYPE rfs_db_pool_max_size gauge\n\
+            rfs_db_pool_max_size {}\n",
+            pool.size,
+            pool.available,
+            pool.max_size,
+        )?;
+
+        Ok(output)
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
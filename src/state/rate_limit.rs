@@ -0,0 +1,85 @@
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rfs_lib::ids;
+use moka::sync::Cache;
+
+use crate::config;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    User(ids::UserId),
+    Addr(IpAddr),
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+/// token bucket rate limiter. each key is given its own bucket that will
+/// refill over time up to the specified capacity
+#[derive(Debug)]
+pub struct RateLimiter {
+    enabled: bool,
+    capacity: f64,
+    refill: f64,
+    buckets: Cache<Key, Arc<Mutex<Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn from_config(config: &config::Config) -> Self {
+        let capacity = config.settings.rate_limit.capacity.max(1) as f64;
+        let refill = config.settings.rate_limit.refill.max(1) as f64;
+
+        // once a bucket has been idle long enough to refill it is the same
+        // as a new bucket so it can be dropped
+        let idle = Duration::from_secs_f64(capacity / refill);
+
+        RateLimiter {
+            enabled: config.settings.rate_limit.enabled,
+            capacity,
+            refill,
+            buckets: Cache::builder()
+                .name("rate_limit_buckets")
+                .max_capacity(10_000)
+                .time_to_idle(idle)
+                .build(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// attempts to take a token from the bucket of the given key. if no
+    /// tokens are available then the amount of time until the next token is
+    /// returned
+    pub fn check(&self, key: Key) -> Result<(), Duration> {
+        let now = Instant::now();
+        let bucket = self.buckets.get_with(key, || Arc::new(Mutex::new(Bucket {
+            tokens: self.capacity,
+            last: now,
+        })));
+
+        let mut bucket = match bucket.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+
+        bucket.tokens = (bucket.tokens + elapsed * self.refill).min(self.capacity);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill))
+        }
+    }
+}
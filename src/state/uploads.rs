@@ -0,0 +1,40 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config;
+use crate::fs::backend;
+
+/// limits the number of uploads that can be written at the same time. each
+/// type of storage medium has its own limit
+#[derive(Debug)]
+pub struct Uploads {
+    local: Arc<Semaphore>,
+    wait: Duration,
+}
+
+impl Uploads {
+    pub fn from_config(config: &config::Config) -> Self {
+        let uploads = &config.settings.limits.uploads;
+
+        Uploads {
+            local: Arc::new(Semaphore::new(uploads.local)),
+            wait: Duration::from_secs(uploads.wait),
+        }
+    }
+
+    /// waits for an open slot for the given storage medium. if one does not
+    /// open up in time then the amount of time that was waited is returned
+    pub async fn acquire(&self, backend: &backend::Config) -> Result<OwnedSemaphorePermit, Duration> {
+        let semaphore = match backend {
+            backend::Config::Local(_) => self.local.clone(),
+        };
+
+        match tokio::time::timeout(self.wait, semaphore.acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            // the semaphore is never closed so this should not happen
+            Ok(Err(_)) | Err(_) => Err(self.wait),
+        }
+    }
+}
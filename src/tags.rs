@@ -172,3 +172,287 @@ where
 
     Ok(())
 }
+
+/// adds the tags to the existing tags of the record. tags that already exist
+/// will have their value replaced
+pub async fn add_tags<I>(
+    conn: &impl GenericClient,
+    table: &str,
+    id_field: &str,
+    id: &I,
+    tags: &TagMap
+) -> Result<(), PgError>
+where
+    I: ToSql + Sync
+{
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let mut insert_query = String::new();
+    let mut params = sql::ParamsVec::with_capacity(tags.len() * 2 + 1);
+    params.push(id);
+
+    for (tag, value) in tags {
+        if params.len() > 1 {
+            insert_query.push(',');
+        }
+
+        write!(
+            &mut insert_query,
+            "($1, ${}, ${})",
+            sql::push_param(&mut params, tag),
+            sql::push_param(&mut params, value)
+        ).unwrap();
+    }
+
+    let query = format!(
+        "\
+        insert into {table} ({id_field}, tag, value) values {insert_query} \
+        on conflict ({id_field}, tag) do update set \
+            value = EXCLUDED.value"
+    );
+
+    conn.execute(query.as_str(), params.as_slice()).await?;
+
+    Ok(())
+}
+
+/// groups tag rows by the record that they belong to. each row is the id of
+/// the record, the tag, and its value
+pub fn group_rows<K, I>(rows: I) -> HashMap<K, TagMap>
+where
+    K: Eq + std::hash::Hash,
+    I: IntoIterator<Item = (K, String, Option<String>)>,
+{
+    let mut rtn: HashMap<K, TagMap> = HashMap::new();
+
+    for (id, tag, value) in rows {
+        rtn.entry(id)
+            .or_default()
+            .insert(tag, value);
+    }
+
+    rtn
+}
+
+/// removes the given keys from the map if they exist
+pub fn remove_keys(tags: &mut TagMap, keys: &[String]) {
+    for key in keys {
+        tags.remove(key);
+    }
+}
+
+/// checks that all of the given keys are valid tag keys
+pub fn validate_keys(keys: &[String]) -> bool {
+    keys.iter().all(rfs_lib::tags::key_valid)
+}
+
+/// removes the given tags from the record if they exist
+pub async fn remove_tags<I>(
+    conn: &impl GenericClient,
+    table: &str,
+    id_field: &str,
+    id: &I,
+    tags: &[String]
+) -> Result<(), PgError>
+where
+    I: ToSql + Sync
+{
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let params: sql::ParamsArray<2> = [id, &tags];
+    let query = format!("delete from {table} where {id_field} = $1 and tag = any($2)");
+
+    conn.execute(query.as_str(), &params).await?;
+
+    Ok(())
+}
+
+/// a tag that an item must have. if a value is given then the tag must also
+/// have the same value
+#[derive(Debug)]
+pub struct TagFilter {
+    pub tag: String,
+    pub value: Option<String>,
+    /// a like pattern for matching any tag that starts with the tag given
+    pub prefix: Option<String>,
+    /// compare the value without case
+    pub ignore_case: bool,
+}
+
+impl TagFilter {
+    /// parses a filter in the form of "name" or "name:value". the name and
+    /// value must be valid for a tag
+    pub fn parse(given: &str) -> Option<TagFilter> {
+        let (tag, value) = match given.split_once(':') {
+            Some((tag, "")) => (tag.to_owned(), None),
+            Some((tag, value)) => (tag.to_owned(), Some(value.to_owned())),
+            None => (given.to_owned(), None),
+        };
+
+        if !rfs_lib::tags::key_valid(&tag) {
+            return None;
+        }
+
+        if let Some(v) = &value {
+            if !rfs_lib::tags::value_valid(v) {
+                return None;
+            }
+        }
+
+        Some(TagFilter {
+            tag,
+            value,
+            prefix: None,
+            ignore_case: false,
+        })
+    }
+
+    /// changes the filter to match any tag that starts with the tag given
+    pub fn into_prefix(mut self) -> Self {
+        self.prefix = Some(format!("{}%", escape_like(&self.tag)));
+        self
+    }
+}
+
+/// escapes the characters that have special meaning in a like pattern
+pub fn escape_like(given: &str) -> String {
+    let mut rtn = String::with_capacity(given.len());
+
+    for ch in given.chars() {
+        if ch == '\\' || ch == '%' || ch == '_' {
+            rtn.push('\\');
+        }
+
+        rtn.push(ch);
+    }
+
+    rtn
+}
+
+/// adds a condition to the where clause of the query for each filter given.
+/// `outer_id` is the column of the item being filtered. prefix and case
+/// insensitive filters rely on the "tag_search" indexes from the migrations
+pub fn write_filters<'a>(
+    query: &mut String,
+    params: &mut sql::ParamsVec<'a>,
+    table: &str,
+    id_field: &str,
+    outer_id: &str,
+    filters: &'a [TagFilter],
+) {
+    for filter in filters {
+        write!(
+            query,
+            " and exists (\
+                select 1 \
+                from {table} \
+                where {table}.{id_field} = {outer_id} and "
+        ).unwrap();
+
+        if let Some(prefix) = &filter.prefix {
+            write!(
+                query,
+                "{table}.tag like ${}",
+                sql::push_param(params, prefix)
+            ).unwrap();
+        } else {
+            write!(
+                query,
+                "{table}.tag = ${}",
+                sql::push_param(params, &filter.tag)
+            ).unwrap();
+        }
+
+        if let Some(value) = &filter.value {
+            if filter.ignore_case {
+                write!(
+                    query,
+                    " and lower({table}.value) = lower(${})",
+                    sql::push_param(params, value)
+                ).unwrap();
+            } else {
+                write!(
+                    query,
+                    " and {table}.value = ${}",
+                    sql::push_param(params, value)
+                ).unwrap();
+            }
+        }
+
+        query.push(')');
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_filter() {
+        let filter = TagFilter::parse("name").unwrap();
+        assert_eq!(filter.tag, "name");
+        assert_eq!(filter.value, None);
+
+        let filter = TagFilter::parse("name:").unwrap();
+        assert_eq!(filter.tag, "name");
+        assert_eq!(filter.value, None);
+
+        let filter = TagFilter::parse("name:value:more").unwrap();
+        assert_eq!(filter.tag, "name");
+        assert_eq!(filter.value.as_deref(), Some("value:more"));
+
+        assert!(TagFilter::parse("").is_none());
+        assert!(TagFilter::parse(":value").is_none());
+    }
+
+    #[test]
+    fn prefix_filter() {
+        let filter = TagFilter::parse("my_tag").unwrap().into_prefix();
+        assert_eq!(filter.prefix.as_deref(), Some("my\\_tag%"));
+
+        assert_eq!(escape_like("100%"), "100\\%");
+        assert_eq!(escape_like("a\\b"), "a\\\\b");
+    }
+
+    #[test]
+    fn remove_one_key() {
+        let mut tags = TagMap::from([
+            ("one".to_owned(), None),
+            ("two".to_owned(), Some("2".to_owned())),
+            ("three".to_owned(), Some("3".to_owned())),
+        ]);
+
+        remove_keys(&mut tags, &["two".to_owned(), "missing".to_owned()]);
+
+        assert_eq!(tags.len(), 2);
+        assert!(tags.contains_key("one"));
+        assert!(!tags.contains_key("two"));
+        assert_eq!(tags.get("three"), Some(&Some("3".to_owned())));
+    }
+
+    #[test]
+    fn group_rows_multiple_tags() {
+        let rows = vec![
+            (1, String::from("project"), Some(String::from("rfs"))),
+            (2, String::from("archive"), None),
+            (1, String::from("backup"), None),
+        ];
+
+        let mut grouped = group_rows(rows);
+
+        let first = grouped.remove(&1).expect("storage with two tags is missing");
+        assert_eq!(first.len(), 2);
+        assert_eq!(first.get("project"), Some(&Some(String::from("rfs"))));
+        assert_eq!(first.get("backup"), Some(&None));
+
+        let second = grouped.remove(&2).expect("storage with one tag is missing");
+        assert_eq!(second.len(), 1);
+        assert_eq!(second.get("archive"), Some(&None));
+
+        assert!(grouped.is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use rfs_lib::ids;
 use tokio_postgres::{Error as PgError};
 use deadpool_postgres::GenericClient;
 
+pub mod email;
 pub mod group;
 
 pub async fn check_username<U>(
@@ -97,6 +98,8 @@ pub struct User {
     pub id: ids::UserSet,
     pub username: String,
     pub email: Option<UserEmail>,
+    pub display_name: Option<String>,
+    pub preferences: serde_json::Value,
 }
 
 impl User {
@@ -105,6 +108,21 @@ impl User {
     }
 }
 
+impl From<User> for rfs_api::users::User {
+    fn from(user: User) -> Self {
+        rfs_api::users::User {
+            uid: user.id.into(),
+            username: user.username,
+            email: user.email.map(|e| rfs_api::users::Email {
+                email: e.email,
+                verified: e.verified,
+            }),
+            display_name: user.display_name,
+            preferences: user.preferences,
+        }
+    }
+}
+
 impl User {
     pub async fn retrieve(
         conn: &impl GenericClient,
@@ -115,7 +133,9 @@ impl User {
             select users.uid, \
                    users.username, \
                    users.email, \
-                   users.email_verified \
+                   users.email_verified, \
+                   users.display_name, \
+                   users.preferences \
             from users \
             where users.id = $1",
             &[id]
@@ -129,7 +149,9 @@ impl User {
                 })
             } else {
                 None
-            }
+            },
+            display_name: row.get(4),
+            preferences: row.get(5),
         }))
     }
 
@@ -142,7 +164,9 @@ impl User {
             select users.id, \
                    users.username, \
                    users.email, \
-                   users.email_verified \
+                   users.email_verified, \
+                   users.display_name, \
+                   users.preferences \
             from users \
             where users.uid = $1",
             &[uid]
@@ -156,7 +180,9 @@ impl User {
                 })
             } else {
                 None
-            }
+            },
+            display_name: row.get(4),
+            preferences: row.get(5),
         }))
     }
 
@@ -164,7 +190,9 @@ impl User {
         User::retrieve(conn, id).await
     }
 
-    pub async fn retrieve_username<U>(
+    /// retrieves the user with the given username. usernames are unique so
+    /// at most one user will be found
+    pub async fn retrieve_by_username<U>(
         conn: &impl GenericClient,
         username: U
     ) -> Result<Option<User>, PgError>
@@ -179,7 +207,9 @@ impl User {
                    users.uid, \
                    users.username, \
                    users.email, \
-                   users.email_verified \
+                   users.email_verified, \
+                   users.display_name, \
+                   users.preferences \
             from users \
             where users.username = $1",
             &[&username_ref]
@@ -193,11 +223,9 @@ impl User {
                 })
             } else {
                 None
-            }
+            },
+            display_name: row.get(5),
+            preferences: row.get(6),
         }))
     }
-
-    pub async fn query_with_username(conn: &impl GenericClient, username: &String) -> Result<Option<User>, PgError> {
-        User::retrieve_username(conn, username).await
-    }
 }
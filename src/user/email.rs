@@ -0,0 +1,108 @@
+use rfs_lib::ids;
+use chrono::{DateTime, Utc, TimeDelta};
+use deadpool_postgres::GenericClient;
+use rand::RngCore;
+use tokio_postgres::{Error as PgError};
+
+/// the number of random bytes in a verification token
+pub const TOKEN_BYTES: usize = 20;
+
+/// the number of hours that a verification token is valid for
+pub const VERIFY_HOURS: i64 = 24;
+
+pub type Hash = blake3::Hash;
+
+/// creates a new verification token along with the hash that will be
+/// stored in the database. the token is only given to the user
+pub fn gen_token() -> Result<(String, Hash), rand::Error> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    rand::thread_rng().try_fill_bytes(&mut bytes)?;
+
+    Ok((data_encoding::BASE32_NOPAD.encode(&bytes), blake3::hash(&bytes)))
+}
+
+/// decodes the token given by the user into the hash that was stored
+pub fn decode_token(given: &str) -> Option<Hash> {
+    let bytes = data_encoding::BASE32_NOPAD.decode(given.as_bytes()).ok()?;
+
+    if bytes.len() != TOKEN_BYTES {
+        return None;
+    }
+
+    Some(blake3::hash(&bytes))
+}
+
+/// a pending verification of an email address for a user. a user will only
+/// have one pending verification at a time
+#[derive(Debug)]
+pub struct Verification {
+    pub user_id: ids::UserId,
+    pub email: String,
+    pub expires: DateTime<Utc>,
+}
+
+impl Verification {
+    /// creates a new verification for the user replacing any that were
+    /// previously pending
+    pub async fn create(
+        conn: &impl GenericClient,
+        user_id: &ids::UserId,
+        email: String,
+        hash: &Hash,
+    ) -> Result<Self, PgError> {
+        let issued = Utc::now();
+        let expires = issued + TimeDelta::hours(VERIFY_HOURS);
+
+        conn.execute(
+            "delete from user_email_verify where user_id = $1",
+            &[user_id]
+        ).await?;
+
+        conn.execute(
+            "\
+            insert into user_email_verify (user_id, email, token_hash, issued, expires) \
+            values ($1, $2, $3, $4, $5)",
+            &[user_id, &email, &hash.as_bytes().as_slice(), &issued, &expires]
+        ).await?;
+
+        Ok(Verification {
+            user_id: *user_id,
+            email,
+            expires,
+        })
+    }
+
+    pub async fn retrieve_hash(
+        conn: &impl GenericClient,
+        user_id: &ids::UserId,
+        hash: &Hash,
+    ) -> Result<Option<Self>, PgError> {
+        Ok(conn.query_opt(
+            "\
+            select user_email_verify.user_id, \
+                   user_email_verify.email, \
+                   user_email_verify.expires \
+            from user_email_verify \
+            where user_email_verify.user_id = $1 and \
+                  user_email_verify.token_hash = $2",
+            &[user_id, &hash.as_bytes().as_slice()]
+        ).await?.map(|row| Verification {
+            user_id: row.get(0),
+            email: row.get(1),
+            expires: row.get(2),
+        }))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires <= Utc::now()
+    }
+
+    pub async fn delete(&self, conn: &impl GenericClient) -> Result<(), PgError> {
+        conn.execute(
+            "delete from user_email_verify where user_id = $1",
+            &[&self.user_id]
+        ).await?;
+
+        Ok(())
+    }
+}
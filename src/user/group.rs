@@ -16,7 +16,7 @@ impl Group {
         group_uid: &ids::GroupUid,
     ) -> Result<Option<Self>, PgError> {
         Ok(conn.query_opt(
-            "select id, name, create, updated from groups where uid = $1",
+            "select id, name, created, updated from groups where uid = $1",
             &[group_uid]
         ).await?.map(|row| Group {
             id: ids::GroupSet::new(row.get(0), group_uid.clone()),